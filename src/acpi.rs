@@ -0,0 +1,354 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ptr;
+use core::slice;
+
+use uefi::table::cfg::{ACPI2_GUID, ACPI_GUID};
+
+use crate::env;
+#[cfg(feature = "gfx")]
+use crate::gfx::{pos, Buffer, Pos};
+#[cfg(feature = "gfx")]
+use crate::image;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Table {
+    pub signature: [u8; 4],
+    addr: *const u8,
+    len: usize,
+}
+
+impl Table {
+    unsafe fn at(addr: *const u8) -> Self {
+        let signature = ptr::read_unaligned(addr as *const [u8; 4]);
+        let len = ptr::read_unaligned(addr.add(4) as *const u32) as usize;
+        Self { signature, addr, len }
+    }
+
+    pub fn bytes(&self) -> &'static [u8] {
+        unsafe { slice::from_raw_parts(self.addr, self.len) }
+    }
+
+    pub fn addr(&self) -> u64 {
+        self.addr as u64
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn name(&self) -> String {
+        String::from_utf8_lossy(&self.signature).into_owned()
+    }
+
+    pub fn header(&self) -> Option<Header> {
+        Header::parse(self.bytes())
+    }
+
+    pub fn checksum_valid(&self) -> bool {
+        self.bytes().iter().fold(0u8, |acc, x| acc.wrapping_add(*x)) == 0
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Header {
+    pub signature: [u8; 4],
+    pub length: u32,
+    pub revision: u8,
+    pub checksum: u8,
+    pub oem_id: [u8; 6],
+    pub oem_table_id: [u8; 8],
+    pub oem_revision: u32,
+    pub creator_id: [u8; 4],
+    pub creator_revision: u32,
+}
+
+impl Header {
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 36 {
+            return None;
+        }
+        Some(Self {
+            signature: bytes[0..4].try_into().unwrap(),
+            length: u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+            revision: bytes[8],
+            checksum: bytes[9],
+            oem_id: bytes[10..16].try_into().unwrap(),
+            oem_table_id: bytes[16..24].try_into().unwrap(),
+            oem_revision: u32::from_le_bytes(bytes[24..28].try_into().unwrap()),
+            creator_id: bytes[28..32].try_into().unwrap(),
+            creator_revision: u32::from_le_bytes(bytes[32..36].try_into().unwrap()),
+        })
+    }
+
+    pub fn oem(&self) -> String {
+        String::from_utf8_lossy(&self.oem_id).trim_end().into()
+    }
+
+    pub fn oem_table(&self) -> String {
+        String::from_utf8_lossy(&self.oem_table_id).trim_end().into()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Rsdp {
+    pub revision: u8,
+    pub oem_id: [u8; 6],
+    pub rsdt: u32,
+    pub xsdt: u64,
+}
+
+impl Rsdp {
+    pub fn get() -> Option<Self> {
+        let addr = rsdp()?;
+        unsafe {
+            if ptr::read_unaligned(addr as *const [u8; 8]) != *b"RSD PTR " {
+                return None;
+            }
+            let revision = *addr.add(15);
+            Some(Self {
+                revision,
+                oem_id: ptr::read_unaligned(addr.add(9) as *const [u8; 6]),
+                rsdt: ptr::read_unaligned(addr.add(16) as *const u32),
+                xsdt: if revision >= 2 { ptr::read_unaligned(addr.add(24) as *const u64) } else { 0 },
+            })
+        }
+    }
+}
+
+fn rsdp() -> Option<*const u8> {
+    let st = env::try_system_table()?;
+    let entries = st.config_table();
+    entries.iter().find(|x| x.guid == ACPI2_GUID)
+        .or_else(|| entries.iter().find(|x| x.guid == ACPI_GUID))
+        .map(|x| x.address as *const u8)
+}
+
+pub fn tables() -> Vec<Table> {
+    let rsdp = if let Some(x) = Rsdp::get() { x } else { return Vec::new(); };
+    unsafe {
+        let (root, entry_size) = if rsdp.xsdt != 0 {
+            (Table::at(rsdp.xsdt as usize as *const u8), 8)
+        } else {
+            (Table::at(rsdp.rsdt as usize as *const u8), 4)
+        };
+        if root.len < 36 {
+            return Vec::new();
+        }
+        root.bytes()[36..].chunks_exact(entry_size).map(|entry| {
+            let addr = if entry_size == 8 {
+                u64::from_le_bytes(entry.try_into().unwrap()) as usize
+            } else {
+                u32::from_le_bytes(entry.try_into().unwrap()) as usize
+            };
+            Table::at(addr as *const u8)
+        }).collect()
+    }
+}
+
+pub fn find(signature: &[u8; 4]) -> Option<Table> {
+    tables().into_iter().find(|x| &x.signature == signature)
+}
+
+pub fn find_all(signature: &[u8; 4]) -> Vec<Table> {
+    tables().into_iter().filter(|x| &x.signature == signature).collect()
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct GenericAddress {
+    pub space: u8,
+    pub bit_width: u8,
+    pub bit_offset: u8,
+    pub access_size: u8,
+    pub address: u64,
+}
+
+impl GenericAddress {
+    fn parse(bytes: &[u8]) -> Self {
+        Self {
+            space: bytes[0],
+            bit_width: bytes[1],
+            bit_offset: bytes[2],
+            access_size: bytes[3],
+            address: u64::from_le_bytes(bytes[4..12].try_into().unwrap()),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Fadt {
+    pub revision: u8,
+    pub facs: u64,
+    pub dsdt: u64,
+    pub pm_profile: u8,
+    pub sci_interrupt: u16,
+    pub century: u8,
+    pub boot_arch: u16,
+    pub flags: u32,
+    pub reset_reg: Option<GenericAddress>,
+    pub reset_value: u8,
+}
+
+impl Fadt {
+    pub const FLAG_RESET_REG_SUP: u32 = 1 << 10;
+    pub const FLAG_HW_REDUCED_ACPI: u32 = 1 << 20;
+    pub const FLAG_LOW_POWER_S0_IDLE: u32 = 1 << 21;
+
+    pub fn get() -> Option<Self> {
+        let table = find(b"FACP")?;
+        let bytes = table.bytes();
+        if bytes.len() < 116 {
+            return None;
+        }
+        let u32_at = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        let u64_at = |at: usize| bytes.get(at..at + 8).map_or(0, |x| u64::from_le_bytes(x.try_into().unwrap()));
+        let flags = u32_at(112);
+        let x_facs = u64_at(132);
+        let x_dsdt = u64_at(140);
+        let reset_reg = if bytes.len() >= 129 && flags & Self::FLAG_RESET_REG_SUP != 0 {
+            Some(GenericAddress::parse(&bytes[116..128]))
+        } else {
+            None
+        };
+        Some(Self {
+            revision: bytes[8],
+            facs: if x_facs != 0 { x_facs } else { u32_at(36) as u64 },
+            dsdt: if x_dsdt != 0 { x_dsdt } else { u32_at(40) as u64 },
+            pm_profile: bytes[45],
+            sci_interrupt: u16::from_le_bytes(bytes[46..48].try_into().unwrap()),
+            century: bytes[108],
+            boot_arch: u16::from_le_bytes(bytes[109..111].try_into().unwrap()),
+            flags,
+            reset_value: if reset_reg.is_some() { bytes[128] } else { 0 },
+            reset_reg,
+        })
+    }
+
+    pub fn dsdt_table(&self) -> Option<Table> {
+        if self.dsdt == 0 {
+            return None;
+        }
+        Some(unsafe { Table::at(self.dsdt as usize as *const u8) })
+    }
+
+    pub fn is_hardware_reduced(&self) -> bool {
+        self.flags & Self::FLAG_HW_REDUCED_ACPI != 0
+    }
+
+    pub fn pm_profile_name(&self) -> &'static str {
+        match self.pm_profile {
+            1 => "Desktop",
+            2 => "Mobile",
+            3 => "Workstation",
+            4 => "Enterprise Server",
+            5 => "SOHO Server",
+            6 => "Appliance PC",
+            7 => "Performance Server",
+            8 => "Tablet",
+            _ => "Unspecified",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct McfgEntry {
+    pub base: u64,
+    pub segment: u16,
+    pub start_bus: u8,
+    pub end_bus: u8,
+}
+
+impl McfgEntry {
+    pub fn config_address(&self, bus: u8, device: u8, function: u8) -> Option<u64> {
+        if !(self.start_bus..=self.end_bus).contains(&bus) || device >= 32 || function >= 8 {
+            return None;
+        }
+        let offset = (((bus - self.start_bus) as u64) << 20) | ((device as u64) << 15) | ((function as u64) << 12);
+        Some(self.base + offset)
+    }
+}
+
+pub fn mcfg() -> Vec<McfgEntry> {
+    let table = if let Some(x) = find(b"MCFG") { x } else { return Vec::new(); };
+    let bytes = table.bytes();
+    if bytes.len() < 44 {
+        return Vec::new();
+    }
+    bytes[44..].chunks_exact(16).map(|entry| McfgEntry {
+        base: u64::from_le_bytes(entry[0..8].try_into().unwrap()),
+        segment: u16::from_le_bytes(entry[8..10].try_into().unwrap()),
+        start_bus: entry[10],
+        end_bus: entry[11],
+    }).collect()
+}
+
+#[cfg(feature = "gfx")]
+#[derive(Debug, Copy, Clone)]
+pub struct Bgrt {
+    pub version: u16,
+    pub status: u8,
+    pub image_type: u8,
+    pub image_address: u64,
+    pub offset: Pos,
+}
+
+#[cfg(feature = "gfx")]
+impl Bgrt {
+    pub fn get() -> Option<Self> {
+        let table = find(b"BGRT")?;
+        let bytes = table.bytes();
+        if bytes.len() < 56 {
+            return None;
+        }
+        Some(Self {
+            version: u16::from_le_bytes(bytes[36..38].try_into().unwrap()),
+            status: bytes[38],
+            image_type: bytes[39],
+            image_address: u64::from_le_bytes(bytes[40..48].try_into().unwrap()),
+            offset: pos(
+                u32::from_le_bytes(bytes[48..52].try_into().unwrap()) as i32,
+                u32::from_le_bytes(bytes[52..56].try_into().unwrap()) as i32,
+            ),
+        })
+    }
+
+    pub fn displayed(&self) -> bool {
+        self.status & 1 != 0
+    }
+
+    pub fn image_bytes(&self) -> Option<&'static [u8]> {
+        if self.image_type != 0 || self.image_address == 0 {
+            return None;
+        }
+        let addr = self.image_address as usize as *const u8;
+        let header = unsafe { slice::from_raw_parts(addr, 6) };
+        let size = image::bmp_size(header)?;
+        Some(unsafe { slice::from_raw_parts(addr, size) })
+    }
+
+    pub fn image(&self) -> Option<Buffer> {
+        image::decode_bmp(self.image_bytes()?).ok()
+    }
+}
+
+#[cfg(feature = "gfx")]
+pub struct BootLogo {
+    pub buffer: Buffer,
+    pub pos: Pos,
+}
+
+#[cfg(feature = "gfx")]
+impl BootLogo {
+    pub fn get() -> Option<Self> {
+        let bgrt = Bgrt::get()?;
+        Some(Self { buffer: bgrt.image()?, pos: bgrt.offset })
+    }
+
+    pub fn draw(&self, buffer: &mut Buffer) {
+        buffer.blit(&self.buffer, self.buffer.area(), self.pos);
+    }
+}
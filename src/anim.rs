@@ -0,0 +1,256 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::f32::consts::TAU;
+
+use crate::gfx::{Color, Dim, DimF, Math, Pos, PosF, Rect};
+use crate::widget::{NodeId, Scene, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+fn elastic_out(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t.clamp(0.0, 1.0);
+    }
+    Math::exp2(-10.0 * t) * Math::sin((t * 10.0 - 0.75) * TAU / 3.0) + 1.0
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => Math::exp_2_slope_s(t),
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => {
+                let u = 1.0 - t;
+                1.0 - u * u * u
+            }
+            Easing::CubicInOut => if t < 0.5 {
+                4.0 * t * t * t
+            } else {
+                let u = 2.0 - 2.0 * t;
+                1.0 - u * u * u / 2.0
+            },
+            Easing::ElasticIn => 1.0 - elastic_out(1.0 - t),
+            Easing::ElasticOut => elastic_out(t),
+            Easing::ElasticInOut => if t < 0.5 {
+                (1.0 - elastic_out(1.0 - 2.0 * t)) / 2.0
+            } else {
+                (1.0 + elastic_out(2.0 * t - 1.0)) / 2.0
+            },
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => if t < 0.5 {
+                (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0
+            } else {
+                (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0
+            },
+        }
+    }
+}
+
+pub trait Lerp: Copy {
+    fn lerp(self, to: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for i32 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        Math::round((self as f32).lerp(to as f32, t)) as i32
+    }
+}
+
+impl Lerp for u8 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        (self as f32 + (to as f32 - self as f32) * t).clamp(0.0, 255.0) as u8
+    }
+}
+
+impl Lerp for PosF {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        PosF { x: self.x.lerp(to.x, t), y: self.y.lerp(to.y, t) }
+    }
+}
+
+impl Lerp for DimF {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        DimF { w: self.w.lerp(to.w, t), h: self.h.lerp(to.h, t) }
+    }
+}
+
+impl Lerp for Pos {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        PosF::from(self).lerp(to.into(), t).round()
+    }
+}
+
+impl Lerp for Dim {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        DimF::from(self).lerp(to.into(), t).round()
+    }
+}
+
+impl Lerp for Rect {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        let pos = PosF::from(self.pos).lerp(to.pos.into(), t);
+        let dim = DimF::from(self.dim).lerp(to.dim.into(), t);
+        Rect::snap(pos, dim)
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        Color {
+            b: self.b.lerp(to.b, t),
+            g: self.g.lerp(to.g, t),
+            r: self.r.lerp(to.r, t),
+            a: self.a.lerp(to.a, t),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tween<T: Lerp> {
+    pub from: T,
+    pub to: T,
+    pub duration: f32,
+    pub delay: f32,
+    pub easing: Easing,
+    elapsed: f32,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(from: T, to: T, duration: f32, easing: Easing) -> Self {
+        Self { from, to, duration, delay: 0.0, easing, elapsed: 0.0 }
+    }
+
+    pub fn delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return if self.elapsed >= self.delay { 1.0 } else { 0.0 };
+        }
+        ((self.elapsed - self.delay) / self.duration).clamp(0.0, 1.0)
+    }
+
+    pub fn value(&self) -> T {
+        self.from.lerp(self.to, self.easing.apply(self.progress()))
+    }
+
+    pub fn tick(&mut self, dt: f32) -> T {
+        self.elapsed = (self.elapsed + dt).min(self.delay + self.duration.max(0.0));
+        self.value()
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= self.delay + self.duration
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+    }
+
+    pub fn retarget(&mut self, to: T) {
+        self.from = self.value();
+        self.to = to;
+        self.elapsed = self.delay;
+    }
+}
+
+pub type AnimationId = u32;
+
+pub type StepFn<'a> = Box<dyn FnMut(&mut Scene<'a>, f32) -> bool + 'a>;
+
+struct Animation<'a> {
+    id: AnimationId,
+    step: StepFn<'a>,
+}
+
+#[derive(Default)]
+pub struct Animator<'a> {
+    animations: Vec<Animation<'a>>,
+    next_id: AnimationId,
+}
+
+impl<'a> Animator<'a> {
+    pub fn new() -> Self {
+        Self { animations: Vec::new(), next_id: 0 }
+    }
+
+    pub fn add(&mut self, step: StepFn<'a>) -> AnimationId {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.animations.push(Animation { id, step });
+        id
+    }
+
+    pub fn tween<T: Lerp + 'a>(
+        &mut self, node: NodeId, mut tween: Tween<T>,
+        mut apply: impl FnMut(&mut (dyn Widget + 'a), T) + 'a,
+    ) -> AnimationId {
+        self.add(Box::new(move |scene, dt| {
+            let value = tween.tick(dt);
+            scene.update(node, |x| apply(x, value));
+            !tween.finished()
+        }))
+    }
+
+    pub fn cancel(&mut self, id: AnimationId) {
+        self.animations.retain(|x| x.id != id);
+    }
+
+    pub fn is_running(&self, id: AnimationId) -> bool {
+        self.animations.iter().any(|x| x.id == id)
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.animations.is_empty()
+    }
+
+    pub fn tick(&mut self, scene: &mut Scene<'a>, dt: f32) -> bool {
+        self.animations.retain_mut(|x| (x.step)(scene, dt));
+        !self.animations.is_empty()
+    }
+}
@@ -0,0 +1,108 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+use crate::selfinfo;
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Args {
+    pub program: Option<String>,
+    pub positional: Vec<String>,
+    pub options: Vec<(String, Option<String>)>,
+}
+
+pub fn split(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut started = false;
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match (quote, ch) {
+            (Some(q), ch) if ch == q => quote = None,
+            (Some('"'), '\\') if matches!(chars.peek(), Some('"' | '\\')) => {
+                current.extend(chars.next());
+            }
+            (Some(_), ch) => current.push(ch),
+            (None, '"' | '\'') => {
+                quote = Some(ch);
+                started = true;
+            }
+            (None, ch) if ch.is_whitespace() || ch == '\0' => {
+                if started || !current.is_empty() {
+                    args.push(core::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            (None, ch) => current.push(ch),
+        }
+    }
+    if started || !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+fn is_program(arg: &str) -> bool {
+    arg.len() > 4 && arg.get(arg.len() - 4..).is_some_and(|x| x.eq_ignore_ascii_case(".efi"))
+}
+
+impl Args {
+    pub fn parse(line: &str) -> Self {
+        Self::from_vec(split(line))
+    }
+
+    pub fn from_vec(args: Vec<String>) -> Self {
+        let mut parsed = Self::default();
+        let mut iter = args.into_iter().peekable();
+        if iter.peek().is_some_and(|x| is_program(x)) {
+            parsed.program = iter.next();
+        }
+        let mut options_done = false;
+        for arg in iter {
+            if options_done || arg == "-" || !arg.starts_with('-') {
+                parsed.positional.push(arg);
+            } else if arg == "--" {
+                options_done = true;
+            } else if let Some(long) = arg.strip_prefix("--") {
+                match long.split_once('=') {
+                    Some((key, value)) => parsed.options.push((String::from(key), Some(String::from(value)))),
+                    None => parsed.options.push((String::from(long), None)),
+                }
+            } else if let Some((key, value)) = arg[1..].split_once('=') {
+                parsed.options.push((String::from(key), Some(String::from(value))));
+            } else {
+                parsed.options.extend(arg[1..].chars().map(|x| (String::from(x), None)));
+            }
+        }
+        parsed
+    }
+
+    pub fn from_load_options() -> Self {
+        Self::parse(&selfinfo::load_options())
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|x| x.0 == name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values(name).last()
+    }
+
+    pub fn values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.options.iter().filter(move |x| x.0 == name).filter_map(|x| x.1.as_deref())
+    }
+
+    pub fn get<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.value(name)?.parse().ok()
+    }
+
+    pub fn get_or<T: FromStr>(&self, name: &str, default: T) -> T {
+        self.get(name).unwrap_or(default)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positional.is_empty() && self.options.is_empty()
+    }
+}
@@ -0,0 +1,264 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use baked_font::{Font, Glyph};
+use ruzstd::io::Read;
+use uefi::Handle;
+
+use crate::fs::{self, EfiPath, EfiPathBuf};
+use crate::gfx::Buffer;
+use crate::image;
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xB5, 0x4A, 0x86];
+const PSF_COLUMNS: usize = 32;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum AssetError {
+    Io(uefi::Error),
+    Decode(String),
+    Manifest(usize),
+}
+
+impl From<uefi::Error> for AssetError {
+    fn from(value: uefi::Error) -> Self {
+        AssetError::Io(value)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AssetKind {
+    Font,
+    Image,
+    Data,
+}
+
+pub fn decompress(data: Vec<u8>) -> Option<Vec<u8>> {
+    if data.get(..4) != Some(&ZSTD_MAGIC[..]) {
+        return Some(data);
+    }
+    let mut decoder = ruzstd::StreamingDecoder::new(data.as_slice()).ok()?;
+    let mut out = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = decoder.read(&mut chunk).ok()?;
+        if read == 0 {
+            return Some(out);
+        }
+        out.extend_from_slice(&chunk[..read]);
+    }
+}
+
+fn psf_header(data: &[u8]) -> Option<(usize, usize, usize, usize, Option<bool>)> {
+    if data.get(..2) == Some(&PSF1_MAGIC[..]) {
+        let mode = *data.get(2)?;
+        let count = if mode & 0x01 != 0 { 512 } else { 256 };
+        let table = (mode & 0x06 != 0).then_some(false);
+        return Some((4, count, 8, *data.get(3)? as usize, table));
+    }
+    if data.get(..4) != Some(&PSF2_MAGIC[..]) {
+        return None;
+    }
+    let field = |i: usize| {
+        let x = data.get(i * 4..i * 4 + 4)?;
+        Some(u32::from_le_bytes([x[0], x[1], x[2], x[3]]) as usize)
+    };
+    let (header_len, flags, count, glyph_len, height, width) =
+        (field(2)?, field(3)?, field(4)?, field(5)?, field(6)?, field(7)?);
+    if glyph_len != height.checked_mul(width.div_ceil(8))? {
+        return None;
+    }
+    Some((header_len, count, width, height, (flags & 1 != 0).then_some(true)))
+}
+
+fn psf_chars(table: &[u8], utf8: bool, count: usize) -> Vec<Vec<char>> {
+    let mut out = Vec::with_capacity(count);
+    if utf8 {
+        for entry in table.split(|x| *x == 0xFF).take(count) {
+            let single = entry.split(|x| *x == 0xFE).next().unwrap_or(&[]);
+            out.push(core::str::from_utf8(single).map(|x| x.chars().collect()).unwrap_or_default());
+        }
+    } else {
+        let mut chars = Vec::new();
+        let mut sequence = false;
+        for x in table.chunks_exact(2).map(|x| u16::from_le_bytes([x[0], x[1]])) {
+            match x {
+                0xFFFF => {
+                    out.push(core::mem::take(&mut chars));
+                    sequence = false;
+                    if out.len() == count {
+                        break;
+                    }
+                }
+                0xFFFE => sequence = true,
+                x if !sequence => chars.extend(char::from_u32(x as u32)),
+                _ => {}
+            }
+        }
+    }
+    out
+}
+
+pub fn decode_psf(data: &[u8]) -> Option<Font> {
+    let (header_len, count, width, height, table) = psf_header(data)?;
+    if count == 0 || width == 0 || height == 0 {
+        return None;
+    }
+    let stride = width.div_ceil(8);
+    let glyph_len = stride * height;
+    let end = header_len.checked_add(count.checked_mul(glyph_len)?)?;
+    let glyphs = data.get(header_len..end)?;
+    let columns = count.min(PSF_COLUMNS);
+    let rows = count.div_ceil(columns);
+    let atlas_w = u16::try_from(columns.checked_mul(width)?).ok()? as usize;
+    let atlas_h = u16::try_from(rows.checked_mul(height)?).ok()? as usize;
+    let mut bitmap = alloc::vec![0u8; atlas_w * atlas_h];
+    for (i, glyph) in glyphs.chunks_exact(glyph_len).enumerate() {
+        let (gx, gy) = (i % columns * width, i / columns * height);
+        for (y, row) in glyph.chunks_exact(stride).enumerate() {
+            for x in 0..width {
+                if row[x / 8] & (0x80 >> (x % 8)) != 0 {
+                    bitmap[gx + x + (gy + y) * atlas_w] = 0xFF;
+                }
+            }
+        }
+    }
+    let chars = match table {
+        Some(utf8) => psf_chars(&data[end..], utf8, count),
+        None => (0..count as u32).map(|x| char::from_u32(x).into_iter().collect()).collect(),
+    };
+    let glyphs = chars.into_iter().enumerate().flat_map(|(i, chars)| {
+        let glyph = Glyph {
+            pos: ((i % columns * width) as u16, (i / columns * height) as u16),
+            size: (width as u16, height as u16),
+            offset: (0, 0),
+        };
+        chars.into_iter().map(move |ch| (ch, glyph))
+    }).collect();
+    Some(Font { bitmap, width: atlas_w as u32, glyphs })
+}
+
+pub fn is_psf(data: &[u8]) -> bool {
+    data.starts_with(&PSF1_MAGIC) || data.starts_with(&PSF2_MAGIC)
+}
+
+fn parse_font(data: &[u8]) -> Option<Font> {
+    if is_psf(data) {
+        return decode_psf(data);
+    }
+    postcard::from_bytes(data).ok()
+}
+
+pub fn decode_font(data: &[u8]) -> Option<Font> {
+    let data = decompress(data.to_vec())?;
+    parse_font(&data)
+}
+
+pub struct Assets {
+    pub volume: Handle,
+    pub base: EfiPathBuf,
+    fonts: BTreeMap<String, Font>,
+    images: BTreeMap<String, Buffer>,
+    data: BTreeMap<String, Vec<u8>>,
+}
+
+impl Assets {
+    pub fn new(volume: Handle, base: impl AsRef<EfiPath>) -> Self {
+        Self {
+            volume,
+            base: base.as_ref().to_path_buf(),
+            fonts: BTreeMap::new(),
+            images: BTreeMap::new(),
+            data: BTreeMap::new(),
+        }
+    }
+
+    pub fn boot(base: impl AsRef<EfiPath>) -> Option<Self> {
+        let volume = fs::volumes().into_iter().find(|x| x.boot)?;
+        Some(Self::new(volume.handle, base))
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        let data = fs::read(self.volume, self.base.join(path))?;
+        decompress(data).ok_or_else(|| AssetError::Decode(path.to_string()))
+    }
+
+    pub fn load(&mut self, kind: AssetKind, name: &str, path: &str) -> Result<(), AssetError> {
+        let data = self.read(path)?;
+        let error = || AssetError::Decode(path.to_string());
+        match kind {
+            AssetKind::Font => {
+                let font = parse_font(&data).ok_or_else(error)?;
+                self.fonts.insert(name.to_string(), font);
+            }
+            AssetKind::Image => {
+                let image = image::decode(&data).map_err(|_| error())?;
+                self.images.insert(name.to_string(), image);
+            }
+            AssetKind::Data => {
+                self.data.insert(name.to_string(), data);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load_font(&mut self, name: &str, path: &str) -> Result<&Font, AssetError> {
+        if !self.fonts.contains_key(name) {
+            self.load(AssetKind::Font, name, path)?;
+        }
+        Ok(&self.fonts[name])
+    }
+
+    pub fn load_image(&mut self, name: &str, path: &str) -> Result<&Buffer, AssetError> {
+        if !self.images.contains_key(name) {
+            self.load(AssetKind::Image, name, path)?;
+        }
+        Ok(&self.images[name])
+    }
+
+    pub fn load_manifest(&mut self, path: &str) -> Result<usize, AssetError> {
+        let data = self.read(path)?;
+        let text = core::str::from_utf8(&data).map_err(|_| AssetError::Decode(path.to_string()))?;
+        let mut count = 0;
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let (kind, name, file) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(kind), Some(name), Some(file)) => (kind, name, file),
+                _ => return Err(AssetError::Manifest(index + 1)),
+            };
+            let kind = match kind {
+                "font" => AssetKind::Font,
+                "image" => AssetKind::Image,
+                "data" => AssetKind::Data,
+                _ => return Err(AssetError::Manifest(index + 1)),
+            };
+            self.load(kind, name, file)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    pub fn font(&self, name: &str) -> Option<&Font> {
+        self.fonts.get(name)
+    }
+
+    pub fn image(&self, name: &str) -> Option<&Buffer> {
+        self.images.get(name)
+    }
+
+    pub fn data(&self, name: &str) -> Option<&[u8]> {
+        self.data.get(name).map(|x| x.as_slice())
+    }
+
+    pub fn unload(&mut self, name: &str) {
+        self.fonts.remove(name);
+        self.images.remove(name);
+        self.data.remove(name);
+    }
+}
@@ -0,0 +1,54 @@
+use core::ffi::c_void;
+use core::ptr;
+
+use uefi::proto::ProtocolPointer;
+use uefi::table::boot::{OpenProtocolAttributes, OpenProtocolParams};
+use uefi::{Handle, Status};
+
+use crate::env;
+
+#[repr(C)]
+pub(crate) struct ServiceBinding {
+    create_child: unsafe extern "efiapi" fn(*mut ServiceBinding, *mut *mut c_void) -> Status,
+    destroy_child: unsafe extern "efiapi" fn(*mut ServiceBinding, *mut c_void) -> Status,
+}
+
+pub(crate) fn open_raw<P: ProtocolPointer>(handle: Handle) -> uefi::Result<*mut P> {
+    let st = env::system_table()?;
+    let bs = st.boot_services();
+    let params = OpenProtocolParams { handle, agent: bs.image_handle(), controller: None };
+    let mut protocol = unsafe { bs.open_protocol::<P>(params, OpenProtocolAttributes::GetProtocol)? };
+    let pointer = &mut *protocol as *mut P;
+    core::mem::forget(protocol);
+    Ok(pointer)
+}
+
+pub(crate) struct Child<P> {
+    binding: *mut ServiceBinding,
+    handle: Handle,
+    pub(crate) protocol: *mut P,
+}
+
+impl<P: ProtocolPointer> Child<P> {
+    pub(crate) fn create<B: ProtocolPointer>() -> uefi::Result<Self> {
+        let st = env::system_table()?;
+        let handle = st.boot_services().get_handle_for_protocol::<B>()?;
+        let binding = open_raw::<B>(handle)? as *mut ServiceBinding;
+        let mut raw = ptr::null_mut();
+        unsafe { ((*binding).create_child)(binding, &mut raw) }.to_result()?;
+        let handle = unsafe { Handle::from_ptr(raw) }.ok_or(Status::PROTOCOL_ERROR)?;
+        match open_raw::<P>(handle) {
+            Ok(protocol) => Ok(Self { binding, handle, protocol }),
+            Err(err) => {
+                unsafe { ((*binding).destroy_child)(binding, handle.as_ptr()) };
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<P> Drop for Child<P> {
+    fn drop(&mut self) {
+        unsafe { ((*self.binding).destroy_child)(self.binding, self.handle.as_ptr()) };
+    }
+}
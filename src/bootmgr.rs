@@ -0,0 +1,217 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem::MaybeUninit;
+
+use uefi::proto::device_path::build::{self, DevicePathBuilder};
+use uefi::proto::device_path::DevicePath;
+use uefi::table::runtime::ResetType;
+use uefi::{Handle, Status};
+
+use crate::env;
+use crate::fs::{EfiPath, EfiPathBuf};
+use crate::vars::{self, GLOBAL};
+
+pub const LOAD_OPTION_ACTIVE: u32 = 0x0000_0001;
+pub const LOAD_OPTION_FORCE_RECONNECT: u32 = 0x0000_0002;
+pub const LOAD_OPTION_HIDDEN: u32 = 0x0000_0008;
+pub const LOAD_OPTION_CATEGORY_APP: u32 = 0x0000_0100;
+
+pub const OS_INDICATIONS_BOOT_TO_FW_UI: u64 = 0x0000_0001;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BootEntry {
+    pub number: u16,
+    pub attributes: u32,
+    pub description: String,
+    pub device_path: Vec<u8>,
+    pub optional_data: Vec<u8>,
+}
+
+pub fn option_name(number: u16) -> String {
+    format!("Boot{:04X}", number)
+}
+
+impl BootEntry {
+    pub fn new(description: &str, device_path: &DevicePath) -> Self {
+        Self {
+            number: 0,
+            attributes: LOAD_OPTION_ACTIVE,
+            description: String::from(description),
+            device_path: device_path.as_bytes().to_vec(),
+            optional_data: Vec::new(),
+        }
+    }
+
+    pub fn for_file(description: &str, volume: Handle, path: impl AsRef<EfiPath>) -> uefi::Result<Self> {
+        let st = env::system_table()?;
+        let volume_path = st.boot_services().open_protocol_exclusive::<DevicePath>(volume)?;
+        let name = path.as_ref().to_cstring16().ok_or(uefi::Error::from(Status::INVALID_PARAMETER))?;
+        let mut buf: Vec<MaybeUninit<u8>> = Vec::new();
+        let invalid = |_| uefi::Error::from(Status::INVALID_PARAMETER);
+        let mut builder = DevicePathBuilder::with_vec(&mut buf);
+        for node in volume_path.node_iter() {
+            builder = builder.push(&node).map_err(invalid)?;
+        }
+        let full = builder
+            .push(&build::media::FilePath { path_name: &name }).map_err(invalid)?
+            .finalize().map_err(invalid)?;
+        Ok(Self::new(description, full))
+    }
+
+    pub fn parse(number: u16, data: &[u8]) -> Option<Self> {
+        let attributes = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?);
+        let path_len = u16::from_le_bytes(data.get(4..6)?.try_into().ok()?) as usize;
+        let mut units = Vec::new();
+        let mut at = 6;
+        loop {
+            let unit = u16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?);
+            at += 2;
+            if unit == 0 {
+                break;
+            }
+            units.push(unit);
+        }
+        let description = char::decode_utf16(units.into_iter())
+            .map(|x| x.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+        let device_path = data.get(at..at + path_len)?.to_vec();
+        let optional_data = data.get(at + path_len..)?.to_vec();
+        Some(Self { number, attributes, description, device_path, optional_data })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&self.attributes.to_le_bytes());
+        data.extend_from_slice(&(self.device_path.len() as u16).to_le_bytes());
+        for unit in self.description.encode_utf16().chain(core::iter::once(0)) {
+            data.extend_from_slice(&unit.to_le_bytes());
+        }
+        data.extend_from_slice(&self.device_path);
+        data.extend_from_slice(&self.optional_data);
+        data
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.attributes & LOAD_OPTION_ACTIVE != 0
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.attributes & LOAD_OPTION_HIDDEN != 0
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        if active {
+            self.attributes |= LOAD_OPTION_ACTIVE;
+        } else {
+            self.attributes &= !LOAD_OPTION_ACTIVE;
+        }
+    }
+
+    pub fn device_path(&self) -> Option<&DevicePath> {
+        <&DevicePath>::try_from(self.device_path.as_slice()).ok()
+    }
+
+    pub fn file_path(&self) -> Option<EfiPathBuf> {
+        EfiPathBuf::from_device_path(self.device_path()?)
+    }
+}
+
+pub fn boot_order() -> Vec<u16> {
+    vars::get_u16_list("BootOrder", GLOBAL).unwrap_or_default()
+}
+
+pub fn set_boot_order(order: &[u16]) -> uefi::Result {
+    vars::set_u16_list("BootOrder", GLOBAL, order)
+}
+
+pub fn get(number: u16) -> Option<BootEntry> {
+    let data = vars::get(&option_name(number), GLOBAL).ok()?;
+    BootEntry::parse(number, &data)
+}
+
+pub fn entries() -> Vec<BootEntry> {
+    let order = boot_order();
+    let mut entries: Vec<BootEntry> = order.iter().filter_map(|x| get(*x)).collect();
+    let mut rest: Vec<u16> = vars::iter()
+        .filter(|x| x.vendor == GLOBAL && x.name.len() == 8 && x.name.starts_with("Boot"))
+        .filter_map(|x| u16::from_str_radix(&x.name[4..], 16).ok())
+        .filter(|x| !order.contains(x))
+        .collect();
+    rest.sort_unstable();
+    entries.extend(rest.into_iter().filter_map(get));
+    entries
+}
+
+fn free_number() -> Option<u16> {
+    (0..=0xFFFFu16).find(|x| !vars::exists(&option_name(*x), GLOBAL))
+}
+
+pub fn write(entry: &BootEntry) -> uefi::Result {
+    vars::set(&option_name(entry.number), GLOBAL, vars::DEFAULT_ATTRIBUTES, &entry.to_bytes())
+}
+
+pub fn create(entry: &mut BootEntry, first: bool) -> uefi::Result<u16> {
+    entry.number = free_number().ok_or(uefi::Error::from(Status::OUT_OF_RESOURCES))?;
+    write(entry)?;
+    let mut order = boot_order();
+    if first {
+        order.insert(0, entry.number);
+    } else {
+        order.push(entry.number);
+    }
+    set_boot_order(&order)?;
+    Ok(entry.number)
+}
+
+pub fn update(entry: &BootEntry) -> uefi::Result {
+    if !vars::exists(&option_name(entry.number), GLOBAL) {
+        return Err(Status::NOT_FOUND.into());
+    }
+    write(entry)
+}
+
+pub fn delete(number: u16) -> uefi::Result {
+    vars::delete(&option_name(number), GLOBAL)?;
+    let order: Vec<u16> = boot_order().into_iter().filter(|x| *x != number).collect();
+    set_boot_order(&order)
+}
+
+pub fn boot_next_number() -> Option<u16> {
+    vars::get_u16("BootNext", GLOBAL)
+}
+
+pub fn set_boot_next(number: u16) -> uefi::Result {
+    vars::set_u16("BootNext", GLOBAL, number)
+}
+
+pub fn clear_boot_next() -> uefi::Result {
+    vars::delete("BootNext", GLOBAL)
+}
+
+pub fn reset(kind: ResetType) -> uefi::Result {
+    let st = env::system_table()?;
+    st.runtime_services().reset(kind, Status::SUCCESS, None)
+}
+
+pub fn boot_next(entry: &BootEntry) -> uefi::Result {
+    if !vars::exists(&option_name(entry.number), GLOBAL) {
+        return Err(Status::NOT_FOUND.into());
+    }
+    set_boot_next(entry.number)?;
+    reset(ResetType::WARM)
+}
+
+pub fn firmware_ui_supported() -> bool {
+    vars::get_u64("OsIndicationsSupported", GLOBAL)
+        .is_some_and(|x| x & OS_INDICATIONS_BOOT_TO_FW_UI != 0)
+}
+
+pub fn reboot_to_firmware_ui() -> uefi::Result {
+    if !firmware_ui_supported() {
+        return Err(Status::UNSUPPORTED.into());
+    }
+    let indications = vars::get_u64("OsIndications", GLOBAL).unwrap_or(0);
+    vars::set_u64("OsIndications", GLOBAL, indications | OS_INDICATIONS_BOOT_TO_FW_UI)?;
+    reset(ResetType::COLD)
+}
@@ -0,0 +1,239 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use uefi::Handle;
+
+use crate::fs::{self, EfiPath};
+use crate::gfx::{rgba, Color};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ConfigError {
+    Io(uefi::Error),
+    Encoding,
+    Parse(usize, &'static str),
+}
+
+impl From<uefi::Error> for ConfigError {
+    fn from(value: uefi::Error) -> Self {
+        ConfigError::Io(value)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Value {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+    Color(Color),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        if let Value::Str(x) = self { Some(x) } else { None }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        if let Value::Int(x) = self { Some(*x) } else { None }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        if let Value::Bool(x) = self { Some(*x) } else { None }
+    }
+
+    pub fn as_color(&self) -> Option<Color> {
+        if let Value::Color(x) = self { Some(*x) } else { None }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub entries: Vec<(String, Value)>,
+}
+
+impl Section {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.iter().rev().find(|x| x.0 == key).map(|x| &x.1)
+    }
+
+    pub fn str(&self, key: &str) -> Option<&str> {
+        self.get(key)?.as_str()
+    }
+
+    pub fn int(&self, key: &str) -> Option<i64> {
+        self.get(key)?.as_int()
+    }
+
+    pub fn bool(&self, key: &str) -> Option<bool> {
+        self.get(key)?.as_bool()
+    }
+
+    pub fn color(&self, key: &str) -> Option<Color> {
+        self.get(key)?.as_color()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Config {
+    sections: Vec<Section>,
+}
+
+fn hex_byte(s: &str) -> Option<u8> {
+    u8::from_str_radix(s, 16).ok()
+}
+
+fn parse_color(s: &str) -> Option<Color> {
+    let hex = s.strip_prefix('#')?;
+    if !hex.is_ascii() {
+        return None;
+    }
+    let a = match hex.len() {
+        6 => 255,
+        8 => hex_byte(&hex[6..8])?,
+        _ => return None,
+    };
+    Some(rgba(hex_byte(&hex[0..2])?, hex_byte(&hex[2..4])?, hex_byte(&hex[4..6])?, a))
+}
+
+fn parse_int(s: &str) -> Option<i64> {
+    let (negative, body) = match s.strip_prefix('-') {
+        Some(x) => (true, x),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let body: String = body.chars().filter(|x| *x != '_').collect();
+    let value = if let Some(hex) = body.strip_prefix("0x").or_else(|| body.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16).ok()?
+    } else {
+        if body.is_empty() || !body.bytes().all(|x| x.is_ascii_digit()) {
+            return None;
+        }
+        body.parse().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
+
+fn parse_quoted(s: &str, line: usize) -> Result<(String, &str), ConfigError> {
+    let quote = s.chars().next().unwrap();
+    let mut out = String::new();
+    let mut chars = s.char_indices().skip(1);
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '\\' if quote == '"' => {
+                let (_, esc) = chars.next().ok_or(ConfigError::Parse(line, "unterminated string"))?;
+                out.push(match esc {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    '0' => '\0',
+                    other => other,
+                });
+            }
+            ch if ch == quote => return Ok((out, &s[i + 1..])),
+            ch => out.push(ch),
+        }
+    }
+    Err(ConfigError::Parse(line, "unterminated string"))
+}
+
+fn strip_comment(s: &str) -> &str {
+    let index = s.as_bytes().windows(2)
+        .position(|x| x[0].is_ascii_whitespace() && (x[1] == b'#' || x[1] == b';'));
+    match index {
+        Some(i) => s[..i].trim_end(),
+        None => s,
+    }
+}
+
+fn parse_value(raw: &str, line: usize) -> Result<Value, ConfigError> {
+    if raw.starts_with('"') || raw.starts_with('\'') {
+        let (text, rest) = parse_quoted(raw, line)?;
+        let rest = strip_comment(rest).trim();
+        if !rest.is_empty() && !rest.starts_with('#') && !rest.starts_with(';') {
+            return Err(ConfigError::Parse(line, "trailing characters after string"));
+        }
+        return Ok(Value::Str(text));
+    }
+    let raw = strip_comment(raw).trim();
+    if let Some(color) = parse_color(raw) {
+        return Ok(Value::Color(color));
+    }
+    if let Some(int) = parse_int(raw) {
+        return Ok(Value::Int(int));
+    }
+    Ok(match raw {
+        "true" | "yes" | "on" => Value::Bool(true),
+        "false" | "no" | "off" => Value::Bool(false),
+        _ => Value::Str(raw.to_string()),
+    })
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let mut sections = Vec::from([Section { name: String::new(), entries: Vec::new() }]);
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix('[') {
+                let end = rest.find(']').ok_or(ConfigError::Parse(number, "unterminated section header"))?;
+                let name = rest[..end].trim().to_string();
+                if name.is_empty() {
+                    return Err(ConfigError::Parse(number, "empty section name"));
+                }
+                sections.push(Section { name, entries: Vec::new() });
+                continue;
+            }
+            let eq = line.find('=').ok_or(ConfigError::Parse(number, "expected key = value"))?;
+            let key = line[..eq].trim().trim_matches('"');
+            if key.is_empty() {
+                return Err(ConfigError::Parse(number, "empty key"));
+            }
+            let value = parse_value(line[eq + 1..].trim(), number)?;
+            sections.last_mut().unwrap().entries.push((key.to_string(), value));
+        }
+        Ok(Self { sections })
+    }
+
+    pub fn load(volume: Handle, path: impl AsRef<EfiPath>) -> Result<Self, ConfigError> {
+        let data = fs::read(volume, path)?;
+        let text = core::str::from_utf8(&data).map_err(|_| ConfigError::Encoding)?;
+        Self::parse(text.strip_prefix('\u{FEFF}').unwrap_or(text))
+    }
+
+    pub fn root(&self) -> &Section {
+        &self.sections[0]
+    }
+
+    pub fn sections(&self) -> impl DoubleEndedIterator<Item = &Section> {
+        self.sections.iter().skip(1)
+    }
+
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        if name.is_empty() {
+            return Some(self.root());
+        }
+        self.sections().rev().find(|x| x.name == name)
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<&Value> {
+        self.sections.iter().rev().filter(|x| x.name == section).find_map(|x| x.get(key))
+    }
+
+    pub fn str(&self, section: &str, key: &str) -> Option<&str> {
+        self.get(section, key)?.as_str()
+    }
+
+    pub fn int(&self, section: &str, key: &str) -> Option<i64> {
+        self.get(section, key)?.as_int()
+    }
+
+    pub fn bool(&self, section: &str, key: &str) -> Option<bool> {
+        self.get(section, key)?.as_bool()
+    }
+
+    pub fn color(&self, section: &str, key: &str) -> Option<Color> {
+        self.get(section, key)?.as_color()
+    }
+}
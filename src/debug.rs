@@ -0,0 +1,138 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, Buffer, Color, Pos, Rect, Screen};
+use crate::mem;
+
+const HISTORY: usize = 32;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    frame_us: [u32; HISTORY],
+    next: usize,
+    count: usize,
+    pub dirty_pixels: u64,
+    pub screen_pixels: u64,
+}
+
+impl FrameStats {
+    pub fn record(&mut self, frame_us: u32, dirty_pixels: u64, screen_pixels: u64) {
+        self.frame_us[self.next] = frame_us;
+        self.next = (self.next + 1) % HISTORY;
+        self.count = (self.count + 1).min(HISTORY);
+        self.dirty_pixels = dirty_pixels;
+        self.screen_pixels = screen_pixels;
+    }
+
+    pub fn avg_frame_us(&self) -> u32 {
+        if self.count == 0 {
+            return 0;
+        }
+        let sum: u64 = self.frame_us[..self.count].iter().map(|&x| x as u64).sum();
+        (sum / self.count as u64) as u32
+    }
+
+    pub fn fps(&self) -> f32 {
+        let avg = self.avg_frame_us();
+        if avg == 0 { 0.0 } else { 1_000_000.0 / avg as f32 }
+    }
+
+    pub fn dirty_percent(&self) -> f32 {
+        if self.screen_pixels == 0 {
+            return 0.0;
+        }
+        self.dirty_pixels as f32 * 100.0 / self.screen_pixels as f32
+    }
+}
+
+pub struct DebugOverlay<'a> {
+    pub enabled: bool,
+    pub corner: Corner,
+    pub line_height: i32,
+    pub fg: Color,
+    pub bg: Color,
+    pub heap_used: Option<usize>,
+    pub stats: FrameStats,
+    font: &'a Font,
+}
+
+impl<'a> DebugOverlay<'a> {
+    pub fn new(font: &'a Font, line_height: i32) -> Self {
+        Self {
+            enabled: false,
+            corner: Corner::TopRight,
+            line_height,
+            fg: Color::WHITE,
+            bg: Color::black_alpha(0xC0),
+            heap_used: None,
+            stats: FrameStats::default(),
+            font,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn frame(&mut self, frame_us: u32) {
+        let screen = Screen::get().dim;
+        let dirty = Screen::take_presented();
+        self.stats.record(frame_us, dirty, screen.w as u64 * screen.h as u64);
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        lines.push(format!("FPS {:.1}", self.stats.fps()));
+        lines.push(format!("frame {:.2} ms", self.stats.avg_frame_us() as f32 / 1000.0));
+        lines.push(format!("dirty {:.1}%", self.stats.dirty_percent()));
+        if let Some(heap) = mem::heap_stats() {
+            lines.push(format!("heap {} KiB (peak {} KiB)", heap.current / 1024, heap.peak / 1024));
+            lines.push(format!("allocs {}", heap.live()));
+        } else if let Some(heap) = self.heap_used {
+            lines.push(format!("heap {} KiB", heap / 1024));
+        }
+        lines
+    }
+
+    pub fn draw(&self, buffer: &mut Buffer) -> Option<Rect> {
+        if !self.enabled {
+            return None;
+        }
+        let lines = self.lines();
+        let pad = 4;
+        let width = lines.iter()
+            .map(|x| gfx::text_dim(self.font, x).w)
+            .max().unwrap_or(0) + pad * 2;
+        let height = lines.len() as i32 * self.line_height + pad * 2;
+        let loc = match self.corner {
+            Corner::TopLeft => pos(0, 0),
+            Corner::TopRight => pos(buffer.dim.w - width, 0),
+            Corner::BottomLeft => pos(0, buffer.dim.h - height),
+            Corner::BottomRight => pos(buffer.dim.w - width, buffer.dim.h - height),
+        };
+        let r = rect(loc, dim(width, height));
+        buffer.fill_over(r.area(), self.bg);
+        for (i, line) in lines.iter().enumerate() {
+            let loc = loc + pos(pad, pad + i as i32 * self.line_height);
+            buffer.draw_text(loc, self.font, line, self.fg);
+        }
+        Some(r)
+    }
+
+    pub fn present(&self) {
+        if let Some(r) = self.draw(Screen::get()) {
+            Screen::present(r);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use core::arch::asm;
+use core::fmt;
+
+use log::LevelFilter;
+
+use crate::logger;
+
+pub const QEMU_PORT: u16 = 0x402;
+pub const BOCHS_PORT: u16 = 0xE9;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Debugcon {
+    port: u16,
+}
+
+unsafe fn outb(port: u16, value: u8) {
+    asm!("out dx, al", in("dx") port, in("al") value, options(nomem, nostack, preserves_flags));
+}
+
+unsafe fn inb(port: u16) -> u8 {
+    let value: u8;
+    asm!("in al, dx", out("al") value, in("dx") port, options(nomem, nostack, preserves_flags));
+    value
+}
+
+impl Debugcon {
+    pub fn new(port: u16) -> Self {
+        Self { port }
+    }
+
+    pub fn qemu() -> Self {
+        Self::new(QEMU_PORT)
+    }
+
+    pub fn bochs() -> Self {
+        Self::new(BOCHS_PORT)
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn is_present(&self) -> bool {
+        self.port == BOCHS_PORT && unsafe { inb(self.port) } == 0xE9
+    }
+
+    pub fn write_bytes(&self, data: &[u8]) {
+        for byte in data {
+            unsafe { outb(self.port, *byte) };
+        }
+    }
+}
+
+impl fmt::Write for Debugcon {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl logger::Sink for Debugcon {
+    fn write_line(&mut self, line: &str) {
+        self.write_bytes(line.as_bytes());
+        self.write_bytes(b"\n");
+    }
+}
+
+static mut LOG_PORT: u16 = QEMU_PORT;
+
+fn raw_sink(line: &str) {
+    logger::Sink::write_line(&mut Debugcon::new(unsafe { LOG_PORT }), line);
+}
+
+pub fn mirror_log(port: u16) {
+    unsafe { LOG_PORT = port };
+    logger::set_raw_sink(Some(raw_sink));
+}
+
+pub fn init_logger(port: u16, level: LevelFilter) {
+    logger::init_early(level);
+    mirror_log(port);
+}
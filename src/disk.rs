@@ -0,0 +1,95 @@
+use alloc::vec::Vec;
+
+use uefi::proto::media::block::BlockIO;
+use uefi::table::boot::{OpenProtocolAttributes, OpenProtocolParams, SearchType};
+use uefi::Handle;
+
+use crate::env;
+
+pub mod fat;
+pub mod gpt;
+pub mod iso;
+pub mod mbr;
+pub mod reader;
+
+pub use fat::{FatEntry, FatError, FatFs, FatType};
+pub use gpt::{Gpt, GptError, Partition, PartitionKind};
+pub use iso::{BootImage, IsoEntry, IsoError, IsoFs, NameFormat};
+pub use mbr::{Mbr, MbrError, MbrKind, MbrPartition};
+pub use reader::BlockReader;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DiskInfo {
+    pub handle: Handle,
+    pub media_id: u32,
+    pub block_size: u32,
+    pub last_block: u64,
+    pub removable: bool,
+    pub present: bool,
+    pub read_only: bool,
+    pub partition: bool,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PartitionTable {
+    Gpt(Gpt),
+    Mbr(Mbr),
+}
+
+impl DiskInfo {
+    pub fn size(&self) -> u64 {
+        (self.last_block + 1) * self.block_size as u64
+    }
+}
+
+pub(crate) fn with_block_io<R>(handle: Handle, f: impl FnOnce(&BlockIO) -> uefi::Result<R>) -> uefi::Result<R> {
+    let st = env::system_table()?;
+    let bs = st.boot_services();
+    let params = OpenProtocolParams { handle, agent: bs.image_handle(), controller: None };
+    let block = unsafe { bs.open_protocol::<BlockIO>(params, OpenProtocolAttributes::GetProtocol)? };
+    f(&block)
+}
+
+pub fn info(handle: Handle) -> uefi::Result<DiskInfo> {
+    with_block_io(handle, |block| {
+        let media = block.media();
+        Ok(DiskInfo {
+            handle,
+            media_id: media.media_id(),
+            block_size: media.block_size(),
+            last_block: media.last_block(),
+            removable: media.is_removable_media(),
+            present: media.is_media_present(),
+            read_only: media.is_read_only(),
+            partition: media.is_logical_partition(),
+        })
+    })
+}
+
+pub fn devices() -> Vec<DiskInfo> {
+    let st = if let Some(x) = env::try_system_table() { x } else { return Vec::new(); };
+    let handles = st.boot_services()
+        .locate_handle_buffer(SearchType::from_proto::<BlockIO>())
+        .map(|x| x.to_vec())
+        .unwrap_or_default();
+    handles.into_iter().filter_map(|x| info(x).ok()).collect()
+}
+
+pub fn disks() -> Vec<DiskInfo> {
+    devices().into_iter().filter(|x| !x.partition && x.present).collect()
+}
+
+pub fn partition_table(handle: Handle) -> Option<PartitionTable> {
+    let mut reader = BlockReader::open(handle).ok()?;
+    let mbr = Mbr::read_from(&mut reader).ok();
+    let gpt = match &mbr {
+        Some(x) => x.is_gpt(),
+        None => true,
+    };
+    if gpt {
+        if let Ok(gpt) = Gpt::read_from(&mut reader) {
+            return Some(PartitionTable::Gpt(gpt));
+        }
+    }
+    mbr.filter(|x| x.kind != MbrKind::Protective).map(PartitionTable::Mbr)
+}
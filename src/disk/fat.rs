@@ -0,0 +1,343 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use uefi::Handle;
+
+use crate::disk::BlockReader;
+use crate::fs::EfiPath;
+
+pub const ATTR_READ_ONLY: u8 = 0x01;
+pub const ATTR_HIDDEN: u8 = 0x02;
+pub const ATTR_SYSTEM: u8 = 0x04;
+pub const ATTR_VOLUME_ID: u8 = 0x08;
+pub const ATTR_DIRECTORY: u8 = 0x10;
+pub const ATTR_ARCHIVE: u8 = 0x20;
+const ATTR_LONG_NAME: u8 = 0x0F;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FatError {
+    Io(uefi::Error),
+    NotFat,
+    Corrupt(&'static str),
+    NotFound,
+    NotADirectory,
+    IsADirectory,
+}
+
+impl From<uefi::Error> for FatError {
+    fn from(value: uefi::Error) -> Self {
+        FatError::Io(value)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FatType {
+    Fat12,
+    Fat16,
+    Fat32,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FatEntry {
+    pub name: String,
+    pub attribute: u8,
+    pub cluster: u32,
+    pub size: u32,
+}
+
+impl FatEntry {
+    pub fn is_dir(&self) -> bool {
+        self.attribute & ATTR_DIRECTORY != 0
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.attribute & ATTR_HIDDEN != 0
+    }
+}
+
+pub struct FatFs {
+    reader: BlockReader,
+    fat_type: FatType,
+    bytes_per_sector: u64,
+    cluster_size: u64,
+    fat_start: u64,
+    root_start: u64,
+    root_entries: u32,
+    root_cluster: u32,
+    data_start: u64,
+    clusters: u32,
+    label: String,
+}
+
+fn u16_at(data: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([data[at], data[at + 1]])
+}
+
+fn u32_at(data: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(data[at..at + 4].try_into().unwrap())
+}
+
+fn short_name(entry: &[u8]) -> String {
+    let lower_base = entry[12] & 0x08 != 0;
+    let lower_ext = entry[12] & 0x10 != 0;
+    let mut raw = [0u8; 11];
+    raw.copy_from_slice(&entry[..11]);
+    if raw[0] == 0x05 {
+        raw[0] = 0xE5;
+    }
+    let part = |bytes: &[u8], lower: bool| -> String {
+        let text: String = bytes.iter().map(|x| *x as char).collect();
+        let text = text.trim_end();
+        if lower { text.to_ascii_lowercase() } else { String::from(text) }
+    };
+    let base = part(&raw[..8], lower_base);
+    let ext = part(&raw[8..], lower_ext);
+    if ext.is_empty() { base } else { base + "." + &ext }
+}
+
+fn short_checksum(entry: &[u8]) -> u8 {
+    entry[..11].iter().fold(0u8, |sum, x| ((sum & 1) << 7).wrapping_add(sum >> 1).wrapping_add(*x))
+}
+
+fn parse_dir(data: &[u8]) -> Vec<FatEntry> {
+    let mut entries = Vec::new();
+    let mut long: Vec<[u16; 13]> = Vec::new();
+    let mut long_checksum = 0;
+    for entry in data.chunks_exact(32) {
+        if entry[0] == 0 {
+            break;
+        }
+        if entry[0] == 0xE5 {
+            long.clear();
+            continue;
+        }
+        if entry[11] & 0x3F == ATTR_LONG_NAME {
+            let seq = (entry[0] & 0x1F) as usize;
+            if entry[0] & 0x40 != 0 {
+                long = vec![[0xFFFF; 13]; seq];
+                long_checksum = entry[13];
+            }
+            if seq == 0 || seq > long.len() || entry[13] != long_checksum {
+                long.clear();
+                continue;
+            }
+            let part = &mut long[seq - 1];
+            let offsets = (1..11).step_by(2).chain((14..26).step_by(2)).chain((28..32).step_by(2));
+            for (slot, at) in part.iter_mut().zip(offsets) {
+                *slot = u16_at(entry, at);
+            }
+            continue;
+        }
+        if entry[11] & ATTR_VOLUME_ID != 0 || entry[0] == b'.' {
+            long.clear();
+            continue;
+        }
+        let name = if !long.is_empty() && long_checksum == short_checksum(entry) {
+            let units = long.iter().flatten().copied().take_while(|x| *x != 0 && *x != 0xFFFF);
+            char::decode_utf16(units).map(|x| x.unwrap_or(char::REPLACEMENT_CHARACTER)).collect()
+        } else {
+            short_name(entry)
+        };
+        long.clear();
+        entries.push(FatEntry {
+            name,
+            attribute: entry[11],
+            cluster: ((u16_at(entry, 20) as u32) << 16) | u16_at(entry, 26) as u32,
+            size: u32_at(entry, 28),
+        });
+    }
+    entries
+}
+
+impl FatFs {
+    pub fn open(mut reader: BlockReader) -> Result<Self, FatError> {
+        let boot = reader.read_vec(0, 512)?;
+        if boot[510..512] != [0x55, 0xAA] {
+            return Err(FatError::NotFat);
+        }
+        let bytes_per_sector = u16_at(&boot, 11) as u64;
+        let sectors_per_cluster = boot[13] as u64;
+        let reserved = u16_at(&boot, 14) as u64;
+        let fats = boot[16] as u64;
+        let root_entries = u16_at(&boot, 17) as u32;
+        let total = match u16_at(&boot, 19) {
+            0 => u32_at(&boot, 32) as u64,
+            x => x as u64,
+        };
+        let fat_size = match u16_at(&boot, 22) {
+            0 => u32_at(&boot, 36) as u64,
+            x => x as u64,
+        };
+        if !matches!(bytes_per_sector, 512 | 1024 | 2048 | 4096)
+            || !sectors_per_cluster.is_power_of_two()
+            || reserved == 0 || fats == 0 || fat_size == 0 {
+            return Err(FatError::NotFat);
+        }
+        let root_sectors = (root_entries as u64 * 32).div_ceil(bytes_per_sector);
+        let data_sector = reserved + fats * fat_size + root_sectors;
+        if total <= data_sector {
+            return Err(FatError::NotFat);
+        }
+        let clusters = ((total - data_sector) / sectors_per_cluster) as u32;
+        let fat_type = match clusters {
+            0..=4084 => FatType::Fat12,
+            4085..=65524 => FatType::Fat16,
+            _ => FatType::Fat32,
+        };
+        let (label_at, root_cluster) = match fat_type {
+            FatType::Fat32 => (71, u32_at(&boot, 44)),
+            _ => (43, 0),
+        };
+        let label: String = boot[label_at..label_at + 11].iter().map(|x| *x as char).collect();
+        Ok(Self {
+            reader,
+            fat_type,
+            bytes_per_sector,
+            cluster_size: bytes_per_sector * sectors_per_cluster,
+            fat_start: reserved * bytes_per_sector,
+            root_start: (reserved + fats * fat_size) * bytes_per_sector,
+            root_entries,
+            root_cluster,
+            data_start: data_sector * bytes_per_sector,
+            clusters,
+            label: String::from(label.trim_end()),
+        })
+    }
+
+    pub fn open_partition(handle: Handle, first_lba: u64, blocks: u64) -> Result<Self, FatError> {
+        Self::open(BlockReader::open(handle)?.window(first_lba, blocks))
+    }
+
+    pub fn fat_type(&self) -> FatType {
+        self.fat_type
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn cluster_size(&self) -> u64 {
+        self.cluster_size
+    }
+
+    pub fn sector_size(&self) -> u64 {
+        self.bytes_per_sector
+    }
+
+    pub fn into_reader(self) -> BlockReader {
+        self.reader
+    }
+
+    fn next_cluster(&mut self, cluster: u32) -> Result<Option<u32>, FatError> {
+        let (offset, width) = match self.fat_type {
+            FatType::Fat12 => (cluster as u64 + cluster as u64 / 2, 2),
+            FatType::Fat16 => (cluster as u64 * 2, 2),
+            FatType::Fat32 => (cluster as u64 * 4, 4),
+        };
+        let mut buf = [0u8; 4];
+        self.reader.read_at(self.fat_start + offset, &mut buf[..width])?;
+        let raw = u32::from_le_bytes(buf);
+        let (value, end) = match self.fat_type {
+            FatType::Fat12 => (if cluster & 1 != 0 { raw >> 4 } else { raw & 0xFFF }, 0xFF8),
+            FatType::Fat16 => (raw, 0xFFF8),
+            FatType::Fat32 => (raw & 0x0FFF_FFFF, 0x0FFF_FFF8),
+        };
+        if value >= end {
+            Ok(None)
+        } else if value < 2 || value >= self.clusters + 2 {
+            Err(FatError::Corrupt("cluster chain"))
+        } else {
+            Ok(Some(value))
+        }
+    }
+
+    fn chain(&mut self, first: u32) -> Result<Vec<u32>, FatError> {
+        let mut chain = Vec::new();
+        let mut current = if first >= 2 { Some(first) } else { None };
+        while let Some(cluster) = current {
+            if chain.len() > self.clusters as usize {
+                return Err(FatError::Corrupt("cluster loop"));
+            }
+            chain.push(cluster);
+            current = self.next_cluster(cluster)?;
+        }
+        Ok(chain)
+    }
+
+    fn read_chain(&mut self, first: u32, limit: Option<u64>) -> Result<Vec<u8>, FatError> {
+        let chain = self.chain(first)?;
+        let total = chain.len() as u64 * self.cluster_size;
+        let len = limit.map_or(total, |x| x.min(total)) as usize;
+        let mut out = vec![0u8; len];
+        let mut done = 0;
+        let mut index = 0;
+        while index < chain.len() && done < len {
+            let mut run = 1;
+            while index + run < chain.len() && chain[index + run] == chain[index] + run as u32 {
+                run += 1;
+            }
+            let offset = self.data_start + (chain[index] as u64 - 2) * self.cluster_size;
+            let size = (run as u64 * self.cluster_size).min((len - done) as u64) as usize;
+            self.reader.read_at(offset, &mut out[done..done + size])?;
+            done += size;
+            index += run;
+        }
+        Ok(out)
+    }
+
+    fn list(&mut self, cluster: u32) -> Result<Vec<FatEntry>, FatError> {
+        let data = if cluster == 0 && self.fat_type != FatType::Fat32 {
+            self.reader.read_vec(self.root_start, self.root_entries as usize * 32)?
+        } else if cluster == 0 {
+            let root = self.root_cluster;
+            self.read_chain(root, None)?
+        } else {
+            self.read_chain(cluster, None)?
+        };
+        Ok(parse_dir(&data))
+    }
+
+    pub fn metadata(&mut self, path: impl AsRef<EfiPath>) -> Result<FatEntry, FatError> {
+        let mut current = FatEntry {
+            name: String::new(),
+            attribute: ATTR_DIRECTORY,
+            cluster: 0,
+            size: 0,
+        };
+        for part in path.as_ref().normalized().components() {
+            if !current.is_dir() {
+                return Err(FatError::NotADirectory);
+            }
+            current = self.list(current.cluster)?
+                .into_iter()
+                .find(|x| x.name.eq_ignore_ascii_case(part))
+                .ok_or(FatError::NotFound)?;
+        }
+        Ok(current)
+    }
+
+    pub fn read_dir(&mut self, path: impl AsRef<EfiPath>) -> Result<Vec<FatEntry>, FatError> {
+        let entry = self.metadata(path)?;
+        if !entry.is_dir() {
+            return Err(FatError::NotADirectory);
+        }
+        self.list(entry.cluster)
+    }
+
+    pub fn read(&mut self, path: impl AsRef<EfiPath>) -> Result<Vec<u8>, FatError> {
+        let entry = self.metadata(path)?;
+        if entry.is_dir() {
+            return Err(FatError::IsADirectory);
+        }
+        let data = self.read_chain(entry.cluster, Some(entry.size as u64))?;
+        if data.len() < entry.size as usize {
+            return Err(FatError::Corrupt("file shorter than its size"));
+        }
+        Ok(data)
+    }
+
+    pub fn exists(&mut self, path: impl AsRef<EfiPath>) -> bool {
+        self.metadata(path).is_ok()
+    }
+}
@@ -0,0 +1,276 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use uefi::{guid, Guid, Handle};
+
+use crate::disk::BlockReader;
+use crate::hash::{crc32, crc32_without};
+
+const SIGNATURE: &[u8; 8] = b"EFI PART";
+const MAX_ENTRIES_LEN: u64 = 1 << 20;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GptError {
+    Io(uefi::Error),
+    NoHeader,
+    HeaderCrc,
+    EntriesCrc,
+    Invalid(&'static str),
+}
+
+impl From<uefi::Error> for GptError {
+    fn from(value: uefi::Error) -> Self {
+        GptError::Io(value)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PartitionKind {
+    Esp,
+    BiosBoot,
+    MicrosoftReserved,
+    MicrosoftBasicData,
+    WindowsRecovery,
+    LinuxFilesystem,
+    LinuxRootX64,
+    LinuxRootArm64,
+    LinuxHome,
+    LinuxSwap,
+    LinuxLvm,
+    LinuxRaid,
+    AppleHfs,
+    AppleApfs,
+    Unknown,
+}
+
+const KINDS: [(Guid, PartitionKind); 14] = [
+    (guid!("c12a7328-f81f-11d2-ba4b-00a0c93ec93b"), PartitionKind::Esp),
+    (guid!("21686148-6449-6e6f-744e-656564454649"), PartitionKind::BiosBoot),
+    (guid!("e3c9e316-0b5c-4db8-817d-f92df00215ae"), PartitionKind::MicrosoftReserved),
+    (guid!("ebd0a0a2-b9e5-4433-87c0-68b6b72699c7"), PartitionKind::MicrosoftBasicData),
+    (guid!("de94bba4-06d1-4d40-a16a-bfd50179d6ac"), PartitionKind::WindowsRecovery),
+    (guid!("0fc63daf-8483-4772-8e79-3d69d8477de4"), PartitionKind::LinuxFilesystem),
+    (guid!("4f68bce3-e8cd-4db1-96e7-fbcaf984b709"), PartitionKind::LinuxRootX64),
+    (guid!("b921b045-1df0-41c3-af44-4c6f280d3fae"), PartitionKind::LinuxRootArm64),
+    (guid!("933ac7e1-2eb4-4f13-b844-0e14e2aef915"), PartitionKind::LinuxHome),
+    (guid!("0657fd6d-a4ab-43c4-84e5-0933c84b4f4f"), PartitionKind::LinuxSwap),
+    (guid!("e6d6d379-f507-44c2-a23c-238f2a3df928"), PartitionKind::LinuxLvm),
+    (guid!("a19d880f-05fc-4d3b-a006-743f0f84911e"), PartitionKind::LinuxRaid),
+    (guid!("48465300-0000-11aa-aa11-00306543ecac"), PartitionKind::AppleHfs),
+    (guid!("7c3457ef-0000-11aa-aa11-00306543ecac"), PartitionKind::AppleApfs),
+];
+
+impl PartitionKind {
+    pub fn from_guid(guid: Guid) -> Self {
+        KINDS.iter().find(|x| x.0 == guid).map_or(PartitionKind::Unknown, |x| x.1)
+    }
+
+    pub fn guid(self) -> Option<Guid> {
+        KINDS.iter().find(|x| x.1 == self).map(|x| x.0)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PartitionKind::Esp => "EFI System",
+            PartitionKind::BiosBoot => "BIOS Boot",
+            PartitionKind::MicrosoftReserved => "Microsoft Reserved",
+            PartitionKind::MicrosoftBasicData => "Basic Data",
+            PartitionKind::WindowsRecovery => "Windows Recovery",
+            PartitionKind::LinuxFilesystem => "Linux Filesystem",
+            PartitionKind::LinuxRootX64 => "Linux Root (x86-64)",
+            PartitionKind::LinuxRootArm64 => "Linux Root (AArch64)",
+            PartitionKind::LinuxHome => "Linux Home",
+            PartitionKind::LinuxSwap => "Linux Swap",
+            PartitionKind::LinuxLvm => "Linux LVM",
+            PartitionKind::LinuxRaid => "Linux RAID",
+            PartitionKind::AppleHfs => "Apple HFS+",
+            PartitionKind::AppleApfs => "Apple APFS",
+            PartitionKind::Unknown => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Partition {
+    pub index: u32,
+    pub type_guid: Guid,
+    pub unique_guid: Guid,
+    pub first_lba: u64,
+    pub last_lba: u64,
+    pub attributes: u64,
+    pub name: String,
+}
+
+impl Partition {
+    pub fn kind(&self) -> PartitionKind {
+        PartitionKind::from_guid(self.type_guid)
+    }
+
+    pub fn is_esp(&self) -> bool {
+        self.kind() == PartitionKind::Esp
+    }
+
+    pub fn blocks(&self) -> u64 {
+        self.last_lba - self.first_lba + 1
+    }
+
+    pub fn size(&self, block_size: u32) -> u64 {
+        self.blocks() * block_size as u64
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct GptHeader {
+    pub revision: u32,
+    pub my_lba: u64,
+    pub alternate_lba: u64,
+    pub first_usable_lba: u64,
+    pub last_usable_lba: u64,
+    pub disk_guid: Guid,
+    pub entries_lba: u64,
+    pub entry_count: u32,
+    pub entry_size: u32,
+    pub entries_crc: u32,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Gpt {
+    pub header: GptHeader,
+    pub block_size: u32,
+    pub partitions: Vec<Partition>,
+    pub from_backup: bool,
+}
+
+fn u32_at(data: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(data[at..at + 4].try_into().unwrap())
+}
+
+fn u64_at(data: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(data[at..at + 8].try_into().unwrap())
+}
+
+fn guid_at(data: &[u8], at: usize) -> Guid {
+    Guid::from_bytes(data[at..at + 16].try_into().unwrap())
+}
+
+impl GptHeader {
+    pub fn parse(block: &[u8]) -> Result<Self, GptError> {
+        if block.len() < 92 || &block[..8] != SIGNATURE {
+            return Err(GptError::NoHeader);
+        }
+        let size = u32_at(block, 12) as usize;
+        if !(92..=block.len()).contains(&size) {
+            return Err(GptError::Invalid("header size"));
+        }
+        if crc32_without(&block[..size], 16..20) != u32_at(block, 16) {
+            return Err(GptError::HeaderCrc);
+        }
+        let header = Self {
+            revision: u32_at(block, 8),
+            my_lba: u64_at(block, 24),
+            alternate_lba: u64_at(block, 32),
+            first_usable_lba: u64_at(block, 40),
+            last_usable_lba: u64_at(block, 48),
+            disk_guid: guid_at(block, 56),
+            entries_lba: u64_at(block, 72),
+            entry_count: u32_at(block, 80),
+            entry_size: u32_at(block, 84),
+            entries_crc: u32_at(block, 88),
+        };
+        if header.entry_size < 128 || header.entry_size % 8 != 0 {
+            return Err(GptError::Invalid("entry size"));
+        }
+        if header.entry_count as u64 * header.entry_size as u64 > MAX_ENTRIES_LEN {
+            return Err(GptError::Invalid("entry array size"));
+        }
+        if header.first_usable_lba > header.last_usable_lba {
+            return Err(GptError::Invalid("usable range"));
+        }
+        Ok(header)
+    }
+
+    pub fn entries_len(&self) -> usize {
+        self.entry_count as usize * self.entry_size as usize
+    }
+}
+
+pub fn parse_entries(header: &GptHeader, data: &[u8]) -> Result<Vec<Partition>, GptError> {
+    let data = data.get(..header.entries_len()).ok_or(GptError::Invalid("entry array"))?;
+    if crc32(data) != header.entries_crc {
+        return Err(GptError::EntriesCrc);
+    }
+    let mut partitions = Vec::new();
+    for (index, entry) in data.chunks_exact(header.entry_size as usize).enumerate() {
+        let type_guid = guid_at(entry, 0);
+        if type_guid == Guid::ZERO {
+            continue;
+        }
+        let first_lba = u64_at(entry, 32);
+        let last_lba = u64_at(entry, 40);
+        if first_lba > last_lba {
+            return Err(GptError::Invalid("partition range"));
+        }
+        let units = entry[56..128].chunks_exact(2)
+            .map(|x| u16::from_le_bytes([x[0], x[1]]))
+            .take_while(|x| *x != 0);
+        let name = char::decode_utf16(units)
+            .map(|x| x.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+        partitions.push(Partition {
+            index: index as u32 + 1,
+            type_guid,
+            unique_guid: guid_at(entry, 16),
+            first_lba,
+            last_lba,
+            attributes: u64_at(entry, 48),
+            name,
+        });
+    }
+    Ok(partitions)
+}
+
+fn read_at(reader: &mut BlockReader, lba: u64) -> Result<(GptHeader, Vec<Partition>), GptError> {
+    let header = GptHeader::parse(reader.read_block(lba)?)?;
+    if header.my_lba != lba {
+        return Err(GptError::Invalid("header location"));
+    }
+    let offset = header.entries_lba.checked_mul(reader.block_size() as u64)
+        .filter(|x| x.checked_add(header.entries_len() as u64).is_some_and(|end| end <= reader.len()))
+        .ok_or(GptError::Invalid("entry array location"))?;
+    let data = reader.read_vec(offset, header.entries_len())?;
+    let partitions = parse_entries(&header, &data)?;
+    Ok((header, partitions))
+}
+
+impl Gpt {
+    pub fn read(handle: Handle) -> Result<Self, GptError> {
+        Self::read_from(&mut BlockReader::open(handle)?)
+    }
+
+    pub fn read_from(reader: &mut BlockReader) -> Result<Self, GptError> {
+        let block_size = reader.block_size();
+        match read_at(reader, 1) {
+            Ok((header, partitions)) => {
+                Ok(Self { header, block_size, partitions, from_backup: false })
+            }
+            Err(GptError::Io(err)) => Err(GptError::Io(err)),
+            Err(primary) => {
+                let last = reader.blocks() - 1;
+                let (header, partitions) = read_at(reader, last).map_err(|_| primary)?;
+                Ok(Self { header, block_size, partitions, from_backup: true })
+            }
+        }
+    }
+
+    pub fn partition(&self, index: u32) -> Option<&Partition> {
+        self.partitions.iter().find(|x| x.index == index)
+    }
+
+    pub fn esp(&self) -> Option<&Partition> {
+        self.partitions.iter().find(|x| x.is_esp())
+    }
+
+    pub fn of_kind(&self, kind: PartitionKind) -> impl Iterator<Item = &Partition> {
+        self.partitions.iter().filter(move |x| x.kind() == kind)
+    }
+}
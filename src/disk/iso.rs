@@ -0,0 +1,337 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use uefi::Handle;
+
+use crate::disk::BlockReader;
+use crate::fs::EfiPath;
+
+const DESCRIPTOR_START: u64 = 16;
+const DESCRIPTOR_LIMIT: u64 = 64;
+const VIRTUAL_SECTOR: u64 = 512;
+
+pub const FLAG_HIDDEN: u8 = 0x01;
+pub const FLAG_DIRECTORY: u8 = 0x02;
+
+pub const PLATFORM_X86: u8 = 0x00;
+pub const PLATFORM_EFI: u8 = 0xEF;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum IsoError {
+    Io(uefi::Error),
+    NotIso,
+    Corrupt(&'static str),
+    NotFound,
+    NotADirectory,
+    IsADirectory,
+}
+
+impl From<uefi::Error> for IsoError {
+    fn from(value: uefi::Error) -> Self {
+        IsoError::Io(value)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NameFormat {
+    Iso,
+    Joliet,
+    RockRidge,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct IsoEntry {
+    pub name: String,
+    pub flags: u8,
+    pub lba: u32,
+    pub size: u32,
+}
+
+impl IsoEntry {
+    pub fn is_dir(&self) -> bool {
+        self.flags & FLAG_DIRECTORY != 0
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.flags & FLAG_HIDDEN != 0
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BootImage {
+    pub platform: u8,
+    pub bootable: bool,
+    pub media: u8,
+    pub sector_count: u16,
+    pub lba: u32,
+}
+
+impl BootImage {
+    pub fn is_efi(&self) -> bool {
+        self.platform == PLATFORM_EFI
+    }
+}
+
+pub struct IsoFs {
+    reader: BlockReader,
+    block_size: u64,
+    volume_id: String,
+    root: IsoEntry,
+    format: NameFormat,
+    boot_catalog: Option<u32>,
+}
+
+fn u16_at(data: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([data[at], data[at + 1]])
+}
+
+fn u32_at(data: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(data[at..at + 4].try_into().unwrap())
+}
+
+fn decode_ucs2_be(data: &[u8]) -> String {
+    let units = data.chunks_exact(2).map(|x| u16::from_be_bytes([x[0], x[1]]));
+    char::decode_utf16(units).map(|x| x.unwrap_or(char::REPLACEMENT_CHARACTER)).collect()
+}
+
+fn iso_name(raw: &[u8]) -> String {
+    let text: String = raw.iter().map(|x| *x as char).collect();
+    let text = text.split(';').next().unwrap_or("");
+    String::from(text.strip_suffix('.').unwrap_or(text))
+}
+
+fn system_use(record: &[u8]) -> &[u8] {
+    let name_len = record[32] as usize;
+    let start = 33 + name_len + (1 - name_len % 2);
+    record.get(start..).unwrap_or(&[])
+}
+
+fn rock_ridge_name(record: &[u8]) -> Option<String> {
+    let mut area = system_use(record);
+    let mut name = Vec::new();
+    let mut found = false;
+    while area.len() >= 4 {
+        let len = area[2] as usize;
+        if len < 4 || len > area.len() {
+            break;
+        }
+        if &area[..2] == b"NM" && len >= 5 {
+            name.extend_from_slice(&area[5..len]);
+            found = true;
+            if area[4] & 0x01 == 0 {
+                break;
+            }
+        }
+        area = &area[len..];
+    }
+    if !found {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&name).into_owned())
+}
+
+fn parse_record(record: &[u8], format: NameFormat) -> IsoEntry {
+    let name_len = record[32] as usize;
+    let raw = &record[33..33 + name_len];
+    let name = match format {
+        NameFormat::RockRidge => rock_ridge_name(record).unwrap_or_else(|| iso_name(raw)),
+        NameFormat::Joliet => {
+            let name = decode_ucs2_be(raw);
+            String::from(name.split(';').next().unwrap_or(""))
+        }
+        NameFormat::Iso => iso_name(raw),
+    };
+    IsoEntry {
+        name,
+        flags: record[25],
+        lba: u32_at(record, 2),
+        size: u32_at(record, 10),
+    }
+}
+
+impl IsoFs {
+    pub fn open(mut reader: BlockReader) -> Result<Self, IsoError> {
+        let mut primary = None;
+        let mut joliet = None;
+        let mut boot_catalog = None;
+        for index in DESCRIPTOR_START..DESCRIPTOR_LIMIT {
+            let desc = reader.read_vec(index * 2048, 2048)?;
+            if &desc[1..6] != b"CD001" {
+                return Err(IsoError::NotIso);
+            }
+            match desc[0] {
+                0 if desc[7..30] == *b"EL TORITO SPECIFICATION" => {
+                    boot_catalog = Some(u32_at(&desc, 71));
+                }
+                1 if primary.is_none() => primary = Some(desc),
+                2 if matches!(&desc[88..91], b"%/@" | b"%/C" | b"%/E") => joliet = Some(desc),
+                255 => break,
+                _ => {}
+            }
+        }
+        let primary = primary.ok_or(IsoError::NotIso)?;
+        let block_size = u16_at(&primary, 128) as u64;
+        if !matches!(block_size, 512 | 1024 | 2048) {
+            return Err(IsoError::Corrupt("logical block size"));
+        }
+        let mut fs = Self {
+            reader,
+            block_size,
+            volume_id: iso_name(&primary[40..72]).trim_end().into(),
+            root: parse_record(&primary[156..190], NameFormat::Iso),
+            format: NameFormat::Iso,
+            boot_catalog,
+        };
+        let root = fs.read_extent(&fs.root.clone())?;
+        let dot_len = root.first().copied().unwrap_or(0) as usize;
+        let rock_ridge = (34..=root.len()).contains(&dot_len)
+            && system_use(&root[..dot_len]).starts_with(b"SP");
+        if rock_ridge {
+            fs.format = NameFormat::RockRidge;
+        } else if let Some(joliet) = joliet {
+            fs.format = NameFormat::Joliet;
+            fs.root = parse_record(&joliet[156..190], NameFormat::Joliet);
+            fs.volume_id = decode_ucs2_be(&joliet[40..72]).trim_end().into();
+        }
+        Ok(fs)
+    }
+
+    pub fn open_device(handle: Handle) -> Result<Self, IsoError> {
+        Self::open(BlockReader::open(handle)?)
+    }
+
+    pub fn volume_id(&self) -> &str {
+        &self.volume_id
+    }
+
+    pub fn name_format(&self) -> NameFormat {
+        self.format
+    }
+
+    pub fn into_reader(self) -> BlockReader {
+        self.reader
+    }
+
+    fn read_extent(&mut self, entry: &IsoEntry) -> Result<Vec<u8>, IsoError> {
+        Ok(self.reader.read_vec(entry.lba as u64 * self.block_size, entry.size as usize)?)
+    }
+
+    fn list(&mut self, dir: &IsoEntry) -> Result<Vec<IsoEntry>, IsoError> {
+        let data = self.read_extent(dir)?;
+        let mut entries = Vec::new();
+        let mut at = 0;
+        while at < data.len() {
+            let len = data[at] as usize;
+            if len == 0 {
+                at = (at / self.block_size as usize + 1) * self.block_size as usize;
+                continue;
+            }
+            if len < 34 || at + len > data.len() {
+                return Err(IsoError::Corrupt("directory record"));
+            }
+            let record = &data[at..at + len];
+            let name_len = record[32] as usize;
+            if 33 + name_len > len {
+                return Err(IsoError::Corrupt("directory record"));
+            }
+            if !(name_len == 1 && record[33] <= 1) {
+                entries.push(parse_record(record, self.format));
+            }
+            at += len;
+        }
+        Ok(entries)
+    }
+
+    pub fn metadata(&mut self, path: impl AsRef<EfiPath>) -> Result<IsoEntry, IsoError> {
+        let mut current = self.root.clone();
+        for part in path.as_ref().normalized().components() {
+            if !current.is_dir() {
+                return Err(IsoError::NotADirectory);
+            }
+            let entries = self.list(&current)?;
+            let exact = entries.iter().position(|x| x.name == part);
+            let index = exact.or_else(|| entries.iter().position(|x| x.name.eq_ignore_ascii_case(part)));
+            current = entries.into_iter().nth(index.ok_or(IsoError::NotFound)?).unwrap();
+        }
+        Ok(current)
+    }
+
+    pub fn read_dir(&mut self, path: impl AsRef<EfiPath>) -> Result<Vec<IsoEntry>, IsoError> {
+        let entry = self.metadata(path)?;
+        if !entry.is_dir() {
+            return Err(IsoError::NotADirectory);
+        }
+        self.list(&entry)
+    }
+
+    pub fn read(&mut self, path: impl AsRef<EfiPath>) -> Result<Vec<u8>, IsoError> {
+        let entry = self.metadata(path)?;
+        if entry.is_dir() {
+            return Err(IsoError::IsADirectory);
+        }
+        self.read_extent(&entry)
+    }
+
+    pub fn exists(&mut self, path: impl AsRef<EfiPath>) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    pub fn boot_images(&mut self) -> Result<Vec<BootImage>, IsoError> {
+        let lba = if let Some(x) = self.boot_catalog { x } else { return Ok(Vec::new()); };
+        let catalog = self.reader.read_vec(lba as u64 * self.block_size, 2048)?;
+        let validation = &catalog[..32];
+        let checksum = validation.chunks_exact(2)
+            .fold(0u16, |sum, x| sum.wrapping_add(u16::from_le_bytes([x[0], x[1]])));
+        if validation[0] != 0x01 || validation[30..32] != [0x55, 0xAA] || checksum != 0 {
+            return Err(IsoError::Corrupt("boot catalog"));
+        }
+        let entry = |platform: u8, data: &[u8]| BootImage {
+            platform,
+            bootable: data[0] == 0x88,
+            media: data[1] & 0x0F,
+            sector_count: u16_at(data, 6),
+            lba: u32_at(data, 8),
+        };
+        let mut images = Vec::from([entry(validation[1], &catalog[32..64])]);
+        let mut at = 64;
+        while at + 32 <= catalog.len() {
+            let header = &catalog[at..at + 32];
+            if header[0] != 0x90 && header[0] != 0x91 {
+                break;
+            }
+            let platform = header[1];
+            let count = u16_at(header, 2) as usize;
+            at += 32;
+            for _ in 0..count {
+                if at + 32 > catalog.len() {
+                    break;
+                }
+                if catalog[at] == 0x88 || catalog[at] == 0x00 {
+                    images.push(entry(platform, &catalog[at..at + 32]));
+                }
+                at += 32;
+            }
+            if header[0] == 0x91 {
+                break;
+            }
+        }
+        Ok(images)
+    }
+
+    pub fn read_boot_image(&mut self, image: &BootImage) -> Result<Vec<u8>, IsoError> {
+        let offset = image.lba as u64 * self.block_size;
+        let mut len = image.sector_count as u64 * VIRTUAL_SECTOR;
+        if image.sector_count <= 1 {
+            let boot = self.reader.read_vec(offset, 512)?;
+            let total = match u16_at(&boot, 19) {
+                0 => u32_at(&boot, 32) as u64,
+                x => x as u64,
+            };
+            if boot[510..512] == [0x55, 0xAA] && total > 0 {
+                len = total * u16_at(&boot, 11) as u64;
+            }
+        }
+        let len = len.min(self.reader.len().saturating_sub(offset));
+        Ok(self.reader.read_vec(offset, len as usize)?)
+    }
+}
@@ -0,0 +1,193 @@
+use alloc::vec::Vec;
+
+use uefi::Handle;
+
+use crate::disk::BlockReader;
+
+const MAX_LOGICAL: u32 = 128;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MbrError {
+    Io(uefi::Error),
+    NoSignature,
+    Invalid(&'static str),
+}
+
+impl From<uefi::Error> for MbrError {
+    fn from(value: uefi::Error) -> Self {
+        MbrError::Io(value)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MbrKind {
+    Classic,
+    Protective,
+    Hybrid,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MbrPartition {
+    pub index: u32,
+    pub bootable: bool,
+    pub type_id: u8,
+    pub first_lba: u64,
+    pub blocks: u64,
+    pub logical: bool,
+}
+
+impl MbrPartition {
+    pub fn last_lba(&self) -> u64 {
+        self.first_lba + self.blocks.max(1) - 1
+    }
+
+    pub fn size(&self, block_size: u32) -> u64 {
+        self.blocks * block_size as u64
+    }
+
+    pub fn is_extended(&self) -> bool {
+        matches!(self.type_id, 0x05 | 0x0F | 0x85)
+    }
+
+    pub fn is_protective(&self) -> bool {
+        self.type_id == 0xEE
+    }
+
+    pub fn is_esp(&self) -> bool {
+        self.type_id == 0xEF
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self.type_id {
+            0x01 => "FAT12",
+            0x04 | 0x06 | 0x0E => "FAT16",
+            0x05 | 0x0F | 0x85 => "Extended",
+            0x07 => "NTFS/exFAT",
+            0x0B | 0x0C => "FAT32",
+            0x27 => "Windows Recovery",
+            0x82 => "Linux Swap",
+            0x83 => "Linux",
+            0x8E => "Linux LVM",
+            0xA5 => "FreeBSD",
+            0xA8 => "Apple UFS",
+            0xAF => "Apple HFS+",
+            0xEE => "GPT Protective",
+            0xEF => "EFI System",
+            0xFD => "Linux RAID",
+            _ => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Mbr {
+    pub kind: MbrKind,
+    pub disk_signature: u32,
+    pub partitions: Vec<MbrPartition>,
+}
+
+fn entries(sector: &[u8]) -> Result<[Option<(bool, u8, u32, u32)>; 4], MbrError> {
+    if sector.len() < 512 || sector[510..512] != [0x55, 0xAA] {
+        return Err(MbrError::NoSignature);
+    }
+    let mut out = [None; 4];
+    for (i, slot) in out.iter_mut().enumerate() {
+        let entry = &sector[446 + i * 16..446 + (i + 1) * 16];
+        let status = entry[0];
+        if status != 0 && status != 0x80 {
+            return Err(MbrError::Invalid("partition status"));
+        }
+        let type_id = entry[4];
+        if type_id == 0 {
+            continue;
+        }
+        let first = u32::from_le_bytes(entry[8..12].try_into().unwrap());
+        let count = u32::from_le_bytes(entry[12..16].try_into().unwrap());
+        *slot = Some((status == 0x80, type_id, first, count));
+    }
+    Ok(out)
+}
+
+impl Mbr {
+    pub fn parse(sector: &[u8]) -> Result<Self, MbrError> {
+        let mut partitions = Vec::new();
+        for (i, entry) in entries(sector)?.iter().enumerate() {
+            if let Some((bootable, type_id, first, count)) = *entry {
+                partitions.push(MbrPartition {
+                    index: i as u32 + 1,
+                    bootable,
+                    type_id,
+                    first_lba: first as u64,
+                    blocks: count as u64,
+                    logical: false,
+                });
+            }
+        }
+        let protective = partitions.iter().filter(|x| x.is_protective()).count();
+        let kind = match (protective, partitions.len()) {
+            (0, _) => MbrKind::Classic,
+            (1, 1) => MbrKind::Protective,
+            _ => MbrKind::Hybrid,
+        };
+        Ok(Self {
+            kind,
+            disk_signature: u32::from_le_bytes(sector[440..444].try_into().unwrap()),
+            partitions,
+        })
+    }
+
+    pub fn read(handle: Handle) -> Result<Self, MbrError> {
+        Self::read_from(&mut BlockReader::open(handle)?)
+    }
+
+    pub fn read_from(reader: &mut BlockReader) -> Result<Self, MbrError> {
+        let mut mbr = Self::parse(reader.read_block(0)?)?;
+        let extended = mbr.partitions.iter().find(|x| x.is_extended()).copied();
+        if let Some(extended) = extended {
+            mbr.read_logical(reader, extended.first_lba)?;
+        }
+        Ok(mbr)
+    }
+
+    fn read_logical(&mut self, reader: &mut BlockReader, base: u64) -> Result<(), MbrError> {
+        let mut ebr = base;
+        let mut index = 5;
+        while index < 5 + MAX_LOGICAL {
+            let [first, next, ..] = entries(reader.read_block(ebr)?)?;
+            if let Some((bootable, type_id, start, count)) = first {
+                self.partitions.push(MbrPartition {
+                    index,
+                    bootable,
+                    type_id,
+                    first_lba: ebr + start as u64,
+                    blocks: count as u64,
+                    logical: true,
+                });
+                index += 1;
+            }
+            match next {
+                Some((_, _, start, _)) if start != 0 => {
+                    let link = base + start as u64;
+                    if link <= ebr {
+                        return Err(MbrError::Invalid("extended partition loop"));
+                    }
+                    ebr = link;
+                }
+                _ => return Ok(()),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_gpt(&self) -> bool {
+        self.kind != MbrKind::Classic
+    }
+
+    pub fn partition(&self, index: u32) -> Option<&MbrPartition> {
+        self.partitions.iter().find(|x| x.index == index)
+    }
+
+    pub fn usable(&self) -> impl Iterator<Item = &MbrPartition> {
+        self.partitions.iter().filter(|x| !x.is_extended() && !x.is_protective())
+    }
+}
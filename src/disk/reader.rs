@@ -0,0 +1,230 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use uefi::proto::media::block::BlockIO;
+use uefi::table::boot::{OpenProtocolAttributes, OpenProtocolParams};
+use uefi::{Handle, Status};
+
+use crate::env;
+
+const DEFAULT_CAPACITY: usize = 64;
+
+pub struct BlockReader {
+    handle: Handle,
+    block: *const BlockIO,
+    media_id: u32,
+    block_size: u32,
+    io_align: usize,
+    changed: bool,
+    base: u64,
+    blocks: u64,
+    cache: VecDeque<(u64, Vec<u8>)>,
+    capacity: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl BlockReader {
+    pub fn open(handle: Handle) -> uefi::Result<Self> {
+        let st = env::system_table()?;
+        let bs = st.boot_services();
+        let params = OpenProtocolParams { handle, agent: bs.image_handle(), controller: None };
+        let protocol = unsafe { bs.open_protocol::<BlockIO>(params, OpenProtocolAttributes::GetProtocol)? };
+        let block = &*protocol as *const BlockIO;
+        core::mem::forget(protocol);
+        let media = unsafe { (*block).media() };
+        if !media.is_media_present() {
+            return Err(Status::NO_MEDIA.into());
+        }
+        Ok(Self {
+            handle,
+            block,
+            media_id: media.media_id(),
+            block_size: media.block_size(),
+            io_align: (media.io_align() as usize).max(1),
+            changed: false,
+            base: 0,
+            blocks: media.last_block() + 1,
+            cache: VecDeque::new(),
+            capacity: DEFAULT_CAPACITY,
+            hits: 0,
+            misses: 0,
+        })
+    }
+
+    pub fn capacity(mut self, blocks: usize) -> Self {
+        self.capacity = blocks.max(1);
+        self
+    }
+
+    pub fn window(mut self, first_lba: u64, blocks: u64) -> Self {
+        let first_lba = first_lba.min(self.blocks);
+        self.base += first_lba;
+        self.blocks = blocks.min(self.blocks - first_lba);
+        self.cache.clear();
+        self
+    }
+
+    pub fn handle(&self) -> Handle {
+        self.handle
+    }
+
+    pub fn block_size(&self) -> u32 {
+        self.block_size
+    }
+
+    pub fn blocks(&self) -> u64 {
+        self.blocks
+    }
+
+    pub fn len(&self) -> u64 {
+        self.blocks * self.block_size as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks == 0
+    }
+
+    pub fn stats(&self) -> (u64, u64) {
+        (self.hits, self.misses)
+    }
+
+    pub fn invalidate(&mut self) {
+        self.cache.clear();
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.changed
+    }
+
+    /// Picks up the media currently in the device. Any window is dropped.
+    pub fn reset(&mut self) -> uefi::Result {
+        let media = unsafe { (*self.block).media() };
+        self.invalidate();
+        if !media.is_media_present() {
+            return Err(Status::NO_MEDIA.into());
+        }
+        self.media_id = media.media_id();
+        self.block_size = media.block_size();
+        self.io_align = (media.io_align() as usize).max(1);
+        self.base = 0;
+        self.blocks = media.last_block() + 1;
+        self.changed = false;
+        Ok(())
+    }
+
+    fn check_media(&mut self) -> uefi::Result {
+        if self.changed {
+            return Err(Status::MEDIA_CHANGED.into());
+        }
+        let media = unsafe { (*self.block).media() };
+        if !media.is_media_present() {
+            self.invalidate();
+            return Err(Status::NO_MEDIA.into());
+        }
+        if media.media_id() != self.media_id || media.block_size() != self.block_size {
+            self.invalidate();
+            self.changed = true;
+            return Err(Status::MEDIA_CHANGED.into());
+        }
+        Ok(())
+    }
+
+    fn raw_read(&mut self, lba: u64, buf: &mut [u8]) -> uefi::Result {
+        let count = (buf.len() / self.block_size as usize) as u64;
+        if !lba.checked_add(count).is_some_and(|x| x <= self.blocks) {
+            return Err(Status::INVALID_PARAMETER.into());
+        }
+        self.check_media()?;
+        let result = if buf.as_ptr() as usize % self.io_align == 0 {
+            unsafe { (*self.block).read_blocks(self.media_id, self.base + lba, buf) }
+        } else {
+            let mut bounce = vec![0u8; buf.len() + self.io_align - 1];
+            let start = bounce.as_ptr().align_offset(self.io_align);
+            let aligned = &mut bounce[start..start + buf.len()];
+            let result = unsafe { (*self.block).read_blocks(self.media_id, self.base + lba, aligned) };
+            if result.is_ok() {
+                buf.copy_from_slice(aligned);
+            }
+            result
+        };
+        if result.as_ref().is_err_and(|x| x.status() == Status::MEDIA_CHANGED) {
+            self.invalidate();
+            self.changed = true;
+        }
+        result
+    }
+
+    fn check_range(&self, offset: u64, len: usize) -> uefi::Result {
+        match offset.checked_add(len as u64) {
+            Some(end) if end <= self.len() => Ok(()),
+            _ => Err(Status::INVALID_PARAMETER.into()),
+        }
+    }
+
+    fn cached(&self, lba: u64) -> bool {
+        self.cache.iter().any(|x| x.0 == lba)
+    }
+
+    pub fn read_block(&mut self, lba: u64) -> uefi::Result<&[u8]> {
+        if let Some(index) = self.cache.iter().position(|x| x.0 == lba) {
+            self.hits += 1;
+            let entry = self.cache.remove(index).unwrap();
+            self.cache.push_front(entry);
+        } else {
+            self.misses += 1;
+            let mut data = vec![0u8; self.block_size as usize];
+            self.raw_read(lba, &mut data)?;
+            if self.cache.len() >= self.capacity {
+                self.cache.pop_back();
+            }
+            self.cache.push_front((lba, data));
+        }
+        Ok(&self.cache[0].1)
+    }
+
+    pub fn read_blocks(&mut self, lba: u64, count: usize) -> uefi::Result<Vec<u8>> {
+        let block_size = self.block_size as usize;
+        let offset = lba.checked_mul(block_size as u64);
+        let len = count.checked_mul(block_size);
+        let (offset, len) = if let (Some(x), Some(y)) = (offset, len) { (x, y) } else {
+            return Err(Status::INVALID_PARAMETER.into());
+        };
+        self.read_vec(offset, len)
+    }
+
+    pub fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> uefi::Result {
+        self.check_range(offset, buf.len())?;
+        let block_size = self.block_size as usize;
+        let mut done = 0;
+        while done < buf.len() {
+            let pos = offset + done as u64;
+            let lba = pos / block_size as u64;
+            let within = (pos % block_size as u64) as usize;
+            let remaining = buf.len() - done;
+            if within == 0 && remaining >= block_size && !self.cached(lba) {
+                let mut run = 1;
+                while run < remaining / block_size && !self.cached(lba + run as u64) {
+                    run += 1;
+                }
+                self.misses += run as u64;
+                self.raw_read(lba, &mut buf[done..done + run * block_size])?;
+                done += run * block_size;
+                continue;
+            }
+            let len = (block_size - within).min(remaining);
+            let data = self.read_block(lba)?;
+            buf[done..done + len].copy_from_slice(&data[within..within + len]);
+            done += len;
+        }
+        Ok(())
+    }
+
+    pub fn read_vec(&mut self, offset: u64, len: usize) -> uefi::Result<Vec<u8>> {
+        self.check_range(offset, len)?;
+        let mut buf = vec![0u8; len];
+        self.read_at(offset, &mut buf)?;
+        Ok(buf)
+    }
+}
@@ -0,0 +1,168 @@
+use core::ptr;
+
+use log::LevelFilter;
+use uefi::prelude::*;
+use uefi::table::boot::{BootServices, MemoryMap, MemoryType};
+use uefi::table::Runtime;
+
+#[cfg(feature = "gfx")]
+use crate::gfx::{self, Framebuffer};
+#[cfg(feature = "input")]
+use crate::input::keyboard;
+use crate::logger;
+use crate::timer;
+#[cfg(feature = "widgets")]
+use crate::widget::terminal;
+use crate::Result;
+
+const WATCHDOG_CODE: u64 = 0x10000;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Watchdog {
+    Disabled,
+    Timeout(usize),
+}
+
+#[cfg(feature = "gfx")]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ScreenInit {
+    None,
+    Current,
+    Resolution(usize, usize),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct EnvConfig {
+    pub watchdog: Watchdog,
+    pub reset_console: bool,
+    #[cfg(feature = "gfx")]
+    pub screen: ScreenInit,
+    pub log_level: LevelFilter,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        Self {
+            watchdog: Watchdog::Disabled,
+            reset_console: true,
+            #[cfg(feature = "gfx")]
+            screen: ScreenInit::None,
+            log_level: LevelFilter::Info,
+        }
+    }
+}
+
+static mut SYSTEM_TABLE: Option<SystemTable<Boot>> = None;
+
+pub fn attach(system_table: &SystemTable<Boot>) {
+    unsafe { SYSTEM_TABLE = Some(system_table.unsafe_clone()) };
+}
+
+pub fn try_system_table() -> Option<SystemTable<Boot>> {
+    unsafe { (*ptr::addr_of!(SYSTEM_TABLE)).as_ref().map(|x| x.unsafe_clone()) }
+}
+
+pub fn system_table() -> uefi::Result<SystemTable<Boot>> {
+    try_system_table().ok_or(Status::NOT_READY.into())
+}
+
+pub struct Env {
+    system_table: SystemTable<Boot>,
+    config: EnvConfig,
+}
+
+pub struct RuntimeEnv {
+    pub system_table: SystemTable<Runtime>,
+    pub memory_map: MemoryMap<'static>,
+    #[cfg(feature = "gfx")]
+    pub framebuffer: Option<Framebuffer>,
+}
+
+pub fn init(mut system_table: SystemTable<Boot>, config: EnvConfig) -> Result<Env> {
+    attach(&system_table);
+    logger::init(config.log_level);
+    if config.reset_console {
+        system_table.stdin().reset(false)?;
+        system_table.stdout().reset(false)?;
+    }
+    let env = Env { system_table, config };
+    env.rearm_watchdog()?;
+    #[cfg(feature = "gfx")]
+    init_screen(config.screen)?;
+    Ok(env)
+}
+
+#[cfg(feature = "gfx")]
+fn init_screen(screen: ScreenInit) -> Result {
+    match screen {
+        ScreenInit::None => Ok(()),
+        ScreenInit::Current => gfx::Screen::init(),
+        ScreenInit::Resolution(w, h) => {
+            let mode = gfx::Screen::modes()?.into_iter()
+                .find(|x| x.info().resolution() == (w, h));
+            match mode {
+                Some(mode) => gfx::Screen::init_mode(&mode),
+                None => gfx::Screen::init(),
+            }
+        }
+    }
+}
+
+impl Env {
+    pub fn system_table(&mut self) -> &mut SystemTable<Boot> {
+        &mut self.system_table
+    }
+
+    pub fn boot_services(&self) -> &BootServices {
+        self.system_table.boot_services()
+    }
+
+    pub fn config(&self) -> &EnvConfig {
+        &self.config
+    }
+
+    pub fn set_watchdog(&self, watchdog: Watchdog) -> Result {
+        let timeout = match watchdog {
+            Watchdog::Disabled => 0,
+            Watchdog::Timeout(secs) => secs,
+        };
+        Ok(self.boot_services().set_watchdog_timer(timeout, WATCHDOG_CODE, None)?)
+    }
+
+    pub fn rearm_watchdog(&self) -> Result {
+        self.set_watchdog(self.config.watchdog)
+    }
+
+    pub fn long_operation<R>(&self, timeout: usize, f: impl FnOnce() -> R) -> R {
+        let _ = self.set_watchdog(Watchdog::Timeout(timeout));
+        let result = f();
+        let _ = self.rearm_watchdog();
+        result
+    }
+
+    pub fn stall(&self, us: usize) {
+        self.boot_services().stall(us);
+    }
+
+    pub fn sleep(&self, us: u64) -> Result {
+        timer::sleep(us)
+    }
+}
+
+pub fn exit_boot_services(env: Env, memory_type: MemoryType) -> RuntimeEnv {
+    #[cfg(feature = "gfx")]
+    let framebuffer = gfx::Screen::enter_runtime();
+    #[cfg(feature = "widgets")]
+    drop(terminal::uninstall());
+    #[cfg(feature = "input")]
+    keyboard::release();
+    logger::exit_services();
+    unsafe { SYSTEM_TABLE = None };
+    let (system_table, memory_map) = env.system_table.exit_boot_services(memory_type);
+    RuntimeEnv {
+        system_table,
+        memory_map,
+        #[cfg(feature = "gfx")]
+        framebuffer,
+    }
+}
@@ -0,0 +1,148 @@
+use core::fmt;
+
+#[cfg(feature = "assets")]
+use crate::assets::AssetError;
+#[cfg(feature = "config")]
+use crate::config::ConfigError;
+#[cfg(feature = "disk")]
+use crate::disk::{FatError, GptError, IsoError, MbrError};
+#[cfg(feature = "gfx")]
+use crate::image::ImageError;
+#[cfg(feature = "net")]
+use crate::net::NetError;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    Uefi(uefi::Error),
+    NoGraphics,
+    UnsupportedMode,
+    #[cfg(feature = "gfx")]
+    Image(ImageError),
+    #[cfg(feature = "assets")]
+    Asset(AssetError),
+    #[cfg(feature = "config")]
+    Config(ConfigError),
+    #[cfg(feature = "disk")]
+    Gpt(GptError),
+    #[cfg(feature = "disk")]
+    Mbr(MbrError),
+    #[cfg(feature = "disk")]
+    Fat(FatError),
+    #[cfg(feature = "disk")]
+    Iso(IsoError),
+    #[cfg(feature = "net")]
+    Net(NetError),
+}
+
+pub type Result<T = (), E = Error> = core::result::Result<T, E>;
+
+impl Error {
+    pub fn status(&self) -> Option<uefi::Status> {
+        match self {
+            Error::Uefi(x) => Some(x.status()),
+            #[cfg(feature = "assets")]
+            Error::Asset(AssetError::Io(x)) => Some(x.status()),
+            #[cfg(feature = "config")]
+            Error::Config(ConfigError::Io(x)) => Some(x.status()),
+            #[cfg(feature = "disk")]
+            Error::Gpt(GptError::Io(x)) | Error::Mbr(MbrError::Io(x)) | Error::Fat(FatError::Io(x)) | Error::Iso(IsoError::Io(x)) => Some(x.status()),
+            #[cfg(feature = "net")]
+            Error::Net(NetError::Io(x)) => Some(x.status()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Uefi(x) => write!(f, "firmware error: {:?}", x.status()),
+            Error::NoGraphics => f.write_str("no graphics output available"),
+            Error::UnsupportedMode => f.write_str("unsupported graphics mode"),
+            #[cfg(feature = "gfx")]
+            Error::Image(x) => write!(f, "image error: {:?}", x),
+            #[cfg(feature = "assets")]
+            Error::Asset(x) => write!(f, "asset error: {:?}", x),
+            #[cfg(feature = "config")]
+            Error::Config(x) => write!(f, "config error: {:?}", x),
+            #[cfg(feature = "disk")]
+            Error::Gpt(x) => write!(f, "GPT error: {:?}", x),
+            #[cfg(feature = "disk")]
+            Error::Mbr(x) => write!(f, "MBR error: {:?}", x),
+            #[cfg(feature = "disk")]
+            Error::Fat(x) => write!(f, "FAT error: {:?}", x),
+            #[cfg(feature = "disk")]
+            Error::Iso(x) => write!(f, "ISO 9660 error: {:?}", x),
+            #[cfg(feature = "net")]
+            Error::Net(x) => write!(f, "network error: {:?}", x),
+        }
+    }
+}
+
+impl From<uefi::Error> for Error {
+    fn from(value: uefi::Error) -> Self {
+        Error::Uefi(value)
+    }
+}
+
+impl From<uefi::Status> for Error {
+    fn from(value: uefi::Status) -> Self {
+        Error::Uefi(value.into())
+    }
+}
+
+#[cfg(feature = "gfx")]
+impl From<ImageError> for Error {
+    fn from(value: ImageError) -> Self {
+        Error::Image(value)
+    }
+}
+
+#[cfg(feature = "assets")]
+impl From<AssetError> for Error {
+    fn from(value: AssetError) -> Self {
+        Error::Asset(value)
+    }
+}
+
+#[cfg(feature = "config")]
+impl From<ConfigError> for Error {
+    fn from(value: ConfigError) -> Self {
+        Error::Config(value)
+    }
+}
+
+#[cfg(feature = "disk")]
+impl From<GptError> for Error {
+    fn from(value: GptError) -> Self {
+        Error::Gpt(value)
+    }
+}
+
+#[cfg(feature = "disk")]
+impl From<MbrError> for Error {
+    fn from(value: MbrError) -> Self {
+        Error::Mbr(value)
+    }
+}
+
+#[cfg(feature = "disk")]
+impl From<FatError> for Error {
+    fn from(value: FatError) -> Self {
+        Error::Fat(value)
+    }
+}
+
+#[cfg(feature = "disk")]
+impl From<IsoError> for Error {
+    fn from(value: IsoError) -> Self {
+        Error::Iso(value)
+    }
+}
+
+#[cfg(feature = "net")]
+impl From<NetError> for Error {
+    fn from(value: NetError) -> Self {
+        Error::Net(value)
+    }
+}
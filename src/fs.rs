@@ -0,0 +1,331 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use uefi::proto::loaded_image::LoadedImage;
+use uefi::proto::media::file::{
+    Directory, File, FileAttribute, FileInfo, FileMode, FileSystemInfo, RegularFile,
+};
+use uefi::proto::media::fs::SimpleFileSystem;
+use uefi::table::boot::SearchType;
+use uefi::table::runtime::Time;
+use uefi::{CString16, Handle, ResultExt, Status};
+
+use crate::env;
+use crate::hash::{Algorithm, Digest, Hasher};
+use crate::progress::{Progress, Tracker};
+
+pub mod path;
+
+pub use path::{EfiPath, EfiPathBuf};
+
+const CHUNK: usize = 1 << 20;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DirEntry {
+    pub name: String,
+    pub size: u64,
+    pub attribute: FileAttribute,
+    pub created: Time,
+    pub modified: Time,
+    pub accessed: Time,
+}
+
+impl DirEntry {
+    pub fn is_dir(&self) -> bool {
+        self.attribute.contains(FileAttribute::DIRECTORY)
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.attribute.contains(FileAttribute::HIDDEN)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Volume {
+    pub handle: Handle,
+    pub label: String,
+    pub size: u64,
+    pub free: u64,
+    pub read_only: bool,
+    pub boot: bool,
+}
+
+pub struct ReadDir {
+    dir: Directory,
+    buf: Vec<u64>,
+}
+
+impl Iterator for ReadDir {
+    type Item = DirEntry;
+
+    fn next(&mut self) -> Option<DirEntry> {
+        loop {
+            let bytes = unsafe {
+                core::slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut u8, self.buf.len() * 8)
+            };
+            let info = match self.dir.read_entry(bytes) {
+                Ok(Some(x)) => x,
+                Ok(None) => return None,
+                Err(err) => {
+                    let needed = (*err.data())?;
+                    self.buf.resize(needed.div_ceil(8), 0);
+                    continue;
+                }
+            };
+            let name = format!("{}", info.file_name());
+            if name == "." || name == ".." {
+                continue;
+            }
+            return Some(DirEntry {
+                name,
+                size: info.file_size(),
+                attribute: info.attribute(),
+                created: *info.create_time(),
+                modified: *info.modification_time(),
+                accessed: *info.last_access_time(),
+            });
+        }
+    }
+}
+
+fn open_root(volume: Handle) -> uefi::Result<Directory> {
+    let st = env::system_table()?;
+    let mut sfs = st.boot_services().open_protocol_exclusive::<SimpleFileSystem>(volume)?;
+    sfs.open_volume()
+}
+
+fn file_name(path: &EfiPath) -> uefi::Result<CString16> {
+    path.to_cstring16().ok_or_else(|| Status::INVALID_PARAMETER.into())
+}
+
+fn open_file(volume: Handle, path: &EfiPath, mode: FileMode) -> uefi::Result<RegularFile> {
+    let mut root = open_root(volume)?;
+    let name = file_name(path)?;
+    let file = root.open(&name, mode, FileAttribute::empty())?;
+    file.into_regular_file().ok_or_else(|| Status::INVALID_PARAMETER.into())
+}
+
+fn rename(file: &mut RegularFile, name: &str) -> uefi::Result {
+    let name = CString16::try_from(name).map_err(|_| uefi::Error::from(Status::INVALID_PARAMETER))?;
+    let info = file.get_boxed_info::<FileInfo>()?;
+    let mut buf = vec![0u64; 16 + name.num_bytes()];
+    let bytes = unsafe { core::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, buf.len() * 8) };
+    let renamed = FileInfo::new(
+        bytes,
+        info.file_size(),
+        info.physical_size(),
+        *info.create_time(),
+        *info.last_access_time(),
+        *info.modification_time(),
+        info.attribute(),
+        &name,
+    ).map_err(|_| uefi::Error::from(Status::INVALID_PARAMETER))?;
+    file.set_info(renamed)
+}
+
+fn replace<R>(
+    volume: Handle,
+    path: &EfiPath,
+    fill: impl FnOnce(&mut RegularFile) -> uefi::Result<R>,
+) -> uefi::Result<R> {
+    let path = path.normalized();
+    let name = path.file_name().ok_or(uefi::Error::from(Status::INVALID_PARAMETER))?;
+    let temp = EfiPathBuf::from(format!("{}.tmp", path));
+    if let Ok(stale) = open_file(volume, &temp, FileMode::ReadWrite) {
+        stale.delete()?;
+    }
+    let mut file = open_file(volume, &temp, FileMode::CreateReadWrite)?;
+    let result = match fill(&mut file).and_then(|x| file.flush().map(|_| x)) {
+        Ok(x) => x,
+        Err(err) => {
+            let _ = file.delete();
+            return Err(err);
+        }
+    };
+    if let Ok(existing) = open_file(volume, &path, FileMode::ReadWrite) {
+        existing.delete()?;
+    }
+    rename(&mut file, name)?;
+    Ok(result)
+}
+
+fn write_all(file: &mut RegularFile, data: &[u8]) -> uefi::Result {
+    for chunk in data.chunks(CHUNK) {
+        file.write(chunk).discard_errdata()?;
+    }
+    file.flush()
+}
+
+pub fn size(volume: Handle, path: impl AsRef<EfiPath>) -> uefi::Result<u64> {
+    let path = path.as_ref();
+    let mut file = open_file(volume, path, FileMode::Read)?;
+    Ok(file.get_boxed_info::<FileInfo>()?.file_size())
+}
+
+pub fn read(volume: Handle, path: impl AsRef<EfiPath>) -> uefi::Result<Vec<u8>> {
+    let path = path.as_ref();
+    let mut file = open_file(volume, path, FileMode::Read)?;
+    let size = file.get_boxed_info::<FileInfo>()?.file_size() as usize;
+    let mut data = vec![0u8; size];
+    let mut filled = 0;
+    while filled < size {
+        let end = (filled + CHUNK).min(size);
+        let read = file.read(&mut data[filled..end]).discard_errdata()?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+    }
+    data.truncate(filled);
+    Ok(data)
+}
+
+pub fn write(volume: Handle, path: impl AsRef<EfiPath>, data: &[u8]) -> uefi::Result {
+    replace(volume, path.as_ref(), |file| write_all(file, data))
+}
+
+pub fn copy(
+    from_volume: Handle,
+    from: impl AsRef<EfiPath>,
+    to_volume: Handle,
+    to: impl AsRef<EfiPath>,
+    progress: &mut impl Progress,
+) -> uefi::Result<u64> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    if from_volume == to_volume && from.normalized().as_str().eq_ignore_ascii_case(to.normalized().as_str()) {
+        return Err(Status::INVALID_PARAMETER.into());
+    }
+    let mut src = open_file(from_volume, from, FileMode::Read)?;
+    let size = src.get_boxed_info::<FileInfo>()?.file_size();
+    replace(to_volume, to, |dst| {
+        let mut tracker = Tracker::new(progress, Some(size));
+        let mut buf = vec![0u8; CHUNK];
+        loop {
+            let read = src.read(&mut buf).discard_errdata()?;
+            if read == 0 {
+                break;
+            }
+            dst.write(&buf[..read]).discard_errdata()?;
+            tracker.advance(read as u64);
+        }
+        Ok(tracker.done())
+    })
+}
+
+pub fn hash_file(volume: Handle, path: impl AsRef<EfiPath>, algorithm: Algorithm) -> uefi::Result<Digest> {
+    let mut file = open_file(volume, path.as_ref(), FileMode::Read)?;
+    let mut hasher = Hasher::new(algorithm);
+    let mut buf = vec![0u8; CHUNK];
+    loop {
+        let read = file.read(&mut buf).discard_errdata()?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    hasher.finalize()
+}
+
+pub fn append(volume: Handle, path: impl AsRef<EfiPath>, data: &[u8]) -> uefi::Result {
+    let path = path.as_ref();
+    let mut file = open_file(volume, path, FileMode::CreateReadWrite)?;
+    file.set_position(RegularFile::END_OF_FILE)?;
+    write_all(&mut file, data)
+}
+
+pub fn create_dir_all(volume: Handle, path: impl AsRef<EfiPath>) -> uefi::Result {
+    let path = path.as_ref();
+    let mut root = open_root(volume)?;
+    let mut current = EfiPathBuf::root();
+    for part in path.normalized().components() {
+        current.push(part);
+        let name = file_name(&current)?;
+        let dir = root.open(&name, FileMode::CreateReadWrite, FileAttribute::DIRECTORY)?;
+        if !dir.is_directory()? {
+            return Err(Status::ACCESS_DENIED.into());
+        }
+    }
+    Ok(())
+}
+
+pub fn read_dir(volume: Handle, path: impl AsRef<EfiPath>) -> uefi::Result<ReadDir> {
+    let path = path.as_ref();
+    let mut root = open_root(volume)?;
+    let dir = if path.is_root() {
+        root
+    } else {
+        let name = file_name(path)?;
+        let dir = root.open(&name, FileMode::Read, FileAttribute::empty())?;
+        dir.into_directory().ok_or(uefi::Error::from(Status::INVALID_PARAMETER))?
+    };
+    Ok(ReadDir { dir, buf: vec![0u64; 128] })
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BootVolume {
+    pub volume: Volume,
+    pub image_path: EfiPathBuf,
+    pub dir: EfiPathBuf,
+}
+
+impl BootVolume {
+    pub fn path(&self, relative: impl AsRef<EfiPath>) -> EfiPathBuf {
+        self.dir.join(relative)
+    }
+
+    pub fn read(&self, relative: impl AsRef<EfiPath>) -> uefi::Result<Vec<u8>> {
+        read(self.volume.handle, self.path(relative))
+    }
+
+    pub fn exists(&self, relative: impl AsRef<EfiPath>) -> bool {
+        size(self.volume.handle, self.path(relative)).is_ok()
+    }
+}
+
+fn image_device() -> Option<Handle> {
+    let st = env::try_system_table()?;
+    let bs = st.boot_services();
+    let image = bs.open_protocol_exclusive::<LoadedImage>(bs.image_handle()).ok()?;
+    image.device()
+}
+
+fn image_file_path() -> Option<EfiPathBuf> {
+    let st = env::try_system_table()?;
+    let bs = st.boot_services();
+    let image = bs.open_protocol_exclusive::<LoadedImage>(bs.image_handle()).ok()?;
+    EfiPathBuf::from_device_path(image.file_path()?)
+}
+
+fn volume_info(handle: Handle, boot: bool) -> Option<Volume> {
+    let mut root = open_root(handle).ok()?;
+    let info = root.get_boxed_info::<FileSystemInfo>().ok()?;
+    Some(Volume {
+        handle,
+        label: format!("{}", info.volume_label()),
+        size: info.volume_size(),
+        free: info.free_space(),
+        read_only: info.read_only(),
+        boot,
+    })
+}
+
+pub fn volumes() -> Vec<Volume> {
+    let st = if let Some(x) = env::try_system_table() { x } else { return Vec::new(); };
+    let handles = st.boot_services()
+        .locate_handle_buffer(SearchType::from_proto::<SimpleFileSystem>())
+        .map(|x| x.to_vec())
+        .unwrap_or_default();
+    let boot = image_device();
+    handles.into_iter()
+        .filter_map(|handle| volume_info(handle, Some(handle) == boot))
+        .collect()
+}
+
+pub fn boot_volume() -> Option<BootVolume> {
+    let volume = volume_info(image_device()?, true)?;
+    let image_path = image_file_path().unwrap_or_else(EfiPathBuf::root);
+    let dir = image_path.parent().map_or_else(EfiPathBuf::root, |x| x.to_path_buf());
+    Some(BootVolume { volume, image_path, dir })
+}
@@ -0,0 +1,276 @@
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+
+use uefi::proto::device_path::build::{self, DevicePathBuilder};
+use uefi::proto::device_path::{DevicePath, DevicePathNodeEnum};
+use uefi::CString16;
+
+pub const SEPARATOR: char = '\\';
+
+fn is_separator(c: char) -> bool {
+    c == SEPARATOR || c == '/'
+}
+
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct EfiPath(str);
+
+#[derive(Debug, Clone, Default)]
+pub struct EfiPathBuf(String);
+
+impl EfiPath {
+    pub fn new<S: AsRef<str> + ?Sized>(s: &S) -> &EfiPath {
+        unsafe { &*(s.as_ref() as *const str as *const EfiPath) }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        self.0.starts_with(is_separator)
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.chars().all(is_separator)
+    }
+
+    pub fn components(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+        self.0.split(is_separator).filter(|x| !x.is_empty() && *x != ".")
+    }
+
+    fn trimmed(&self) -> &str {
+        let trimmed = self.0.trim_end_matches(is_separator);
+        if trimmed.is_empty() && self.is_absolute() { &self.0[..1] } else { trimmed }
+    }
+
+    pub fn parent(&self) -> Option<&EfiPath> {
+        let trimmed = self.trimmed();
+        let index = trimmed.rfind(is_separator)?;
+        if index == 0 && trimmed.len() == 1 {
+            return None;
+        }
+        let parent = trimmed[..index].trim_end_matches(is_separator);
+        Some(EfiPath::new(if parent.is_empty() { &trimmed[..1] } else { parent }))
+    }
+
+    pub fn file_name(&self) -> Option<&str> {
+        self.components().next_back()
+    }
+
+    pub fn extension(&self) -> Option<&str> {
+        let name = self.file_name()?;
+        let index = name.rfind('.')?;
+        if index == 0 { None } else { Some(&name[index + 1..]) }
+    }
+
+    pub fn join(&self, other: impl AsRef<EfiPath>) -> EfiPathBuf {
+        let mut buf = self.to_path_buf();
+        buf.push(other);
+        buf
+    }
+
+    pub fn to_path_buf(&self) -> EfiPathBuf {
+        EfiPathBuf(self.0.to_owned())
+    }
+
+    pub fn to_cstring16(&self) -> Option<CString16> {
+        CString16::try_from(self.normalized().as_str()).ok()
+    }
+
+    pub fn normalized(&self) -> EfiPathBuf {
+        let mut parts: Vec<&str> = Vec::new();
+        for part in self.components() {
+            if part == ".." {
+                parts.pop();
+            } else {
+                parts.push(part);
+            }
+        }
+        let mut out = String::new();
+        if self.is_absolute() {
+            out.push(SEPARATOR);
+        }
+        for (i, part) in parts.iter().enumerate() {
+            if i > 0 {
+                out.push(SEPARATOR);
+            }
+            out.push_str(part);
+        }
+        EfiPathBuf(out)
+    }
+
+    pub fn to_device_path<'a>(&self, buf: &'a mut Vec<MaybeUninit<u8>>) -> Option<&'a DevicePath> {
+        let name = self.to_cstring16()?;
+        DevicePathBuilder::with_vec(buf)
+            .push(&build::media::FilePath { path_name: &name }).ok()?
+            .finalize().ok()
+    }
+}
+
+impl EfiPathBuf {
+    pub fn new() -> Self {
+        Self(String::new())
+    }
+
+    pub fn root() -> Self {
+        Self(String::from(SEPARATOR))
+    }
+
+    pub fn from_device_path(path: &DevicePath) -> Option<Self> {
+        let mut buf = Self::new();
+        let mut found = false;
+        for node in path.node_iter() {
+            if let Ok(DevicePathNodeEnum::MediaFilePath(file)) = node.as_enum() {
+                let name = file.path_name().to_cstring16().ok()?;
+                buf.push(name.to_string().as_str());
+                found = true;
+            }
+        }
+        found.then_some(buf)
+    }
+
+    pub fn as_path(&self) -> &EfiPath {
+        EfiPath::new(&self.0)
+    }
+
+    pub fn push(&mut self, other: impl AsRef<EfiPath>) {
+        let other = other.as_ref();
+        if other.is_absolute() {
+            self.0.clear();
+        } else if !self.0.is_empty() && !self.0.ends_with(is_separator) {
+            self.0.push(SEPARATOR);
+        }
+        self.0.push_str(other.as_str());
+    }
+
+    pub fn pop(&mut self) -> bool {
+        let len = if let Some(x) = self.parent() { x.as_str().len() } else { return false; };
+        self.0.truncate(len);
+        true
+    }
+
+    pub fn set_extension(&mut self, ext: &str) {
+        let name = if let Some(x) = self.file_name() { x } else { return; };
+        let stem = name.rfind('.').filter(|x| *x > 0).map_or(name.len(), |x| x);
+        let start = name.as_ptr() as usize - self.0.as_ptr() as usize;
+        self.0.truncate(start + stem);
+        if !ext.is_empty() {
+            self.0.push('.');
+            self.0.push_str(ext);
+        }
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl From<&str> for EfiPathBuf {
+    fn from(value: &str) -> Self {
+        Self(value.replace('/', "\\"))
+    }
+}
+
+impl From<String> for EfiPathBuf {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl Deref for EfiPathBuf {
+    type Target = EfiPath;
+
+    fn deref(&self) -> &EfiPath {
+        self.as_path()
+    }
+}
+
+impl Borrow<EfiPath> for EfiPathBuf {
+    fn borrow(&self) -> &EfiPath {
+        self.as_path()
+    }
+}
+
+impl PartialEq for EfiPath {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.normalized(), other.normalized());
+        a.is_absolute() == b.is_absolute() && a.components().eq(b.components())
+    }
+}
+
+impl Eq for EfiPath {}
+
+impl Hash for EfiPath {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalized();
+        normalized.is_absolute().hash(state);
+        for part in normalized.components() {
+            part.hash(state);
+        }
+    }
+}
+
+impl PartialEq for EfiPathBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_path() == other.as_path()
+    }
+}
+
+impl Eq for EfiPathBuf {}
+
+impl Hash for EfiPathBuf {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_path().hash(state);
+    }
+}
+
+impl ToOwned for EfiPath {
+    type Owned = EfiPathBuf;
+
+    fn to_owned(&self) -> EfiPathBuf {
+        self.to_path_buf()
+    }
+}
+
+impl AsRef<EfiPath> for EfiPath {
+    fn as_ref(&self) -> &EfiPath {
+        self
+    }
+}
+
+impl AsRef<EfiPath> for EfiPathBuf {
+    fn as_ref(&self) -> &EfiPath {
+        self.as_path()
+    }
+}
+
+impl AsRef<EfiPath> for str {
+    fn as_ref(&self) -> &EfiPath {
+        EfiPath::new(self)
+    }
+}
+
+impl AsRef<EfiPath> for String {
+    fn as_ref(&self) -> &EfiPath {
+        EfiPath::new(self)
+    }
+}
+
+impl fmt::Display for EfiPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Display for EfiPathBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -1,11 +1,17 @@
+use alloc::format;
 use alloc::vec;
 use alloc::vec::Vec;
+use core::f32::consts::{FRAC_PI_2, PI, TAU};
 use core::ops::{Add, Sub};
 use core::slice;
 
 use baked_font::{Font, Glyph, GlyphResult};
-use log::info;
-use uefi::proto::console::gop::{BltOp, BltPixel, BltRegion, GraphicsOutput, Mode};
+use log::{info, warn};
+use uefi::proto::console::gop::{BltOp, BltPixel, BltRegion, GraphicsOutput, Mode, PixelFormat};
+use uefi::table::boot::{BootServices, ScopedProtocol};
+
+use crate::env;
+use crate::{Error, Result};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
 pub struct Pos {
@@ -27,10 +33,7 @@ impl Add for Pos {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        Self {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
+        self.saturating_add(other)
     }
 }
 
@@ -38,10 +41,7 @@ impl Sub for Pos {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        Self {
-            x: self.x - other.x,
-            y: self.y - other.y,
-        }
+        self.saturating_sub(other)
     }
 }
 
@@ -49,6 +49,22 @@ impl Pos {
     pub const fn dim(self) -> Dim {
         Dim { w: self.x, h: self.y }
     }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Self { x: self.x.checked_add(other.x)?, y: self.y.checked_add(other.y)? })
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        Some(Self { x: self.x.checked_sub(other.x)?, y: self.y.checked_sub(other.y)? })
+    }
+
+    pub fn saturating_add(self, other: Self) -> Self {
+        Self { x: self.x.saturating_add(other.x), y: self.y.saturating_add(other.y) }
+    }
+
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self { x: self.x.saturating_sub(other.x), y: self.y.saturating_sub(other.y) }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
@@ -71,10 +87,7 @@ impl Add for Dim {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        Self {
-            w: self.w + other.w,
-            h: self.h + other.h,
-        }
+        self.saturating_add(other)
     }
 }
 
@@ -82,10 +95,7 @@ impl Sub for Dim {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        Self {
-            w: self.w - other.w,
-            h: self.h - other.h,
-        }
+        self.saturating_sub(other)
     }
 }
 
@@ -93,6 +103,138 @@ impl Dim {
     pub const fn pos(self) -> Pos {
         Pos { x: self.w, y: self.h }
     }
+
+    pub fn area(self) -> Area {
+        rect(pos(0, 0), self).area()
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.w <= 0 || self.h <= 0
+    }
+
+    pub fn non_negative(self) -> Self {
+        Self { w: self.w.max(0), h: self.h.max(0) }
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Self { w: self.w.checked_add(other.w)?, h: self.h.checked_add(other.h)? })
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        Some(Self { w: self.w.checked_sub(other.w)?, h: self.h.checked_sub(other.h)? })
+    }
+
+    pub fn saturating_add(self, other: Self) -> Self {
+        Self { w: self.w.saturating_add(other.w), h: self.h.saturating_add(other.h) }
+    }
+
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self { w: self.w.saturating_sub(other.w), h: self.h.saturating_sub(other.h) }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+pub struct PosF {
+    pub x: f32,
+    pub y: f32,
+}
+
+pub const fn posf(x: f32, y: f32) -> PosF {
+    PosF { x, y }
+}
+
+impl From<(f32, f32)> for PosF {
+    fn from((x, y): (f32, f32)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<Pos> for PosF {
+    fn from(value: Pos) -> Self {
+        Self { x: value.x as f32, y: value.y as f32 }
+    }
+}
+
+impl Add for PosF {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self { x: self.x + other.x, y: self.y + other.y }
+    }
+}
+
+impl Sub for PosF {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self { x: self.x - other.x, y: self.y - other.y }
+    }
+}
+
+impl PosF {
+    pub const fn dim(self) -> DimF {
+        DimF { w: self.x, h: self.y }
+    }
+
+    pub fn round(self) -> Pos {
+        Pos { x: Math::round(self.x) as i32, y: Math::round(self.y) as i32 }
+    }
+
+    pub fn floor(self) -> Pos {
+        Pos { x: Math::floor(self.x) as i32, y: Math::floor(self.y) as i32 }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+pub struct DimF {
+    pub w: f32,
+    pub h: f32,
+}
+
+pub const fn dimf(w: f32, h: f32) -> DimF {
+    DimF { w, h }
+}
+
+impl From<(f32, f32)> for DimF {
+    fn from((w, h): (f32, f32)) -> Self {
+        Self { w, h }
+    }
+}
+
+impl From<Dim> for DimF {
+    fn from(value: Dim) -> Self {
+        Self { w: value.w as f32, h: value.h as f32 }
+    }
+}
+
+impl Add for DimF {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self { w: self.w + other.w, h: self.h + other.h }
+    }
+}
+
+impl Sub for DimF {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self { w: self.w - other.w, h: self.h - other.h }
+    }
+}
+
+impl DimF {
+    pub const fn pos(self) -> PosF {
+        PosF { x: self.w, y: self.h }
+    }
+
+    pub fn round(self) -> Dim {
+        Dim { w: Math::round(self.w) as i32, h: Math::round(self.h) as i32 }
+    }
+
+    pub fn floor(self) -> Dim {
+        Dim { w: Math::floor(self.w) as i32, h: Math::floor(self.h) as i32 }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
@@ -106,24 +248,32 @@ pub const fn rect(pos: Pos, dim: Dim) -> Rect {
 }
 
 impl Rect {
+    pub fn snap(pos: PosF, dim: DimF) -> Self {
+        let pos1 = pos.round();
+        let pos2 = (pos + dim.pos()).round();
+        Self { pos: pos1, dim: (pos2 - pos1).dim() }
+    }
+
     pub fn area(self) -> Area {
         Area {
             pos1: self.pos,
-            pos2: self.pos + self.dim.pos(),
+            pos2: self.pos.saturating_add(self.dim.non_negative().pos()),
         }
     }
+
+    pub fn checked_area(self) -> Option<Area> {
+        Some(Area {
+            pos1: self.pos,
+            pos2: self.pos.checked_add(self.dim.non_negative().pos())?,
+        })
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.dim.is_empty()
+    }
     
     pub fn normalize(self) -> Self {
-        Self {
-            pos: Pos {
-                x: self.pos.x.min(self.pos.x + self.dim.w),
-                y: self.pos.y.min(self.pos.y + self.dim.h),
-            },
-            dim: Dim {
-                w: self.dim.w.abs(),
-                h: self.dim.h.abs(),
-            },
-        }
+        area(self.pos, self.pos.saturating_add(self.dim.pos())).normalize().rect()
     }
     
     pub fn translate(self, pos: Pos) -> Self {
@@ -147,9 +297,75 @@ impl Rect {
         }
     }
     
+    pub fn centered(self, dim: Dim) -> Self {
+        Self {
+            pos: Pos {
+                x: self.pos.x.saturating_add(self.dim.w.saturating_sub(dim.w) / 2),
+                y: self.pos.y.saturating_add(self.dim.h.saturating_sub(dim.h) / 2),
+            },
+            dim,
+        }
+    }
+    
     pub fn contains(self, pos: Pos) -> bool {
-        pos.x >= self.pos.x && pos.x < self.pos.x + self.dim.w &&
-        pos.y >= self.pos.y && pos.y < self.pos.y + self.dim.h
+        self.area().contains(pos)
+    }
+    
+    pub fn union(self, other: Self) -> Self {
+        if other.is_empty() {
+            return self;
+        }
+        if self.is_empty() {
+            return other;
+        }
+        let (a, b) = (self.area(), other.area());
+        area(
+            pos(a.pos1.x.min(b.pos1.x), a.pos1.y.min(b.pos1.y)),
+            pos(a.pos2.x.max(b.pos2.x), a.pos2.y.max(b.pos2.y)),
+        ).rect()
+    }
+
+    pub fn inset(self, insets: Insets) -> Self {
+        Self {
+            pos: Pos {
+                x: self.pos.x.saturating_add(insets.left),
+                y: self.pos.y.saturating_add(insets.top),
+            },
+            dim: Dim {
+                w: self.dim.w.saturating_sub(insets.horizontal()).max(0),
+                h: self.dim.h.saturating_sub(insets.vertical()).max(0),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Insets {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Insets {
+    pub const fn all(n: i32) -> Self {
+        Self { left: n, top: n, right: n, bottom: n }
+    }
+    
+    pub const fn symmetric(h: i32, v: i32) -> Self {
+        Self { left: h, top: v, right: h, bottom: v }
+    }
+    
+    pub fn horizontal(self) -> i32 {
+        self.left.saturating_add(self.right)
+    }
+    
+    pub fn vertical(self) -> i32 {
+        self.top.saturating_add(self.bottom)
+    }
+    
+    pub fn dim(self) -> Dim {
+        Dim { w: self.horizontal(), h: self.vertical() }
     }
 }
 
@@ -167,9 +383,23 @@ impl Area {
     pub fn rect(self) -> Rect {
         Rect {
             pos: self.pos1,
-            dim: (self.pos2 - self.pos1).dim(),
+            dim: self.dim(),
         }
     }
+
+    pub fn dim(self) -> Dim {
+        let w = (self.pos2.x as i64 - self.pos1.x as i64).clamp(0, i32::MAX as i64);
+        let h = (self.pos2.y as i64 - self.pos1.y as i64).clamp(0, i32::MAX as i64);
+        Dim { w: w as i32, h: h as i32 }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.pos1.x >= self.pos2.x || self.pos1.y >= self.pos2.y
+    }
+
+    pub fn contains(self, pos: Pos) -> bool {
+        pos.x >= self.pos1.x && pos.x < self.pos2.x && pos.y >= self.pos1.y && pos.y < self.pos2.y
+    }
     
     pub fn normalize(self) -> Self {
         Self {
@@ -206,10 +436,10 @@ impl Area {
                 y: self.pos2.y.min(other.pos2.y),
             },
         };
-        if area.pos1.x < area.pos2.x && area.pos1.y < area.pos2.y {
-            Some(area)
-        } else {
+        if area.is_empty() {
             None
+        } else {
+            Some(area)
         }
     }
 }
@@ -307,36 +537,92 @@ pub struct Buffer {
     pub dim: Dim,
 }
 
-impl Buffer {
-    pub fn new(dim: Dim) -> Self {
-        Self {
-            data: vec![Color::BLACK; (dim.w * dim.h) as usize],
-            dim,
+fn pixel_index(dim: Dim, loc: Pos) -> usize {
+    loc.x as usize + loc.y as usize * dim.w as usize
+}
+
+pub trait Canvas {
+    fn dim(&self) -> Dim;
+    fn pixels(&self) -> &[Color];
+    fn pixels_mut(&mut self) -> &mut [Color];
+
+    fn rect(&self) -> Rect {
+        rect(pos(0, 0), self.dim())
+    }
+
+    fn area(&self) -> Area {
+        area(pos(0, 0), self.dim().pos())
+    }
+
+    fn get(&self, loc: Pos) -> Option<Color> {
+        if !self.area().contains(loc) {
+            return None;
         }
+        Some(self.pixels()[pixel_index(self.dim(), loc)])
     }
-    
-    pub fn new_cleared(dim: Dim, color: Color) -> Self {
-        Self {
-            data: vec![color; (dim.w * dim.h) as usize],
-            dim,
+
+    fn set(&mut self, loc: Pos, color: Color) {
+        if self.area().contains(loc) {
+            let idx = pixel_index(self.dim(), loc);
+            self.pixels_mut()[idx] = color;
         }
     }
-    
-    pub fn rect(&self) -> Rect {
-        rect(pos(0, 0), self.dim)
+
+    fn clear(&mut self, color: Color) {
+        for pixel in self.pixels_mut().iter_mut() {
+            *pixel = color;
+        }
     }
-    
-    pub fn area(&self) -> Area { 
-        area(pos(0, 0), self.dim.pos())
+
+    fn fill(&mut self, area: Area, color: Color) {
+        let area = if let Some(x) = area.intersection(self.area()) { x } else { return; };
+        let dim = self.dim();
+        let data = self.pixels_mut();
+        for loc in area.pos_iter() {
+            data[pixel_index(dim, loc)] = color;
+        }
     }
-    
-    pub fn clear(&mut self, color: Color) {
-        for pixel in self.data.iter_mut() {
-            *pixel = color;
+
+    fn fill_over(&mut self, area: Area, color: Color) {
+        let area = if let Some(x) = area.intersection(self.area()) { x } else { return; };
+        let dim = self.dim();
+        let data = self.pixels_mut();
+        for loc in area.pos_iter() {
+            let px = &mut data[pixel_index(dim, loc)];
+            *px = px.premultiplied_over(color);
         }
     }
-    
-    pub fn area_apply(
+
+    fn fill_circle(&mut self, center: Pos, radius: i32, color: Color) {
+        let bounds = area(center - pos(radius, radius), center + pos(radius.saturating_add(1), radius.saturating_add(1)));
+        let bounds = if let Some(x) = bounds.intersection(self.area()) { x } else { return; };
+        let r2 = radius as i64 * radius as i64 + radius as i64;
+        let dim = self.dim();
+        let data = self.pixels_mut();
+        for loc in bounds.pos_iter() {
+            let (dx, dy) = (loc.x as i64 - center.x as i64, loc.y as i64 - center.y as i64);
+            if dx * dx + dy * dy <= r2 {
+                let px = &mut data[pixel_index(dim, loc)];
+                *px = px.premultiplied_over(color);
+            }
+        }
+    }
+
+    fn stroke(&mut self, area: Area, width: i32, color: Color) {
+        let Area { pos1: p1, pos2: p2 } = area.normalize();
+        let width = width
+            .min(((p2.x as i64 - p1.x as i64 + 1) / 2) as i32)
+            .min(((p2.y as i64 - p1.y as i64 + 1) / 2) as i32);
+        if width <= 0 {
+            return;
+        }
+        self.fill_over(Area { pos1: p1, pos2: pos(p2.x, p1.y + width) }, color);
+        self.fill_over(Area { pos1: pos(p1.x, p2.y - width), pos2: p2 }, color);
+        self.fill_over(Area { pos1: pos(p1.x, p1.y + width), pos2: pos(p1.x + width, p2.y - width) }, color);
+        self.fill_over(Area { pos1: pos(p2.x - width, p1.y + width), pos2: pos(p2.x, p2.y - width) }, color);
+    }
+
+    fn area_apply(
         &self, other_bounds: Area, other_area: Area, pos: Pos
     ) -> Option<(Area, Pos)> {
         let other_area = other_area.intersection(other_bounds);
@@ -346,42 +632,252 @@ impl Buffer {
         let dst_area = dst_area?;
         Some((other_area, dst_area.pos1))
     }
-    
-    pub fn apply(
-        &mut self, src: &Buffer, src_area: Area, dst_pos: Pos, op: impl FnMut(&mut Color, Color)
+
+    fn apply(
+        &mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_pos: Pos,
+        op: impl FnMut(&mut Color, Color),
     ) {
-        let (src_area, dst_pos) = if let Some(x) = 
+        let (src_area, dst_pos) = if let Some(x) =
             self.area_apply(src.area(), src_area, dst_pos) { x } else { return; };
         self.apply_unchecked(src, src_area, dst_pos, op)
     }
-    
-    pub fn apply_unchecked(
-        &mut self, src: &Buffer, src_area: Area, dst_pos: Pos, mut op: impl FnMut(&mut Color, Color)
+
+    fn apply_unchecked(
+        &mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_pos: Pos,
+        mut op: impl FnMut(&mut Color, Color),
     ) {
         let dim = src_area.rect().dim;
         let src_pos = src_area.pos1;
+        let (src_dim, dst_dim) = (src.dim(), self.dim());
+        let (src_data, dst_data) = (src.pixels(), self.pixels_mut());
         for y in 0..dim.h {
             for x in 0..dim.w {
-                let src_pos = src_pos + pos(x, y);
-                let dst_pos = dst_pos + pos(x, y);
-                let src_idx = src_pos.y as usize * src.dim.w as usize + src_pos.x as usize;
-                let dst_idx = dst_pos.y as usize * self.dim.w as usize + dst_pos.x as usize;
-                op(&mut self.data[dst_idx], src.data[src_idx]);
+                let src_idx = pixel_index(src_dim, src_pos + pos(x, y));
+                let dst_idx = pixel_index(dst_dim, dst_pos + pos(x, y));
+                op(&mut dst_data[dst_idx], src_data[src_idx]);
             }
         }
     }
-    
-    pub fn premultiplied_over(&mut self, src: &Buffer, src_area: Area, dst_pos: Pos) {
+
+    fn premultiplied_over(&mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_pos: Pos) {
         self.apply(src, src_area, dst_pos, |dst, src| {
             *dst = dst.premultiplied_over(src);
         });
     }
-    
-    pub fn additive_over(&mut self, src: &Buffer, src_area: Area, dst_pos: Pos) {
+
+    fn additive_over(&mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_pos: Pos) {
         self.apply(src, src_area, dst_pos, |dst, src| {
             *dst = dst.additive_over(src);
         });
     }
+
+    fn blit(&mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_pos: Pos) {
+        self.apply(src, src_area, dst_pos, |dst, src| {
+            *dst = src;
+        });
+    }
+
+    fn blit_scaled(&mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_rect: Rect) {
+        let src_area = if let Some(x) = src_area.intersection(src.area()) { x } else { return; };
+        let src_dim = src_area.rect().dim;
+        let dst_rect = dst_rect.normalize();
+        if dst_rect.dim.w == 0 || dst_rect.dim.h == 0 {
+            return;
+        }
+        let area = dst_rect.area().intersection(self.area());
+        let area = if let Some(x) = area { x } else { return; };
+        let (src_full, dst_dim) = (src.dim(), self.dim());
+        let (src_data, dst_data) = (src.pixels(), self.pixels_mut());
+        for loc in area.pos_iter() {
+            let rel = loc - dst_rect.pos;
+            let sx = src_area.pos1.x
+                + (rel.x as i64 * src_dim.w as i64 / dst_rect.dim.w as i64) as i32;
+            let sy = src_area.pos1.y
+                + (rel.y as i64 * src_dim.h as i64 / dst_rect.dim.h as i64) as i32;
+            dst_data[pixel_index(dst_dim, loc)] = src_data[pixel_index(src_full, pos(sx, sy))];
+        }
+    }
+
+    fn draw_glyph(&mut self, loc: Pos, font: &Font, glyph: Glyph, color: Color) {
+        let glyph_loc = pos(glyph.pos.0 as i32, glyph.pos.1 as i32) - loc;
+        let sz = dim(glyph.size.0 as i32, glyph.size.1 as i32);
+        self.draw_font_rect(loc, font, glyph_loc, sz, color);
+    }
+
+    fn draw_font_rect(
+        &mut self, loc: Pos, font: &Font, glyph_loc: Pos, sz: Dim, color: Color
+    ) {
+        self.draw_font_rect_clipped(loc, font, glyph_loc, sz, color, self.area());
+    }
+
+    fn draw_font_rect_clipped(
+        &mut self, loc: Pos, font: &Font, glyph_loc: Pos, sz: Dim, color: Color, clip: Area
+    ) {
+        let glyph_loc = glyph_loc - loc;
+        let area = self.area().intersection(clip)
+            .and_then(|x| x.intersection(rect(loc, sz).area()));
+        let area = if let Some (x) = area { x } else { return; };
+        let dim = self.dim();
+        let data = self.pixels_mut();
+        for loc in area.pos_iter() {
+            let glyph_loc = glyph_loc + loc;
+            let alpha = font.bitmap[
+                glyph_loc.x as usize + glyph_loc.y as usize * font.width as usize];
+            let color = color.apply_alpha(alpha);
+            let px = &mut data[pixel_index(dim, loc)];
+            *px = px.premultiplied_over(color);
+        }
+    }
+
+    fn draw_text(&mut self, loc: Pos, font: &Font, text: &str, color: Color) {
+        font.lookup_string(text).glyph_coords().draw_each(self, loc, font, color);
+    }
+}
+
+impl Canvas for Buffer {
+    fn dim(&self) -> Dim {
+        self.dim
+    }
+
+    fn pixels(&self) -> &[Color] {
+        &self.data
+    }
+
+    fn pixels_mut(&mut self) -> &mut [Color] {
+        &mut self.data
+    }
+}
+
+impl Buffer {
+    pub fn new(dim: Dim) -> Self {
+        Self {
+            data: vec![Color::BLACK; (dim.w * dim.h) as usize],
+            dim,
+        }
+    }
+    
+    pub fn new_cleared(dim: Dim, color: Color) -> Self {
+        Self {
+            data: vec![color; (dim.w * dim.h) as usize],
+            dim,
+        }
+    }
+    
+    pub fn rect(&self) -> Rect {
+        Canvas::rect(self)
+    }
+    
+    pub fn area(&self) -> Area { 
+        Canvas::area(self)
+    }
+    
+    pub fn clear(&mut self, color: Color) {
+        Canvas::clear(self, color)
+    }
+    
+    pub fn fill(&mut self, area: Area, color: Color) {
+        Canvas::fill(self, area, color)
+    }
+    
+    pub fn fill_over(&mut self, area: Area, color: Color) {
+        Canvas::fill_over(self, area, color)
+    }
+    
+    pub fn fill_circle(&mut self, center: Pos, radius: i32, color: Color) {
+        Canvas::fill_circle(self, center, radius, color)
+    }
+    
+    pub fn stroke(&mut self, area: Area, width: i32, color: Color) {
+        Canvas::stroke(self, area, width, color)
+    }
+    
+    pub fn premultiplied_over(&mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_pos: Pos) {
+        Canvas::premultiplied_over(self, src, src_area, dst_pos)
+    }
+    
+    pub fn additive_over(&mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_pos: Pos) {
+        Canvas::additive_over(self, src, src_area, dst_pos)
+    }
+    
+    pub fn blit(&mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_pos: Pos) {
+        Canvas::blit(self, src, src_area, dst_pos)
+    }
+    
+    pub fn blit_scaled(&mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_rect: Rect) {
+        Canvas::blit_scaled(self, src, src_area, dst_rect)
+    }
+    
+    pub fn scaled(&self, dim: Dim) -> Buffer {
+        let mut buffer = Buffer::new(dim);
+        buffer.blit_scaled(self, self.area(), buffer.rect());
+        buffer
+    }
+
+    pub fn draw_glyph(&mut self, loc: Pos, font: &Font, glyph: Glyph, color: Color) {
+        Canvas::draw_glyph(self, loc, font, glyph, color)
+    }
+    
+    pub fn draw_font_rect(
+        &mut self, loc: Pos, font: &Font, glyph_loc: Pos, sz: Dim, color: Color
+    ) {
+        Canvas::draw_font_rect(self, loc, font, glyph_loc, sz, color)
+    }
+    
+    pub fn draw_font_rect_clipped(
+        &mut self, loc: Pos, font: &Font, glyph_loc: Pos, sz: Dim, color: Color, clip: Area
+    ) {
+        Canvas::draw_font_rect_clipped(self, loc, font, glyph_loc, sz, color, clip)
+    }
+
+    pub fn draw_text(&mut self, loc: Pos, font: &Font, text: &str, color: Color) {
+        Canvas::draw_text(self, loc, font, text, color)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FixedBuffer<const W: usize, const H: usize> {
+    pub data: [[Color; W]; H],
+}
+
+impl<const W: usize, const H: usize> FixedBuffer<W, H> {
+    pub const fn new() -> Self {
+        Self::new_cleared(Color::BLACK)
+    }
+
+    pub const fn new_cleared(color: Color) -> Self {
+        Self { data: [[color; W]; H] }
+    }
+
+    pub fn to_buffer(&self) -> Buffer {
+        Buffer { data: self.pixels().to_vec(), dim: Canvas::dim(self) }
+    }
+}
+
+impl<const W: usize, const H: usize> Default for FixedBuffer<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> Canvas for FixedBuffer<W, H> {
+    fn dim(&self) -> Dim {
+        dim(W as i32, H as i32)
+    }
+
+    fn pixels(&self) -> &[Color] {
+        self.data.as_flattened()
+    }
+
+    fn pixels_mut(&mut self) -> &mut [Color] {
+        self.data.as_flattened_mut()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PreserveContent {
+    Scale,
+    Clear,
+    Crop,
 }
 
 static mut SCREEN: Buffer = Buffer {
@@ -389,37 +885,91 @@ static mut SCREEN: Buffer = Buffer {
     dim: dim(0, 0),
 };
 
+static mut PRESENTED: u64 = 0;
+
+static mut FRAMEBUFFER: Option<Framebuffer> = None;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Framebuffer {
+    pub base: *mut u32,
+    pub size: usize,
+    pub dim: Dim,
+    pub stride: usize,
+    pub swap_rb: bool,
+}
+
+impl Framebuffer {
+    fn write(&self, src: &Buffer, rect: Rect) {
+        let bounds = self.dim.area().intersection(src.area());
+        let area = if let Some(x) = bounds.and_then(|x| x.intersection(rect.area())) { x } else { return; };
+        for y in area.pos1.y..area.pos2.y {
+            let src_row = y as usize * src.dim.w as usize;
+            let dst_row = y as usize * self.stride;
+            for x in area.pos1.x..area.pos2.x {
+                let c = src.data[src_row + x as usize];
+                let px = if self.swap_rb {
+                    c.r as u32 | ((c.g as u32) << 8) | ((c.b as u32) << 16)
+                } else {
+                    c.b as u32 | ((c.g as u32) << 8) | ((c.r as u32) << 16)
+                };
+                unsafe { self.base.add(dst_row + x as usize).write_volatile(px) };
+            }
+        }
+    }
+}
+
 pub struct Screen {}
 
 impl Screen {
-    pub fn init() {
-        let st = uefi_services::system_table();
-        let gop_handle = st.boot_services()
-            .get_handle_for_protocol::<GraphicsOutput>().unwrap();
-        let gop = st.boot_services()
-            .open_protocol_exclusive::<GraphicsOutput>(gop_handle).unwrap();
+    fn gop() -> Result<ScopedProtocol<'static, GraphicsOutput>> {
+        let st = env::try_system_table().ok_or(Error::NoGraphics)?;
+        let bs = unsafe { &*(st.boot_services() as *const BootServices) };
+        let gop_handle = bs.get_handle_for_protocol::<GraphicsOutput>()
+            .map_err(|_| Error::NoGraphics)?;
+        Ok(bs.open_protocol_exclusive::<GraphicsOutput>(gop_handle)?)
+    }
+
+    pub fn init() -> Result {
+        let gop = Self::gop()?;
         let res = gop.current_mode_info().resolution();
         unsafe { SCREEN = Buffer::new(dim(res.0 as i32, res.1 as i32)); }
+        Ok(())
+    }
+
+    pub fn modes() -> Result<Vec<Mode>> {
+        let gop = Self::gop()?;
+        let st = env::system_table()?;
+        Ok(gop.modes(st.boot_services()).collect())
     }
 
-    pub fn modes() -> Vec<Mode> {
-        let st = uefi_services::system_table();
-        let gop_handle = st.boot_services()
-            .get_handle_for_protocol::<GraphicsOutput>().unwrap();
-        let gop = st.boot_services()
-            .open_protocol_exclusive::<GraphicsOutput>(gop_handle).unwrap();
-        gop.modes(st.boot_services()).collect()
+    pub fn init_headless(dim: Dim) {
+        unsafe { SCREEN = Buffer::new(dim); }
     }
 
-    pub fn init_mode(mode: &Mode) {
-        let st = uefi_services::system_table();
-        let gop_handle = st.boot_services()
-            .get_handle_for_protocol::<GraphicsOutput>().unwrap();
-        let mut gop = st.boot_services()
-            .open_protocol_exclusive::<GraphicsOutput>(gop_handle).unwrap();
-        gop.set_mode(&mode).unwrap();
+    pub fn init_mode(mode: &Mode) -> Result {
+        let mut gop = Self::gop()?;
+        gop.set_mode(mode).map_err(|_| Error::UnsupportedMode)?;
         drop(gop);
-        Self::init();
+        Self::init()
+    }
+
+    pub fn change_mode(mode: &Mode, preserve: PreserveContent) -> Result {
+        let old = core::mem::take(Self::get());
+        if let Err(err) = Self::init_mode(mode) {
+            *Self::get() = old;
+            return Err(err);
+        }
+        let screen = Self::get();
+        match preserve {
+            PreserveContent::Scale => *screen = old.scaled(screen.dim),
+            PreserveContent::Crop => screen.blit(&old, old.area(), pos(0, 0)),
+            PreserveContent::Clear => {}
+        }
+        Self::try_present(Self::rect())
+    }
+
+    pub fn is_init() -> bool {
+        unsafe { SCREEN.dim.w != 0 }
     }
 
     pub fn get() -> &'static mut Buffer {
@@ -432,13 +982,53 @@ impl Screen {
         rect(pos(0, 0), Self::get().dim)
     }
 
+    pub fn take_presented() -> u64 {
+        unsafe { core::mem::take(&mut PRESENTED) }
+    }
+
+    pub fn enter_runtime() -> Option<Framebuffer> {
+        let mut gop = Self::gop().ok()?;
+        let info = gop.current_mode_info();
+        let swap_rb = match info.pixel_format() {
+            PixelFormat::Bgr => false,
+            PixelFormat::Rgb => true,
+            _ => return None,
+        };
+        let (w, h) = info.resolution();
+        let mut fb = gop.frame_buffer();
+        let framebuffer = Framebuffer {
+            base: fb.as_mut_ptr() as *mut u32,
+            size: fb.size(),
+            dim: dim(w as i32, h as i32),
+            stride: info.stride(),
+            swap_rb,
+        };
+        unsafe { FRAMEBUFFER = Some(framebuffer) };
+        Some(framebuffer)
+    }
+
+    pub fn framebuffer() -> Option<Framebuffer> {
+        unsafe { FRAMEBUFFER }
+    }
+
     pub fn present(rect: Rect) {
+        if let Err(err) = Self::try_present(rect) {
+            warn!("Failed to present screen: {}", err);
+        }
+    }
+
+    pub fn try_present(rect: Rect) -> Result {
         let screen = Self::get();
-        let st= uefi_services::system_table();
-        let gop_handle = st.boot_services()
-            .get_handle_for_protocol::<GraphicsOutput>().unwrap();
-        let mut gop = st.boot_services()
-            .open_protocol_exclusive::<GraphicsOutput>(gop_handle).unwrap();
+        let rect = if let Some(x) = rect.area().intersection(screen.area()) { x.rect() } else { return Ok(()); };
+        unsafe { PRESENTED += rect.dim.w as u64 * rect.dim.h as u64; }
+        if let Some(framebuffer) = Self::framebuffer() {
+            framebuffer.write(screen, rect);
+            return Ok(());
+        }
+        if cfg!(feature = "host") {
+            return Ok(());
+        }
+        let mut gop = Self::gop()?;
         let buffer = unsafe {
             slice::from_raw_parts(screen.data.as_ptr() as *const BltPixel, screen.data.len())
         };
@@ -451,7 +1041,8 @@ impl Screen {
             },
             dest: coord,
             dims: (rect.dim.w as usize, rect.dim.h as usize),
-        }).unwrap();
+        })?;
+        Ok(())
     }
 }
 
@@ -600,37 +1191,34 @@ impl Iterator for AreaPosIter {
     }
 }
 
-impl Buffer {
-    pub fn draw_glyph(&mut self, loc: Pos, font: &Font, glyph: Glyph, color: Color) {
-        let glyph_loc = pos(glyph.pos.0 as i32, glyph.pos.1 as i32) - loc;
-        let sz = dim(glyph.size.0 as i32, glyph.size.1 as i32);
-        self.draw_font_rect(loc, font, glyph_loc, sz, color);
+pub fn text_dim(font: &Font, text: &str) -> Dim {
+    let mut sz = dim(0, 0);
+    for gcr in font.lookup_string(text).glyph_coords() {
+        if let GlyphCoordResult::Handled(gc) = gcr {
+            sz.w = sz.w.max(gc.offset.x + gc.glyph_dim.w);
+            sz.h = sz.h.max(gc.offset.y + gc.glyph_dim.h);
+        }
     }
-    
-    pub fn draw_font_rect(
-        &mut self, loc: Pos, font: &Font, glyph_loc: Pos, sz: Dim, color: Color
-    ) {
-        let glyph_loc = glyph_loc - loc;
-        let area = self.area().intersection(rect(loc, sz).area());
-        let area = if let Some (x) = area { x } else { return; };
-        for loc in area.pos_iter() {
-            let glyph_loc = glyph_loc + loc;
-            let alpha = font.bitmap[
-                glyph_loc.x as usize + glyph_loc.y as usize * font.width as usize];
-            let color = color.apply_alpha(alpha);
-            let px = &mut self.data[
-                loc.x as usize + loc.y as usize * self.dim.w as usize];
-            *px = px.premultiplied_over(color);
+    sz
+}
+
+pub fn wrapped_text_dim(font: &Font, text: &str, width: i32, line_height: i32) -> Dim {
+    let mut sz = dim(0, 0);
+    for gcr in font.lookup_string(text).glyph_coords().line_wrap(width, line_height) {
+        if let GlyphCoordResult::Handled(gc) = gcr {
+            sz.w = sz.w.max(gc.offset.x + gc.glyph_dim.w);
+            sz.h = sz.h.max(gc.offset.y + gc.glyph_dim.h);
         }
     }
+    sz
 }
 
 pub trait GlyphCoordIteratorExt {
-    fn draw_each(&mut self, buffer: &mut Buffer, loc: Pos, font: &Font, color: Color);
+    fn draw_each(&mut self, buffer: &mut (impl Canvas + ?Sized), loc: Pos, font: &Font, color: Color);
 }
 
 impl<T: Iterator<Item=GlyphCoordResult>> GlyphCoordIteratorExt for T {
-    fn draw_each(&mut self, buffer: &mut Buffer, loc: Pos, font: &Font, color: Color) {
+    fn draw_each(&mut self, buffer: &mut (impl Canvas + ?Sized), loc: Pos, font: &Font, color: Color) {
         for gcr in self {
             match gcr {
                 GlyphCoordResult::Handled(gc) => {
@@ -645,14 +1233,202 @@ impl<T: Iterator<Item=GlyphCoordResult>> GlyphCoordIteratorExt for T {
     }
 }
 
+pub struct View<'a> {
+    pub buffer: &'a mut Buffer,
+    pub rect: Rect,
+    clip: Option<Area>,
+}
+
+impl<'a> View<'a> {
+    pub fn new(buffer: &'a mut Buffer, rect: Rect) -> Self {
+        let rect = rect.normalize();
+        let clip = rect.area().intersection(buffer.area());
+        Self { buffer, rect, clip }
+    }
+    
+    pub fn dim(&self) -> Dim {
+        self.rect.dim
+    }
+    
+    pub fn area(&self) -> Area {
+        area(pos(0, 0), self.rect.dim.pos())
+    }
+    
+    pub fn to_global(&self, loc: Pos) -> Pos {
+        loc + self.rect.pos
+    }
+    
+    pub fn view(&mut self, rect: Rect) -> View<'_> {
+        let rect = rect.normalize().translate(self.rect.pos);
+        let clip = self.clip.and_then(|x| x.intersection(rect.area()));
+        View { buffer: &mut *self.buffer, rect, clip }
+    }
+    
+    pub fn clear(&mut self, color: Color) {
+        if let Some(clip) = self.clip {
+            self.buffer.fill(clip, color);
+        }
+    }
+    
+    pub fn fill(&mut self, area: Area, color: Color) {
+        let area = area.map_all(|x| self.to_global(x));
+        if let Some(area) = self.clip.and_then(|x| x.intersection(area)) {
+            self.buffer.fill(area, color);
+        }
+    }
+    
+    pub fn fill_over(&mut self, area: Area, color: Color) {
+        let area = area.map_all(|x| self.to_global(x));
+        if let Some(area) = self.clip.and_then(|x| x.intersection(area)) {
+            self.buffer.fill_over(area, color);
+        }
+    }
+    
+    fn clip_apply(&self, src: &(impl Canvas + ?Sized), src_area: Area, dst_pos: Pos) -> Option<(Area, Pos)> {
+        let clip = self.clip?;
+        let src_area = src_area.intersection(src.area())?;
+        let dst_pos = self.to_global(dst_pos);
+        let dst_area = rect(dst_pos, src_area.rect().dim).area().intersection(clip)?;
+        let src_pos = src_area.pos1 + (dst_area.pos1 - dst_pos);
+        Some((rect(src_pos, dst_area.rect().dim).area(), dst_area.pos1))
+    }
+    
+    pub fn premultiplied_over(&mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_pos: Pos) {
+        if let Some((src_area, dst_pos)) = self.clip_apply(src, src_area, dst_pos) {
+            self.buffer.apply_unchecked(src, src_area, dst_pos, |dst, src| {
+                *dst = dst.premultiplied_over(src);
+            });
+        }
+    }
+    
+    pub fn blit(&mut self, src: &(impl Canvas + ?Sized), src_area: Area, dst_pos: Pos) {
+        if let Some((src_area, dst_pos)) = self.clip_apply(src, src_area, dst_pos) {
+            self.buffer.apply_unchecked(src, src_area, dst_pos, |dst, src| {
+                *dst = src;
+            });
+        }
+    }
+    
+    pub fn draw_font_rect(&mut self, loc: Pos, font: &Font, glyph_loc: Pos, sz: Dim, color: Color) {
+        if let Some(clip) = self.clip {
+            let loc = self.to_global(loc);
+            self.buffer.draw_font_rect_clipped(loc, font, glyph_loc, sz, color, clip);
+        }
+    }
+    
+    pub fn draw_text(&mut self, loc: Pos, font: &Font, text: &str, color: Color) {
+        for gcr in font.lookup_string(text).glyph_coords() {
+            if let GlyphCoordResult::Handled(gc) = gcr {
+                self.draw_font_rect(gc.offset + loc, font, gc.glyph_pos, gc.glyph_dim, color);
+            }
+        }
+    }
+}
+
+pub struct Letterbox {}
+
+impl Letterbox {
+    pub fn target(design: Dim, screen: Dim) -> Rect {
+        let off = pos(((screen.w - design.w) / 2).max(0), ((screen.h - design.h) / 2).max(0));
+        rect(off, design)
+    }
+    
+    pub fn apply(buffer: &mut Buffer, design: Dim, border: Color) -> View<'_> {
+        let target = Self::target(design, buffer.dim);
+        let outer = buffer.area();
+        let inner = target.area();
+        buffer.fill(area(outer.pos1, pos(outer.pos2.x, inner.pos1.y)), border);
+        buffer.fill(area(pos(outer.pos1.x, inner.pos2.y), outer.pos2), border);
+        buffer.fill(area(pos(outer.pos1.x, inner.pos1.y), pos(inner.pos1.x, inner.pos2.y)), border);
+        buffer.fill(area(pos(inner.pos2.x, inner.pos1.y), pos(outer.pos2.x, inner.pos2.y)), border);
+        View::new(buffer, target)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ProgressStyle {
+    pub segment: i32,
+    pub gap: i32,
+    pub rounded: bool,
+    pub border_width: i32,
+    pub border: Color,
+}
+
+impl ProgressStyle {
+    fn visible(&self, area: Area, pos: Pos) -> bool {
+        let local_x = pos.x - area.pos1.x;
+        if self.segment > 0 && local_x % (self.segment + self.gap) >= self.segment {
+            return false;
+        }
+        if !self.rounded {
+            return true;
+        }
+        let r = (area.pos2.y - area.pos1.y) / 2;
+        let cy = area.pos1.y + r;
+        let cx = if pos.x < area.pos1.x + r {
+            area.pos1.x + r
+        } else if pos.x >= area.pos2.x - r {
+            area.pos2.x - r - 1
+        } else {
+            return true;
+        };
+        let (dx, dy) = (pos.x - cx, pos.y - cy);
+        dx * dx + dy * dy <= r * r + r
+    }
+    
+    fn filled_until(&self, area: Area, progress_x: i32) -> i32 {
+        if self.segment <= 0 {
+            return progress_x;
+        }
+        let block = self.segment + self.gap;
+        let local = progress_x - area.pos1.x;
+        area.pos1.x + (local + self.segment - 1) / block * block
+    }
+}
+
 pub struct ProgressBar {
     pub area: Area,
     pub progress: f32,
+    pub target: f32,
+    pub smoothing: f32,
     pub fg: Color,
     pub bg: Color,
+    pub style: ProgressStyle,
 }
 
 impl ProgressBar {
+    pub fn new(area: Area, fg: Color, bg: Color) -> Self {
+        Self {
+            area,
+            progress: 0.0,
+            target: 0.0,
+            smoothing: 0.0,
+            fg,
+            bg,
+            style: ProgressStyle::default(),
+        }
+    }
+    
+    pub fn set_target(&mut self, target: f32) {
+        self.target = target.clamp(0.0, 1.0);
+        if self.smoothing <= 0.0 {
+            self.progress = self.target;
+        }
+    }
+    
+    pub fn tick(&mut self, dt: f32) {
+        if self.smoothing <= 0.0 {
+            self.progress = self.target;
+            return;
+        }
+        let step = (dt * self.smoothing).min(1.0);
+        self.progress += (self.target - self.progress) * step;
+        let rest = self.target - self.progress;
+        if rest > -0.001 && rest < 0.001 {
+            self.progress = self.target;
+        }
+    }
+    
     pub fn draw_normal(&self, buffer: &mut Buffer) {
         let fg = self.fg;
         let bg = self.bg;
@@ -662,12 +1438,29 @@ impl ProgressBar {
         let progress_x = (
             self.area.pos1.x as f32 + (self.area.pos2.x - self.area.pos1.x) as f32 * progress
         ) as i32;
+        let progress_x = self.style.filled_until(self.area, progress_x);
         actual.pos_iter().for_each(|pos| {
+            if !self.style.visible(self.area, pos) {
+                return;
+            }
             let color = if pos.x < progress_x { fg } else { bg };
             let tc = &mut buffer.data[
                 pos.x as usize + pos.y as usize * buffer.dim.w as usize];
             *tc = tc.premultiplied_over(color);
         });
+        self.draw_border(buffer);
+    }
+    
+    pub fn draw_border(&self, buffer: &mut Buffer) {
+        if self.style.border_width > 0 {
+            buffer.stroke(self.area, self.style.border_width, self.style.border);
+        }
+    }
+    
+    pub fn draw_percent(&self, buffer: &mut Buffer, font: &Font, color: Color) {
+        let text = format!("{}%", (self.progress.clamp(0.0, 1.0) * 100.0) as i32);
+        let loc = self.area.rect().centered(text_dim(font, &text)).pos;
+        buffer.draw_text(loc, font, &text, color);
     }
     
     pub fn draw_marquee_custom(&self, buffer: &mut Buffer, frac_fns: &[fn(f32) -> f32]) {
@@ -705,6 +1498,44 @@ impl Math {
         if frac >= 1.0 { 2.0 - frac } else { frac }
     }
 
+    pub fn floor(x: f32) -> f32 {
+        let t = x as i32 as f32;
+        if t > x { t - 1.0 } else { t }
+    }
+
+    pub fn round(x: f32) -> f32 {
+        Self::floor(x + 0.5)
+    }
+
+    pub fn fract(x: f32) -> f32 {
+        x - Self::floor(x)
+    }
+
+    pub fn sin(x: f32) -> f32 {
+        let x = Self::fract(x / TAU + 0.5) * TAU - PI;
+        let x = if x > FRAC_PI_2 {
+            PI - x
+        } else if x < -FRAC_PI_2 {
+            -PI - x
+        } else {
+            x
+        };
+        let x2 = x * x;
+        x * (1.0 - x2 / 6.0 * (1.0 - x2 / 20.0 * (1.0 - x2 / 42.0)))
+    }
+
+    pub fn cos(x: f32) -> f32 {
+        Self::sin(x + FRAC_PI_2)
+    }
+
+    pub fn exp2(x: f32) -> f32 {
+        let i = Self::floor(x);
+        let f = x - i;
+        let p = 1.0 + f * (0.693_147 + f * (0.240_227 + f * (0.055_504 + f * 0.009_618)));
+        let e = (i as i32 + 127).clamp(0, 255) as u32;
+        f32::from_bits(e << 23) * p
+    }
+
     pub fn exp_2_slope_s(frac: f32) -> f32 {
         if frac < 0.5 {
             let frac = frac * 2.0;
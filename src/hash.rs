@@ -0,0 +1,433 @@
+use alloc::string::String;
+use core::ffi::c_void;
+use core::fmt;
+use core::ops::Range;
+
+use uefi::proto::unsafe_protocol;
+use uefi::{guid, Guid, Status};
+
+use crate::binding::{Child, ServiceBinding};
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const SHA256_INIT: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const SHA1_INIT: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Algorithm {
+    Sha1,
+    Sha256,
+}
+
+impl Algorithm {
+    pub fn digest_len(self) -> usize {
+        match self {
+            Algorithm::Sha1 => 20,
+            Algorithm::Sha256 => 32,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Sha1 => "SHA-1",
+            Algorithm::Sha256 => "SHA-256",
+        }
+    }
+
+    fn guid(self) -> Guid {
+        match self {
+            Algorithm::Sha1 => guid!("2ae9d80f-3fb2-4095-b7b1-e93157b946b6"),
+            Algorithm::Sha256 => guid!("51aa59de-fdf2-4ea3-bc63-875fb7842ee9"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Digest {
+    pub algorithm: Algorithm,
+    bytes: [u8; 32],
+}
+
+impl Digest {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.algorithm.digest_len()]
+    }
+
+    pub fn to_hex(&self) -> String {
+        let mut out = String::with_capacity(self.algorithm.digest_len() * 2);
+        for byte in self.as_bytes() {
+            out.push(char::from_digit((byte >> 4) as u32, 16).unwrap());
+            out.push(char::from_digit((byte & 0xF) as u32, 16).unwrap());
+        }
+        out
+    }
+
+    pub fn matches_hex(&self, hex: &str) -> bool {
+        self.to_hex().eq_ignore_ascii_case(hex.trim())
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+struct Block {
+    buf: [u8; 64],
+    len: usize,
+    total: u64,
+}
+
+impl Block {
+    fn new() -> Self {
+        Self { buf: [0; 64], len: 0, total: 0 }
+    }
+
+    fn update(&mut self, mut data: &[u8], mut compress: impl FnMut(&[u8; 64])) {
+        self.total += data.len() as u64;
+        if self.len > 0 {
+            let take = (64 - self.len).min(data.len());
+            self.buf[self.len..self.len + take].copy_from_slice(&data[..take]);
+            self.len += take;
+            data = &data[take..];
+            if self.len < 64 {
+                return;
+            }
+            compress(&self.buf);
+            self.len = 0;
+        }
+        let mut chunks = data.chunks_exact(64);
+        for chunk in &mut chunks {
+            compress(chunk.try_into().unwrap());
+        }
+        let rest = chunks.remainder();
+        self.buf[..rest.len()].copy_from_slice(rest);
+        self.len = rest.len();
+    }
+
+    fn finish(&mut self, mut compress: impl FnMut(&[u8; 64])) {
+        let bits = self.total.wrapping_mul(8);
+        self.buf[self.len] = 0x80;
+        self.buf[self.len + 1..].fill(0);
+        if self.len >= 56 {
+            compress(&self.buf);
+            self.buf.fill(0);
+        }
+        self.buf[56..].copy_from_slice(&bits.to_be_bytes());
+        compress(&self.buf);
+    }
+}
+
+pub struct Sha256 {
+    state: [u32; 8],
+    block: Block,
+}
+
+fn sha256_compress(state: &mut [u32; 8], block: &[u8; 64]) {
+    let mut w = [0u32; 64];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes(word.try_into().unwrap());
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(SHA256_K[i]).wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (x, y) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *x = x.wrapping_add(y);
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Self { state: SHA256_INIT, block: Block::new() }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.block.update(data, |x| sha256_compress(state, x));
+    }
+
+    pub fn finalize(mut self) -> [u8; 32] {
+        let state = &mut self.state;
+        self.block.finish(|x| sha256_compress(state, x));
+        let mut out = [0u8; 32];
+        for (chunk, word) in out.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        out
+    }
+}
+
+pub struct Sha1 {
+    state: [u32; 5],
+    block: Block,
+}
+
+fn sha1_compress(state: &mut [u32; 5], block: &[u8; 64]) {
+    let mut w = [0u32; 80];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes(word.try_into().unwrap());
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for (i, word) in w.iter().enumerate() {
+        let (f, k) = match i {
+            0..=19 => ((b & c) | (!b & d), 0x5a827999),
+            20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+            _ => (b ^ c ^ d, 0xca62c1d6),
+        };
+        let t = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*word);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = t;
+    }
+    for (x, y) in state.iter_mut().zip([a, b, c, d, e]) {
+        *x = x.wrapping_add(y);
+    }
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha1 {
+    pub fn new() -> Self {
+        Self { state: SHA1_INIT, block: Block::new() }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.block.update(data, |x| sha1_compress(state, x));
+    }
+
+    pub fn finalize(mut self) -> [u8; 20] {
+        let state = &mut self.state;
+        self.block.finish(|x| sha1_compress(state, x));
+        let mut out = [0u8; 20];
+        for (chunk, word) in out.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        out
+    }
+}
+
+#[repr(C)]
+#[unsafe_protocol("da836f8d-217f-4ca0-99c2-1ca4e16077ea")]
+struct Hash2ServiceBinding(ServiceBinding);
+
+#[repr(C)]
+#[allow(dead_code)]
+#[unsafe_protocol("55b1d734-c5e1-49db-9647-b16afb0e305b")]
+struct Hash2Protocol {
+    get_hash_size: *const c_void,
+    hash: *const c_void,
+    hash_init: unsafe extern "efiapi" fn(*mut Hash2Protocol, *const Guid) -> Status,
+    hash_update: unsafe extern "efiapi" fn(*mut Hash2Protocol, *const u8, usize) -> Status,
+    hash_final: unsafe extern "efiapi" fn(*mut Hash2Protocol, *mut [u8; 64]) -> Status,
+}
+
+enum Engine {
+    Firmware(Child<Hash2Protocol>, Status),
+    Sha1(Sha1),
+    Sha256(Sha256),
+}
+
+pub struct Hasher {
+    algorithm: Algorithm,
+    engine: Engine,
+}
+
+fn firmware(algorithm: Algorithm) -> Option<Child<Hash2Protocol>> {
+    let child = Child::<Hash2Protocol>::create::<Hash2ServiceBinding>().ok()?;
+    let guid = algorithm.guid();
+    let status = unsafe { ((*child.protocol).hash_init)(child.protocol, &guid) };
+    if status == Status::SUCCESS { Some(child) } else { None }
+}
+
+impl Hasher {
+    pub fn new(algorithm: Algorithm) -> Self {
+        let engine = match firmware(algorithm) {
+            Some(child) => Engine::Firmware(child, Status::SUCCESS),
+            None => Self::software(algorithm).engine,
+        };
+        Self { algorithm, engine }
+    }
+
+    pub fn software(algorithm: Algorithm) -> Self {
+        let engine = match algorithm {
+            Algorithm::Sha1 => Engine::Sha1(Sha1::new()),
+            Algorithm::Sha256 => Engine::Sha256(Sha256::new()),
+        };
+        Self { algorithm, engine }
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn is_firmware(&self) -> bool {
+        matches!(self.engine, Engine::Firmware(..))
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.engine {
+            Engine::Firmware(child, status) => {
+                if *status == Status::SUCCESS && !data.is_empty() {
+                    *status = unsafe { ((*child.protocol).hash_update)(child.protocol, data.as_ptr(), data.len()) };
+                }
+            }
+            Engine::Sha1(x) => x.update(data),
+            Engine::Sha256(x) => x.update(data),
+        }
+    }
+
+    pub fn finalize(self) -> uefi::Result<Digest> {
+        let mut bytes = [0u8; 32];
+        match self.engine {
+            Engine::Firmware(child, status) => {
+                status.to_result()?;
+                let mut out = [0u8; 64];
+                unsafe { ((*child.protocol).hash_final)(child.protocol, &mut out) }.to_result()?;
+                let len = self.algorithm.digest_len();
+                bytes[..len].copy_from_slice(&out[..len]);
+            }
+            Engine::Sha1(x) => bytes[..20].copy_from_slice(&x.finalize()),
+            Engine::Sha256(x) => bytes = x.finalize(),
+        }
+        Ok(Digest { algorithm: self.algorithm, bytes })
+    }
+}
+
+pub fn digest(algorithm: Algorithm, data: &[u8]) -> Digest {
+    let mut hasher = Hasher::new(algorithm);
+    hasher.update(data);
+    hasher.finalize().unwrap_or_else(|_| {
+        let mut hasher = Hasher::software(algorithm);
+        hasher.update(data);
+        hasher.finalize().unwrap()
+    })
+}
+
+pub fn sha256(data: &[u8]) -> Digest {
+    digest(Algorithm::Sha256, data)
+}
+
+pub fn sha1(data: &[u8]) -> Digest {
+    digest(Algorithm::Sha1, data)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crc32 {
+    pub fn new() -> Self {
+        Self { state: !0 }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.state = CRC32_TABLE[((self.state ^ *byte as u32) & 0xFF) as usize] ^ (self.state >> 8);
+        }
+    }
+
+    pub fn update_zeroed(&mut self, len: usize) {
+        for _ in 0..len {
+            self.update(&[0]);
+        }
+    }
+
+    pub fn finalize(&self) -> u32 {
+        !self.state
+    }
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.finalize()
+}
+
+pub fn crc32_without(data: &[u8], field: Range<usize>) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(&data[..field.start]);
+    crc.update_zeroed(field.len());
+    crc.update(&data[field.end..]);
+    crc.finalize()
+}
+
+pub fn table_crc_valid(table: &[u8]) -> bool {
+    if table.len() < 24 {
+        return false;
+    }
+    let size = u32::from_le_bytes(table[12..16].try_into().unwrap()) as usize;
+    let header = if let Some(x) = table.get(..size) { x } else { return false; };
+    size >= 24 && crc32_without(header, 16..20) == u32::from_le_bytes(table[16..20].try_into().unwrap())
+}
@@ -0,0 +1,412 @@
+use alloc::vec::Vec;
+
+#[cfg(feature = "fs")]
+use uefi::Handle;
+
+#[cfg(feature = "fs")]
+use crate::fs::{self, EfiPath};
+use crate::gfx::{dim, rgb, rgba, Buffer, Color};
+
+#[cfg(feature = "gif")]
+pub mod gif;
+
+#[cfg(feature = "gif")]
+pub use gif::{decode_gif, is_gif, Animation, Frame};
+
+const MAX_DIM: i32 = 16384;
+
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
+
+const QOI_MAGIC: &[u8; 4] = b"qoif";
+const QOI_END: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+const QOI_OP_INDEX: u8 = 0x00;
+const QOI_OP_DIFF: u8 = 0x40;
+const QOI_OP_LUMA: u8 = 0x80;
+const QOI_OP_RUN: u8 = 0xC0;
+const QOI_OP_RGB: u8 = 0xFE;
+const QOI_OP_RGBA: u8 = 0xFF;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ImageFormat {
+    Bmp,
+    Qoi,
+}
+
+impl ImageFormat {
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        if ext.eq_ignore_ascii_case("bmp") {
+            Some(ImageFormat::Bmp)
+        } else if ext.eq_ignore_ascii_case("qoi") {
+            Some(ImageFormat::Qoi)
+        } else {
+            None
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Bmp => "bmp",
+            ImageFormat::Qoi => "qoi",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ImageError {
+    Truncated,
+    Invalid(&'static str),
+    Unsupported(&'static str),
+}
+
+fn u16_at(data: &[u8], off: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(off..off + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], off: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(off..off + 4)?.try_into().ok()?))
+}
+
+pub fn bmp_size(data: &[u8]) -> Option<usize> {
+    if data.get(0..2)? != b"BM" {
+        return None;
+    }
+    Some(u32_at(data, 2)? as usize)
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Channel {
+    mask: u32,
+    shift: u32,
+    max: u32,
+}
+
+impl Channel {
+    fn new(mask: u32) -> Self {
+        let shift = if mask == 0 { 0 } else { mask.trailing_zeros() };
+        Self { mask, shift, max: mask >> shift }
+    }
+
+    fn get(self, value: u32, default: u8) -> u8 {
+        if self.max == 0 {
+            return default;
+        }
+        (((value & self.mask) >> self.shift) as u64 * 255 / self.max as u64) as u8
+    }
+}
+
+fn read_masks(data: &[u8], header_size: u32, compression: u32) -> Result<[u32; 4], ImageError> {
+    let mut masks = [0; 4];
+    let count = if compression == BI_ALPHABITFIELDS || header_size >= 56 { 4 } else { 3 };
+    for (i, mask) in masks.iter_mut().take(count).enumerate() {
+        *mask = u32_at(data, 54 + i * 4).ok_or(ImageError::Truncated)?;
+    }
+    Ok(masks)
+}
+
+fn read_palette(data: &[u8], header_size: u32, bpp: u16, colors_used: u32) -> Result<Vec<Color>, ImageError> {
+    let max = 1u32 << bpp;
+    let count = if colors_used == 0 { max as usize } else { colors_used.min(max) as usize };
+    let start = 14 + header_size as usize;
+    let bytes = data.get(start..start + count * 4).ok_or(ImageError::Truncated)?;
+    Ok(bytes.chunks_exact(4).map(|x| rgb(x[2], x[1], x[0])).collect())
+}
+
+pub fn encode_bmp(buffer: &Buffer) -> Vec<u8> {
+    const HEADER: usize = 14 + 108;
+    let (w, h) = (buffer.dim.w.max(0) as usize, buffer.dim.h.max(0) as usize);
+    let image_size = w * h * 4;
+    let mut out = Vec::with_capacity(HEADER + image_size);
+    out.extend_from_slice(b"BM");
+    out.extend_from_slice(&((HEADER + image_size) as u32).to_le_bytes());
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&(HEADER as u32).to_le_bytes());
+    out.extend_from_slice(&108u32.to_le_bytes());
+    out.extend_from_slice(&(w as i32).to_le_bytes());
+    out.extend_from_slice(&(h as i32).to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&32u16.to_le_bytes());
+    out.extend_from_slice(&BI_BITFIELDS.to_le_bytes());
+    out.extend_from_slice(&(image_size as u32).to_le_bytes());
+    out.extend_from_slice(&2835u32.to_le_bytes());
+    out.extend_from_slice(&2835u32.to_le_bytes());
+    out.extend_from_slice(&[0; 8]);
+    for mask in [0x00FF_0000u32, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000] {
+        out.extend_from_slice(&mask.to_le_bytes());
+    }
+    out.extend_from_slice(b"BGRs");
+    out.extend_from_slice(&[0; 48]);
+    for y in (0..h).rev() {
+        for color in &buffer.data[y * w..(y + 1) * w] {
+            let [r, g, b, a] = straight(*color);
+            out.extend_from_slice(&[b, g, r, a]);
+        }
+    }
+    out
+}
+
+pub fn decode_bmp(data: &[u8]) -> Result<Buffer, ImageError> {
+    if data.get(0..2) != Some(&b"BM"[..]) {
+        return Err(ImageError::Invalid("signature"));
+    }
+    let pixels = u32_at(data, 10).ok_or(ImageError::Truncated)? as usize;
+    let header_size = u32_at(data, 14).ok_or(ImageError::Truncated)?;
+    if header_size < 40 {
+        return Err(ImageError::Unsupported("core header"));
+    }
+    let width = u32_at(data, 18).ok_or(ImageError::Truncated)? as i32;
+    let height = u32_at(data, 22).ok_or(ImageError::Truncated)? as i32;
+    let bpp = u16_at(data, 28).ok_or(ImageError::Truncated)?;
+    let compression = u32_at(data, 30).ok_or(ImageError::Truncated)?;
+    let colors_used = u32_at(data, 46).ok_or(ImageError::Truncated)?;
+    if width <= 0 || height == 0 || height == i32::MIN {
+        return Err(ImageError::Invalid("dimensions"));
+    }
+    if width > MAX_DIM || height.abs() > MAX_DIM {
+        return Err(ImageError::Unsupported("dimensions"));
+    }
+    let masks = match (compression, bpp) {
+        (BI_RGB, 1 | 4 | 8 | 24) => None,
+        (BI_RGB, 16) => Some([0x7C00, 0x03E0, 0x001F, 0]),
+        (BI_RGB, 32) => Some([0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000]),
+        (BI_BITFIELDS | BI_ALPHABITFIELDS, 16 | 32) => Some(read_masks(data, header_size, compression)?),
+        (BI_RGB, _) => return Err(ImageError::Unsupported("bit depth")),
+        _ => return Err(ImageError::Unsupported("compression")),
+    };
+    let palette = if bpp <= 8 { read_palette(data, header_size, bpp, colors_used)? } else { Vec::new() };
+
+    let bottom_up = height > 0;
+    let height = height.abs();
+    let stride = (width as usize * bpp as usize).div_ceil(32) * 4;
+    let row_len = (width as usize * bpp as usize).div_ceil(8);
+    let row = |y: usize| {
+        let src_y = if bottom_up { height as usize - 1 - y } else { y };
+        let start = src_y.checked_mul(stride).and_then(|x| x.checked_add(pixels)).ok_or(ImageError::Truncated)?;
+        let end = start.checked_add(row_len).ok_or(ImageError::Truncated)?;
+        data.get(start..end).ok_or(ImageError::Truncated)
+    };
+
+    let [r, g, b, a] = masks.unwrap_or_default().map(Channel::new);
+    let mut use_alpha = a.max != 0;
+    if use_alpha && compression == BI_RGB {
+        use_alpha = false;
+        for y in 0..height as usize {
+            if row(y)?.chunks_exact(4).any(|x| x[3] != 0) {
+                use_alpha = true;
+                break;
+            }
+        }
+    }
+
+    let mut buffer = Buffer::new(dim(width, height));
+    for y in 0..height as usize {
+        let row = row(y)?;
+        let out = &mut buffer.data[y * width as usize..(y + 1) * width as usize];
+        match bpp {
+            1 | 4 | 8 => {
+                let per_byte = 8 / bpp as usize;
+                let mask = ((1u16 << bpp) - 1) as u8;
+                for (x, px) in out.iter_mut().enumerate() {
+                    let shift = 8 - bpp as usize * (x % per_byte + 1);
+                    let index = (row[x / per_byte] >> shift) & mask;
+                    *px = palette.get(index as usize).copied().unwrap_or(Color::BLACK);
+                }
+            }
+            24 => {
+                for (px, src) in out.iter_mut().zip(row.chunks_exact(3)) {
+                    *px = rgb(src[2], src[1], src[0]);
+                }
+            }
+            _ => {
+                let bytes_pp = bpp as usize / 8;
+                for (px, src) in out.iter_mut().zip(row.chunks_exact(bytes_pp)) {
+                    let value = if bytes_pp == 2 {
+                        u16::from_le_bytes([src[0], src[1]]) as u32
+                    } else {
+                        u32::from_le_bytes([src[0], src[1], src[2], src[3]])
+                    };
+                    let alpha = if use_alpha { a.get(value, 255) } else { 255 };
+                    *px = rgba(r.get(value, 0), g.get(value, 0), b.get(value, 0), alpha);
+                }
+            }
+        }
+    }
+    Ok(buffer)
+}
+
+fn qoi_hash(px: [u8; 4]) -> usize {
+    (px[0] as usize * 3 + px[1] as usize * 5 + px[2] as usize * 7 + px[3] as usize * 11) % 64
+}
+
+fn straight(color: Color) -> [u8; 4] {
+    match color.a {
+        0 => [0; 4],
+        255 => [color.r, color.g, color.b, 255],
+        a => {
+            let un = |x: u8| (x as u32 * 255 / a as u32).min(255) as u8;
+            [un(color.r), un(color.g), un(color.b), a]
+        }
+    }
+}
+
+pub fn is_qoi(data: &[u8]) -> bool {
+    data.get(0..4) == Some(&QOI_MAGIC[..])
+}
+
+pub fn decode_qoi(data: &[u8]) -> Result<Buffer, ImageError> {
+    if !is_qoi(data) {
+        return Err(ImageError::Invalid("signature"));
+    }
+    let header = data.get(4..14).ok_or(ImageError::Truncated)?;
+    let width = u32::from_be_bytes(header[0..4].try_into().unwrap());
+    let height = u32::from_be_bytes(header[4..8].try_into().unwrap());
+    if width == 0 || height == 0 {
+        return Err(ImageError::Invalid("dimensions"));
+    }
+    if width > MAX_DIM as u32 || height > MAX_DIM as u32 {
+        return Err(ImageError::Unsupported("dimensions"));
+    }
+    let mut buffer = Buffer::new(dim(width as i32, height as i32));
+    let mut index = [[0u8; 4]; 64];
+    let mut px = [0u8, 0, 0, 255];
+    let mut run = 0;
+    let mut pos = 14;
+    for out in buffer.data.iter_mut() {
+        if run > 0 {
+            run -= 1;
+        } else {
+            let op = *data.get(pos).ok_or(ImageError::Truncated)?;
+            pos += 1;
+            match op {
+                QOI_OP_RGB => {
+                    px[..3].copy_from_slice(data.get(pos..pos + 3).ok_or(ImageError::Truncated)?);
+                    pos += 3;
+                }
+                QOI_OP_RGBA => {
+                    px.copy_from_slice(data.get(pos..pos + 4).ok_or(ImageError::Truncated)?);
+                    pos += 4;
+                }
+                _ => match op & 0xC0 {
+                    QOI_OP_INDEX => px = index[op as usize],
+                    QOI_OP_DIFF => {
+                        px[0] = px[0].wrapping_add((op >> 4) & 3).wrapping_sub(2);
+                        px[1] = px[1].wrapping_add((op >> 2) & 3).wrapping_sub(2);
+                        px[2] = px[2].wrapping_add(op & 3).wrapping_sub(2);
+                    }
+                    QOI_OP_LUMA => {
+                        let next = *data.get(pos).ok_or(ImageError::Truncated)?;
+                        pos += 1;
+                        let vg = (op & 0x3F).wrapping_sub(32);
+                        px[0] = px[0].wrapping_add(vg.wrapping_sub(8).wrapping_add(next >> 4));
+                        px[1] = px[1].wrapping_add(vg);
+                        px[2] = px[2].wrapping_add(vg.wrapping_sub(8).wrapping_add(next & 0x0F));
+                    }
+                    _ => run = op & 0x3F,
+                },
+            }
+            index[qoi_hash(px)] = px;
+        }
+        *out = rgba(px[0], px[1], px[2], px[3]);
+    }
+    Ok(buffer)
+}
+
+pub fn encode_qoi(buffer: &Buffer) -> Vec<u8> {
+    let opaque = buffer.data.iter().all(|x| x.a == 255);
+    let mut out = Vec::with_capacity(14 + buffer.data.len() + QOI_END.len());
+    out.extend_from_slice(QOI_MAGIC);
+    out.extend_from_slice(&(buffer.dim.w as u32).to_be_bytes());
+    out.extend_from_slice(&(buffer.dim.h as u32).to_be_bytes());
+    out.push(if opaque { 3 } else { 4 });
+    out.push(0);
+    let mut index = [[0u8; 4]; 64];
+    let mut prev = [0u8, 0, 0, 255];
+    let mut run = 0u8;
+    let last = buffer.data.len().saturating_sub(1);
+    for (i, color) in buffer.data.iter().enumerate() {
+        let px = straight(*color);
+        if px == prev {
+            run += 1;
+            if run == 62 || i == last {
+                out.push(QOI_OP_RUN | (run - 1));
+                run = 0;
+            }
+            continue;
+        }
+        if run > 0 {
+            out.push(QOI_OP_RUN | (run - 1));
+            run = 0;
+        }
+        let hash = qoi_hash(px);
+        if index[hash] == px {
+            out.push(QOI_OP_INDEX | hash as u8);
+        } else {
+            index[hash] = px;
+            if px[3] == prev[3] {
+                let vr = px[0].wrapping_sub(prev[0]) as i8;
+                let vg = px[1].wrapping_sub(prev[1]) as i8;
+                let vb = px[2].wrapping_sub(prev[2]) as i8;
+                let vg_r = vr.wrapping_sub(vg);
+                let vg_b = vb.wrapping_sub(vg);
+                if (-2..=1).contains(&vr) && (-2..=1).contains(&vg) && (-2..=1).contains(&vb) {
+                    out.push(QOI_OP_DIFF | (((vr + 2) as u8) << 4) | (((vg + 2) as u8) << 2) | (vb + 2) as u8);
+                } else if (-8..=7).contains(&vg_r) && (-32..=31).contains(&vg) && (-8..=7).contains(&vg_b) {
+                    out.push(QOI_OP_LUMA | (vg + 32) as u8);
+                    out.push((((vg_r + 8) as u8) << 4) | (vg_b + 8) as u8);
+                } else {
+                    out.extend_from_slice(&[QOI_OP_RGB, px[0], px[1], px[2]]);
+                }
+            } else {
+                out.extend_from_slice(&[QOI_OP_RGBA, px[0], px[1], px[2], px[3]]);
+            }
+        }
+        prev = px;
+    }
+    out.extend_from_slice(&QOI_END);
+    out
+}
+
+pub fn decode(data: &[u8]) -> Result<Buffer, ImageError> {
+    #[cfg(feature = "gif")]
+    if is_gif(data) {
+        let (mut frames, _) = decode_gif(data)?;
+        return Ok(frames.swap_remove(0).buffer);
+    }
+    if is_qoi(data) {
+        decode_qoi(data)
+    } else if bmp_size(data).is_some() {
+        decode_bmp(data)
+    } else {
+        Err(ImageError::Unsupported("format"))
+    }
+}
+
+pub fn encode(buffer: &Buffer, format: ImageFormat) -> Vec<u8> {
+    match format {
+        ImageFormat::Bmp => encode_bmp(buffer),
+        ImageFormat::Qoi => encode_qoi(buffer),
+    }
+}
+
+#[cfg(feature = "fs")]
+pub fn save(volume: Handle, path: impl AsRef<EfiPath>, buffer: &Buffer, format: ImageFormat) -> uefi::Result {
+    fs::write(volume, path, &encode(buffer, format))
+}
+
+#[cfg(feature = "fs")]
+pub fn save_qoi(volume: Handle, path: impl AsRef<EfiPath>, buffer: &Buffer) -> uefi::Result {
+    save(volume, path, buffer, ImageFormat::Qoi)
+}
+
+#[cfg(feature = "fs")]
+pub fn save_bmp(volume: Handle, path: impl AsRef<EfiPath>, buffer: &Buffer) -> uefi::Result {
+    save(volume, path, buffer, ImageFormat::Bmp)
+}
+
+#[cfg(feature = "fs")]
+pub fn save_screenshot(volume: Handle, path: impl AsRef<EfiPath>) -> uefi::Result {
+    let path = path.as_ref();
+    let format = path.extension().and_then(ImageFormat::from_extension).unwrap_or(ImageFormat::Qoi);
+    save(volume, path, crate::gfx::Screen::get(), format)
+}
@@ -0,0 +1,369 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::gfx::{dim, pos, rect, rgb, Buffer, Color, Dim, Pos};
+
+use super::{ImageError, MAX_DIM};
+
+const MAX_CODES: usize = 4096;
+const DEFAULT_DELAY: f32 = 0.1;
+const MIN_DELAY: f32 = 0.01;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Disposal {
+    Keep,
+    Background,
+    Previous,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Control {
+    delay: u16,
+    disposal: Disposal,
+    transparent: Option<u8>,
+}
+
+impl Default for Control {
+    fn default() -> Self {
+        Self { delay: 0, disposal: Disposal::Keep, transparent: None }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Frame {
+    pub buffer: Buffer,
+    pub delay: f32,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    off: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn u8(&mut self) -> Result<u8, ImageError> {
+        let x = *self.data.get(self.off).ok_or(ImageError::Truncated)?;
+        self.off += 1;
+        Ok(x)
+    }
+
+    fn u16(&mut self) -> Result<u16, ImageError> {
+        Ok(u16::from_le_bytes([self.u8()?, self.u8()?]))
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], ImageError> {
+        let x = self.data.get(self.off..self.off + len).ok_or(ImageError::Truncated)?;
+        self.off += len;
+        Ok(x)
+    }
+
+    fn palette(&mut self, bits: u8) -> Result<Vec<Color>, ImageError> {
+        let raw = self.bytes(3 << (bits + 1))?;
+        Ok(raw.chunks_exact(3).map(|x| rgb(x[0], x[1], x[2])).collect())
+    }
+
+    fn sub_blocks(&mut self) -> Result<Vec<u8>, ImageError> {
+        let mut out = Vec::new();
+        loop {
+            let len = self.u8()? as usize;
+            if len == 0 {
+                return Ok(out);
+            }
+            out.extend_from_slice(self.bytes(len)?);
+        }
+    }
+
+    fn skip_sub_blocks(&mut self) -> Result<(), ImageError> {
+        loop {
+            let len = self.u8()? as usize;
+            if len == 0 {
+                return Ok(());
+            }
+            self.bytes(len)?;
+        }
+    }
+}
+
+fn lzw_decode(min_size: u8, data: &[u8], len: usize) -> Result<Vec<u8>, ImageError> {
+    if !(1..=11).contains(&min_size) {
+        return Err(ImageError::Invalid("LZW code size"));
+    }
+    let clear = 1usize << min_size;
+    let end = clear + 1;
+    let mut prefix = vec![0u16; MAX_CODES];
+    let mut suffix = vec![0u8; MAX_CODES];
+    for (i, x) in suffix.iter_mut().enumerate().take(clear) {
+        *x = i as u8;
+    }
+    let mut stack = vec![0u8; MAX_CODES];
+    let mut out = Vec::with_capacity(len);
+    let mut size = min_size as u32 + 1;
+    let mut next = end + 1;
+    let mut prev: Option<usize> = None;
+    let (mut acc, mut bits, mut off) = (0u32, 0u32, 0usize);
+    while out.len() < len {
+        while bits < size {
+            let byte = if let Some(x) = data.get(off) { *x } else { return Ok(out); };
+            acc |= (byte as u32) << bits;
+            bits += 8;
+            off += 1;
+        }
+        let code = (acc & ((1 << size) - 1)) as usize;
+        acc >>= size;
+        bits -= size;
+        if code == clear {
+            size = min_size as u32 + 1;
+            next = end + 1;
+            prev = None;
+            continue;
+        }
+        if code == end {
+            break;
+        }
+        let p = if let Some(x) = prev { x } else {
+            if code > clear {
+                return Err(ImageError::Invalid("LZW code"));
+            }
+            out.push(code as u8);
+            prev = Some(code);
+            continue;
+        };
+        let mut c = match code {
+            x if x < next => x,
+            x if x == next => p,
+            _ => return Err(ImageError::Invalid("LZW code")),
+        };
+        let mut n = 0;
+        loop {
+            stack[n] = suffix[c];
+            n += 1;
+            if c < clear {
+                break;
+            }
+            c = prefix[c] as usize;
+        }
+        let first = stack[n - 1];
+        out.extend(stack[..n].iter().rev());
+        if code == next {
+            out.push(first);
+        }
+        if next < MAX_CODES {
+            prefix[next] = p as u16;
+            suffix[next] = first;
+            next += 1;
+            if next == 1 << size && size < 12 {
+                size += 1;
+            }
+        }
+        prev = Some(code);
+    }
+    out.truncate(len);
+    Ok(out)
+}
+
+fn interlaced_rows(h: usize) -> Vec<usize> {
+    [(0, 8), (4, 8), (2, 4), (1, 2)].iter()
+        .flat_map(|&(start, step)| (start..h).step_by(step))
+        .collect()
+}
+
+pub fn is_gif(data: &[u8]) -> bool {
+    data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")
+}
+
+pub fn decode_gif(data: &[u8]) -> Result<(Vec<Frame>, Option<u32>), ImageError> {
+    if !is_gif(data) {
+        return Err(ImageError::Invalid("signature"));
+    }
+    let mut r = Reader { data, off: 6 };
+    let width = r.u16()? as i32;
+    let height = r.u16()? as i32;
+    let flags = r.u8()?;
+    r.u8()?;
+    r.u8()?;
+    if width == 0 || height == 0 {
+        return Err(ImageError::Invalid("dimensions"));
+    }
+    if width > MAX_DIM || height > MAX_DIM {
+        return Err(ImageError::Unsupported("dimensions"));
+    }
+    let global = if flags & 0x80 != 0 { Some(r.palette(flags & 7)?) } else { None };
+    let mut canvas = Buffer::new_cleared(dim(width, height), Color::default());
+    let mut frames = Vec::new();
+    let mut plays = Some(1);
+    let mut control = Control::default();
+    loop {
+        match r.u8()? {
+            0x21 => match r.u8()? {
+                0xF9 => {
+                    let block = r.sub_blocks()?;
+                    if block.len() < 4 {
+                        return Err(ImageError::Invalid("graphic control"));
+                    }
+                    control = Control {
+                        delay: u16::from_le_bytes([block[1], block[2]]),
+                        disposal: match (block[0] >> 2) & 7 {
+                            2 => Disposal::Background,
+                            3 => Disposal::Previous,
+                            _ => Disposal::Keep,
+                        },
+                        transparent: (block[0] & 1 != 0).then_some(block[3]),
+                    };
+                }
+                0xFF => {
+                    let block = r.sub_blocks()?;
+                    let ident = block.get(..11);
+                    if (ident == Some(&b"NETSCAPE2.0"[..]) || ident == Some(&b"ANIMEXTS1.0"[..]))
+                        && block.get(11) == Some(&1)
+                        && block.len() >= 14
+                    {
+                        let count = u16::from_le_bytes([block[12], block[13]]) as u32;
+                        plays = if count == 0 { None } else { Some(count + 1) };
+                    }
+                }
+                _ => r.skip_sub_blocks()?,
+            },
+            0x2C => {
+                let left = r.u16()? as i32;
+                let top = r.u16()? as i32;
+                let w = r.u16()? as usize;
+                let h = r.u16()? as usize;
+                let flags = r.u8()?;
+                let local = if flags & 0x80 != 0 { Some(r.palette(flags & 7)?) } else { None };
+                let palette = local.as_ref().or(global.as_ref())
+                    .ok_or(ImageError::Invalid("missing palette"))?;
+                let min_size = r.u8()?;
+                let indices = lzw_decode(min_size, &r.sub_blocks()?, w * h)?;
+                let previous = (control.disposal == Disposal::Previous).then(|| canvas.clone());
+                let rows = if flags & 0x40 != 0 { interlaced_rows(h) } else { (0..h).collect() };
+                for (i, y) in rows.into_iter().enumerate() {
+                    let y = top + y as i32;
+                    if y >= height {
+                        break;
+                    }
+                    for x in 0..w {
+                        let x_abs = left + x as i32;
+                        if x_abs >= width {
+                            break;
+                        }
+                        let index = if let Some(x) = indices.get(i * w + x) { *x } else { break; };
+                        if control.transparent == Some(index) {
+                            continue;
+                        }
+                        if let Some(color) = palette.get(index as usize) {
+                            canvas.data[(x_abs + y * width) as usize] = *color;
+                        }
+                    }
+                }
+                let delay = if control.delay <= 1 { DEFAULT_DELAY } else { control.delay as f32 / 100.0 };
+                frames.push(Frame { buffer: canvas.clone(), delay });
+                match control.disposal {
+                    Disposal::Keep => {}
+                    Disposal::Background => {
+                        canvas.fill(rect(pos(left, top), dim(w as i32, h as i32)).area(), Color::default());
+                    }
+                    Disposal::Previous => {
+                        if let Some(x) = previous {
+                            canvas = x;
+                        }
+                    }
+                }
+                control = Control::default();
+            }
+            0x3B => break,
+            _ => return Err(ImageError::Invalid("block")),
+        }
+    }
+    if frames.is_empty() {
+        return Err(ImageError::Invalid("no frames"));
+    }
+    Ok((frames, plays))
+}
+
+#[derive(Debug, Clone)]
+pub struct Animation {
+    frames: Vec<Frame>,
+    plays: Option<u32>,
+    current: usize,
+    elapsed: f32,
+    played: u32,
+}
+
+impl Animation {
+    pub fn new(frames: Vec<Frame>, plays: Option<u32>) -> Option<Self> {
+        if frames.is_empty() {
+            return None;
+        }
+        Some(Self { frames, plays, current: 0, elapsed: 0.0, played: 0 })
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, ImageError> {
+        let (frames, plays) = decode_gif(data)?;
+        Self::new(frames, plays).ok_or(ImageError::Invalid("no frames"))
+    }
+
+    pub fn set_plays(&mut self, plays: Option<u32>) {
+        self.plays = plays;
+    }
+
+    pub fn dim(&self) -> Dim {
+        self.frames[0].buffer.dim
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn index(&self) -> usize {
+        self.current
+    }
+
+    pub fn frame(&self) -> &Buffer {
+        &self.frames[self.current].buffer
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|x| x.delay).sum()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.plays.is_some_and(|x| self.played >= x)
+    }
+
+    pub fn reset(&mut self) {
+        self.current = 0;
+        self.elapsed = 0.0;
+        self.played = 0;
+    }
+
+    pub fn tick(&mut self, dt: f32) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        let start = self.current;
+        self.elapsed += dt;
+        loop {
+            let delay = self.frames[self.current].delay.max(MIN_DELAY);
+            if self.elapsed < delay {
+                break;
+            }
+            self.elapsed -= delay;
+            if self.current + 1 < self.frames.len() {
+                self.current += 1;
+                continue;
+            }
+            self.played += 1;
+            if self.is_finished() {
+                self.elapsed = 0.0;
+                break;
+            }
+            self.current = 0;
+        }
+        self.current != start
+    }
+
+    pub fn draw(&self, buffer: &mut Buffer, pos: Pos) {
+        let frame = self.frame();
+        buffer.premultiplied_over(frame, frame.area(), pos);
+    }
+}
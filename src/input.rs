@@ -0,0 +1,125 @@
+use uefi::proto::console::text::{Key, ScanCode};
+
+use crate::gfx::Pos;
+
+pub mod event;
+pub mod keyboard;
+pub mod keystate;
+pub mod mouse;
+pub mod record;
+pub mod serial;
+pub mod touch;
+pub mod wait;
+
+pub use event::{Event, EventLoop, TimerId};
+pub use keyboard::{KeyEvent, Keyboard, Modifiers, Toggles};
+pub use keystate::KeyState;
+pub use mouse::Mouse;
+pub use record::{Recorder, Replayer};
+pub use serial::SerialConsole;
+pub use touch::{Gesture, GestureRecognizer, SwipeDirection, Touch};
+pub use wait::{Source, WaitMode, WaitSet};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum KeyCode {
+    Char(char),
+    Enter,
+    Escape,
+    Backspace,
+    Tab,
+    BackTab,
+    Insert,
+    Delete,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    F(u8),
+}
+
+impl KeyCode {
+    pub fn is_activate(self) -> bool {
+        matches!(self, KeyCode::Enter | KeyCode::Char(' '))
+    }
+
+    pub fn from_key(key: Key) -> Option<Self> {
+        match key {
+            Key::Printable(ch) => match char::from(ch) {
+                '\r' | '\n' => Some(KeyCode::Enter),
+                '\x08' => Some(KeyCode::Backspace),
+                '\t' => Some(KeyCode::Tab),
+                '\x1b' => Some(KeyCode::Escape),
+                '\0' => None,
+                ch => Some(KeyCode::Char(ch)),
+            },
+            Key::Special(code) => Some(match code {
+                ScanCode::UP => KeyCode::Up,
+                ScanCode::DOWN => KeyCode::Down,
+                ScanCode::LEFT => KeyCode::Left,
+                ScanCode::RIGHT => KeyCode::Right,
+                ScanCode::HOME => KeyCode::Home,
+                ScanCode::END => KeyCode::End,
+                ScanCode::INSERT => KeyCode::Insert,
+                ScanCode::DELETE => KeyCode::Delete,
+                ScanCode::PAGE_UP => KeyCode::PageUp,
+                ScanCode::PAGE_DOWN => KeyCode::PageDown,
+                ScanCode::ESCAPE => KeyCode::Escape,
+                ScanCode::FUNCTION_1 => KeyCode::F(1),
+                ScanCode::FUNCTION_2 => KeyCode::F(2),
+                ScanCode::FUNCTION_3 => KeyCode::F(3),
+                ScanCode::FUNCTION_4 => KeyCode::F(4),
+                ScanCode::FUNCTION_5 => KeyCode::F(5),
+                ScanCode::FUNCTION_6 => KeyCode::F(6),
+                ScanCode::FUNCTION_7 => KeyCode::F(7),
+                ScanCode::FUNCTION_8 => KeyCode::F(8),
+                ScanCode::FUNCTION_9 => KeyCode::F(9),
+                ScanCode::FUNCTION_10 => KeyCode::F(10),
+                ScanCode::FUNCTION_11 => KeyCode::F(11),
+                ScanCode::FUNCTION_12 => KeyCode::F(12),
+                _ => return None,
+            }),
+        }
+    }
+}
+
+pub fn wait_key() -> crate::Result<KeyCode> {
+    Keyboard::read_key()
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PointerButton {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PointerEvent {
+    Move(Pos),
+    Down(Pos, PointerButton),
+    Up(Pos, PointerButton),
+    Wheel(Pos, i32),
+}
+
+impl PointerEvent {
+    pub fn pos(self) -> Pos {
+        match self {
+            PointerEvent::Move(pos) => pos,
+            PointerEvent::Down(pos, _) => pos,
+            PointerEvent::Up(pos, _) => pos,
+            PointerEvent::Wheel(pos, _) => pos,
+        }
+    }
+
+    pub fn translate(self, off: Pos) -> Self {
+        match self {
+            PointerEvent::Move(pos) => PointerEvent::Move(pos + off),
+            PointerEvent::Down(pos, button) => PointerEvent::Down(pos + off, button),
+            PointerEvent::Up(pos, button) => PointerEvent::Up(pos + off, button),
+            PointerEvent::Wheel(pos, delta) => PointerEvent::Wheel(pos + off, delta),
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::env;
+use crate::gfx::{Dim, Screen};
+use crate::input::{
+    Gesture, GestureRecognizer, KeyCode, Keyboard, Mouse, PointerEvent, SerialConsole, Source, Touch, WaitMode, WaitSet,
+};
+use crate::timer::{self, Timer};
+
+pub type TimerId = usize;
+
+const GESTURE_TICK_US: u64 = 20_000;
+const SERIAL_POLL_US: u64 = 10_000;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Event {
+    Key(KeyCode),
+    Pointer(PointerEvent),
+    Gesture(Gesture),
+    Timer(TimerId),
+    ModeChange(Dim),
+    Custom(u32),
+}
+
+struct Slot {
+    id: TimerId,
+    timer: Timer,
+    callback: Option<Box<dyn FnMut()>>,
+}
+
+pub struct EventLoop {
+    timers: Vec<Slot>,
+    pending: VecDeque<Event>,
+    mode: Option<Dim>,
+    mouse: Option<Mouse>,
+    touch: Option<Touch>,
+    gestures: GestureRecognizer,
+    gesture_timer: Option<TimerId>,
+    serial: Option<SerialConsole>,
+    serial_timer: Option<TimerId>,
+    sources: Vec<(u32, uefi::Event)>,
+    wait_mode: WaitMode,
+    idle: Option<Box<dyn FnMut()>>,
+    next_timer: TimerId,
+}
+
+impl Default for EventLoop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn screen_dim() -> Option<Dim> {
+    if Screen::is_init() { Some(Screen::get().dim) } else { None }
+}
+
+impl EventLoop {
+    pub fn new() -> Self {
+        Self {
+            timers: Vec::new(),
+            pending: VecDeque::new(),
+            mode: screen_dim(),
+            mouse: None,
+            touch: None,
+            gestures: GestureRecognizer::new(),
+            gesture_timer: None,
+            serial: None,
+            serial_timer: None,
+            sources: Vec::new(),
+            wait_mode: WaitMode::Block,
+            idle: None,
+            next_timer: 0,
+        }
+    }
+
+    pub fn wait_mode(mut self, mode: WaitMode) -> Self {
+        self.wait_mode = mode;
+        self
+    }
+
+    pub fn set_wait_mode(&mut self, mode: WaitMode) {
+        self.wait_mode = mode;
+    }
+
+    pub fn on_idle(&mut self, f: impl FnMut() + 'static) {
+        self.idle = Some(Box::new(f));
+    }
+
+    pub fn clear_idle(&mut self) {
+        self.idle = None;
+    }
+
+    pub fn with_mouse(mut self) -> Self {
+        self.mouse = Mouse::open();
+        self
+    }
+
+    pub fn set_mouse(&mut self, mouse: Option<Mouse>) {
+        self.mouse = mouse;
+    }
+
+    pub fn mouse(&mut self) -> Option<&mut Mouse> {
+        self.mouse.as_mut()
+    }
+
+    pub fn with_touch(mut self) -> Self {
+        self.touch = Touch::open();
+        self
+    }
+
+    pub fn set_touch(&mut self, touch: Option<Touch>) {
+        self.touch = touch;
+    }
+
+    pub fn touch(&mut self) -> Option<&mut Touch> {
+        self.touch.as_mut()
+    }
+
+    pub fn with_serial(mut self) -> crate::Result<Self> {
+        self.set_serial(SerialConsole::open())?;
+        Ok(self)
+    }
+
+    pub fn set_serial(&mut self, serial: Option<SerialConsole>) -> crate::Result {
+        if let Some(id) = self.serial_timer.take() {
+            self.remove_timer(id);
+        }
+        if serial.is_some() {
+            self.serial_timer = Some(self.add_timer(SERIAL_POLL_US, true)?);
+        }
+        self.serial = serial;
+        Ok(())
+    }
+
+    pub fn serial(&mut self) -> Option<&mut SerialConsole> {
+        self.serial.as_mut()
+    }
+
+    pub fn gestures(&mut self) -> &mut GestureRecognizer {
+        &mut self.gestures
+    }
+
+    fn sync_gesture_timer(&mut self) -> crate::Result {
+        match (self.gestures.busy(), self.gesture_timer) {
+            (true, None) => self.gesture_timer = Some(self.add_timer(GESTURE_TICK_US, true)?),
+            (false, Some(id)) => {
+                self.remove_timer(id);
+                self.gesture_timer = None;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn insert_timer(&mut self, timer: Timer, callback: Option<Box<dyn FnMut()>>) -> TimerId {
+        let id = self.next_timer;
+        self.next_timer += 1;
+        self.timers.push(Slot { id, timer, callback });
+        id
+    }
+
+    pub fn add_timer(&mut self, period_us: u64, repeat: bool) -> crate::Result<TimerId> {
+        let timer = if repeat { timer::periodic(period_us)? } else { timer::one_shot(period_us)? };
+        Ok(self.insert_timer(timer, None))
+    }
+
+    pub fn attach(&mut self, timer: Timer) -> TimerId {
+        self.insert_timer(timer, None)
+    }
+
+    pub fn every(&mut self, interval_us: u64, f: impl FnMut() + 'static) -> crate::Result<TimerId> {
+        Ok(self.insert_timer(timer::periodic(interval_us)?, Some(Box::new(f))))
+    }
+
+    pub fn after(&mut self, delay_us: u64, f: impl FnMut() + 'static) -> crate::Result<TimerId> {
+        Ok(self.insert_timer(timer::one_shot(delay_us)?, Some(Box::new(f))))
+    }
+
+    pub fn timer(&mut self, id: TimerId) -> Option<&mut Timer> {
+        self.timers.iter_mut().find(|x| x.id == id).map(|x| &mut x.timer)
+    }
+
+    pub fn remove_timer(&mut self, id: TimerId) -> Option<Timer> {
+        let index = self.timers.iter().position(|x| x.id == id)?;
+        Some(self.timers.remove(index).timer)
+    }
+
+    fn dispatch(&mut self, id: TimerId) -> bool {
+        let slot = if let Some(x) = self.timers.iter_mut().find(|x| x.id == id) { x } else { return false; };
+        let repeat = slot.timer.is_periodic();
+        let handled = if let Some(f) = slot.callback.as_mut() {
+            f();
+            true
+        } else {
+            false
+        };
+        if !repeat {
+            self.remove_timer(id);
+        }
+        handled
+    }
+
+    pub fn add_source(&mut self, tag: u32, event: uefi::Event) {
+        self.sources.push((tag, event));
+    }
+
+    pub fn remove_source(&mut self, tag: u32) -> Option<uefi::Event> {
+        let index = self.sources.iter().position(|x| x.0 == tag)?;
+        Some(self.sources.remove(index).1)
+    }
+
+    pub fn post(&mut self, event: Event) {
+        self.pending.push_back(event);
+    }
+
+    fn check_mode(&mut self) {
+        let dim = screen_dim();
+        if dim != self.mode {
+            self.mode = dim;
+            if let Some(dim) = dim {
+                if let Some(mouse) = self.mouse.as_mut() {
+                    mouse.bounds = Screen::rect();
+                    mouse.set_pos(mouse.pos());
+                }
+                if let Some(touch) = self.touch.as_mut() {
+                    touch.bounds = Screen::rect();
+                }
+                self.pending.push_back(Event::ModeChange(dim));
+            }
+        }
+    }
+
+    pub fn poll(&mut self) -> Option<Event> {
+        self.check_mode();
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+        if let Some(key) = Keyboard::poll_key() {
+            return Some(Event::Key(key));
+        }
+        if let Some(key) = self.serial.as_mut().and_then(|x| x.poll_key()) {
+            return Some(Event::Key(key));
+        }
+        if let Some(event) = self.mouse.as_mut().and_then(|x| x.poll()) {
+            return Some(Event::Pointer(event));
+        }
+        let event = self.touch.as_mut()?.poll()?;
+        if let Some(gesture) = self.gestures.pointer(event) {
+            self.pending.push_back(Event::Gesture(gesture));
+        }
+        Some(Event::Pointer(event))
+    }
+
+    pub fn next(&mut self, timeout_us: Option<u64>) -> crate::Result<Option<Event>> {
+        let timeout = timeout_us.map(timer::one_shot).transpose()?;
+        loop {
+            if let Some(event) = self.poll() {
+                return Ok(Some(event));
+            }
+            self.sync_gesture_timer()?;
+            let mut set = WaitSet::new();
+            set.keyboard()?;
+            if let Some(event) = self.mouse.as_ref().and_then(|x| x.wait_event()) {
+                set.pointer(0, &event);
+            }
+            if let Some(event) = self.touch.as_ref().and_then(|x| x.wait_event()) {
+                set.pointer(1, &event);
+            }
+            for slot in self.timers.iter() {
+                set.timer(slot.id, slot.timer.event());
+            }
+            for (tag, event) in self.sources.iter() {
+                set.custom(*tag, event);
+            }
+            if let Some(timeout) = timeout.as_ref() {
+                set.add(Source::Timeout, timeout.event());
+            }
+            loop {
+                let idle = &mut self.idle;
+                let source = set.wait_idle(self.wait_mode, || {
+                    if let Some(f) = idle.as_mut() {
+                        f();
+                    }
+                })?;
+                match source {
+                    Source::Keyboard | Source::Pointer(_) => {
+                        if let Some(event) = self.poll() {
+                            return Ok(Some(event));
+                        }
+                    }
+                    Source::Timer(id) if Some(id) == self.serial_timer => {
+                        if let Some(event) = self.poll() {
+                            return Ok(Some(event));
+                        }
+                    }
+                    Source::Timer(id) if Some(id) == self.gesture_timer => {
+                        if let Some(gesture) = self.gestures.tick(GESTURE_TICK_US as f32 / 1_000_000.0) {
+                            return Ok(Some(Event::Gesture(gesture)));
+                        }
+                        break;
+                    }
+                    Source::Timer(id) => {
+                        if self.dispatch(id) {
+                            break;
+                        }
+                        return Ok(Some(Event::Timer(id)));
+                    }
+                    Source::Custom(tag) => return Ok(Some(Event::Custom(tag))),
+                    Source::Timeout => return Ok(self.poll()),
+                }
+            }
+        }
+    }
+
+    pub fn wait(&mut self) -> crate::Result<Event> {
+        loop {
+            if let Some(event) = self.next(None)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    pub fn run(&mut self, mut f: impl FnMut(&mut Self, Event) -> bool) -> crate::Result {
+        loop {
+            let event = self.wait()?;
+            if !f(self, event) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Drop for EventLoop {
+    fn drop(&mut self) {
+        let st = if let Some(x) = env::try_system_table() { x } else { return; };
+        for (_, event) in self.sources.drain(..) {
+            let _ = st.boot_services().close_event(event);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use core::ffi::c_void;
+use core::ptr;
+
+use uefi::proto::console::text::{Key, ScanCode};
+use uefi::proto::unsafe_protocol;
+use uefi::table::boot::{OpenProtocolAttributes, OpenProtocolParams};
+use uefi::{Char16, ResultExt, Status};
+
+use crate::env;
+use crate::input::KeyCode;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub logo: bool,
+}
+
+impl Modifiers {
+    pub fn none(self) -> bool {
+        self == Self::default()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Toggles {
+    pub caps_lock: bool,
+    pub num_lock: bool,
+    pub scroll_lock: bool,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: Modifiers,
+    pub toggles: Option<Toggles>,
+}
+
+const SHIFT_VALID: u32 = 0x8000_0000;
+const TOGGLE_VALID: u8 = 0x80;
+
+#[repr(C)]
+struct RawKeyData {
+    scan_code: u16,
+    unicode_char: u16,
+    shift_state: u32,
+    toggle_state: u8,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+#[unsafe_protocol("dd9e7534-7762-4698-8c14-f58517a625aa")]
+struct InputEx {
+    reset: unsafe extern "efiapi" fn(*mut InputEx, u8) -> Status,
+    read_key_stroke_ex: unsafe extern "efiapi" fn(*mut InputEx, *mut RawKeyData) -> Status,
+    wait_for_key_ex: *mut c_void,
+    set_state: unsafe extern "efiapi" fn(*mut InputEx, *const u8) -> Status,
+    register_key_notify: *const c_void,
+    unregister_key_notify: *const c_void,
+}
+
+static mut INPUT_EX: Option<*mut InputEx> = None;
+static mut PROBED: bool = false;
+
+fn input_ex() -> Option<*mut InputEx> {
+    unsafe {
+        if !PROBED {
+            PROBED = true;
+            INPUT_EX = open_input_ex();
+        }
+        INPUT_EX
+    }
+}
+
+pub(crate) fn release() {
+    unsafe {
+        PROBED = true;
+        INPUT_EX = None;
+    }
+}
+
+fn open_input_ex() -> Option<*mut InputEx> {
+    let st = env::try_system_table()?;
+    let bs = st.boot_services();
+    let handle = bs.get_handle_for_protocol::<InputEx>().ok()?;
+    let params = OpenProtocolParams { handle, agent: bs.image_handle(), controller: None };
+    let mut protocol = unsafe {
+        bs.open_protocol::<InputEx>(params, OpenProtocolAttributes::GetProtocol).ok()?
+    };
+    let raw = &mut *protocol as *mut InputEx;
+    core::mem::forget(protocol);
+    Some(raw)
+}
+
+impl KeyEvent {
+    fn from_raw(data: &RawKeyData) -> Option<Self> {
+        let key = if data.scan_code != 0 {
+            Key::Special(ScanCode(data.scan_code))
+        } else {
+            Key::Printable(Char16::try_from(data.unicode_char).ok()?)
+        };
+        let mut code = KeyCode::from_key(key)?;
+        let shift = data.shift_state;
+        let modifiers = if shift & SHIFT_VALID != 0 {
+            Modifiers {
+                shift: shift & 0x03 != 0,
+                ctrl: shift & 0x0C != 0,
+                alt: shift & 0x30 != 0,
+                logo: shift & 0xC0 != 0,
+            }
+        } else {
+            Modifiers::default()
+        };
+        if code == KeyCode::Tab && modifiers.shift {
+            code = KeyCode::BackTab;
+        }
+        let toggle = data.toggle_state;
+        let toggles = (toggle & TOGGLE_VALID != 0).then_some(Toggles {
+            scroll_lock: toggle & 0x01 != 0,
+            num_lock: toggle & 0x02 != 0,
+            caps_lock: toggle & 0x04 != 0,
+        });
+        Some(Self { code, modifiers, toggles })
+    }
+}
+
+pub struct Keyboard {}
+
+impl Keyboard {
+    pub fn has_modifiers() -> bool {
+        input_ex().is_some()
+    }
+
+    pub fn poll_key_event() -> Option<KeyEvent> {
+        if let Some(ex) = input_ex() {
+            loop {
+                let mut data = RawKeyData { scan_code: 0, unicode_char: 0, shift_state: 0, toggle_state: 0 };
+                let status = unsafe { ((*ex).read_key_stroke_ex)(ex, &mut data) };
+                if status != Status::SUCCESS {
+                    return None;
+                }
+                if let Some(event) = KeyEvent::from_raw(&data) {
+                    return Some(event);
+                }
+            }
+        }
+        let mut st = env::try_system_table()?;
+        loop {
+            let key = st.stdin().read_key().ok().flatten()?;
+            if let Some(code) = KeyCode::from_key(key) {
+                return Some(KeyEvent { code, modifiers: Modifiers::default(), toggles: None });
+            }
+        }
+    }
+
+    pub fn read_key_event() -> crate::Result<KeyEvent> {
+        let st = env::system_table()?;
+        loop {
+            if let Some(event) = Self::poll_key_event() {
+                return Ok(event);
+            }
+            let mut events = [Self::wait_event()?];
+            st.boot_services().wait_for_event(&mut events).discard_errdata()?;
+        }
+    }
+
+    pub fn poll_key() -> Option<KeyCode> {
+        Self::poll_key_event().map(|x| x.code)
+    }
+
+    pub fn read_key() -> crate::Result<KeyCode> {
+        Ok(Self::read_key_event()?.code)
+    }
+
+    pub fn wait_event() -> crate::Result<uefi::Event> {
+        if let Some(ex) = input_ex() {
+            if let Some(event) = unsafe { uefi::Event::from_ptr((*ex).wait_for_key_ex) } {
+                return Ok(event);
+            }
+        }
+        let mut st = env::system_table()?;
+        Ok(st.stdin().wait_for_key_event().ok_or(Status::UNSUPPORTED)?)
+    }
+
+    pub fn set_toggles(toggles: Toggles) -> bool {
+        let ex = if let Some(x) = input_ex() { x } else { return false; };
+        let state = TOGGLE_VALID
+            | toggles.scroll_lock as u8
+            | (toggles.num_lock as u8) << 1
+            | (toggles.caps_lock as u8) << 2;
+        unsafe { ((*ex).set_state)(ex, ptr::addr_of!(state)) == Status::SUCCESS }
+    }
+
+    pub fn flush() {
+        while Self::poll_key_event().is_some() {}
+    }
+
+    pub fn reset() -> crate::Result {
+        if let Some(ex) = input_ex() {
+            return Ok(unsafe { ((*ex).reset)(ex, 0) }.to_result()?);
+        }
+        let mut st = env::system_table()?;
+        Ok(st.stdin().reset(false)?)
+    }
+}
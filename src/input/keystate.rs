@@ -0,0 +1,80 @@
+use alloc::vec::Vec;
+
+use crate::input::{Event, KeyCode};
+
+struct Held {
+    key: KeyCode,
+    age: f32,
+    repeating: bool,
+}
+
+pub struct KeyState {
+    pub initial_timeout: f32,
+    pub repeat_timeout: f32,
+    held: Vec<Held>,
+}
+
+impl Default for KeyState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyState {
+    pub fn new() -> Self {
+        Self {
+            initial_timeout: 0.6,
+            repeat_timeout: 0.15,
+            held: Vec::new(),
+        }
+    }
+
+    pub fn timeouts(mut self, initial: f32, repeat: f32) -> Self {
+        self.initial_timeout = initial;
+        self.repeat_timeout = repeat;
+        self
+    }
+
+    pub fn press(&mut self, key: KeyCode) {
+        if let Some(held) = self.held.iter_mut().find(|x| x.key == key) {
+            held.age = 0.0;
+            held.repeating = true;
+            return;
+        }
+        self.held.push(Held { key, age: 0.0, repeating: false });
+    }
+
+    pub fn release(&mut self, key: KeyCode) {
+        self.held.retain(|x| x.key != key);
+    }
+
+    pub fn event(&mut self, event: &Event) {
+        if let Event::Key(key) = event {
+            self.press(*key);
+        }
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        let (initial, repeat) = (self.initial_timeout, self.repeat_timeout);
+        self.held.retain_mut(|x| {
+            x.age += dt;
+            x.age < if x.repeating { repeat } else { initial }
+        });
+    }
+
+    pub fn is_down(&self, key: KeyCode) -> bool {
+        self.held.iter().any(|x| x.key == key)
+    }
+
+    pub fn any_down(&self) -> bool {
+        !self.held.is_empty()
+    }
+
+    pub fn held(&self) -> impl Iterator<Item = KeyCode> + '_ {
+        self.held.iter().map(|x| x.key)
+    }
+
+    pub fn clear(&mut self) {
+        self.held.clear();
+    }
+}
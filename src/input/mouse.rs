@@ -0,0 +1,125 @@
+use alloc::collections::VecDeque;
+
+use uefi::proto::console::pointer::Pointer;
+use uefi::table::boot::{OpenProtocolAttributes, OpenProtocolParams};
+
+use crate::env;
+use crate::gfx::{dim, pos, rect, Pos, Rect, Screen};
+use crate::input::{PointerButton, PointerEvent};
+
+const BUTTONS: [PointerButton; 2] = [PointerButton::Left, PointerButton::Right];
+
+pub struct Mouse {
+    pub bounds: Rect,
+    pub sensitivity: f32,
+    pub wheel_sensitivity: f32,
+    pointer: *mut Pointer,
+    pos: Pos,
+    buttons: [bool; 2],
+    remainder: [f32; 3],
+    pending: VecDeque<PointerEvent>,
+}
+
+impl Mouse {
+    pub fn open() -> Option<Self> {
+        let st = env::try_system_table()?;
+        let bs = st.boot_services();
+        let handle = bs.get_handle_for_protocol::<Pointer>().ok()?;
+        let params = OpenProtocolParams { handle, agent: bs.image_handle(), controller: None };
+        let mut protocol = unsafe {
+            bs.open_protocol::<Pointer>(params, OpenProtocolAttributes::GetProtocol).ok()?
+        };
+        protocol.reset(false).ok()?;
+        let pointer = &mut *protocol as *mut Pointer;
+        core::mem::forget(protocol);
+        let bounds = if Screen::is_init() { Screen::rect() } else { rect(pos(0, 0), dim(0, 0)) };
+        Some(Self {
+            bounds,
+            sensitivity: 1.0,
+            wheel_sensitivity: 1.0,
+            pointer,
+            pos: bounds.centered(dim(0, 0)).pos,
+            buttons: [false; 2],
+            remainder: [0.0; 3],
+            pending: VecDeque::new(),
+        })
+    }
+
+    pub fn sensitivity(mut self, sensitivity: f32) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    pub fn bounds(mut self, bounds: Rect) -> Self {
+        self.bounds = bounds;
+        self.pos = self.clamp(self.pos);
+        self
+    }
+
+    pub fn pos(&self) -> Pos {
+        self.pos
+    }
+
+    pub fn set_pos(&mut self, at: Pos) {
+        self.pos = self.clamp(at);
+    }
+
+    pub fn pressed(&self, button: PointerButton) -> bool {
+        self.buttons[BUTTONS.iter().position(|x| *x == button).unwrap()]
+    }
+
+    pub fn has_button(&self, button: PointerButton) -> bool {
+        let index = BUTTONS.iter().position(|x| *x == button).unwrap();
+        unsafe { (*self.pointer).mode().has_button[index] }
+    }
+
+    pub fn wait_event(&self) -> Option<uefi::Event> {
+        unsafe { (*self.pointer).wait_for_input_event() }
+    }
+
+    fn clamp(&self, at: Pos) -> Pos {
+        let b = self.bounds;
+        let x = at.x.min(b.pos.x + b.dim.w - 1).max(b.pos.x);
+        let y = at.y.min(b.pos.y + b.dim.h - 1).max(b.pos.y);
+        pos(x, y)
+    }
+
+    fn scaled(&mut self, axis: usize, counts: i32, sensitivity: f32) -> i32 {
+        let total = self.remainder[axis] + counts as f32 * sensitivity;
+        let whole = total as i32;
+        self.remainder[axis] = total - whole as f32;
+        whole
+    }
+
+    fn read(&mut self) {
+        let state = if let Ok(Some(x)) = unsafe { (*self.pointer).read_state() } { x } else { return; };
+        let [dx, dy, dz] = state.relative_movement;
+        let dx = self.scaled(0, dx, self.sensitivity);
+        let dy = self.scaled(1, dy, self.sensitivity);
+        let dz = self.scaled(2, dz, self.wheel_sensitivity);
+        let moved = self.clamp(self.pos + pos(dx, dy));
+        if moved != self.pos {
+            self.pos = moved;
+            self.pending.push_back(PointerEvent::Move(moved));
+        }
+        if dz != 0 {
+            self.pending.push_back(PointerEvent::Wheel(self.pos, dz));
+        }
+        for (i, button) in BUTTONS.iter().enumerate() {
+            let down = state.button[i];
+            if down == self.buttons[i] {
+                continue;
+            }
+            self.buttons[i] = down;
+            let event = if down { PointerEvent::Down(self.pos, *button) } else { PointerEvent::Up(self.pos, *button) };
+            self.pending.push_back(event);
+        }
+    }
+
+    pub fn poll(&mut self) -> Option<PointerEvent> {
+        if self.pending.is_empty() {
+            self.read();
+        }
+        self.pending.pop_front()
+    }
+}
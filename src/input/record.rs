@@ -0,0 +1,320 @@
+use alloc::vec::Vec;
+
+use crate::gfx::{dim, pos, Pos};
+use crate::input::{Event, EventLoop, Gesture, KeyCode, PointerButton, PointerEvent, SwipeDirection};
+
+const MAGIC: &[u8; 4] = b"UEVR";
+const VERSION: u8 = 1;
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn put_signed(out: &mut Vec<u8>, value: i32) {
+    put_varint(out, ((value << 1) ^ (value >> 31)) as u32 as u64);
+}
+
+fn put_pos(out: &mut Vec<u8>, at: Pos) {
+    put_signed(out, at.x);
+    put_signed(out, at.y);
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Option<u8> {
+        let byte = *self.data.get(self.at)?;
+        self.at += 1;
+        Some(byte)
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn signed(&mut self) -> Option<i32> {
+        let raw = self.varint()? as u32;
+        Some(((raw >> 1) as i32) ^ -((raw & 1) as i32))
+    }
+
+    fn pos(&mut self) -> Option<Pos> {
+        Some(pos(self.signed()?, self.signed()?))
+    }
+
+    fn button(&mut self) -> Option<PointerButton> {
+        match self.byte()? {
+            0 => Some(PointerButton::Left),
+            1 => Some(PointerButton::Right),
+            _ => None,
+        }
+    }
+}
+
+fn put_key(out: &mut Vec<u8>, key: KeyCode) {
+    let tag = match key {
+        KeyCode::Char(ch) => {
+            out.push(0);
+            put_varint(out, ch as u64);
+            return;
+        }
+        KeyCode::F(n) => {
+            out.push(1);
+            out.push(n);
+            return;
+        }
+        KeyCode::Enter => 2,
+        KeyCode::Escape => 3,
+        KeyCode::Backspace => 4,
+        KeyCode::Tab => 5,
+        KeyCode::BackTab => 6,
+        KeyCode::Insert => 7,
+        KeyCode::Delete => 8,
+        KeyCode::Up => 9,
+        KeyCode::Down => 10,
+        KeyCode::Left => 11,
+        KeyCode::Right => 12,
+        KeyCode::Home => 13,
+        KeyCode::End => 14,
+        KeyCode::PageUp => 15,
+        KeyCode::PageDown => 16,
+    };
+    out.push(tag);
+}
+
+fn read_key(r: &mut Reader) -> Option<KeyCode> {
+    Some(match r.byte()? {
+        0 => KeyCode::Char(char::from_u32(r.varint()? as u32)?),
+        1 => KeyCode::F(r.byte()?),
+        2 => KeyCode::Enter,
+        3 => KeyCode::Escape,
+        4 => KeyCode::Backspace,
+        5 => KeyCode::Tab,
+        6 => KeyCode::BackTab,
+        7 => KeyCode::Insert,
+        8 => KeyCode::Delete,
+        9 => KeyCode::Up,
+        10 => KeyCode::Down,
+        11 => KeyCode::Left,
+        12 => KeyCode::Right,
+        13 => KeyCode::Home,
+        14 => KeyCode::End,
+        15 => KeyCode::PageUp,
+        16 => KeyCode::PageDown,
+        _ => return None,
+    })
+}
+
+fn put_event(out: &mut Vec<u8>, event: &Event) {
+    match *event {
+        Event::Key(key) => {
+            out.push(0);
+            put_key(out, key);
+        }
+        Event::Pointer(pointer) => {
+            out.push(1);
+            match pointer {
+                PointerEvent::Move(at) => {
+                    out.push(0);
+                    put_pos(out, at);
+                }
+                PointerEvent::Down(at, button) | PointerEvent::Up(at, button) => {
+                    out.push(if matches!(pointer, PointerEvent::Down(..)) { 1 } else { 2 });
+                    put_pos(out, at);
+                    out.push(button as u8);
+                }
+                PointerEvent::Wheel(at, delta) => {
+                    out.push(3);
+                    put_pos(out, at);
+                    put_signed(out, delta);
+                }
+            }
+        }
+        Event::Gesture(gesture) => {
+            out.push(2);
+            match gesture {
+                Gesture::Tap(at) => {
+                    out.push(0);
+                    put_pos(out, at);
+                }
+                Gesture::DoubleTap(at) => {
+                    out.push(1);
+                    put_pos(out, at);
+                }
+                Gesture::LongPress(at) => {
+                    out.push(2);
+                    put_pos(out, at);
+                }
+                Gesture::Swipe(at, dir) => {
+                    out.push(3);
+                    put_pos(out, at);
+                    out.push(dir as u8);
+                }
+            }
+        }
+        Event::Timer(id) => {
+            out.push(3);
+            put_varint(out, id as u64);
+        }
+        Event::ModeChange(size) => {
+            out.push(4);
+            put_signed(out, size.w);
+            put_signed(out, size.h);
+        }
+        Event::Custom(tag) => {
+            out.push(5);
+            put_varint(out, tag as u64);
+        }
+    }
+}
+
+fn read_event(r: &mut Reader) -> Option<Event> {
+    Some(match r.byte()? {
+        0 => Event::Key(read_key(r)?),
+        1 => Event::Pointer(match r.byte()? {
+            0 => PointerEvent::Move(r.pos()?),
+            1 => PointerEvent::Down(r.pos()?, r.button()?),
+            2 => PointerEvent::Up(r.pos()?, r.button()?),
+            3 => PointerEvent::Wheel(r.pos()?, r.signed()?),
+            _ => return None,
+        }),
+        2 => Event::Gesture(match r.byte()? {
+            0 => Gesture::Tap(r.pos()?),
+            1 => Gesture::DoubleTap(r.pos()?),
+            2 => Gesture::LongPress(r.pos()?),
+            3 => {
+                let at = r.pos()?;
+                let dir = match r.byte()? {
+                    0 => SwipeDirection::Left,
+                    1 => SwipeDirection::Right,
+                    2 => SwipeDirection::Up,
+                    3 => SwipeDirection::Down,
+                    _ => return None,
+                };
+                Gesture::Swipe(at, dir)
+            }
+            _ => return None,
+        }),
+        3 => Event::Timer(r.varint()? as usize),
+        4 => Event::ModeChange(dim(r.signed()?, r.signed()?)),
+        5 => Event::Custom(r.varint()? as u32),
+        _ => return None,
+    })
+}
+
+pub struct Recorder {
+    data: Vec<u8>,
+    elapsed: f32,
+    count: usize,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        let mut data = Vec::new();
+        data.extend_from_slice(MAGIC);
+        data.push(VERSION);
+        Self { data, elapsed: 0.0, count: 0 }
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.elapsed += dt;
+    }
+
+    pub fn record(&mut self, event: &Event) {
+        let ms = (self.elapsed * 1000.0) as u64;
+        self.elapsed -= ms as f32 / 1000.0;
+        put_varint(&mut self.data, ms);
+        put_event(&mut self.data, event);
+        self.count += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+pub struct Replayer {
+    events: Vec<(u32, Event)>,
+    next: usize,
+    wait: f32,
+}
+
+impl Replayer {
+    pub fn new(data: &[u8]) -> Option<Self> {
+        if data.len() < 5 || &data[..4] != MAGIC || data[4] != VERSION {
+            return None;
+        }
+        let mut r = Reader { data, at: 5 };
+        let mut events = Vec::new();
+        while r.at < data.len() {
+            let delay = r.varint()? as u32;
+            events.push((delay, read_event(&mut r)?));
+        }
+        let wait = events.first().map_or(0.0, |x| x.0 as f32 / 1000.0);
+        Some(Self { events, next: 0, wait })
+    }
+
+    pub fn finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+
+    pub fn rewind(&mut self) {
+        self.next = 0;
+        self.wait = self.events.first().map_or(0.0, |x| x.0 as f32 / 1000.0);
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = Event> + '_ {
+        self.events.iter().map(|x| x.1)
+    }
+
+    pub fn tick(&mut self, dt: f32, mut f: impl FnMut(Event)) {
+        self.wait -= dt;
+        while self.wait <= 0.0 {
+            let (_, event) = if let Some(x) = self.events.get(self.next) { *x } else { return; };
+            f(event);
+            self.next += 1;
+            let delay = self.events.get(self.next).map_or(0.0, |x| x.0 as f32 / 1000.0);
+            self.wait += delay;
+        }
+    }
+
+    pub fn feed(&mut self, event_loop: &mut EventLoop, dt: f32) {
+        self.tick(dt, |x| event_loop.post(x));
+    }
+}
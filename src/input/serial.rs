@@ -0,0 +1,159 @@
+use alloc::collections::VecDeque;
+use alloc::format;
+use core::fmt;
+
+use uefi::proto::console::serial::{ControlBits, Serial};
+use uefi::table::boot::{OpenProtocolAttributes, OpenProtocolParams};
+
+use crate::env;
+use crate::input::KeyCode;
+
+pub struct SerialConsole {
+    serial: *mut Serial,
+    pending: VecDeque<u8>,
+}
+
+impl SerialConsole {
+    pub fn open() -> Option<Self> {
+        let st = env::try_system_table()?;
+        let bs = st.boot_services();
+        let handle = bs.get_handle_for_protocol::<Serial>().ok()?;
+        let params = OpenProtocolParams { handle, agent: bs.image_handle(), controller: None };
+        let mut protocol = unsafe {
+            bs.open_protocol::<Serial>(params, OpenProtocolAttributes::GetProtocol).ok()?
+        };
+        let serial = &mut *protocol as *mut Serial;
+        core::mem::forget(protocol);
+        Some(Self { serial, pending: VecDeque::new() })
+    }
+
+    fn serial(&mut self) -> &mut Serial {
+        unsafe { &mut *self.serial }
+    }
+
+    fn available(&mut self) -> bool {
+        self.serial().get_control_bits()
+            .is_ok_and(|x| !x.contains(ControlBits::INPUT_BUFFER_EMPTY))
+    }
+
+    fn fill(&mut self) {
+        let mut byte = [0u8; 1];
+        while self.available() {
+            if self.serial().read(&mut byte).is_err() {
+                break;
+            }
+            self.pending.push_back(byte[0]);
+        }
+    }
+
+    fn next_byte(&mut self, settle: bool) -> Option<u8> {
+        if self.pending.is_empty() {
+            if let (true, Some(st)) = (settle, env::try_system_table()) {
+                st.boot_services().stall(2_000);
+            }
+            self.fill();
+        }
+        self.pending.pop_front()
+    }
+
+    fn tilde(&mut self, first: u8) -> Option<KeyCode> {
+        let mut n = (first - b'0') as u32;
+        loop {
+            match self.next_byte(true)? {
+                b'~' => break,
+                b @ b'0'..=b'9' => n = n * 10 + (b - b'0') as u32,
+                _ => return None,
+            }
+        }
+        Some(match n {
+            1 | 7 => KeyCode::Home,
+            2 => KeyCode::Insert,
+            3 => KeyCode::Delete,
+            4 | 8 => KeyCode::End,
+            5 => KeyCode::PageUp,
+            6 => KeyCode::PageDown,
+            11..=15 => KeyCode::F((n - 10) as u8),
+            17..=21 => KeyCode::F((n - 11) as u8),
+            23 | 24 => KeyCode::F((n - 12) as u8),
+            _ => return None,
+        })
+    }
+
+    fn escape(&mut self) -> Option<KeyCode> {
+        let intro = if let Some(x) = self.next_byte(true) { x } else { return Some(KeyCode::Escape); };
+        let code = self.next_byte(true)?;
+        match (intro, code) {
+            (b'[', b'A') | (b'O', b'A') => Some(KeyCode::Up),
+            (b'[', b'B') | (b'O', b'B') => Some(KeyCode::Down),
+            (b'[', b'C') | (b'O', b'C') => Some(KeyCode::Right),
+            (b'[', b'D') | (b'O', b'D') => Some(KeyCode::Left),
+            (b'[', b'H') | (b'O', b'H') => Some(KeyCode::Home),
+            (b'[', b'F') | (b'O', b'F') => Some(KeyCode::End),
+            (b'[', b'Z') => Some(KeyCode::BackTab),
+            (b'O', b @ b'P'..=b'S') => Some(KeyCode::F(b - b'P' + 1)),
+            (b'[', b @ b'0'..=b'9') => self.tilde(b),
+            _ => None,
+        }
+    }
+
+    fn utf8(&mut self, lead: u8) -> Option<char> {
+        let len = match lead {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => return None,
+        };
+        let mut buf = [lead, 0, 0, 0];
+        for slot in buf.iter_mut().take(len).skip(1) {
+            *slot = self.next_byte(true)?;
+        }
+        core::str::from_utf8(&buf[..len]).ok()?.chars().next()
+    }
+
+    pub fn poll_key(&mut self) -> Option<KeyCode> {
+        loop {
+            let byte = self.next_byte(false)?;
+            let key = match byte {
+                0x1B => self.escape(),
+                b'\r' | b'\n' => Some(KeyCode::Enter),
+                0x08 | 0x7F => Some(KeyCode::Backspace),
+                b'\t' => Some(KeyCode::Tab),
+                0x20..=0x7E => Some(KeyCode::Char(byte as char)),
+                0x80..=0xFF => self.utf8(byte).map(KeyCode::Char),
+                _ => None,
+            };
+            if key.is_some() {
+                return key;
+            }
+        }
+    }
+
+    pub fn write_raw(&mut self, bytes: &[u8]) {
+        let _ = self.serial().write(bytes);
+    }
+
+    pub fn put_char(&mut self, ch: char) {
+        let mut buf = [0u8; 4];
+        self.write_raw(ch.encode_utf8(&mut buf).as_bytes());
+    }
+
+    pub fn clear(&mut self) {
+        self.write_raw(b"\x1b[2J\x1b[H");
+    }
+
+    pub fn set_cursor(&mut self, col: usize, row: usize) {
+        self.write_raw(format!("\x1b[{};{}H", row + 1, col + 1).as_bytes());
+    }
+}
+
+impl fmt::Write for SerialConsole {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.write_raw(b"\r\n");
+            }
+            self.write_raw(line.as_bytes());
+        }
+        Ok(())
+    }
+}
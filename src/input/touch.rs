@@ -0,0 +1,246 @@
+use alloc::collections::VecDeque;
+use core::ffi::c_void;
+
+use uefi::proto::unsafe_protocol;
+use uefi::table::boot::{OpenProtocolAttributes, OpenProtocolParams};
+use uefi::Status;
+
+use crate::env;
+use crate::gfx::{dim, pos, rect, Pos, Rect, Screen};
+use crate::input::{PointerButton, PointerEvent};
+
+const TOUCH_ACTIVE: u32 = 0x01;
+const ALT_ACTIVE: u32 = 0x02;
+
+#[repr(C)]
+#[allow(dead_code)]
+struct AbsoluteMode {
+    min_x: u64,
+    min_y: u64,
+    min_z: u64,
+    max_x: u64,
+    max_y: u64,
+    max_z: u64,
+    attributes: u32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct AbsoluteState {
+    x: u64,
+    y: u64,
+    z: u64,
+    buttons: u32,
+}
+
+#[repr(C)]
+#[unsafe_protocol("8d59d32b-c655-4ae9-9b15-f25904992a43")]
+struct AbsolutePointer {
+    reset: unsafe extern "efiapi" fn(*mut AbsolutePointer, u8) -> Status,
+    get_state: unsafe extern "efiapi" fn(*mut AbsolutePointer, *mut AbsoluteState) -> Status,
+    wait_for_input: *mut c_void,
+    mode: *const AbsoluteMode,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Gesture {
+    Tap(Pos),
+    DoubleTap(Pos),
+    LongPress(Pos),
+    Swipe(Pos, SwipeDirection),
+}
+
+pub struct Touch {
+    pub bounds: Rect,
+    pointer: *mut AbsolutePointer,
+    pos: Pos,
+    buttons: [bool; 2],
+    pending: VecDeque<PointerEvent>,
+}
+
+impl Touch {
+    pub fn open() -> Option<Self> {
+        let st = env::try_system_table()?;
+        let bs = st.boot_services();
+        let handle = bs.get_handle_for_protocol::<AbsolutePointer>().ok()?;
+        let params = OpenProtocolParams { handle, agent: bs.image_handle(), controller: None };
+        let mut protocol = unsafe {
+            bs.open_protocol::<AbsolutePointer>(params, OpenProtocolAttributes::GetProtocol).ok()?
+        };
+        let pointer = &mut *protocol as *mut AbsolutePointer;
+        core::mem::forget(protocol);
+        if unsafe { ((*pointer).reset)(pointer, 0) } != Status::SUCCESS {
+            return None;
+        }
+        let bounds = if Screen::is_init() { Screen::rect() } else { rect(pos(0, 0), dim(0, 0)) };
+        Some(Self {
+            bounds,
+            pointer,
+            pos: pos(0, 0),
+            buttons: [false; 2],
+            pending: VecDeque::new(),
+        })
+    }
+
+    pub fn bounds(mut self, bounds: Rect) -> Self {
+        self.bounds = bounds;
+        self
+    }
+
+    pub fn pos(&self) -> Pos {
+        self.pos
+    }
+
+    pub fn wait_event(&self) -> Option<uefi::Event> {
+        unsafe { uefi::Event::from_ptr((*self.pointer).wait_for_input) }
+    }
+
+    fn map(value: u64, min: u64, max: u64, origin: i32, extent: i32) -> i32 {
+        let span = max.saturating_sub(min).max(1);
+        let offset = value.clamp(min, max) - min;
+        origin + (offset as i128 * (extent - 1).max(0) as i128 / span as i128) as i32
+    }
+
+    fn read(&mut self) {
+        let mut state = AbsoluteState::default();
+        let status = unsafe { ((*self.pointer).get_state)(self.pointer, &mut state) };
+        if status != Status::SUCCESS {
+            return;
+        }
+        let mode = unsafe { &*(*self.pointer).mode };
+        let b = self.bounds;
+        let at = pos(
+            Self::map(state.x, mode.min_x, mode.max_x, b.pos.x, b.dim.w),
+            Self::map(state.y, mode.min_y, mode.max_y, b.pos.y, b.dim.h),
+        );
+        if at != self.pos {
+            self.pos = at;
+            self.pending.push_back(PointerEvent::Move(at));
+        }
+        let buttons = [state.buttons & TOUCH_ACTIVE != 0, state.buttons & ALT_ACTIVE != 0];
+        for (i, button) in [PointerButton::Left, PointerButton::Right].iter().enumerate() {
+            if buttons[i] == self.buttons[i] {
+                continue;
+            }
+            self.buttons[i] = buttons[i];
+            let event = if buttons[i] { PointerEvent::Down(at, *button) } else { PointerEvent::Up(at, *button) };
+            self.pending.push_back(event);
+        }
+    }
+
+    pub fn poll(&mut self) -> Option<PointerEvent> {
+        if self.pending.is_empty() {
+            self.read();
+        }
+        self.pending.pop_front()
+    }
+}
+
+struct Contact {
+    start: Pos,
+    held: f32,
+    moved: bool,
+    fired: bool,
+}
+
+pub struct GestureRecognizer {
+    pub slop: i32,
+    pub swipe_distance: i32,
+    pub long_press: f32,
+    pub double_tap: f32,
+    contact: Option<Contact>,
+    last_tap: Option<(Pos, f32)>,
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GestureRecognizer {
+    pub fn new() -> Self {
+        Self {
+            slop: 12,
+            swipe_distance: 48,
+            long_press: 0.5,
+            double_tap: 0.3,
+            contact: None,
+            last_tap: None,
+        }
+    }
+
+    pub fn busy(&self) -> bool {
+        self.contact.is_some() || self.last_tap.is_some()
+    }
+
+    fn near(&self, a: Pos, b: Pos) -> bool {
+        (a.x - b.x).abs() <= self.slop && (a.y - b.y).abs() <= self.slop
+    }
+
+    pub fn tick(&mut self, dt: f32) -> Option<Gesture> {
+        if let Some((at, age)) = self.last_tap {
+            let age = age + dt;
+            self.last_tap = if age < self.double_tap { Some((at, age)) } else { None };
+        }
+        let long_press = self.long_press;
+        let contact = self.contact.as_mut()?;
+        contact.held += dt;
+        if !contact.moved && !contact.fired && contact.held >= long_press {
+            contact.fired = true;
+            return Some(Gesture::LongPress(contact.start));
+        }
+        None
+    }
+
+    pub fn pointer(&mut self, event: PointerEvent) -> Option<Gesture> {
+        match event {
+            PointerEvent::Down(at, PointerButton::Left) => {
+                self.contact = Some(Contact { start: at, held: 0.0, moved: false, fired: false });
+                None
+            }
+            PointerEvent::Move(at) => {
+                let slop_exceeded = self.contact.as_ref().is_some_and(|x| !self.near(x.start, at));
+                self.contact.as_mut()?.moved |= slop_exceeded;
+                None
+            }
+            PointerEvent::Up(at, PointerButton::Left) => {
+                let contact = self.contact.take()?;
+                if contact.fired {
+                    return None;
+                }
+                let (dx, dy) = (at.x - contact.start.x, at.y - contact.start.y);
+                if contact.moved || !self.near(contact.start, at) {
+                    if dx.abs().max(dy.abs()) < self.swipe_distance {
+                        return None;
+                    }
+                    let dir = if dx.abs() >= dy.abs() {
+                        if dx < 0 { SwipeDirection::Left } else { SwipeDirection::Right }
+                    } else if dy < 0 {
+                        SwipeDirection::Up
+                    } else {
+                        SwipeDirection::Down
+                    };
+                    return Some(Gesture::Swipe(contact.start, dir));
+                }
+                if let Some((last, _)) = self.last_tap.take() {
+                    if self.near(last, contact.start) {
+                        return Some(Gesture::DoubleTap(contact.start));
+                    }
+                }
+                self.last_tap = Some((contact.start, 0.0));
+                Some(Gesture::Tap(contact.start))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use alloc::vec::Vec;
+
+use uefi::ResultExt;
+
+use crate::env;
+use crate::input::{Keyboard, TimerId};
+use crate::timer;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Source {
+    Keyboard,
+    Pointer(usize),
+    Timer(TimerId),
+    Custom(u32),
+    Timeout,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum WaitMode {
+    Block,
+    Poll,
+}
+
+pub struct WaitSet {
+    sources: Vec<Source>,
+    events: Vec<uefi::Event>,
+}
+
+impl Default for WaitSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaitSet {
+    pub fn new() -> Self {
+        Self { sources: Vec::new(), events: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.sources.clear();
+        self.events.clear();
+    }
+
+    pub fn add(&mut self, source: Source, event: &uefi::Event) {
+        self.sources.push(source);
+        self.events.push(unsafe { event.unsafe_clone() });
+    }
+
+    pub fn keyboard(&mut self) -> crate::Result {
+        let event = Keyboard::wait_event()?;
+        self.sources.push(Source::Keyboard);
+        self.events.push(event);
+        Ok(())
+    }
+
+    pub fn pointer(&mut self, index: usize, event: &uefi::Event) {
+        self.add(Source::Pointer(index), event);
+    }
+
+    pub fn timer(&mut self, id: TimerId, event: &uefi::Event) {
+        self.add(Source::Timer(id), event);
+    }
+
+    pub fn custom(&mut self, tag: u32, event: &uefi::Event) {
+        self.add(Source::Custom(tag), event);
+    }
+
+    pub fn poll(&self) -> Option<Source> {
+        let st = env::try_system_table()?;
+        let bs = st.boot_services();
+        let index = self.events.iter()
+            .position(|x| bs.check_event(unsafe { x.unsafe_clone() }).unwrap_or(false))?;
+        Some(self.sources[index])
+    }
+
+    pub fn wait_idle(&mut self, mode: WaitMode, mut idle: impl FnMut()) -> crate::Result<Source> {
+        match mode {
+            WaitMode::Block => {
+                idle();
+                self.wait(None)
+            }
+            WaitMode::Poll => loop {
+                if let Some(source) = self.poll() {
+                    return Ok(source);
+                }
+                idle();
+            },
+        }
+    }
+
+    pub fn wait(&mut self, timeout_us: Option<u64>) -> crate::Result<Source> {
+        let st = env::system_table()?;
+        let bs = st.boot_services();
+        let timeout = timeout_us.map(timer::one_shot).transpose()?;
+        let mut events: Vec<uefi::Event> = self.events.iter().map(|x| unsafe { x.unsafe_clone() }).collect();
+        if let Some(timeout) = timeout.as_ref() {
+            events.push(unsafe { timeout.event().unsafe_clone() });
+        }
+        if events.is_empty() {
+            return Ok(Source::Timeout);
+        }
+        let index = bs.wait_for_event(&mut events).discard_errdata()?;
+        Ok(self.sources.get(index).copied().unwrap_or(Source::Timeout))
+    }
+}
@@ -1,9 +1,73 @@
 #![no_std]
 extern crate alloc;
+#[cfg(feature = "host")]
+extern crate std;
 
+#[cfg(feature = "acpi")]
+pub mod acpi;
+#[cfg(feature = "widgets")]
+pub mod anim;
+#[cfg(feature = "fs")]
+pub mod args;
+#[cfg(feature = "assets")]
+pub mod assets;
+mod binding;
+#[cfg(feature = "fs")]
+pub mod bootmgr;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "gfx")]
+pub mod debug;
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+pub mod debugcon;
+#[cfg(feature = "disk")]
+pub mod disk;
+pub mod env;
+pub mod error;
+#[cfg(feature = "fs")]
+pub mod fs;
+#[cfg(feature = "gfx")]
 pub mod gfx;
+pub mod hash;
+#[cfg(feature = "gfx")]
+pub mod image;
+#[cfg(feature = "input")]
+pub mod input;
+pub mod logger;
+pub mod mem;
+#[cfg(feature = "net")]
+pub mod net;
+#[cfg(all(feature = "panic_handler", not(feature = "host")))]
+mod panic;
+pub mod progress;
+pub mod rand;
+#[cfg(feature = "fs")]
+pub mod selfinfo;
+pub mod serial;
+#[cfg(feature = "settings")]
+pub mod settings;
+#[cfg(feature = "smbios")]
+pub mod smbios;
+#[cfg(feature = "tcg2")]
+pub mod tcg2;
+#[cfg(all(feature = "host", feature = "gfx"))]
+pub mod testing;
+#[cfg(feature = "text")]
+pub mod text;
+#[cfg(feature = "gfx")]
+pub mod theme;
+pub mod time;
+pub mod timer;
+pub mod vars;
+#[cfg(feature = "widgets")]
+pub mod widget;
+
+pub use error::{Error, Result};
 
 pub mod prelude {
+    pub use crate::env;
+    #[cfg(feature = "gfx")]
     pub use crate::gfx;
-    pub use gfx::{GlyphIteratorExt, GlyphCoordIteratorExt};
-}
\ No newline at end of file
+    #[cfg(feature = "gfx")]
+    pub use crate::gfx::{Canvas, GlyphIteratorExt, GlyphCoordIteratorExt};
+}
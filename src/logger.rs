@@ -0,0 +1,108 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::ptr;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::env;
+use crate::time;
+
+pub trait Sink {
+    fn write_line(&mut self, line: &str);
+}
+
+struct Logger;
+
+struct Line {
+    buf: [u8; 512],
+    len: usize,
+}
+
+impl Line {
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+}
+
+impl Write for Line {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let mut take = s.len().min(self.buf.len() - self.len);
+        while !s.is_char_boundary(take) {
+            take -= 1;
+        }
+        self.buf[self.len..self.len + take].copy_from_slice(&s.as_bytes()[..take]);
+        self.len += take;
+        Ok(())
+    }
+}
+
+static LOGGER: Logger = Logger;
+static mut SINKS: Vec<Box<dyn Sink>> = Vec::new();
+static mut CONSOLE: bool = true;
+static mut SERVICES: bool = false;
+static mut RAW_SINK: Option<fn(&str)> = None;
+
+pub fn init_early(level: LevelFilter) {
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level);
+}
+
+pub fn init(level: LevelFilter) {
+    unsafe { SERVICES = true };
+    init_early(level);
+}
+
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
+
+pub fn set_console(enabled: bool) {
+    unsafe { CONSOLE = enabled };
+}
+
+pub fn add_sink(sink: Box<dyn Sink>) {
+    unsafe { (*ptr::addr_of_mut!(SINKS)).push(sink) };
+}
+
+pub fn set_raw_sink(sink: Option<fn(&str)>) {
+    unsafe { RAW_SINK = sink };
+}
+
+pub fn clear_sinks() {
+    unsafe { (*ptr::addr_of_mut!(SINKS)).clear() };
+}
+
+pub(crate) fn exit_services() {
+    clear_sinks();
+    unsafe { SERVICES = false };
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let services = unsafe { SERVICES };
+        let mut line = Line { buf: [0; 512], len: 0 };
+        if services {
+            let _ = write!(line, "[{}] ", time::timestamp());
+        }
+        let _ = write!(line, "{:<5} {}: {}", record.level(), record.target(), record.args());
+        if let (true, Some(mut st)) = (services && unsafe { CONSOLE }, env::try_system_table()) {
+            let _ = write!(st.stdout(), "{}\r\n", line.as_str());
+        }
+        if let Some(sink) = unsafe { RAW_SINK } {
+            sink(line.as_str());
+        }
+        for sink in unsafe { (*ptr::addr_of_mut!(SINKS)).iter_mut() } {
+            sink.write_line(line.as_str());
+        }
+    }
+
+    fn flush(&self) {}
+}
@@ -0,0 +1,461 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::alloc::{GlobalAlloc, Layout};
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+use uefi::table::boot::{AllocateType, MemoryAttribute, MemoryType};
+use uefi::Status;
+
+use crate::env;
+
+pub const PAGE_SIZE: u64 = 4096;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Region {
+    pub ty: MemoryType,
+    pub start: u64,
+    pub pages: u64,
+    pub attributes: MemoryAttribute,
+}
+
+impl Region {
+    pub fn size(&self) -> u64 {
+        self.pages * PAGE_SIZE
+    }
+
+    pub fn end(&self) -> u64 {
+        self.start + self.size()
+    }
+
+    pub fn is_free(&self) -> bool {
+        self.ty == MemoryType::CONVENTIONAL
+    }
+
+    pub fn is_reclaimable(&self) -> bool {
+        matches!(self.ty, MemoryType::BOOT_SERVICES_CODE | MemoryType::BOOT_SERVICES_DATA
+            | MemoryType::LOADER_CODE | MemoryType::LOADER_DATA)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TypeTotal {
+    pub ty: MemoryType,
+    pub pages: u64,
+    pub regions: usize,
+}
+
+impl TypeTotal {
+    pub fn size(&self) -> u64 {
+        self.pages * PAGE_SIZE
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemorySnapshot {
+    pub regions: Vec<Region>,
+    pub totals: Vec<TypeTotal>,
+}
+
+pub fn type_name(ty: MemoryType) -> &'static str {
+    match ty {
+        MemoryType::RESERVED => "Reserved",
+        MemoryType::LOADER_CODE => "Loader Code",
+        MemoryType::LOADER_DATA => "Loader Data",
+        MemoryType::BOOT_SERVICES_CODE => "Boot Services Code",
+        MemoryType::BOOT_SERVICES_DATA => "Boot Services Data",
+        MemoryType::RUNTIME_SERVICES_CODE => "Runtime Services Code",
+        MemoryType::RUNTIME_SERVICES_DATA => "Runtime Services Data",
+        MemoryType::CONVENTIONAL => "Conventional",
+        MemoryType::UNUSABLE => "Unusable",
+        MemoryType::ACPI_RECLAIM => "ACPI Reclaim",
+        MemoryType::ACPI_NON_VOLATILE => "ACPI NVS",
+        MemoryType::MMIO => "MMIO",
+        MemoryType::MMIO_PORT_SPACE => "MMIO Port Space",
+        MemoryType::PAL_CODE => "PAL Code",
+        MemoryType::PERSISTENT_MEMORY => "Persistent",
+        _ => "Other",
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut unit = 0;
+    let mut scaled = bytes;
+    while scaled >= 1024 * 10 && unit < UNITS.len() - 1 {
+        scaled /= 1024;
+        unit += 1;
+    }
+    format!("{} {}", scaled, UNITS[unit])
+}
+
+pub fn map_snapshot() -> uefi::Result<MemorySnapshot> {
+    let st = env::system_table()?;
+    let bs = st.boot_services();
+    let size = bs.memory_map_size();
+    let mut buf = vec![0u8; size.map_size + 8 * size.entry_size];
+    let mut regions: Vec<Region> = loop {
+        match bs.memory_map(&mut buf) {
+            Ok(map) => break map.entries()
+                .map(|x| Region { ty: x.ty, start: x.phys_start, pages: x.page_count, attributes: x.att })
+                .collect(),
+            Err(err) if err.status() == Status::BUFFER_TOO_SMALL => {
+                buf.resize(buf.len() + 8 * size.entry_size, 0);
+            }
+            Err(err) => return Err(err),
+        }
+    };
+    regions.sort_by_key(|x| x.start);
+    let mut totals: Vec<TypeTotal> = Vec::new();
+    for region in &regions {
+        match totals.iter_mut().find(|x| x.ty == region.ty) {
+            Some(total) => {
+                total.pages += region.pages;
+                total.regions += 1;
+            }
+            None => totals.push(TypeTotal { ty: region.ty, pages: region.pages, regions: 1 }),
+        }
+    }
+    totals.sort_by(|a, b| b.pages.cmp(&a.pages));
+    Ok(MemorySnapshot { regions, totals })
+}
+
+impl MemorySnapshot {
+    pub fn total(&self, ty: MemoryType) -> u64 {
+        self.totals.iter().find(|x| x.ty == ty).map_or(0, |x| x.size())
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.regions.iter()
+            .filter(|x| x.ty != MemoryType::MMIO && x.ty != MemoryType::MMIO_PORT_SPACE)
+            .map(|x| x.size())
+            .sum()
+    }
+
+    pub fn free_bytes(&self) -> u64 {
+        self.total(MemoryType::CONVENTIONAL)
+    }
+
+    pub fn reclaimable_bytes(&self) -> u64 {
+        self.regions.iter().filter(|x| x.is_reclaimable()).map(|x| x.size()).sum()
+    }
+
+    pub fn largest_free(&self) -> Option<Region> {
+        let mut best: Option<Region> = None;
+        let mut current: Option<Region> = None;
+        for region in self.regions.iter().filter(|x| x.is_free()) {
+            let run = match current {
+                Some(mut run) if run.end() == region.start => {
+                    run.pages += region.pages;
+                    run
+                }
+                _ => *region,
+            };
+            current = Some(run);
+            if !best.is_some_and(|x| x.pages >= run.pages) {
+                best = Some(run);
+            }
+        }
+        best
+    }
+
+    pub fn free_below(&self, limit: u64) -> u64 {
+        self.regions.iter()
+            .filter(|x| x.is_free() && x.start < limit)
+            .map(|x| x.end().min(limit) - x.start)
+            .sum()
+    }
+
+    pub fn summary(&self) -> Vec<(String, String)> {
+        let mut lines = Vec::from([
+            (String::from("Total"), format_bytes(self.total_bytes())),
+            (String::from("Free"), format_bytes(self.free_bytes())),
+            (String::from("Reclaimable"), format_bytes(self.reclaimable_bytes())),
+            (String::from("Free below 4 GiB"), format_bytes(self.free_below(1 << 32))),
+        ]);
+        if let Some(x) = self.largest_free() {
+            lines.push((String::from("Largest free"), format!("{} at {:#x}", format_bytes(x.size()), x.start)));
+        }
+        lines.push((String::from("Regions"), format!("{}", self.regions.len())));
+        for total in &self.totals {
+            let value = format!("{} ({} regions)", format_bytes(total.size()), total.regions);
+            lines.push((String::from(type_name(total.ty)), value));
+        }
+        lines
+    }
+}
+
+static TRACKING: AtomicBool = AtomicBool::new(false);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static DEALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static TOTAL: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct HeapStats {
+    pub current: usize,
+    pub peak: usize,
+    pub allocations: usize,
+    pub deallocations: usize,
+    pub total_allocated: u64,
+}
+
+impl HeapStats {
+    pub fn live(&self) -> usize {
+        self.allocations.saturating_sub(self.deallocations)
+    }
+}
+
+pub fn heap_stats() -> Option<HeapStats> {
+    if !TRACKING.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some(HeapStats {
+        current: CURRENT.load(Ordering::Relaxed),
+        peak: PEAK.load(Ordering::Relaxed),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+        total_allocated: TOTAL.load(Ordering::Relaxed),
+    })
+}
+
+pub fn reset_peak() {
+    PEAK.store(CURRENT.load(Ordering::Relaxed), Ordering::Relaxed);
+}
+
+pub struct PoolAllocator;
+
+#[cfg(all(feature = "global_allocator", not(feature = "host")))]
+#[global_allocator]
+static ALLOCATOR: PoolAllocator = PoolAllocator;
+
+unsafe impl GlobalAlloc for PoolAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let st = if let Some(x) = env::try_system_table() { x } else { return ptr::null_mut(); };
+        let bs = st.boot_services();
+        let align = layout.align();
+        if align <= 8 {
+            return bs.allocate_pool(MemoryType::LOADER_DATA, layout.size()).unwrap_or(ptr::null_mut());
+        }
+        let raw = match bs.allocate_pool(MemoryType::LOADER_DATA, layout.size() + align) {
+            Ok(x) => x,
+            Err(_) => return ptr::null_mut(),
+        };
+        let offset = align - (raw as usize % align);
+        let aligned = raw.add(offset);
+        (aligned as *mut *mut u8).sub(1).write(raw);
+        aligned
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let raw = if layout.align() <= 8 { ptr } else { (ptr as *mut *mut u8).sub(1).read() };
+        if let Some(st) = env::try_system_table() {
+            let _ = st.boot_services().free_pool(raw);
+        }
+    }
+}
+
+pub struct TrackingAllocator<A> {
+    inner: A,
+}
+
+impl<A> TrackingAllocator<A> {
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+}
+
+impl TrackingAllocator<PoolAllocator> {
+    pub const fn pool() -> Self {
+        Self::new(PoolAllocator)
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            TRACKING.store(true, Ordering::Relaxed);
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            TOTAL.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Placement {
+    Anywhere,
+    Below(u64),
+    At(u64),
+}
+
+pub const BELOW_4G: Placement = Placement::Below(0xFFFF_FFFF);
+
+pub fn pages_for(bytes: usize) -> usize {
+    bytes.div_ceil(PAGE_SIZE as usize)
+}
+
+#[derive(Debug)]
+pub struct Pages {
+    addr: u64,
+    count: usize,
+    ty: MemoryType,
+}
+
+impl Pages {
+    pub fn allocate(count: usize, ty: MemoryType, placement: Placement) -> uefi::Result<Self> {
+        let st = env::system_table()?;
+        let kind = match placement {
+            Placement::Anywhere => AllocateType::AnyPages,
+            Placement::Below(max) => AllocateType::MaxAddress(max),
+            Placement::At(addr) => AllocateType::Address(addr),
+        };
+        let addr = st.boot_services().allocate_pages(kind, ty, count)?;
+        unsafe { ptr::write_bytes(addr as *mut u8, 0, count * PAGE_SIZE as usize) };
+        Ok(Self { addr, count, ty })
+    }
+
+    pub fn aligned(size: usize, align: u64, ty: MemoryType, placement: Placement) -> uefi::Result<Self> {
+        if !align.is_power_of_two() || matches!(placement, Placement::At(x) if x % align != 0) {
+            return Err(Status::INVALID_PARAMETER.into());
+        }
+        let count = pages_for(size).max(1);
+        if align <= PAGE_SIZE || matches!(placement, Placement::At(_)) {
+            return Self::allocate(count, ty, placement);
+        }
+        let slack = (align / PAGE_SIZE) as usize - 1;
+        let mut pages = Self::allocate(count + slack, ty, placement)?;
+        let start = (pages.addr + align - 1) & !(align - 1);
+        let head = ((start - pages.addr) / PAGE_SIZE) as usize;
+        let tail = slack - head;
+        let st = env::system_table()?;
+        let bs = st.boot_services();
+        unsafe {
+            if head > 0 {
+                bs.free_pages(pages.addr, head)?;
+            }
+            if tail > 0 {
+                bs.free_pages(start + (count * PAGE_SIZE as usize) as u64, tail)?;
+            }
+        }
+        pages.addr = start;
+        pages.count = count;
+        Ok(pages)
+    }
+
+    pub fn addr(&self) -> u64 {
+        self.addr
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn memory_type(&self) -> MemoryType {
+        self.ty
+    }
+
+    pub fn len(&self) -> usize {
+        self.count * PAGE_SIZE as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.addr as *mut u8
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.as_ptr(), self.len()) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.as_ptr(), self.len()) }
+    }
+
+    pub fn leak(self) -> u64 {
+        let addr = self.addr;
+        core::mem::forget(self);
+        addr
+    }
+}
+
+impl Drop for Pages {
+    fn drop(&mut self) {
+        let st = if let Some(x) = env::try_system_table() { x } else { return; };
+        let _ = unsafe { st.boot_services().free_pages(self.addr, self.count) };
+    }
+}
+
+#[derive(Debug)]
+pub struct Pool {
+    ptr: *mut u8,
+    len: usize,
+    ty: MemoryType,
+}
+
+impl Pool {
+    pub fn allocate(len: usize, ty: MemoryType) -> uefi::Result<Self> {
+        let st = env::system_table()?;
+        let ptr = st.boot_services().allocate_pool(ty, len.max(1))?;
+        unsafe { ptr::write_bytes(ptr, 0, len) };
+        Ok(Self { ptr, len, ty })
+    }
+
+    pub fn from_slice(data: &[u8], ty: MemoryType) -> uefi::Result<Self> {
+        let mut pool = Self::allocate(data.len(), ty)?;
+        pool.as_mut_slice().copy_from_slice(data);
+        Ok(pool)
+    }
+
+    pub fn memory_type(&self) -> MemoryType {
+        self.ty
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.ptr
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+
+    pub fn leak(self) -> *mut u8 {
+        let ptr = self.ptr;
+        core::mem::forget(self);
+        ptr
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        let st = if let Some(x) = env::try_system_table() { x } else { return; };
+        let _ = unsafe { st.boot_services().free_pool(self.ptr) };
+    }
+}
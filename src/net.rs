@@ -0,0 +1,171 @@
+use core::ffi::c_void;
+use core::fmt;
+
+use uefi::proto::network::pxe::BaseCode;
+use uefi::proto::network::IpAddress;
+use uefi::proto::ProtocolPointer;
+use uefi::table::boot::{EventType, OpenProtocolAttributes, OpenProtocolParams, Tpl};
+use uefi::Status;
+
+pub(crate) use crate::binding::{Child, ServiceBinding};
+use crate::env;
+use crate::timer::Stopwatch;
+
+pub mod dhcp;
+pub mod http;
+pub mod ping;
+pub mod tcp;
+pub mod tftp;
+pub mod udp;
+
+pub use dhcp::{dhcp_acquire, DhcpLease};
+pub use http::{http_get, http_get_with, http_request, https_available, HttpResponse};
+pub use ping::{ping, ping_with, PingStats};
+pub use tcp::TcpStream;
+pub use udp::UdpSocket;
+pub use tftp::{tftp_get, tftp_get_with, tftp_read, tftp_size};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum NetError {
+    Io(uefi::Error),
+    NoInterface,
+    Timeout,
+    Protocol(&'static str),
+    Http(u16),
+}
+
+impl From<uefi::Error> for NetError {
+    fn from(value: uefi::Error) -> Self {
+        if value.status() == Status::TIMEOUT {
+            NetError::Timeout
+        } else {
+            NetError::Io(value)
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Ipv4(pub [u8; 4]);
+
+impl Ipv4 {
+    pub const UNSPECIFIED: Self = Self([0; 4]);
+    pub const BROADCAST: Self = Self([255; 4]);
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut octets = [0u8; 4];
+        let mut parts = text.trim().split('.');
+        for octet in octets.iter_mut() {
+            *octet = parts.next()?.parse().ok()?;
+        }
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self(octets))
+    }
+
+    pub fn is_unspecified(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    pub fn prefix_len(&self) -> u32 {
+        u32::from_be_bytes(self.0).leading_ones()
+    }
+
+    pub(crate) fn from_ip(ip: &IpAddress) -> Self {
+        Self([ip.0[0], ip.0[1], ip.0[2], ip.0[3]])
+    }
+
+    pub(crate) fn to_ip(self) -> IpAddress {
+        IpAddress::new_v4(self.0)
+    }
+}
+
+impl fmt::Display for Ipv4 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}.{}", self.0[0], self.0[1], self.0[2], self.0[3])
+    }
+}
+
+pub(crate) fn with_pxe<R>(f: impl FnOnce(&mut BaseCode) -> Result<R, NetError>) -> Result<R, NetError> {
+    let st = env::system_table()?;
+    let bs = st.boot_services();
+    let handle = bs.get_handle_for_protocol::<BaseCode>().map_err(|_| NetError::NoInterface)?;
+    let params = OpenProtocolParams { handle, agent: bs.image_handle(), controller: None };
+    let mut pxe = unsafe { bs.open_protocol::<BaseCode>(params, OpenProtocolAttributes::GetProtocol)? };
+    if !pxe.mode().started {
+        match pxe.start(false) {
+            Ok(()) => {}
+            Err(err) if err.status() == Status::ALREADY_STARTED => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    f(&mut pxe)
+}
+
+pub(crate) fn child<P: ProtocolPointer, B: ProtocolPointer>() -> Result<Child<P>, NetError> {
+    Child::create::<B>().map_err(|err| {
+        if err.status() == Status::NOT_FOUND { NetError::NoInterface } else { err.into() }
+    })
+}
+
+#[repr(C)]
+pub(crate) struct Fragment {
+    pub(crate) len: u32,
+    pub(crate) buffer: *mut c_void,
+}
+
+pub(crate) fn configure(timeout_us: u64, mut attempt: impl FnMut() -> Status) -> Result<(), NetError> {
+    let watch = Stopwatch::start();
+    loop {
+        match attempt() {
+            Status::SUCCESS => return Ok(()),
+            Status::NO_MAPPING if watch.elapsed_us() < timeout_us => {
+                env::system_table()?.boot_services().stall(100_000);
+            }
+            Status::NO_MAPPING => return Err(NetError::Timeout),
+            other => return Err(uefi::Error::from(other).into()),
+        }
+    }
+}
+
+pub(crate) struct Signal {
+    event: uefi::Event,
+}
+
+impl Signal {
+    pub(crate) fn new() -> uefi::Result<Self> {
+        let st = env::system_table()?;
+        let event = unsafe { st.boot_services().create_event(EventType::empty(), Tpl::CALLBACK, None, None)? };
+        Ok(Self { event })
+    }
+
+    pub(crate) fn raw(&self) -> *mut c_void {
+        self.event.as_ptr()
+    }
+
+    pub(crate) fn is_set(&self) -> bool {
+        env::try_system_table().is_some_and(|st| {
+            st.boot_services().check_event(unsafe { self.event.unsafe_clone() }).unwrap_or(false)
+        })
+    }
+
+    pub(crate) fn wait(&self, timeout_us: u64, mut poll: impl FnMut()) -> bool {
+        let watch = Stopwatch::start();
+        loop {
+            if self.is_set() {
+                return true;
+            }
+            if watch.elapsed_us() >= timeout_us {
+                return false;
+            }
+            poll();
+        }
+    }
+}
+
+impl Drop for Signal {
+    fn drop(&mut self) {
+        let st = if let Some(x) = env::try_system_table() { x } else { return; };
+        let _ = st.boot_services().close_event(unsafe { self.event.unsafe_clone() });
+    }
+}
@@ -0,0 +1,108 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use uefi::proto::network::pxe::BaseCode;
+
+use crate::net::{with_pxe, Ipv4, NetError};
+use crate::timer::Stopwatch;
+
+const OPTIONS_OFFSET: usize = 240;
+const MAGIC: [u8; 4] = [99, 130, 83, 99];
+
+const OPTION_PAD: u8 = 0;
+const OPTION_SUBNET_MASK: u8 = 1;
+const OPTION_ROUTER: u8 = 3;
+const OPTION_DNS: u8 = 6;
+const OPTION_DOMAIN_NAME: u8 = 15;
+const OPTION_LEASE_TIME: u8 = 51;
+const OPTION_SERVER_ID: u8 = 54;
+const OPTION_END: u8 = 255;
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct DhcpLease {
+    pub address: Ipv4,
+    pub netmask: Ipv4,
+    pub gateway: Option<Ipv4>,
+    pub dns: Vec<Ipv4>,
+    pub server: Option<Ipv4>,
+    pub next_server: Option<Ipv4>,
+    pub domain: Option<String>,
+    pub lease_secs: Option<u32>,
+    pub boot_file: Option<String>,
+}
+
+fn addrs(data: &[u8]) -> Vec<Ipv4> {
+    data.chunks_exact(4).map(|x| Ipv4(x.try_into().unwrap())).collect()
+}
+
+fn c_string(data: &[u8]) -> Option<String> {
+    let end = data.iter().position(|x| *x == 0).unwrap_or(data.len());
+    if end == 0 { None } else { Some(String::from_utf8_lossy(&data[..end]).into_owned()) }
+}
+
+impl DhcpLease {
+    pub fn parse(packet: &[u8]) -> Option<Self> {
+        if packet.len() < OPTIONS_OFFSET || packet[236..240] != MAGIC {
+            return None;
+        }
+        let next_server = Ipv4(packet[20..24].try_into().unwrap());
+        let mut lease = Self {
+            address: Ipv4(packet[16..20].try_into().unwrap()),
+            next_server: if next_server.is_unspecified() { None } else { Some(next_server) },
+            boot_file: c_string(&packet[108..236]),
+            ..Self::default()
+        };
+        let mut at = OPTIONS_OFFSET;
+        while at < packet.len() {
+            let code = packet[at];
+            if code == OPTION_END {
+                break;
+            }
+            if code == OPTION_PAD {
+                at += 1;
+                continue;
+            }
+            let len = *packet.get(at + 1)? as usize;
+            let data = packet.get(at + 2..at + 2 + len)?;
+            match code {
+                OPTION_SUBNET_MASK if len == 4 => lease.netmask = Ipv4(data.try_into().unwrap()),
+                OPTION_ROUTER => lease.gateway = addrs(data).first().copied(),
+                OPTION_DNS => lease.dns = addrs(data),
+                OPTION_DOMAIN_NAME => lease.domain = c_string(data),
+                OPTION_LEASE_TIME if len == 4 => lease.lease_secs = Some(u32::from_be_bytes(data.try_into().unwrap())),
+                OPTION_SERVER_ID if len == 4 => lease.server = Some(Ipv4(data.try_into().unwrap())),
+                _ => {}
+            }
+            at += 2 + len;
+        }
+        Some(lease)
+    }
+}
+
+fn lease_of(pxe: &BaseCode) -> Result<DhcpLease, NetError> {
+    let mode = pxe.mode();
+    let raw: &[u8; 1472] = mode.dhcp_ack.as_ref();
+    let mut lease = DhcpLease::parse(raw).ok_or(NetError::Protocol("malformed DHCP acknowledgement"))?;
+    lease.address = Ipv4::from_ip(&mode.station_ip);
+    lease.netmask = Ipv4::from_ip(&mode.subnet_mask);
+    Ok(lease)
+}
+
+pub fn dhcp_acquire(timeout_us: u64, mut progress: impl FnMut(u32)) -> Result<DhcpLease, NetError> {
+    with_pxe(|pxe| {
+        if pxe.mode().dhcp_ack_received {
+            return lease_of(pxe);
+        }
+        let watch = Stopwatch::start();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            progress(attempt);
+            match pxe.dhcp(false) {
+                Ok(()) => return lease_of(pxe),
+                Err(_) if watch.elapsed_us() < timeout_us => continue,
+                Err(_) => return Err(NetError::Timeout),
+            }
+        }
+    })
+}
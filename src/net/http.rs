@@ -0,0 +1,415 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::ptr;
+
+use uefi::proto::unsafe_protocol;
+use uefi::{CString16, Status};
+
+use crate::env;
+use crate::net::{self, Child, NetError, ServiceBinding, Signal};
+use crate::progress::{NoProgress, Progress, Tracker};
+
+const HTTP_VERSION_11: u32 = 1;
+const METHOD_GET: u32 = 0;
+const TIMEOUT_MS: u32 = 30_000;
+const BODY_CHUNK: usize = 64 * 1024;
+const MAX_REDIRECTS: usize = 5;
+const USER_AGENT: &[u8] = b"uefapi\0";
+
+const STATUS_CODES: [u16; 43] = [
+    0, 100, 101, 200, 201, 202, 203, 204, 205, 206, 300, 301, 302, 303, 304, 305, 307,
+    400, 401, 402, 403, 404, 405, 406, 407, 408, 409, 410, 411, 412, 413, 414, 415, 416, 417,
+    500, 501, 502, 503, 504, 505, 308, 429,
+];
+
+#[repr(C)]
+#[unsafe_protocol("bdc8e6af-d9bc-4379-a72a-e0c4e75dae1c")]
+struct HttpServiceBinding(ServiceBinding);
+
+#[repr(C)]
+#[unsafe_protocol("952cb795-ff36-48cf-a249-4df486d6ab8d")]
+struct TlsServiceBinding(ServiceBinding);
+
+#[repr(C)]
+struct AccessPoint {
+    use_default_address: bool,
+    local_address: [u8; 4],
+    local_subnet: [u8; 4],
+    local_port: u16,
+}
+
+#[repr(C)]
+struct ConfigData {
+    http_version: u32,
+    timeout_ms: u32,
+    local_address_is_ipv6: bool,
+    access_point: *const AccessPoint,
+}
+
+#[repr(C)]
+struct RequestData {
+    method: u32,
+    url: *const u16,
+}
+
+#[repr(C)]
+struct ResponseData {
+    status_code: u32,
+}
+
+#[repr(C)]
+struct Header {
+    field_name: *const u8,
+    field_value: *const u8,
+}
+
+#[repr(C)]
+struct Message {
+    data: *mut c_void,
+    header_count: usize,
+    headers: *mut Header,
+    body_length: usize,
+    body: *mut u8,
+}
+
+#[repr(C)]
+struct Token {
+    event: *mut c_void,
+    status: Status,
+    message: *mut Message,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+#[unsafe_protocol("7a59b29b-910b-4171-8242-a85a0df25b5b")]
+struct HttpProtocol {
+    get_mode_data: *const c_void,
+    configure: unsafe extern "efiapi" fn(*mut HttpProtocol, *const ConfigData) -> Status,
+    request: unsafe extern "efiapi" fn(*mut HttpProtocol, *mut Token) -> Status,
+    cancel: unsafe extern "efiapi" fn(*mut HttpProtocol, *mut Token) -> Status,
+    response: unsafe extern "efiapi" fn(*mut HttpProtocol, *mut Token) -> Status,
+    poll: unsafe extern "efiapi" fn(*mut HttpProtocol) -> Status,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpResponse {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|x| x.0.eq_ignore_ascii_case(name)).map(|x| x.1.as_str())
+    }
+}
+
+enum Chunked {
+    Size,
+    Extension,
+    Data(usize),
+    DataEnd,
+    Trailer(bool),
+    Done,
+}
+
+struct Dechunker {
+    state: Chunked,
+    size: usize,
+}
+
+impl Dechunker {
+    fn size_line_end(&mut self) {
+        self.state = if self.size == 0 { Chunked::Trailer(true) } else { Chunked::Data(self.size) };
+        self.size = 0;
+    }
+
+    fn feed(&mut self, mut data: &[u8], sink: &mut impl FnMut(&[u8])) -> Result<(), NetError> {
+        while let Some(&byte) = data.first() {
+            match self.state {
+                Chunked::Data(left) => {
+                    let take = left.min(data.len());
+                    sink(&data[..take]);
+                    data = &data[take..];
+                    self.state = if take == left { Chunked::DataEnd } else { Chunked::Data(left - take) };
+                    continue;
+                }
+                Chunked::Size => match byte {
+                    b'\r' => {}
+                    b'\n' => self.size_line_end(),
+                    b';' | b' ' | b'\t' => self.state = Chunked::Extension,
+                    _ => {
+                        let digit = (byte as char).to_digit(16).ok_or(NetError::Protocol("bad chunk size"))?;
+                        self.size = self.size.checked_mul(16)
+                            .and_then(|x| x.checked_add(digit as usize))
+                            .ok_or(NetError::Protocol("bad chunk size"))?;
+                    }
+                },
+                Chunked::Extension => {
+                    if byte == b'\n' {
+                        self.size_line_end();
+                    }
+                }
+                Chunked::DataEnd => {
+                    if byte == b'\n' {
+                        self.state = Chunked::Size;
+                    }
+                }
+                Chunked::Trailer(empty) => match byte {
+                    b'\r' => {}
+                    b'\n' if empty => self.state = Chunked::Done,
+                    b'\n' => self.state = Chunked::Trailer(true),
+                    _ => self.state = Chunked::Trailer(false),
+                },
+                Chunked::Done => return Ok(()),
+            }
+            data = &data[1..];
+        }
+        Ok(())
+    }
+
+    fn is_done(&self) -> bool {
+        matches!(self.state, Chunked::Done)
+    }
+}
+
+fn c_bytes(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len() + 1);
+    bytes.extend_from_slice(text.as_bytes());
+    bytes.push(0);
+    bytes
+}
+
+unsafe fn c_str(ptr: *const u8) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    let mut len = 0;
+    while *ptr.add(len) != 0 {
+        len += 1;
+    }
+    String::from_utf8_lossy(core::slice::from_raw_parts(ptr, len)).into_owned()
+}
+
+fn split_url(url: &str) -> Option<(&str, &str, &str)> {
+    let (scheme, rest) = url.split_once("://")?;
+    let end = rest.find('/').unwrap_or(rest.len());
+    Some((scheme, &rest[..end], &rest[end..]))
+}
+
+fn resolve(base: &str, location: &str) -> String {
+    if location.contains("://") {
+        return String::from(location);
+    }
+    let (scheme, authority, path) = if let Some(x) = split_url(base) { x } else { return String::from(location); };
+    if location.starts_with('/') {
+        format!("{}://{}{}", scheme, authority, location)
+    } else {
+        let dir = &path[..path.rfind('/').map_or(0, |x| x + 1)];
+        let dir = if dir.is_empty() { "/" } else { dir };
+        format!("{}://{}{}{}", scheme, authority, dir, location)
+    }
+}
+
+struct Session {
+    child: Child<HttpProtocol>,
+}
+
+impl Session {
+    fn open(timeout_us: u64) -> Result<Self, NetError> {
+        let child = net::child::<HttpProtocol, HttpServiceBinding>()?;
+        let access = AccessPoint { use_default_address: true, local_address: [0; 4], local_subnet: [0; 4], local_port: 0 };
+        let config = ConfigData {
+            http_version: HTTP_VERSION_11,
+            timeout_ms: TIMEOUT_MS,
+            local_address_is_ipv6: false,
+            access_point: &access,
+        };
+        net::configure(timeout_us, || unsafe { ((*child.protocol).configure)(child.protocol, &config) })?;
+        Ok(Self { child })
+    }
+
+    fn submit(
+        &self,
+        call: unsafe extern "efiapi" fn(*mut HttpProtocol, *mut Token) -> Status,
+        message: &mut Message,
+        timeout_us: u64,
+    ) -> Result<Status, NetError> {
+        let protocol = self.child.protocol;
+        let signal = Signal::new()?;
+        let mut token = Token { event: signal.raw(), status: Status::NOT_READY, message };
+        unsafe { call(protocol, &mut token) }.to_result()?;
+        let done = signal.wait(timeout_us, || unsafe {
+            ((*protocol).poll)(protocol);
+        });
+        if !done {
+            unsafe { ((*protocol).cancel)(protocol, &mut token) };
+            return Err(NetError::Timeout);
+        }
+        Ok(token.status)
+    }
+
+    fn get<P: Progress>(
+        &self,
+        url: &str,
+        timeout_us: u64,
+        sink: &mut impl FnMut(&[u8]),
+        tracker: &mut Tracker<'_, P>,
+    ) -> Result<HttpResponse, NetError> {
+        let (_, authority, _) = split_url(url).ok_or(NetError::Protocol("invalid URL"))?;
+        let url16 = CString16::try_from(url).map_err(|_| NetError::Protocol("invalid URL"))?;
+        let host = c_bytes(authority);
+        let mut request_headers = [
+            Header { field_name: b"Host\0".as_ptr(), field_value: host.as_ptr() },
+            Header { field_name: b"Accept\0".as_ptr(), field_value: b"*/*\0".as_ptr() },
+            Header { field_name: b"User-Agent\0".as_ptr(), field_value: USER_AGENT.as_ptr() },
+        ];
+        let mut request = RequestData { method: METHOD_GET, url: url16.as_ptr() as *const u16 };
+        let mut message = Message {
+            data: &mut request as *mut RequestData as *mut c_void,
+            header_count: request_headers.len(),
+            headers: request_headers.as_mut_ptr(),
+            body_length: 0,
+            body: ptr::null_mut(),
+        };
+        self.submit(unsafe { (*self.child.protocol).request }, &mut message, timeout_us)?.to_result()?;
+
+        let mut buf = vec![0u8; BODY_CHUNK];
+        let mut response = ResponseData { status_code: 0 };
+        let mut message = Message {
+            data: &mut response as *mut ResponseData as *mut c_void,
+            header_count: 0,
+            headers: ptr::null_mut(),
+            body_length: buf.len(),
+            body: buf.as_mut_ptr(),
+        };
+        self.submit(unsafe { (*self.child.protocol).response }, &mut message, timeout_us)?.to_result()?;
+        let headers = unsafe { take_headers(&message) };
+        let status = STATUS_CODES.get(response.status_code as usize).copied().unwrap_or(0);
+        let result = HttpResponse { status, headers };
+        if !(200..300).contains(&status) {
+            return Ok(result);
+        }
+
+        let total = result.header("Content-Length").and_then(|x| x.trim().parse::<u64>().ok());
+        let mut chunked = result.header("Transfer-Encoding")
+            .filter(|x| x.to_ascii_lowercase().contains("chunked"))
+            .map(|_| Dechunker { state: Chunked::Size, size: 0 });
+        tracker.set_total(total);
+        let mut received = 0u64;
+        let mut deliver = |data: &[u8], received: &mut u64| {
+            if data.is_empty() {
+                return;
+            }
+            *received += data.len() as u64;
+            sink(data);
+            tracker.advance(data.len() as u64);
+        };
+        let mut first = message.body_length;
+        loop {
+            let data = &buf[..first];
+            match chunked.as_mut() {
+                Some(decoder) => decoder.feed(data, &mut |x| deliver(x, &mut received))?,
+                None => deliver(data, &mut received),
+            }
+            let finished = match (&chunked, total) {
+                (Some(decoder), _) => decoder.is_done(),
+                (None, Some(total)) => received >= total,
+                (None, None) => false,
+            };
+            if finished {
+                break;
+            }
+            let mut message = Message {
+                data: ptr::null_mut(),
+                header_count: 0,
+                headers: ptr::null_mut(),
+                body_length: buf.len(),
+                body: buf.as_mut_ptr(),
+            };
+            let status = self.submit(unsafe { (*self.child.protocol).response }, &mut message, timeout_us)?;
+            match status {
+                Status::SUCCESS if message.body_length > 0 => first = message.body_length,
+                Status::SUCCESS | Status::CONNECTION_FIN if total.is_none() && chunked.is_none() => break,
+                Status::SUCCESS => return Err(NetError::Protocol("connection closed mid-body")),
+                other => return Err(uefi::Error::from(other).into()),
+            }
+        }
+        Ok(result)
+    }
+}
+
+unsafe fn take_headers(message: &Message) -> Vec<(String, String)> {
+    if message.headers.is_null() {
+        return Vec::new();
+    }
+    let raw = core::slice::from_raw_parts(message.headers, message.header_count);
+    let headers = raw.iter().map(|x| (c_str(x.field_name), c_str(x.field_value))).collect();
+    let st = if let Some(x) = env::try_system_table() { x } else { return headers; };
+    let bs = st.boot_services();
+    for header in raw {
+        if !header.field_name.is_null() {
+            let _ = bs.free_pool(header.field_name as *mut u8);
+        }
+        if !header.field_value.is_null() {
+            let _ = bs.free_pool(header.field_value as *mut u8);
+        }
+    }
+    let _ = bs.free_pool(message.headers as *mut u8);
+    headers
+}
+
+pub fn https_available() -> bool {
+    env::try_system_table()
+        .is_some_and(|st| st.boot_services().get_handle_for_protocol::<TlsServiceBinding>().is_ok())
+}
+
+pub fn http_request(
+    url: &str,
+    timeout_us: u64,
+    mut sink: impl FnMut(&[u8]),
+    progress: &mut impl Progress,
+) -> Result<HttpResponse, NetError> {
+    if url.starts_with("https://") && !https_available() {
+        return Err(NetError::Protocol("firmware has no TLS support"));
+    }
+    let mut url = String::from(url);
+    let mut tracker = Tracker::new(progress, None);
+    for _ in 0..=MAX_REDIRECTS {
+        let session = Session::open(timeout_us)?;
+        let response = session.get(&url, timeout_us, &mut sink, &mut tracker)?;
+        let location = match response.status {
+            301 | 302 | 303 | 307 | 308 => response.header("Location").map(|x| resolve(&url, x.trim())),
+            _ => None,
+        };
+        match location {
+            Some(next) => url = next,
+            None => return Ok(response),
+        }
+    }
+    Err(NetError::Protocol("too many redirects"))
+}
+
+pub fn http_get_with(
+    url: &str,
+    mut sink: impl FnMut(&[u8]),
+    progress: &mut impl Progress,
+) -> Result<u64, NetError> {
+    let mut received = 0u64;
+    let response = http_request(url, TIMEOUT_MS as u64 * 1000, |x| {
+        received += x.len() as u64;
+        sink(x);
+    }, progress)?;
+    if !(200..300).contains(&response.status) {
+        return Err(NetError::Http(response.status));
+    }
+    Ok(received)
+}
+
+pub fn http_get(url: &str) -> Result<Vec<u8>, NetError> {
+    let mut body = Vec::new();
+    http_get_with(url, |x| body.extend_from_slice(x), &mut NoProgress)?;
+    Ok(body)
+}
@@ -0,0 +1,278 @@
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::ptr;
+
+use uefi::proto::unsafe_protocol;
+use uefi::{Event, Status};
+
+use crate::env;
+use crate::net::{self, Child, Fragment, Ipv4, NetError, ServiceBinding, Signal};
+use crate::timer::Stopwatch;
+
+const PROTOCOL_ICMP: u8 = 1;
+const ICMP_ECHO_REPLY: u8 = 0;
+const ICMP_ECHO_REQUEST: u8 = 8;
+const PAYLOAD: usize = 32;
+const DEFAULT_TIMEOUT_US: u64 = 1_000_000;
+const INTERVAL_US: u64 = 1_000_000;
+
+#[repr(C)]
+#[unsafe_protocol("c51711e7-b4bf-404a-bfb8-0a048ef1ffe4")]
+struct Ip4ServiceBinding(ServiceBinding);
+
+#[repr(C)]
+struct ConfigData {
+    default_protocol: u8,
+    accept_any_protocol: bool,
+    accept_icmp_errors: bool,
+    accept_broadcast: bool,
+    accept_promiscuous: bool,
+    use_default_address: bool,
+    station_address: [u8; 4],
+    subnet_mask: [u8; 4],
+    type_of_service: u8,
+    time_to_live: u8,
+    do_not_fragment: bool,
+    raw_data: bool,
+    receive_timeout: u32,
+    transmit_timeout: u32,
+}
+
+#[repr(C)]
+struct TxData {
+    destination: [u8; 4],
+    override_data: *const c_void,
+    options_length: u32,
+    options: *const c_void,
+    total_length: u32,
+    fragment_count: u32,
+    fragments: [Fragment; 1],
+}
+
+#[repr(C)]
+struct RxData {
+    time_stamp: [u8; 16],
+    recycle_signal: *mut c_void,
+    header_length: u32,
+    header: *const u8,
+    options_length: u32,
+    options: *const c_void,
+    data_length: u32,
+    fragment_count: u32,
+    fragments: [Fragment; 1],
+}
+
+#[repr(C)]
+struct Token {
+    event: *mut c_void,
+    status: Status,
+    packet: *mut c_void,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+#[unsafe_protocol("41d94cd2-35b6-455a-8258-d4e51334aadd")]
+struct Ip4Protocol {
+    get_mode_data: *const c_void,
+    configure: unsafe extern "efiapi" fn(*mut Ip4Protocol, *const ConfigData) -> Status,
+    groups: *const c_void,
+    routes: *const c_void,
+    transmit: unsafe extern "efiapi" fn(*mut Ip4Protocol, *mut Token) -> Status,
+    receive: unsafe extern "efiapi" fn(*mut Ip4Protocol, *mut Token) -> Status,
+    cancel: unsafe extern "efiapi" fn(*mut Ip4Protocol, *mut Token) -> Status,
+    poll: unsafe extern "efiapi" fn(*mut Ip4Protocol) -> Status,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct PingStats {
+    pub sent: u32,
+    pub received: u32,
+    pub rtts_us: Vec<Option<u64>>,
+}
+
+impl PingStats {
+    fn replies(&self) -> impl Iterator<Item = u64> + '_ {
+        self.rtts_us.iter().filter_map(|x| *x)
+    }
+
+    pub fn min_us(&self) -> Option<u64> {
+        self.replies().min()
+    }
+
+    pub fn max_us(&self) -> Option<u64> {
+        self.replies().max()
+    }
+
+    pub fn avg_us(&self) -> Option<u64> {
+        if self.received == 0 { None } else { Some(self.replies().sum::<u64>() / self.received as u64) }
+    }
+
+    pub fn loss_percent(&self) -> u32 {
+        if self.sent == 0 { 0 } else { (self.sent - self.received) * 100 / self.sent }
+    }
+}
+
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum = data.chunks(2)
+        .map(|x| u16::from_be_bytes([x[0], x.get(1).copied().unwrap_or(0)]) as u32)
+        .sum::<u32>();
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+fn echo_request(id: u16, seq: u16) -> [u8; 8 + PAYLOAD] {
+    let mut packet = [0u8; 8 + PAYLOAD];
+    packet[0] = ICMP_ECHO_REQUEST;
+    packet[4..6].copy_from_slice(&id.to_be_bytes());
+    packet[6..8].copy_from_slice(&seq.to_be_bytes());
+    for (i, byte) in packet[8..].iter_mut().enumerate() {
+        *byte = b'a' + (i % 26) as u8;
+    }
+    let sum = checksum(&packet);
+    packet[2..4].copy_from_slice(&sum.to_be_bytes());
+    packet
+}
+
+struct Pinger {
+    child: Child<Ip4Protocol>,
+}
+
+impl Pinger {
+    fn open() -> Result<Self, NetError> {
+        let child = net::child::<Ip4Protocol, Ip4ServiceBinding>()?;
+        let config = ConfigData {
+            default_protocol: PROTOCOL_ICMP,
+            accept_any_protocol: false,
+            accept_icmp_errors: false,
+            accept_broadcast: false,
+            accept_promiscuous: false,
+            use_default_address: true,
+            station_address: [0; 4],
+            subnet_mask: [0; 4],
+            type_of_service: 0,
+            time_to_live: 64,
+            do_not_fragment: false,
+            raw_data: false,
+            receive_timeout: 0,
+            transmit_timeout: 0,
+        };
+        net::configure(DEFAULT_TIMEOUT_US * 5, || unsafe { ((*child.protocol).configure)(child.protocol, &config) })?;
+        Ok(Self { child })
+    }
+
+    fn run(
+        &self,
+        token: &mut Token,
+        timeout_us: u64,
+        call: unsafe extern "efiapi" fn(*mut Ip4Protocol, *mut Token) -> Status,
+    ) -> Result<(), NetError> {
+        let protocol = self.child.protocol;
+        let signal = Signal::new()?;
+        token.event = signal.raw();
+        unsafe { call(protocol, token) }.to_result()?;
+        let done = signal.wait(timeout_us, || unsafe {
+            ((*protocol).poll)(protocol);
+        });
+        if !done {
+            unsafe { ((*protocol).cancel)(protocol, token) };
+            return Err(NetError::Timeout);
+        }
+        Ok(token.status.to_result()?)
+    }
+
+    fn send(&self, addr: Ipv4, packet: &[u8]) -> Result<(), NetError> {
+        let mut tx = TxData {
+            destination: addr.0,
+            override_data: ptr::null(),
+            options_length: 0,
+            options: ptr::null(),
+            total_length: packet.len() as u32,
+            fragment_count: 1,
+            fragments: [Fragment { len: packet.len() as u32, buffer: packet.as_ptr() as *mut c_void }],
+        };
+        let mut token = Token { event: ptr::null_mut(), status: Status::NOT_READY, packet: &mut tx as *mut TxData as *mut c_void };
+        self.run(&mut token, DEFAULT_TIMEOUT_US, unsafe { (*self.child.protocol).transmit })
+    }
+
+    fn receive(&self, timeout_us: u64) -> Result<(Ipv4, Vec<u8>), NetError> {
+        let mut token = Token { event: ptr::null_mut(), status: Status::NOT_READY, packet: ptr::null_mut() };
+        self.run(&mut token, timeout_us, unsafe { (*self.child.protocol).receive })?;
+        let rx = token.packet as *const RxData;
+        if rx.is_null() {
+            return Err(NetError::Protocol("receive completed without data"));
+        }
+        unsafe {
+            let mut data = Vec::with_capacity((*rx).data_length as usize);
+            let fragments = ptr::addr_of!((*rx).fragments) as *const Fragment;
+            for i in 0..(*rx).fragment_count as usize {
+                let fragment = &*fragments.add(i);
+                data.extend_from_slice(core::slice::from_raw_parts(fragment.buffer as *const u8, fragment.len as usize));
+            }
+            let source = if (*rx).header.is_null() || (*rx).header_length < 16 {
+                Ipv4::UNSPECIFIED
+            } else {
+                Ipv4(ptr::read_unaligned((*rx).header.add(12) as *const [u8; 4]))
+            };
+            if let (Some(recycle), Some(st)) = (Event::from_ptr((*rx).recycle_signal), env::try_system_table()) {
+                let _ = st.boot_services().signal_event(&recycle);
+            }
+            Ok((source, data))
+        }
+    }
+
+    fn echo(&self, addr: Ipv4, id: u16, seq: u16, timeout_us: u64) -> Result<Option<u64>, NetError> {
+        let watch = Stopwatch::start();
+        self.send(addr, &echo_request(id, seq))?;
+        loop {
+            let left = timeout_us.saturating_sub(watch.elapsed_us());
+            if left == 0 {
+                return Ok(None);
+            }
+            let (source, data) = match self.receive(left) {
+                Ok(x) => x,
+                Err(NetError::Timeout) => return Ok(None),
+                Err(err) => return Err(err),
+            };
+            let matches = source == addr
+                && data.len() >= 8
+                && data[0] == ICMP_ECHO_REPLY
+                && data[4..6] == id.to_be_bytes()
+                && data[6..8] == seq.to_be_bytes();
+            if matches {
+                return Ok(Some(watch.elapsed_us()));
+            }
+        }
+    }
+}
+
+pub fn ping_with(
+    addr: Ipv4,
+    count: u32,
+    timeout_us: u64,
+    mut progress: impl FnMut(u32, Option<u64>),
+) -> Result<PingStats, NetError> {
+    let pinger = Pinger::open()?;
+    let id = crate::rand::u32() as u16;
+    let mut stats = PingStats::default();
+    for seq in 0..count {
+        let watch = Stopwatch::start();
+        let rtt = pinger.echo(addr, id, seq as u16, timeout_us)?;
+        stats.sent += 1;
+        if rtt.is_some() {
+            stats.received += 1;
+        }
+        stats.rtts_us.push(rtt);
+        progress(seq + 1, rtt);
+        let elapsed = watch.elapsed_us();
+        if seq + 1 < count && elapsed < INTERVAL_US {
+            env::system_table()?.boot_services().stall((INTERVAL_US - elapsed) as usize);
+        }
+    }
+    Ok(stats)
+}
+
+pub fn ping(addr: Ipv4, count: u32) -> Result<PingStats, NetError> {
+    ping_with(addr, count, DEFAULT_TIMEOUT_US, |_, _| {})
+}
@@ -0,0 +1,215 @@
+use core::ffi::c_void;
+use core::ptr;
+
+use uefi::proto::unsafe_protocol;
+use uefi::Status;
+
+use crate::net::{self, Child, Fragment, Ipv4, NetError, ServiceBinding, Signal};
+
+const DEFAULT_TIMEOUT_US: u64 = 10_000_000;
+
+#[repr(C)]
+#[unsafe_protocol("00720665-67eb-4a99-baf7-d3c33a1c7cd9")]
+struct Tcp4ServiceBinding(ServiceBinding);
+
+#[repr(C)]
+struct AccessPoint {
+    use_default_address: bool,
+    station_address: [u8; 4],
+    subnet_mask: [u8; 4],
+    station_port: u16,
+    remote_address: [u8; 4],
+    remote_port: u16,
+    active: bool,
+}
+
+#[repr(C)]
+struct ConfigData {
+    type_of_service: u8,
+    time_to_live: u8,
+    access_point: AccessPoint,
+    control_option: *const c_void,
+}
+
+#[repr(C)]
+struct Completion {
+    event: *mut c_void,
+    status: Status,
+}
+
+#[repr(C)]
+struct IoToken {
+    completion: Completion,
+    packet: *mut c_void,
+}
+
+#[repr(C)]
+struct CloseToken {
+    completion: Completion,
+    abort: bool,
+}
+
+#[repr(C)]
+struct RxData {
+    urgent: bool,
+    data_length: u32,
+    fragment_count: u32,
+    fragments: [Fragment; 1],
+}
+
+#[repr(C)]
+struct TxData {
+    push: bool,
+    urgent: bool,
+    data_length: u32,
+    fragment_count: u32,
+    fragments: [Fragment; 1],
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+#[unsafe_protocol("65530bc7-a359-410f-b010-5aadc7ec2b62")]
+struct Tcp4Protocol {
+    get_mode_data: *const c_void,
+    configure: unsafe extern "efiapi" fn(*mut Tcp4Protocol, *const ConfigData) -> Status,
+    routes: *const c_void,
+    connect: unsafe extern "efiapi" fn(*mut Tcp4Protocol, *mut Completion) -> Status,
+    accept: *const c_void,
+    transmit: unsafe extern "efiapi" fn(*mut Tcp4Protocol, *mut IoToken) -> Status,
+    receive: unsafe extern "efiapi" fn(*mut Tcp4Protocol, *mut IoToken) -> Status,
+    close: unsafe extern "efiapi" fn(*mut Tcp4Protocol, *mut CloseToken) -> Status,
+    cancel: unsafe extern "efiapi" fn(*mut Tcp4Protocol, *mut Completion) -> Status,
+    poll: unsafe extern "efiapi" fn(*mut Tcp4Protocol) -> Status,
+}
+
+pub struct TcpStream {
+    child: Child<Tcp4Protocol>,
+    peer: (Ipv4, u16),
+    timeout_us: u64,
+}
+
+impl TcpStream {
+    pub fn connect(addr: Ipv4, port: u16, timeout_us: u64) -> Result<Self, NetError> {
+        let child = net::child::<Tcp4Protocol, Tcp4ServiceBinding>()?;
+        let config = ConfigData {
+            type_of_service: 0,
+            time_to_live: 64,
+            access_point: AccessPoint {
+                use_default_address: true,
+                station_address: [0; 4],
+                subnet_mask: [0; 4],
+                station_port: 0,
+                remote_address: addr.0,
+                remote_port: port,
+                active: true,
+            },
+            control_option: ptr::null(),
+        };
+        net::configure(timeout_us, || unsafe { ((*child.protocol).configure)(child.protocol, &config) })?;
+        let stream = Self { child, peer: (addr, port), timeout_us: DEFAULT_TIMEOUT_US };
+        let mut completion = Completion { event: ptr::null_mut(), status: Status::NOT_READY };
+        stream.run(&mut completion, timeout_us, |protocol, token| unsafe { ((*protocol).connect)(protocol, token) })?;
+        completion.status.to_result()?;
+        Ok(stream)
+    }
+
+    pub fn peer(&self) -> (Ipv4, u16) {
+        self.peer
+    }
+
+    pub fn timeout_us(&self) -> u64 {
+        self.timeout_us
+    }
+
+    pub fn set_timeout(&mut self, timeout_us: u64) {
+        self.timeout_us = timeout_us;
+    }
+
+    fn run<T>(
+        &self,
+        token: &mut T,
+        timeout_us: u64,
+        call: impl FnOnce(*mut Tcp4Protocol, *mut T) -> Status,
+    ) -> Result<(), NetError> {
+        let protocol = self.child.protocol;
+        let token = token as *mut T;
+        let completion = token as *mut Completion;
+        let signal = Signal::new()?;
+        unsafe { (*completion).event = signal.raw() };
+        call(protocol, token).to_result()?;
+        let done = signal.wait(timeout_us, || unsafe {
+            ((*protocol).poll)(protocol);
+        });
+        if !done {
+            unsafe { ((*protocol).cancel)(protocol, completion) };
+            return Err(NetError::Timeout);
+        }
+        Ok(())
+    }
+
+    pub fn send(&mut self, data: &[u8]) -> Result<usize, NetError> {
+        let len = data.len().min(u32::MAX as usize);
+        let mut tx = TxData {
+            push: true,
+            urgent: false,
+            data_length: len as u32,
+            fragment_count: 1,
+            fragments: [Fragment { len: len as u32, buffer: data.as_ptr() as *mut c_void }],
+        };
+        let mut token = IoToken {
+            completion: Completion { event: ptr::null_mut(), status: Status::NOT_READY },
+            packet: &mut tx as *mut TxData as *mut c_void,
+        };
+        self.run(&mut token, self.timeout_us, |protocol, token| unsafe { ((*protocol).transmit)(protocol, token) })?;
+        token.completion.status.to_result()?;
+        Ok(len)
+    }
+
+    pub fn send_all(&mut self, mut data: &[u8]) -> Result<(), NetError> {
+        while !data.is_empty() {
+            let sent = self.send(data)?;
+            data = &data[sent..];
+        }
+        Ok(())
+    }
+
+    pub fn recv(&mut self, buf: &mut [u8]) -> Result<usize, NetError> {
+        let len = buf.len().min(u32::MAX as usize) as u32;
+        let mut rx = RxData {
+            urgent: false,
+            data_length: len,
+            fragment_count: 1,
+            fragments: [Fragment { len, buffer: buf.as_mut_ptr() as *mut c_void }],
+        };
+        let mut token = IoToken {
+            completion: Completion { event: ptr::null_mut(), status: Status::NOT_READY },
+            packet: &mut rx as *mut RxData as *mut c_void,
+        };
+        self.run(&mut token, self.timeout_us, |protocol, token| unsafe { ((*protocol).receive)(protocol, token) })?;
+        match token.completion.status {
+            Status::SUCCESS => Ok(rx.data_length as usize),
+            Status::CONNECTION_FIN => Ok(0),
+            other => Err(uefi::Error::from(other).into()),
+        }
+    }
+
+    pub fn recv_exact(&mut self, mut buf: &mut [u8]) -> Result<(), NetError> {
+        while !buf.is_empty() {
+            let read = self.recv(buf)?;
+            if read == 0 {
+                return Err(NetError::Protocol("connection closed"));
+            }
+            buf = &mut buf[read..];
+        }
+        Ok(())
+    }
+
+    pub fn close(self) -> Result<(), NetError> {
+        let mut token = CloseToken {
+            completion: Completion { event: ptr::null_mut(), status: Status::NOT_READY },
+            abort: false,
+        };
+        self.run(&mut token, self.timeout_us, |protocol, token| unsafe { ((*protocol).close)(protocol, token) })?;
+        Ok(token.completion.status.to_result()?)
+    }
+}
@@ -0,0 +1,167 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use uefi::CStr8;
+
+use crate::net::{with_pxe, Ipv4, NetError, UdpSocket};
+use crate::progress::{NoProgress, Progress, Tracker};
+
+const CHUNK: usize = 64 * 1024;
+const PORT: u16 = 69;
+const DEFAULT_BLOCK: usize = 512;
+const BLOCK: usize = 1428;
+const RETRIES: u32 = 5;
+const OP_RRQ: u16 = 1;
+const OP_DATA: u16 = 3;
+const OP_ACK: u16 = 4;
+const OP_ERROR: u16 = 5;
+const OP_OACK: u16 = 6;
+
+fn file_name(filename: &str) -> Result<Vec<u8>, NetError> {
+    if filename.is_empty() || filename.bytes().any(|x| x == 0 || !x.is_ascii()) {
+        return Err(NetError::Protocol("invalid TFTP file name"));
+    }
+    let mut bytes = Vec::with_capacity(filename.len() + 1);
+    bytes.extend_from_slice(filename.as_bytes());
+    bytes.push(0);
+    Ok(bytes)
+}
+
+pub fn tftp_size(server: Ipv4, filename: &str) -> Result<u64, NetError> {
+    let name = file_name(filename)?;
+    let name = CStr8::from_bytes_with_nul(&name).map_err(|_| NetError::Protocol("invalid TFTP file name"))?;
+    with_pxe(|pxe| Ok(pxe.tftp_get_file_size(&server.to_ip(), name)?))
+}
+
+pub fn tftp_read(server: Ipv4, filename: &str) -> Result<Vec<u8>, NetError> {
+    let name = file_name(filename)?;
+    let name = CStr8::from_bytes_with_nul(&name).map_err(|_| NetError::Protocol("invalid TFTP file name"))?;
+    with_pxe(|pxe| {
+        let ip = server.to_ip();
+        let size = pxe.tftp_get_file_size(&ip, name)? as usize;
+        let mut buf = vec![0u8; size];
+        let read = pxe.tftp_read_file(&ip, name, Some(&mut buf))? as usize;
+        buf.truncate(read);
+        Ok(buf)
+    })
+}
+
+fn read_request(filename: &str) -> Vec<u8> {
+    let mut packet = Vec::from(OP_RRQ.to_be_bytes());
+    let block = alloc::format!("{}", BLOCK);
+    for field in [filename, "octet", "blksize", block.as_str(), "tsize", "0"] {
+        packet.extend_from_slice(field.as_bytes());
+        packet.push(0);
+    }
+    packet
+}
+
+fn ack(block: u16) -> [u8; 4] {
+    let [a, b] = OP_ACK.to_be_bytes();
+    let [c, d] = block.to_be_bytes();
+    [a, b, c, d]
+}
+
+fn options(data: &[u8]) -> (usize, Option<u64>) {
+    let mut fields = data.split(|x| *x == 0).map(|x| core::str::from_utf8(x).unwrap_or(""));
+    let (mut block, mut size) = (DEFAULT_BLOCK, None);
+    while let (Some(key), Some(value)) = (fields.next(), fields.next()) {
+        if key.eq_ignore_ascii_case("blksize") {
+            block = value.parse().ok().filter(|x| (8..=BLOCK).contains(x)).unwrap_or(DEFAULT_BLOCK);
+        } else if key.eq_ignore_ascii_case("tsize") {
+            size = value.parse().ok();
+        }
+    }
+    (block, size)
+}
+
+fn stream<P: Progress>(
+    socket: &mut UdpSocket,
+    server: Ipv4,
+    filename: &str,
+    sink: &mut impl FnMut(&[u8]),
+    tracker: &mut Tracker<P>,
+) -> Result<u64, NetError> {
+    let mut last = read_request(filename);
+    let mut port = PORT;
+    let mut peer = None;
+    let mut block_size = DEFAULT_BLOCK;
+    let mut expected: u16 = 1;
+    let mut retries = 0;
+    let mut buf = vec![0u8; 4 + BLOCK];
+    socket.send_to(&last, server, port)?;
+    loop {
+        let (len, addr, from) = match socket.recv_from(&mut buf) {
+            Ok(x) => x,
+            Err(NetError::Timeout) if retries < RETRIES => {
+                retries += 1;
+                socket.send_to(&last, server, port)?;
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        if len < 4 || addr != server || peer.is_some_and(|x| x != from) {
+            continue;
+        }
+        peer = Some(from);
+        port = from;
+        let packet = &buf[..len];
+        let arg = u16::from_be_bytes([packet[2], packet[3]]);
+        match u16::from_be_bytes([packet[0], packet[1]]) {
+            OP_OACK if expected == 1 => {
+                let (block, size) = options(&packet[2..]);
+                block_size = block;
+                tracker.set_total(size);
+                last = Vec::from(ack(0));
+            }
+            OP_DATA if arg == expected => {
+                let data = &packet[4..];
+                sink(data);
+                tracker.advance(data.len() as u64);
+                last = Vec::from(ack(arg));
+                if data.len() < block_size {
+                    socket.send_to(&last, server, port)?;
+                    return Ok(tracker.done());
+                }
+                expected = expected.wrapping_add(1);
+            }
+            OP_DATA if arg == expected.wrapping_sub(1) => {}
+            OP_ERROR if arg == 1 => return Err(uefi::Error::from(uefi::Status::NOT_FOUND).into()),
+            OP_ERROR => return Err(NetError::Protocol("TFTP transfer rejected by server")),
+            _ => return Err(NetError::Protocol("unexpected TFTP packet")),
+        }
+        retries = 0;
+        socket.send_to(&last, server, port)?;
+    }
+}
+
+/// Streams the file block by block over UDP, reporting progress as each
+/// block arrives. Without a UDP4 stack it falls back to reading the whole
+/// file through PXE first, so progress only moves once it is in memory.
+pub fn tftp_get_with(
+    server: Ipv4,
+    filename: &str,
+    mut sink: impl FnMut(&[u8]),
+    progress: &mut impl Progress,
+) -> Result<u64, NetError> {
+    file_name(filename)?;
+    let mut socket = match UdpSocket::bind(0) {
+        Ok(x) => x,
+        Err(NetError::NoInterface) => {
+            let data = tftp_read(server, filename)?;
+            let mut tracker = Tracker::new(progress, Some(data.len() as u64));
+            for chunk in data.chunks(CHUNK) {
+                sink(chunk);
+                tracker.advance(chunk.len() as u64);
+            }
+            return Ok(tracker.done());
+        }
+        Err(err) => return Err(err),
+    };
+    let mut tracker = Tracker::new(progress, None);
+    stream(&mut socket, server, filename, &mut sink, &mut tracker)
+}
+
+pub fn tftp_get(server: Ipv4, filename: &str, sink: impl FnMut(&[u8])) -> Result<u64, NetError> {
+    tftp_get_with(server, filename, sink, &mut NoProgress)
+}
@@ -0,0 +1,180 @@
+use core::ffi::c_void;
+use core::ptr;
+
+use uefi::proto::unsafe_protocol;
+use uefi::{Event, Status};
+
+use crate::env;
+use crate::net::{self, Child, Fragment, Ipv4, NetError, ServiceBinding, Signal};
+
+const DEFAULT_TIMEOUT_US: u64 = 5_000_000;
+
+#[repr(C)]
+#[unsafe_protocol("83f01464-99bd-45e5-b383-af6305d8e9e6")]
+struct Udp4ServiceBinding(ServiceBinding);
+
+#[repr(C)]
+struct ConfigData {
+    accept_broadcast: bool,
+    accept_promiscuous: bool,
+    accept_any_port: bool,
+    allow_duplicate_port: bool,
+    type_of_service: u8,
+    time_to_live: u8,
+    do_not_fragment: bool,
+    receive_timeout: u32,
+    transmit_timeout: u32,
+    use_default_address: bool,
+    station_address: [u8; 4],
+    subnet_mask: [u8; 4],
+    station_port: u16,
+    remote_address: [u8; 4],
+    remote_port: u16,
+}
+
+#[repr(C)]
+struct SessionData {
+    source_address: [u8; 4],
+    source_port: u16,
+    destination_address: [u8; 4],
+    destination_port: u16,
+}
+
+#[repr(C)]
+struct TxData {
+    session: *const SessionData,
+    gateway: *const [u8; 4],
+    data_length: u32,
+    fragment_count: u32,
+    fragments: [Fragment; 1],
+}
+
+#[repr(C)]
+struct RxData {
+    time_stamp: [u8; 16],
+    recycle_signal: *mut c_void,
+    session: SessionData,
+    data_length: u32,
+    fragment_count: u32,
+    fragments: [Fragment; 1],
+}
+
+#[repr(C)]
+struct Token {
+    event: *mut c_void,
+    status: Status,
+    packet: *mut c_void,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+#[unsafe_protocol("3ad9df29-4501-478d-b1f8-7f7fe70e50f3")]
+struct Udp4Protocol {
+    get_mode_data: *const c_void,
+    configure: unsafe extern "efiapi" fn(*mut Udp4Protocol, *const ConfigData) -> Status,
+    groups: *const c_void,
+    routes: *const c_void,
+    transmit: unsafe extern "efiapi" fn(*mut Udp4Protocol, *mut Token) -> Status,
+    receive: unsafe extern "efiapi" fn(*mut Udp4Protocol, *mut Token) -> Status,
+    cancel: unsafe extern "efiapi" fn(*mut Udp4Protocol, *mut Token) -> Status,
+    poll: unsafe extern "efiapi" fn(*mut Udp4Protocol) -> Status,
+}
+
+pub struct UdpSocket {
+    child: Child<Udp4Protocol>,
+    timeout_us: u64,
+}
+
+impl UdpSocket {
+    pub fn bind(port: u16) -> Result<Self, NetError> {
+        let child = net::child::<Udp4Protocol, Udp4ServiceBinding>()?;
+        let config = ConfigData {
+            accept_broadcast: true,
+            accept_promiscuous: false,
+            accept_any_port: false,
+            allow_duplicate_port: false,
+            type_of_service: 0,
+            time_to_live: 64,
+            do_not_fragment: false,
+            receive_timeout: 0,
+            transmit_timeout: 0,
+            use_default_address: true,
+            station_address: [0; 4],
+            subnet_mask: [0; 4],
+            station_port: port,
+            remote_address: [0; 4],
+            remote_port: 0,
+        };
+        net::configure(DEFAULT_TIMEOUT_US, || unsafe { ((*child.protocol).configure)(child.protocol, &config) })?;
+        Ok(Self { child, timeout_us: DEFAULT_TIMEOUT_US })
+    }
+
+    pub fn timeout_us(&self) -> u64 {
+        self.timeout_us
+    }
+
+    pub fn set_timeout(&mut self, timeout_us: u64) {
+        self.timeout_us = timeout_us;
+    }
+
+    fn run(
+        &self,
+        token: &mut Token,
+        call: unsafe extern "efiapi" fn(*mut Udp4Protocol, *mut Token) -> Status,
+    ) -> Result<(), NetError> {
+        let protocol = self.child.protocol;
+        let signal = Signal::new()?;
+        token.event = signal.raw();
+        unsafe { call(protocol, token) }.to_result()?;
+        let done = signal.wait(self.timeout_us, || unsafe {
+            ((*protocol).poll)(protocol);
+        });
+        if !done {
+            unsafe { ((*protocol).cancel)(protocol, token) };
+            return Err(NetError::Timeout);
+        }
+        Ok(token.status.to_result()?)
+    }
+
+    pub fn send_to(&mut self, data: &[u8], addr: Ipv4, port: u16) -> Result<(), NetError> {
+        let session = SessionData {
+            source_address: [0; 4],
+            source_port: 0,
+            destination_address: addr.0,
+            destination_port: port,
+        };
+        let mut tx = TxData {
+            session: &session,
+            gateway: ptr::null(),
+            data_length: data.len() as u32,
+            fragment_count: 1,
+            fragments: [Fragment { len: data.len() as u32, buffer: data.as_ptr() as *mut c_void }],
+        };
+        let mut token = Token { event: ptr::null_mut(), status: Status::NOT_READY, packet: &mut tx as *mut TxData as *mut c_void };
+        self.run(&mut token, unsafe { (*self.child.protocol).transmit })
+    }
+
+    pub fn recv_from(&mut self, buf: &mut [u8]) -> Result<(usize, Ipv4, u16), NetError> {
+        let mut token = Token { event: ptr::null_mut(), status: Status::NOT_READY, packet: ptr::null_mut() };
+        self.run(&mut token, unsafe { (*self.child.protocol).receive })?;
+        let rx = token.packet as *const RxData;
+        if rx.is_null() {
+            return Err(NetError::Protocol("receive completed without data"));
+        }
+        unsafe {
+            let fragments = ptr::addr_of!((*rx).fragments) as *const Fragment;
+            let mut copied = 0;
+            for i in 0..(*rx).fragment_count as usize {
+                let fragment = &*fragments.add(i);
+                let take = (fragment.len as usize).min(buf.len() - copied);
+                ptr::copy_nonoverlapping(fragment.buffer as *const u8, buf.as_mut_ptr().add(copied), take);
+                copied += take;
+            }
+            let source = (Ipv4((*rx).session.source_address), (*rx).session.source_port);
+            if let (Some(recycle), Some(st)) = (Event::from_ptr((*rx).recycle_signal), env::try_system_table()) {
+                let _ = st.boot_services().signal_event(&recycle);
+            }
+            Ok((copied, source.0, source.1))
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use core::panic::PanicInfo;
+
+use log::error;
+
+use crate::env;
+
+const STALL_US: usize = 10_000_000;
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    error!("{}", info);
+    if let Some(st) = env::try_system_table() {
+        st.boot_services().stall(STALL_US);
+    }
+    loop {
+        core::hint::spin_loop();
+    }
+}
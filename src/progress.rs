@@ -0,0 +1,143 @@
+#[cfg(feature = "gfx")]
+use baked_font::Font;
+
+#[cfg(feature = "gfx")]
+use crate::gfx::{Color, ProgressBar, Screen};
+use crate::timer::Stopwatch;
+
+#[cfg(feature = "gfx")]
+const REDRAW_US: u64 = 50_000;
+#[cfg(feature = "gfx")]
+const MARQUEE_STEP: f32 = 0.02;
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Transfer {
+    pub done: u64,
+    pub total: Option<u64>,
+    pub elapsed_us: u64,
+}
+
+impl Transfer {
+    pub fn fraction(&self) -> Option<f32> {
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.done as f64 / total as f64).min(1.0) as f32),
+            None => None,
+        }
+    }
+
+    pub fn bytes_per_sec(&self) -> u64 {
+        if self.elapsed_us == 0 { 0 } else { (self.done as u128 * 1_000_000 / self.elapsed_us as u128) as u64 }
+    }
+
+    pub fn eta_us(&self) -> Option<u64> {
+        let rate = self.bytes_per_sec();
+        let left = self.total?.saturating_sub(self.done);
+        if rate == 0 { None } else { Some((left as u128 * 1_000_000 / rate as u128) as u64) }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.total.is_some_and(|x| self.done >= x)
+    }
+}
+
+pub trait Progress {
+    fn update(&mut self, transfer: &Transfer);
+}
+
+impl<F: FnMut(&Transfer)> Progress for F {
+    fn update(&mut self, transfer: &Transfer) {
+        self(transfer)
+    }
+}
+
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn update(&mut self, _: &Transfer) {}
+}
+
+pub(crate) struct Tracker<'a, P: Progress> {
+    progress: &'a mut P,
+    watch: Stopwatch,
+    transfer: Transfer,
+}
+
+impl<'a, P: Progress> Tracker<'a, P> {
+    pub(crate) fn new(progress: &'a mut P, total: Option<u64>) -> Self {
+        let mut tracker = Self { progress, watch: Stopwatch::start(), transfer: Transfer { total, ..Transfer::default() } };
+        tracker.progress.update(&tracker.transfer);
+        tracker
+    }
+
+    pub(crate) fn set_total(&mut self, total: Option<u64>) {
+        self.transfer.total = total;
+    }
+
+    pub(crate) fn advance(&mut self, bytes: u64) {
+        self.transfer.done += bytes;
+        self.transfer.elapsed_us = self.watch.elapsed_us();
+        self.progress.update(&self.transfer);
+    }
+
+    pub(crate) fn done(&self) -> u64 {
+        self.transfer.done
+    }
+}
+
+#[cfg(feature = "gfx")]
+pub struct BarProgress<'a> {
+    pub bar: &'a mut ProgressBar,
+    pub label: Option<(&'a Font, Color)>,
+    watch: Option<Stopwatch>,
+}
+
+#[cfg(feature = "gfx")]
+impl<'a> BarProgress<'a> {
+    pub fn new(bar: &'a mut ProgressBar) -> Self {
+        Self { bar, label: None, watch: None }
+    }
+
+    pub fn label(mut self, font: &'a Font, color: Color) -> Self {
+        self.label = Some((font, color));
+        self
+    }
+
+    pub fn draw(&mut self, marquee: bool) {
+        let screen = Screen::get();
+        if marquee {
+            self.bar.draw_marquee(screen);
+        } else {
+            self.bar.draw_normal(screen);
+            if let Some((font, color)) = self.label {
+                self.bar.draw_percent(screen, font, color);
+            }
+        }
+        Screen::present(self.bar.area.rect());
+    }
+}
+
+#[cfg(feature = "gfx")]
+impl Progress for BarProgress<'_> {
+    fn update(&mut self, transfer: &Transfer) {
+        let due = match self.watch {
+            Some(watch) => watch.elapsed_us() >= REDRAW_US,
+            None => true,
+        };
+        if !due && !transfer.is_complete() {
+            return;
+        }
+        self.watch = Some(Stopwatch::start());
+        match transfer.fraction() {
+            Some(fraction) => {
+                self.bar.set_target(fraction);
+                self.bar.progress = self.bar.target;
+                self.draw(false);
+            }
+            None => {
+                self.bar.progress = (self.bar.progress + MARQUEE_STEP) % 1.0;
+                self.draw(true);
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use core::ptr;
+
+use uefi::proto::rng::Rng;
+use uefi::Guid;
+
+use crate::env;
+
+pub struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+impl Xoshiro256 {
+    pub fn from_seed(seed: u64) -> Self {
+        let mut state = seed;
+        Self { s: [splitmix64(&mut state), splitmix64(&mut state), splitmix64(&mut state), splitmix64(&mut state)] }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+        result
+    }
+
+    pub fn fill_bytes(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            let value = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&value[..chunk.len()]);
+        }
+    }
+}
+
+static mut FALLBACK: Option<Xoshiro256> = None;
+static mut HARDWARE: Option<bool> = None;
+
+#[cfg(target_arch = "x86_64")]
+fn cycle_counter() -> u64 {
+    unsafe { core::arch::x86_64::_rdtsc() }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn cycle_counter() -> u64 {
+    0
+}
+
+fn entropy_seed() -> u64 {
+    let mut seed = cycle_counter();
+    if let Some(time) = env::try_system_table().and_then(|st| st.runtime_services().get_time().ok()) {
+        seed ^= (time.nanosecond() as u64) << 32;
+        seed ^= ((time.second() as u64) << 16) | ((time.minute() as u64) << 8) | time.hour() as u64;
+        seed = seed.rotate_left(13) ^ (((time.year() as u64) << 40) | ((time.month() as u64) << 36) | time.day() as u64);
+    }
+    let local = 0u8;
+    seed ^ (&local as *const u8 as u64).rotate_left(29)
+}
+
+fn hardware_fill(buf: &mut [u8]) -> bool {
+    if unsafe { HARDWARE } == Some(false) {
+        return false;
+    }
+    let st = if let Some(x) = env::try_system_table() { x } else { return false; };
+    let bs = st.boot_services();
+    let ok = bs.get_handle_for_protocol::<Rng>()
+        .and_then(|handle| bs.open_protocol_exclusive::<Rng>(handle))
+        .and_then(|mut rng| rng.get_rng(None, buf))
+        .is_ok();
+    unsafe { HARDWARE = Some(ok) };
+    ok
+}
+
+pub fn hardware_available() -> bool {
+    let mut probe = [0u8; 8];
+    hardware_fill(&mut probe)
+}
+
+pub fn reseed(seed: u64) {
+    unsafe { FALLBACK = Some(Xoshiro256::from_seed(seed)) };
+}
+
+pub fn fill_bytes(buf: &mut [u8]) {
+    if buf.is_empty() || hardware_fill(buf) {
+        return;
+    }
+    let rng = unsafe { (*ptr::addr_of_mut!(FALLBACK)).get_or_insert_with(|| Xoshiro256::from_seed(entropy_seed())) };
+    rng.fill_bytes(buf);
+}
+
+pub fn u64() -> u64 {
+    let mut buf = [0u8; 8];
+    fill_bytes(&mut buf);
+    u64::from_le_bytes(buf)
+}
+
+pub fn u32() -> u32 {
+    let mut buf = [0u8; 4];
+    fill_bytes(&mut buf);
+    u32::from_le_bytes(buf)
+}
+
+pub fn bool() -> bool {
+    u32() & 1 != 0
+}
+
+pub fn below(bound: u64) -> u64 {
+    if bound == 0 {
+        return 0;
+    }
+    let zone = u64::MAX - u64::MAX % bound;
+    loop {
+        let value = u64();
+        if value < zone {
+            return value % bound;
+        }
+    }
+}
+
+pub fn range(lo: i64, hi: i64) -> i64 {
+    if hi <= lo {
+        return lo;
+    }
+    lo.wrapping_add(below(hi.wrapping_sub(lo) as u64) as i64)
+}
+
+pub fn unit_f32() -> f32 {
+    (u32() >> 8) as f32 / (1u32 << 24) as f32
+}
+
+pub fn range_f32(lo: f32, hi: f32) -> f32 {
+    lo + (hi - lo) * unit_f32()
+}
+
+pub fn shuffle<T>(items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+        let j = below(i as u64 + 1) as usize;
+        items.swap(i, j);
+    }
+}
+
+pub fn choose<T>(items: &[T]) -> Option<&T> {
+    if items.is_empty() { None } else { items.get(below(items.len() as u64) as usize) }
+}
+
+pub fn guid() -> Guid {
+    let mut bytes = [0u8; 16];
+    fill_bytes(&mut bytes);
+    bytes[7] = (bytes[7] & 0x0F) | 0x40;
+    bytes[8] = (bytes[8] & 0x3F) | 0x80;
+    Guid::from_bytes(bytes)
+}
@@ -0,0 +1,69 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use uefi::proto::loaded_image::LoadedImage;
+use uefi::table::boot::MemoryType;
+use uefi::Handle;
+
+use crate::env;
+use crate::fs::EfiPathBuf;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ImageInfo {
+    pub handle: Handle,
+    pub device: Option<Handle>,
+    pub file_path: Option<EfiPathBuf>,
+    pub base: u64,
+    pub size: u64,
+    pub code_type: MemoryType,
+    pub data_type: MemoryType,
+    pub load_options: Vec<u8>,
+}
+
+impl ImageInfo {
+    pub fn get() -> Option<Self> {
+        let st = env::try_system_table()?;
+        let bs = st.boot_services();
+        let handle = bs.image_handle();
+        let image = bs.open_protocol_exclusive::<LoadedImage>(handle).ok()?;
+        let (base, size) = image.info();
+        Some(Self {
+            handle,
+            device: image.device(),
+            file_path: image.file_path().and_then(EfiPathBuf::from_device_path),
+            base: base as u64,
+            size,
+            code_type: image.code_type(),
+            data_type: image.data_type(),
+            load_options: image.load_options_as_bytes().map(|x| x.to_vec()).unwrap_or_default(),
+        })
+    }
+
+    pub fn end(&self) -> u64 {
+        self.base + self.size
+    }
+
+    pub fn contains(&self, addr: u64) -> bool {
+        (self.base..self.end()).contains(&addr)
+    }
+
+    pub fn load_options_ucs2(&self) -> Vec<u16> {
+        let mut units: Vec<u16> = self.load_options.chunks_exact(2)
+            .map(|x| u16::from_le_bytes([x[0], x[1]]))
+            .collect();
+        if let Some(end) = units.iter().position(|x| *x == 0) {
+            units.truncate(end);
+        }
+        units
+    }
+
+    pub fn load_options_str(&self) -> String {
+        char::decode_utf16(self.load_options_ucs2())
+            .map(|x| x.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    }
+}
+
+pub fn load_options() -> String {
+    ImageInfo::get().map(|x| x.load_options_str()).unwrap_or_default()
+}
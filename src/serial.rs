@@ -0,0 +1,178 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+use uefi::proto::console::serial::{IoMode, Serial as SerialIo};
+use uefi::table::boot::{OpenProtocolAttributes, OpenProtocolParams};
+use uefi::{Handle, Status};
+
+pub use uefi::proto::console::serial::{Parity, StopBits};
+
+use crate::env;
+use crate::logger;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SerialConfig {
+    pub baud_rate: u64,
+    pub data_bits: u32,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+    pub timeout_us: u32,
+}
+
+impl Default for SerialConfig {
+    fn default() -> Self {
+        Self {
+            baud_rate: 115200,
+            data_bits: 8,
+            parity: Parity::NONE,
+            stop_bits: StopBits::ONE,
+            timeout_us: 1_000_000,
+        }
+    }
+}
+
+impl SerialConfig {
+    pub fn baud_rate(mut self, baud_rate: u64) -> Self {
+        self.baud_rate = baud_rate;
+        self
+    }
+
+    pub fn data_bits(mut self, data_bits: u32) -> Self {
+        self.data_bits = data_bits;
+        self
+    }
+
+    pub fn parity(mut self, parity: Parity) -> Self {
+        self.parity = parity;
+        self
+    }
+
+    pub fn stop_bits(mut self, stop_bits: StopBits) -> Self {
+        self.stop_bits = stop_bits;
+        self
+    }
+
+    pub fn timeout_us(mut self, timeout_us: u32) -> Self {
+        self.timeout_us = timeout_us;
+        self
+    }
+}
+
+pub struct Serial {
+    handle: Handle,
+    protocol: *mut SerialIo,
+}
+
+pub fn ports() -> Vec<Handle> {
+    let st = if let Some(x) = env::try_system_table() { x } else { return Vec::new(); };
+    st.boot_services().find_handles::<SerialIo>().unwrap_or_default()
+}
+
+impl Serial {
+    pub fn open() -> uefi::Result<Self> {
+        let handle = ports().into_iter().next().ok_or(uefi::Error::from(Status::NOT_FOUND))?;
+        Self::open_handle(handle)
+    }
+
+    pub fn open_handle(handle: Handle) -> uefi::Result<Self> {
+        let st = env::system_table()?;
+        let bs = st.boot_services();
+        let params = OpenProtocolParams { handle, agent: bs.image_handle(), controller: None };
+        let mut protocol = unsafe { bs.open_protocol::<SerialIo>(params, OpenProtocolAttributes::GetProtocol)? };
+        let pointer = &mut *protocol as *mut SerialIo;
+        core::mem::forget(protocol);
+        Ok(Self { handle, protocol: pointer })
+    }
+
+    pub fn handle(&self) -> Handle {
+        self.handle
+    }
+
+    fn io(&mut self) -> &mut SerialIo {
+        unsafe { &mut *self.protocol }
+    }
+
+    fn mode(&self) -> &IoMode {
+        unsafe { (*self.protocol).io_mode() }
+    }
+
+    pub fn config(&self) -> SerialConfig {
+        let mode = self.mode();
+        SerialConfig {
+            baud_rate: mode.baud_rate,
+            data_bits: mode.data_bits,
+            parity: mode.parity,
+            stop_bits: mode.stop_bits,
+            timeout_us: mode.timeout,
+        }
+    }
+
+    pub fn configure(&mut self, config: &SerialConfig) -> uefi::Result {
+        let current = self.mode();
+        let mode = IoMode {
+            control_mask: current.control_mask,
+            timeout: config.timeout_us,
+            baud_rate: config.baud_rate,
+            receive_fifo_depth: current.receive_fifo_depth,
+            data_bits: config.data_bits,
+            parity: config.parity,
+            stop_bits: config.stop_bits,
+        };
+        self.io().set_attributes(&mode)
+    }
+
+    pub fn reset(&mut self) -> uefi::Result {
+        self.io().reset()
+    }
+
+    pub fn write(&mut self, data: &[u8]) -> usize {
+        match self.io().write(data) {
+            Ok(()) => data.len(),
+            Err(err) => *err.data(),
+        }
+    }
+
+    pub fn write_all(&mut self, mut data: &[u8]) -> uefi::Result {
+        while !data.is_empty() {
+            let written = self.write(data);
+            if written == 0 {
+                return Err(Status::TIMEOUT.into());
+            }
+            data = &data[written..];
+        }
+        Ok(())
+    }
+
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        match self.io().read(buf) {
+            Ok(()) => buf.len(),
+            Err(err) => *err.data(),
+        }
+    }
+
+    pub fn read_byte(&mut self) -> Option<u8> {
+        let mut byte = [0u8];
+        if self.read(&mut byte) == 1 { Some(byte[0]) } else { None }
+    }
+}
+
+impl fmt::Write for Serial {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_all(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
+
+impl logger::Sink for Serial {
+    fn write_line(&mut self, line: &str) {
+        let _ = self.write_all(line.as_bytes());
+        let _ = self.write_all(b"\r\n");
+    }
+}
+
+pub fn mirror_log(config: &SerialConfig) -> uefi::Result {
+    let mut serial = Serial::open()?;
+    serial.configure(config)?;
+    logger::add_sink(Box::new(serial));
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use alloc::vec::Vec;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uefi::table::runtime::{VariableAttributes, VariableVendor};
+use uefi::{guid, CString16, Guid, Status};
+
+use crate::env;
+use crate::vars;
+
+pub const VENDOR: Guid = guid!("5b3a1f0e-8c2d-4e7a-9f61-2d4c8b7e3a90");
+
+const MAGIC: &[u8; 4] = b"UAST";
+const HEADER: usize = 10;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LoadStatus {
+    Loaded,
+    Missing,
+    Outdated(u16),
+    Corrupt,
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.iter().fold(0x811C_9DC5u32, |hash, x| (hash ^ *x as u32).wrapping_mul(0x0100_0193))
+}
+
+pub struct Settings<T> {
+    pub value: T,
+    name: CString16,
+    vendor: VariableVendor,
+    version: u16,
+    volatile: bool,
+}
+
+impl<T: Serialize + DeserializeOwned + Default> Settings<T> {
+    pub fn new(name: &str, version: u16) -> uefi::Result<Self> {
+        Ok(Self {
+            value: T::default(),
+            name: vars::name16(name)?,
+            vendor: VariableVendor(VENDOR),
+            version,
+            volatile: false,
+        })
+    }
+
+    pub fn vendor(mut self, guid: Guid) -> Self {
+        self.vendor = VariableVendor(guid);
+        self
+    }
+
+    pub fn volatile(mut self, volatile: bool) -> Self {
+        self.volatile = volatile;
+        self
+    }
+
+    pub fn load(name: &str, version: u16) -> uefi::Result<(Self, LoadStatus)> {
+        let mut settings = Self::new(name, version)?;
+        let status = settings.reload()?;
+        Ok((settings, status))
+    }
+
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<T, LoadStatus> {
+        if data.len() < HEADER || &data[..4] != MAGIC {
+            return Err(LoadStatus::Corrupt);
+        }
+        let version = u16::from_le_bytes([data[4], data[5]]);
+        let sum = u32::from_le_bytes([data[6], data[7], data[8], data[9]]);
+        let payload = &data[HEADER..];
+        if checksum(payload) != sum {
+            return Err(LoadStatus::Corrupt);
+        }
+        if version != self.version {
+            return Err(LoadStatus::Outdated(version));
+        }
+        postcard::from_bytes(payload).map_err(|_| LoadStatus::Corrupt)
+    }
+
+    pub fn reload(&mut self) -> uefi::Result<LoadStatus> {
+        let data = match vars::read(&self.name, self.vendor.0) {
+            Ok(x) => x.0,
+            Err(err) if err.status() == Status::NOT_FOUND => {
+                self.value = T::default();
+                return Ok(LoadStatus::Missing);
+            }
+            Err(err) => return Err(err),
+        };
+        match self.decode(&data) {
+            Ok(value) => {
+                self.value = value;
+                Ok(LoadStatus::Loaded)
+            }
+            Err(status) => {
+                self.value = T::default();
+                Ok(status)
+            }
+        }
+    }
+
+    pub fn save(&self) -> uefi::Result {
+        let payload = postcard::to_allocvec(&self.value).map_err(|_| uefi::Error::from(Status::INVALID_PARAMETER))?;
+        let mut data = Vec::with_capacity(HEADER + payload.len());
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&self.version.to_le_bytes());
+        data.extend_from_slice(&checksum(&payload).to_le_bytes());
+        data.extend_from_slice(&payload);
+        let mut attributes = VariableAttributes::BOOTSERVICE_ACCESS | VariableAttributes::RUNTIME_ACCESS;
+        if !self.volatile {
+            attributes |= VariableAttributes::NON_VOLATILE;
+        }
+        let st = env::system_table()?;
+        st.runtime_services().set_variable(&self.name, &self.vendor, attributes, &data)
+    }
+
+    pub fn reset(&mut self) -> uefi::Result {
+        self.value = T::default();
+        let st = env::system_table()?;
+        match st.runtime_services().delete_variable(&self.name, &self.vendor) {
+            Err(err) if err.status() == Status::NOT_FOUND => Ok(()),
+            other => other,
+        }
+    }
+}
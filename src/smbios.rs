@@ -0,0 +1,273 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use uefi::table::cfg::{SMBIOS3_GUID, SMBIOS_GUID};
+use uefi::Guid;
+
+use crate::env;
+
+pub const TYPE_BIOS: u8 = 0;
+pub const TYPE_SYSTEM: u8 = 1;
+pub const TYPE_BASEBOARD: u8 = 2;
+pub const TYPE_PROCESSOR: u8 = 4;
+pub const TYPE_MEMORY_DEVICE: u8 = 17;
+pub const TYPE_END: u8 = 127;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Structure {
+    pub ty: u8,
+    pub handle: u16,
+    pub data: Vec<u8>,
+    pub strings: Vec<String>,
+}
+
+impl Structure {
+    pub fn byte(&self, offset: usize) -> Option<u8> {
+        self.data.get(offset).copied()
+    }
+
+    pub fn word(&self, offset: usize) -> Option<u16> {
+        Some(u16::from_le_bytes(self.data.get(offset..offset + 2)?.try_into().ok()?))
+    }
+
+    pub fn dword(&self, offset: usize) -> Option<u32> {
+        Some(u32::from_le_bytes(self.data.get(offset..offset + 4)?.try_into().ok()?))
+    }
+
+    pub fn string(&self, offset: usize) -> Option<String> {
+        let index = self.byte(offset)? as usize;
+        let text = self.strings.get(index.checked_sub(1)?)?.trim();
+        if text.is_empty() { None } else { Some(String::from(text)) }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BiosInfo {
+    pub vendor: Option<String>,
+    pub version: Option<String>,
+    pub release_date: Option<String>,
+    pub release: Option<(u8, u8)>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SystemInfo {
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    pub version: Option<String>,
+    pub serial: Option<String>,
+    pub uuid: Option<Guid>,
+    pub sku: Option<String>,
+    pub family: Option<String>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BaseboardInfo {
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    pub version: Option<String>,
+    pub serial: Option<String>,
+    pub asset_tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProcessorInfo {
+    pub socket: Option<String>,
+    pub manufacturer: Option<String>,
+    pub version: Option<String>,
+    pub max_speed_mhz: Option<u16>,
+    pub current_speed_mhz: Option<u16>,
+    pub cores: Option<u8>,
+    pub threads: Option<u8>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemoryDevice {
+    pub locator: Option<String>,
+    pub bank: Option<String>,
+    pub size_mb: u64,
+    pub memory_type: u8,
+    pub speed_mts: Option<u16>,
+    pub manufacturer: Option<String>,
+    pub part_number: Option<String>,
+}
+
+impl MemoryDevice {
+    pub fn is_populated(&self) -> bool {
+        self.size_mb > 0
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self.memory_type {
+            0x12 => "DDR",
+            0x13 => "DDR2",
+            0x18 => "DDR3",
+            0x1A => "DDR4",
+            0x1B => "LPDDR",
+            0x1C => "LPDDR2",
+            0x1D => "LPDDR3",
+            0x1E => "LPDDR4",
+            0x22 => "DDR5",
+            0x23 => "LPDDR5",
+            0x07 => "RAM",
+            0x09 => "ROM",
+            _ => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Smbios {
+    pub version: (u8, u8),
+    pub structures: Vec<Structure>,
+}
+
+fn non_zero<T: Default + PartialEq>(value: Option<T>) -> Option<T> {
+    value.filter(|x| *x != T::default())
+}
+
+impl Smbios {
+    pub fn parse(table: &[u8], version: (u8, u8)) -> Self {
+        let mut structures = Vec::new();
+        let mut at = 0;
+        while at + 4 <= table.len() {
+            let ty = table[at];
+            let len = table[at + 1] as usize;
+            if len < 4 || at + len > table.len() {
+                break;
+            }
+            let handle = u16::from_le_bytes([table[at + 2], table[at + 3]]);
+            let data = table[at..at + len].to_vec();
+            let mut strings = Vec::new();
+            let mut cursor = at + len;
+            loop {
+                let end = match table[cursor..].iter().position(|x| *x == 0) {
+                    Some(x) => cursor + x,
+                    None => table.len(),
+                };
+                if end == cursor {
+                    cursor += 1;
+                    break;
+                }
+                strings.push(String::from_utf8_lossy(&table[cursor..end]).into_owned());
+                cursor = end + 1;
+                if cursor >= table.len() {
+                    break;
+                }
+            }
+            if strings.is_empty() {
+                cursor += 1;
+            }
+            structures.push(Structure { ty, handle, data, strings });
+            if ty == TYPE_END {
+                break;
+            }
+            at = cursor;
+        }
+        Self { version, structures }
+    }
+
+    pub fn locate() -> Option<Self> {
+        let st = env::try_system_table()?;
+        let tables = st.config_table();
+        if let Some(entry) = tables.iter().find(|x| x.guid == SMBIOS3_GUID) {
+            let ep = unsafe { core::slice::from_raw_parts(entry.address as *const u8, 24) };
+            if &ep[..5] != b"_SM3_" {
+                return None;
+            }
+            let len = u32::from_le_bytes(ep[12..16].try_into().ok()?) as usize;
+            let addr = u64::from_le_bytes(ep[16..24].try_into().ok()?);
+            let table = unsafe { core::slice::from_raw_parts(addr as *const u8, len) };
+            return Some(Self::parse(table, (ep[7], ep[8])));
+        }
+        let entry = tables.iter().find(|x| x.guid == SMBIOS_GUID)?;
+        let ep = unsafe { core::slice::from_raw_parts(entry.address as *const u8, 31) };
+        if &ep[..4] != b"_SM_" {
+            return None;
+        }
+        let len = u16::from_le_bytes([ep[0x16], ep[0x17]]) as usize;
+        let addr = u32::from_le_bytes(ep[0x18..0x1C].try_into().ok()?);
+        let table = unsafe { core::slice::from_raw_parts(addr as usize as *const u8, len) };
+        Some(Self::parse(table, (ep[6], ep[7])))
+    }
+
+    pub fn of_type(&self, ty: u8) -> impl Iterator<Item = &Structure> {
+        self.structures.iter().filter(move |x| x.ty == ty)
+    }
+
+    pub fn bios(&self) -> Option<BiosInfo> {
+        let s = self.of_type(TYPE_BIOS).next()?;
+        Some(BiosInfo {
+            vendor: s.string(0x04),
+            version: s.string(0x05),
+            release_date: s.string(0x08),
+            release: s.byte(0x14).zip(s.byte(0x15)).filter(|x| *x != (0xFF, 0xFF)),
+        })
+    }
+
+    pub fn system(&self) -> Option<SystemInfo> {
+        let s = self.of_type(TYPE_SYSTEM).next()?;
+        let uuid = s.data.get(0x08..0x18)
+            .map(|x| Guid::from_bytes(x.try_into().unwrap()))
+            .filter(|x| *x != Guid::ZERO && x.to_bytes() != [0xFF; 16]);
+        Some(SystemInfo {
+            manufacturer: s.string(0x04),
+            product: s.string(0x05),
+            version: s.string(0x06),
+            serial: s.string(0x07),
+            uuid,
+            sku: s.string(0x19),
+            family: s.string(0x1A),
+        })
+    }
+
+    pub fn baseboard(&self) -> Option<BaseboardInfo> {
+        let s = self.of_type(TYPE_BASEBOARD).next()?;
+        Some(BaseboardInfo {
+            manufacturer: s.string(0x04),
+            product: s.string(0x05),
+            version: s.string(0x06),
+            serial: s.string(0x07),
+            asset_tag: s.string(0x08),
+        })
+    }
+
+    pub fn processors(&self) -> Vec<ProcessorInfo> {
+        self.of_type(TYPE_PROCESSOR)
+            .map(|s| ProcessorInfo {
+                socket: s.string(0x04),
+                manufacturer: s.string(0x07),
+                version: s.string(0x10),
+                max_speed_mhz: non_zero(s.word(0x14)),
+                current_speed_mhz: non_zero(s.word(0x16)),
+                cores: non_zero(s.byte(0x23)),
+                threads: non_zero(s.byte(0x25)),
+            })
+            .collect()
+    }
+
+    pub fn memory_devices(&self) -> Vec<MemoryDevice> {
+        self.of_type(TYPE_MEMORY_DEVICE)
+            .map(|s| {
+                let size_mb = match s.word(0x0C).unwrap_or(0) {
+                    0 | 0xFFFF => 0,
+                    0x7FFF => s.dword(0x1C).unwrap_or(0) as u64 & 0x7FFF_FFFF,
+                    x if x & 0x8000 != 0 => (x & 0x7FFF) as u64 / 1024,
+                    x => x as u64,
+                };
+                MemoryDevice {
+                    locator: s.string(0x10),
+                    bank: s.string(0x11),
+                    size_mb,
+                    memory_type: s.byte(0x12).unwrap_or(0),
+                    speed_mts: non_zero(s.word(0x15)),
+                    manufacturer: s.string(0x17),
+                    part_number: s.string(0x1A),
+                }
+            })
+            .collect()
+    }
+
+    pub fn total_memory_mb(&self) -> u64 {
+        self.memory_devices().iter().map(|x| x.size_mb).sum()
+    }
+}
@@ -0,0 +1,257 @@
+use alloc::vec::Vec;
+use core::ffi::c_void;
+
+use uefi::proto::unsafe_protocol;
+use uefi::table::boot::{OpenProtocolAttributes, OpenProtocolParams};
+use uefi::Status;
+
+use crate::env;
+
+pub const EV_POST_CODE: u32 = 0x0000_0001;
+pub const EV_NO_ACTION: u32 = 0x0000_0003;
+pub const EV_SEPARATOR: u32 = 0x0000_0004;
+pub const EV_EVENT_TAG: u32 = 0x0000_0006;
+pub const EV_IPL: u32 = 0x0000_000D;
+pub const EV_EFI_BOOT_SERVICES_APPLICATION: u32 = 0x8000_0003;
+pub const EV_EFI_ACTION: u32 = 0x8000_0007;
+
+pub const ALG_SHA1: u16 = 0x0004;
+pub const ALG_SHA256: u16 = 0x000B;
+pub const ALG_SHA384: u16 = 0x000C;
+pub const ALG_SHA512: u16 = 0x000D;
+pub const ALG_SM3_256: u16 = 0x0012;
+
+const LOG_FORMAT_TCG_2: u32 = 0x0000_0002;
+const EVENT_HEADER_SIZE: u32 = 14;
+const EVENT_HEADER_VERSION: u16 = 1;
+
+#[repr(C, packed)]
+#[derive(Default)]
+struct RawCapability {
+    size: u8,
+    structure_version: [u8; 2],
+    protocol_version: [u8; 2],
+    hash_algorithm_bitmap: u32,
+    supported_event_logs: u32,
+    tpm_present: u8,
+    max_command_size: u16,
+    max_response_size: u16,
+    manufacturer_id: u32,
+    number_of_pcr_banks: u32,
+    active_pcr_banks: u32,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+#[unsafe_protocol("607f766c-7455-42be-930b-e4d76db2720f")]
+struct Tcg2Protocol {
+    get_capability: unsafe extern "efiapi" fn(*mut Tcg2Protocol, *mut RawCapability) -> Status,
+    get_event_log: unsafe extern "efiapi" fn(*mut Tcg2Protocol, u32, *mut u64, *mut u64, *mut u8) -> Status,
+    hash_log_extend_event: unsafe extern "efiapi" fn(*mut Tcg2Protocol, u64, u64, u64, *const u8) -> Status,
+    submit_command: *const c_void,
+    get_active_pcr_banks: *const c_void,
+    set_active_pcr_banks: *const c_void,
+    get_result_of_set_active_pcr_banks: *const c_void,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Capability {
+    pub tpm_present: bool,
+    pub hash_algorithms: u32,
+    pub supported_logs: u32,
+    pub manufacturer_id: u32,
+    pub pcr_banks: u32,
+    pub active_pcr_banks: u32,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LogEvent {
+    pub pcr: u32,
+    pub event_type: u32,
+    pub digests: Vec<(u16, Vec<u8>)>,
+    pub data: Vec<u8>,
+}
+
+impl LogEvent {
+    pub fn digest(&self, algorithm: u16) -> Option<&[u8]> {
+        self.digests.iter().find(|x| x.0 == algorithm).map(|x| x.1.as_slice())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EventLog {
+    pub events: Vec<LogEvent>,
+    pub truncated: bool,
+}
+
+pub struct Tcg2 {
+    protocol: *mut Tcg2Protocol,
+}
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let out = self.data.get(self.at..self.at + len)?;
+        self.at += len;
+        Some(out)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.take(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn header(&mut self) -> Option<LogEvent> {
+        let pcr = self.u32()?;
+        let event_type = self.u32()?;
+        let digest = self.take(20)?.to_vec();
+        let size = self.u32()? as usize;
+        let data = self.take(size)?.to_vec();
+        Some(LogEvent { pcr, event_type, digests: Vec::from([(ALG_SHA1, digest)]), data })
+    }
+
+    fn event(&mut self, sizes: &[(u16, u16)]) -> Option<LogEvent> {
+        let pcr = self.u32()?;
+        let event_type = self.u32()?;
+        let count = self.u32()?;
+        let mut digests = Vec::new();
+        for _ in 0..count {
+            let algorithm = self.u16()?;
+            let size = sizes.iter().find(|x| x.0 == algorithm)?.1 as usize;
+            digests.push((algorithm, self.take(size)?.to_vec()));
+        }
+        let size = self.u32()? as usize;
+        let data = self.take(size)?.to_vec();
+        Some(LogEvent { pcr, event_type, digests, data })
+    }
+}
+
+fn digest_sizes(spec_id: &[u8]) -> Option<Vec<(u16, u16)>> {
+    if spec_id.get(..16)? != b"Spec ID Event03\0" {
+        return None;
+    }
+    let mut cursor = Cursor { data: spec_id, at: 24 };
+    let count = cursor.u32()?;
+    (0..count).map(|_| Some((cursor.u16()?, cursor.u16()?))).collect()
+}
+
+fn parse_log(data: &[u8], last: usize) -> Vec<LogEvent> {
+    let mut events = Vec::new();
+    let mut cursor = Cursor { data, at: 0 };
+    let header = if let Some(x) = cursor.header() { x } else { return events; };
+    let sizes = digest_sizes(&header.data);
+    events.push(header);
+    let sizes = if let Some(x) = sizes { x } else { return events; };
+    while cursor.at <= last {
+        match cursor.event(&sizes) {
+            Some(x) => events.push(x),
+            None => break,
+        }
+    }
+    events
+}
+
+unsafe fn read_u32(ptr: *const u8) -> u32 {
+    core::ptr::read_unaligned(ptr as *const u32)
+}
+
+unsafe fn event_len(event: *const u8, sizes: &[(u16, u16)]) -> usize {
+    let count = read_u32(event.add(8));
+    let mut at = 12;
+    for _ in 0..count {
+        let algorithm = core::ptr::read_unaligned(event.add(at) as *const u16);
+        let size = if let Some(x) = sizes.iter().find(|x| x.0 == algorithm) { x.1 } else { return 0; };
+        at += 2 + size as usize;
+    }
+    at + 4 + read_u32(event.add(at)) as usize
+}
+
+impl Tcg2 {
+    pub fn open() -> Option<Self> {
+        let st = env::try_system_table()?;
+        let bs = st.boot_services();
+        let handle = bs.get_handle_for_protocol::<Tcg2Protocol>().ok()?;
+        let params = OpenProtocolParams { handle, agent: bs.image_handle(), controller: None };
+        let mut protocol = unsafe {
+            bs.open_protocol::<Tcg2Protocol>(params, OpenProtocolAttributes::GetProtocol).ok()?
+        };
+        let pointer = &mut *protocol as *mut Tcg2Protocol;
+        core::mem::forget(protocol);
+        Some(Self { protocol: pointer })
+    }
+
+    pub fn capability(&self) -> Option<Capability> {
+        let mut raw = RawCapability { size: core::mem::size_of::<RawCapability>() as u8, ..Default::default() };
+        let status = unsafe { ((*self.protocol).get_capability)(self.protocol, &mut raw) };
+        if status != Status::SUCCESS {
+            return None;
+        }
+        Some(Capability {
+            tpm_present: raw.tpm_present != 0,
+            hash_algorithms: raw.hash_algorithm_bitmap,
+            supported_logs: raw.supported_event_logs,
+            manufacturer_id: raw.manufacturer_id,
+            pcr_banks: raw.number_of_pcr_banks,
+            active_pcr_banks: raw.active_pcr_banks,
+        })
+    }
+
+    pub fn is_present(&self) -> bool {
+        self.capability().is_some_and(|x| x.tpm_present)
+    }
+
+    pub fn measure(&self, pcr: u32, event_type: u32, description: &[u8], data: &[u8]) -> uefi::Result {
+        let size = 4 + EVENT_HEADER_SIZE as usize + description.len();
+        let mut event = Vec::with_capacity(size);
+        event.extend_from_slice(&(size as u32).to_le_bytes());
+        event.extend_from_slice(&EVENT_HEADER_SIZE.to_le_bytes());
+        event.extend_from_slice(&EVENT_HEADER_VERSION.to_le_bytes());
+        event.extend_from_slice(&pcr.to_le_bytes());
+        event.extend_from_slice(&event_type.to_le_bytes());
+        event.extend_from_slice(description);
+        let status = unsafe {
+            ((*self.protocol).hash_log_extend_event)(
+                self.protocol, 0, data.as_ptr() as u64, data.len() as u64, event.as_ptr(),
+            )
+        };
+        status.to_result()
+    }
+
+    pub fn event_log(&self) -> uefi::Result<EventLog> {
+        let mut location = 0u64;
+        let mut last = 0u64;
+        let mut truncated = 0u8;
+        let status = unsafe {
+            ((*self.protocol).get_event_log)(self.protocol, LOG_FORMAT_TCG_2, &mut location, &mut last, &mut truncated)
+        };
+        status.to_result()?;
+        if location == 0 {
+            return Ok(EventLog { events: Vec::new(), truncated: truncated != 0 });
+        }
+        let base = location as *const u8;
+        let last = last.saturating_sub(location) as usize;
+        let len = unsafe {
+            if last == 0 {
+                32 + read_u32(base.add(28)) as usize
+            } else {
+                let header = core::slice::from_raw_parts(base, 32 + read_u32(base.add(28)) as usize);
+                let sizes = digest_sizes(&header[32..]).unwrap_or_default();
+                last + event_len(base.add(last), &sizes)
+            }
+        };
+        let span = unsafe { core::slice::from_raw_parts(base, len) };
+        Ok(EventLog { events: parse_log(span, last), truncated: truncated != 0 })
+    }
+}
+
+pub fn measure(pcr: u32, event_type: u32, description: &str, data: &[u8]) -> uefi::Result {
+    let tcg = Tcg2::open().ok_or(uefi::Error::from(Status::UNSUPPORTED))?;
+    tcg.measure(pcr, event_type, description.as_bytes(), data)
+}
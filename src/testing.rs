@@ -0,0 +1,168 @@
+use alloc::format;
+use alloc::string::String;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use crate::gfx::{gray, Buffer, Color, Dim};
+use crate::image;
+
+const BLESS_VAR: &str = "UEFAPI_BLESS";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Tolerance {
+    pub channel: u8,
+    pub pixels: usize,
+}
+
+impl Tolerance {
+    pub const EXACT: Self = Self { channel: 0, pixels: 0 };
+
+    pub const fn new(channel: u8, pixels: usize) -> Self {
+        Self { channel, pixels }
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::EXACT
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Mismatch {
+    pub expected: Dim,
+    pub actual: Dim,
+    pub differing: usize,
+    pub max_delta: u8,
+    pub diff: Buffer,
+}
+
+pub fn render(dim: Dim, background: Color, scene: impl FnOnce(&mut Buffer)) -> Buffer {
+    let mut buffer = Buffer::new_cleared(dim, background);
+    scene(&mut buffer);
+    buffer
+}
+
+fn delta(a: Color, b: Color) -> u8 {
+    [a.r.abs_diff(b.r), a.g.abs_diff(b.g), a.b.abs_diff(b.b), a.a.abs_diff(b.a)]
+        .into_iter()
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn diff_image(actual: &Buffer, expected: &Buffer, channel: u8) -> Buffer {
+    let dim = Dim {
+        w: actual.dim.w.max(expected.dim.w),
+        h: actual.dim.h.max(expected.dim.h),
+    };
+    let mut out = Buffer::new_cleared(dim, Color::RED);
+    for y in 0..dim.h.min(actual.dim.h).min(expected.dim.h) {
+        for x in 0..dim.w.min(actual.dim.w).min(expected.dim.w) {
+            let a = actual.data[(x + y * actual.dim.w) as usize];
+            let e = expected.data[(x + y * expected.dim.w) as usize];
+            out.data[(x + y * dim.w) as usize] = if delta(a, e) > channel {
+                Color::RED
+            } else {
+                gray(((a.r as u16 + a.g as u16 + a.b as u16) / 12) as u8)
+            };
+        }
+    }
+    out
+}
+
+pub fn compare(actual: &Buffer, expected: &Buffer, tolerance: Tolerance) -> Result<(), Mismatch> {
+    let mismatch = |differing, max_delta| Mismatch {
+        expected: expected.dim,
+        actual: actual.dim,
+        differing,
+        max_delta,
+        diff: diff_image(actual, expected, tolerance.channel),
+    };
+    if actual.dim != expected.dim {
+        return Err(mismatch(actual.data.len().max(expected.data.len()), u8::MAX));
+    }
+    let mut differing = 0;
+    let mut max_delta = 0;
+    for (a, e) in actual.data.iter().zip(expected.data.iter()) {
+        let d = delta(*a, *e);
+        if d > tolerance.channel {
+            differing += 1;
+        }
+        max_delta = max_delta.max(d);
+    }
+    if differing > tolerance.pixels {
+        return Err(mismatch(differing, max_delta));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct Golden {
+    dir: PathBuf,
+    output: PathBuf,
+    tolerance: Tolerance,
+}
+
+impl Golden {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        Self { output: dir.join("failed"), dir, tolerance: Tolerance::EXACT }
+    }
+
+    pub fn tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn output(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.output = dir.into();
+        self
+    }
+
+    fn blessing() -> bool {
+        env::var_os(BLESS_VAR).is_some_and(|x| x != "0")
+    }
+
+    fn write(path: &Path, buffer: &Buffer) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+        }
+        fs::write(path, image::encode_qoi(buffer)).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn check(&self, name: &str, actual: &Buffer) -> Result<(), String> {
+        let path = self.dir.join(format!("{}.qoi", name));
+        if Self::blessing() {
+            return Self::write(&path, actual);
+        }
+        let data = fs::read(&path).map_err(|e| {
+            format!("{}: {} (run with {}=1 to create it)", path.display(), e, BLESS_VAR)
+        })?;
+        let expected = image::decode(&data).map_err(|e| format!("{}: {:?}", path.display(), e))?;
+        let mismatch = if let Err(x) = compare(actual, &expected, self.tolerance) { x } else {
+            return Ok(());
+        };
+        let actual_path = self.output.join(format!("{}.actual.qoi", name));
+        let diff_path = self.output.join(format!("{}.diff.qoi", name));
+        Self::write(&actual_path, actual)?;
+        Self::write(&diff_path, &mismatch.diff)?;
+        Err(format!(
+            "{}: {} pixels differ (max delta {}, expected {}x{}, got {}x{}); see {} and {}",
+            name,
+            mismatch.differing,
+            mismatch.max_delta,
+            mismatch.expected.w,
+            mismatch.expected.h,
+            mismatch.actual.w,
+            mismatch.actual.h,
+            actual_path.display(),
+            diff_path.display(),
+        ))
+    }
+
+    pub fn assert(&self, name: &str, actual: &Buffer) {
+        if let Err(message) = self.check(name, actual) {
+            panic!("golden image mismatch: {}", message);
+        }
+    }
+}
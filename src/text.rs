@@ -0,0 +1,368 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use baked_font::{Font, GlyphResult};
+
+use crate::gfx::{dim, pos, rect, Buffer, Color, Dim, Insets, Pos, Rect, Screen, View};
+use crate::theme::Theme;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Cluster {
+    pub start: usize,
+    pub len: usize,
+    pub ch: char,
+    pub pos: Pos,
+    pub advance: i32,
+    pub glyph_pos: Pos,
+    pub glyph_dim: Dim,
+    pub glyph_offset: Pos,
+    pub visible: bool,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Line {
+    pub first: usize,
+    pub last: usize,
+    pub start: usize,
+    pub end: usize,
+    pub y: i32,
+    pub width: i32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TextLayout {
+    pub clusters: Vec<Cluster>,
+    pub lines: Vec<Line>,
+    pub line_height: i32,
+    pub len: usize,
+}
+
+fn shape(font: &Font, text: &str, space: i32) -> Vec<Cluster> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    for gr in font.lookup_string(text) {
+        let (ch, len, glyph) = match gr {
+            GlyphResult::Unknown(ch) => (ch, 1, None),
+            GlyphResult::Single(glyph, ch) => (ch, 1, Some(glyph)),
+            GlyphResult::Double(glyph, [ch, _]) => (ch, 2, Some(glyph)),
+        };
+        let cluster = match glyph {
+            Some(glyph) => Cluster {
+                start,
+                len,
+                ch,
+                pos: pos(0, 0),
+                advance: glyph.size.0 as i32,
+                glyph_pos: pos(glyph.pos.0 as i32, glyph.pos.1 as i32),
+                glyph_dim: dim(glyph.size.0 as i32, glyph.size.1 as i32),
+                glyph_offset: pos(glyph.offset.0 as i32, glyph.offset.1 as i32),
+                visible: true,
+            },
+            None => Cluster {
+                start,
+                len,
+                ch,
+                pos: pos(0, 0),
+                advance: if ch == ' ' { space } else if ch == '\t' { space * 4 } else { 0 },
+                glyph_pos: pos(0, 0),
+                glyph_dim: dim(0, 0),
+                glyph_offset: pos(0, 0),
+                visible: false,
+            },
+        };
+        out.push(cluster);
+        start += len;
+    }
+    out
+}
+
+impl TextLayout {
+    pub fn new(font: &Font, text: &str, width: Option<i32>, line_height: i32) -> Self {
+        let mut clusters = shape(font, text, (line_height / 3).max(1));
+        let mut lines = Vec::new();
+        let mut first = 0;
+        let mut x = 0;
+        let mut last_space: Option<usize> = None;
+        let mut i = 0;
+        while i < clusters.len() {
+            let c = clusters[i];
+            if c.ch == '\n' {
+                lines.push((first, i + 1));
+                first = i + 1;
+                x = 0;
+                last_space = None;
+                i += 1;
+                continue;
+            }
+            if let Some(width) = width {
+                if x + c.advance > width && i > first && c.ch != ' ' {
+                    let brk = match last_space {
+                        Some(space) => space + 1,
+                        None => i,
+                    };
+                    lines.push((first, brk));
+                    first = brk;
+                    x = 0;
+                    last_space = None;
+                    i = brk;
+                    continue;
+                }
+            }
+            if c.ch == ' ' {
+                last_space = Some(i);
+            }
+            x += c.advance;
+            i += 1;
+        }
+        lines.push((first, clusters.len()));
+
+        let len = clusters.last().map_or(0, |x| x.start + x.len);
+        let lines = lines.into_iter().enumerate().map(|(n, (first, last))| {
+            let y = n as i32 * line_height;
+            let mut x = 0;
+            for c in clusters[first..last].iter_mut() {
+                c.pos = pos(x, y);
+                x += c.advance;
+            }
+            let start = clusters.get(first).map_or(len, |x| x.start);
+            let end = if last > first {
+                let c = clusters[last - 1];
+                if c.ch == '\n' { c.start } else { c.start + c.len }
+            } else {
+                start
+            };
+            Line { first, last, start, end, y, width: x }
+        }).collect();
+        Self { clusters, lines, line_height, len }
+    }
+
+    pub fn dim(&self) -> Dim {
+        let w = self.lines.iter().map(|x| x.width).max().unwrap_or(0);
+        dim(w, self.lines.len() as i32 * self.line_height)
+    }
+
+    pub fn line_of(&self, index: usize) -> usize {
+        let index = index.min(self.len);
+        self.lines.iter()
+            .rposition(|x| x.start <= index)
+            .unwrap_or(0)
+    }
+
+    pub fn caret_pos(&self, index: usize) -> Pos {
+        let line = &self.lines[self.line_of(index)];
+        for c in &self.clusters[line.first..line.last] {
+            if c.start >= index {
+                return c.pos;
+            }
+        }
+        pos(line.width, line.y)
+    }
+
+    pub fn index_at(&self, loc: Pos) -> usize {
+        if self.lines.is_empty() {
+            return 0;
+        }
+        let n = (loc.y / self.line_height.max(1)).clamp(0, self.lines.len() as i32 - 1);
+        let line = &self.lines[n as usize];
+        for c in &self.clusters[line.first..line.last] {
+            if c.ch == '\n' || loc.x < c.pos.x + c.advance / 2 {
+                return c.start;
+            }
+        }
+        line.end
+    }
+
+    pub fn line_start(&self, index: usize) -> usize {
+        self.lines[self.line_of(index)].start
+    }
+
+    pub fn line_end(&self, index: usize) -> usize {
+        self.lines[self.line_of(index)].end
+    }
+
+    pub fn draw(&self, buffer: &mut Buffer, loc: Pos, font: &Font, color: Color) {
+        for c in self.clusters.iter().filter(|x| x.visible) {
+            buffer.draw_font_rect(loc + c.pos + c.glyph_offset, font, c.glyph_pos, c.glyph_dim, color);
+        }
+    }
+
+    pub fn draw_in(&self, view: &mut View, loc: Pos, font: &Font, color: Color) {
+        for c in self.clusters.iter().filter(|x| x.visible) {
+            view.draw_font_rect(loc + c.pos + c.glyph_offset, font, c.glyph_pos, c.glyph_dim, color);
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TextRegionStyle {
+    pub bg: Color,
+    pub fg: Color,
+    pub line_height: i32,
+    pub padding: i32,
+}
+
+impl From<&Theme> for TextRegionStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            bg: p.surface,
+            fg: p.fg,
+            line_height: m.line_height,
+            padding: m.padding,
+        }
+    }
+}
+
+impl Default for TextRegionStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub struct TextRegion<'a> {
+    pub rect: Rect,
+    pub style: TextRegionStyle,
+    auto_present: bool,
+    lines: Vec<String>,
+    dirty: Option<Rect>,
+    font: &'a Font,
+}
+
+fn byte_index(text: &str, chars: usize) -> usize {
+    text.char_indices().nth(chars).map_or(text.len(), |(i, _)| i)
+}
+
+impl<'a> TextRegion<'a> {
+    pub fn new(rect: Rect, font: &'a Font, style: TextRegionStyle) -> Self {
+        Self {
+            rect,
+            style,
+            auto_present: true,
+            lines: vec![String::new()],
+            dirty: Some(rect),
+            font,
+        }
+    }
+
+    pub fn auto_present(mut self, enabled: bool) -> Self {
+        self.auto_present = enabled;
+        self
+    }
+
+    fn inner(&self) -> Rect {
+        self.rect.inset(Insets::all(self.style.padding))
+    }
+
+    fn line_height(&self) -> i32 {
+        self.style.line_height.max(1)
+    }
+
+    pub fn rows(&self) -> usize {
+        (self.inner().dim.h / self.line_height()).max(1) as usize
+    }
+
+    fn row_rect(&self, row: usize) -> Rect {
+        let inner = self.inner();
+        let lh = self.line_height();
+        rect(inner.pos + pos(0, row as i32 * lh), dim(inner.dim.w, lh))
+    }
+
+    fn invalidate(&mut self, r: Rect) {
+        self.dirty = Some(self.dirty.map_or(r, |x| x.union(r)));
+    }
+
+    pub fn take_dirty(&mut self) -> Option<Rect> {
+        self.dirty.take()
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.trim();
+        self.dirty = Some(rect);
+    }
+
+    pub fn clear(&mut self) {
+        self.lines = vec![String::new()];
+        self.dirty = Some(self.rect);
+    }
+
+    fn trim(&mut self) -> bool {
+        let rows = self.rows();
+        if self.lines.len() <= rows {
+            return false;
+        }
+        self.lines.drain(..self.lines.len() - rows);
+        true
+    }
+
+    fn append(&mut self, text: &str) {
+        let (font, width, lh) = (self.font, self.inner().dim.w, self.line_height());
+        let mut current = self.lines.pop().unwrap_or_default();
+        current.extend(text.chars().filter(|x| !x.is_control() || *x == '\t'));
+        loop {
+            let layout = TextLayout::new(font, &current, Some(width), lh);
+            if layout.lines.len() <= 1 {
+                break;
+            }
+            let split = byte_index(&current, layout.lines[0].end);
+            if split == 0 || split >= current.len() {
+                break;
+            }
+            let rest = current.split_off(split);
+            self.lines.push(core::mem::replace(&mut current, rest));
+        }
+        self.lines.push(current);
+    }
+
+    pub fn print(&mut self, text: &str) {
+        let first = self.lines.len() - 1;
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.lines.push(String::new());
+            }
+            self.append(part);
+        }
+        if self.trim() {
+            self.invalidate(self.rect);
+        } else {
+            for row in first..self.lines.len() {
+                self.invalidate(self.row_rect(row));
+            }
+        }
+    }
+
+    pub fn draw(&self, buffer: &mut Buffer) {
+        buffer.fill(self.rect.area(), self.style.bg);
+        let lh = self.line_height();
+        for (row, line) in self.lines.iter().enumerate() {
+            let layout = TextLayout::new(self.font, line, None, lh);
+            layout.draw(buffer, self.row_rect(row).pos, self.font, self.style.fg);
+        }
+    }
+
+    pub fn present(&mut self) {
+        if !Screen::is_init() {
+            return;
+        }
+        let dirty = if let Some(x) = self.take_dirty() { x } else { return; };
+        self.draw(Screen::get());
+        Screen::present(dirty);
+    }
+}
+
+impl<'a> fmt::Write for TextRegion<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.print(s);
+        if self.auto_present {
+            self.present();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use baked_font::Font;
+
+use crate::gfx::{dim, gray, pos, rect, rgb, Buffer, Color, Rect};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Palette {
+    pub background: Color,
+    pub base: Color,
+    pub input: Color,
+    pub surface: Color,
+    pub raised: Color,
+    pub control: Color,
+    pub line: Color,
+    pub border: Color,
+    pub muted: Color,
+    pub fg: Color,
+    pub fg_dim: Color,
+    pub fg_strong: Color,
+    pub fg_disabled: Color,
+    pub accent: Color,
+    pub accent_pressed: Color,
+    pub accent_fg: Color,
+    pub error: Color,
+    pub scrim: Color,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Metrics {
+    pub padding: i32,
+    pub dialog_padding: i32,
+    pub spacing: i32,
+    pub line_height: i32,
+    pub row_height: i32,
+    pub corner_radius: i32,
+    pub border_width: i32,
+    pub scrollbar_width: i32,
+    pub indent: i32,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FocusStyle {
+    pub color: Color,
+    pub width: i32,
+}
+
+impl FocusStyle {
+    pub fn draw(&self, buffer: &mut Buffer, r: Rect) {
+        let w = self.width;
+        let ring = rect(r.pos - pos(w, w), r.dim + dim(w * 2, w * 2));
+        buffer.stroke(ring.area(), w, self.color);
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+pub struct FontStack {
+    pub body: Option<&'static Font>,
+    pub heading: Option<&'static Font>,
+    pub mono: Option<&'static Font>,
+}
+
+impl FontStack {
+    pub fn body(&self) -> Option<&'static Font> {
+        self.body
+    }
+
+    pub fn heading(&self) -> Option<&'static Font> {
+        self.heading.or(self.body)
+    }
+
+    pub fn mono(&self) -> Option<&'static Font> {
+        self.mono.or(self.body)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Theme {
+    pub name: &'static str,
+    pub palette: Palette,
+    pub metrics: Metrics,
+    pub focus: FocusStyle,
+    pub fonts: FontStack,
+}
+
+const METRICS: Metrics = Metrics {
+    padding: 4,
+    dialog_padding: 12,
+    spacing: 8,
+    line_height: 18,
+    row_height: 20,
+    corner_radius: 0,
+    border_width: 1,
+    scrollbar_width: 8,
+    indent: 16,
+};
+
+impl Theme {
+    pub const DARK: Theme = Theme {
+        name: "Dark",
+        palette: Palette {
+            background: gray(0x10),
+            base: gray(0x20),
+            input: gray(0x18),
+            surface: gray(0x28),
+            raised: gray(0x38),
+            control: gray(0x40),
+            line: gray(0x30),
+            border: gray(0x60),
+            muted: gray(0x80),
+            fg: gray(0xE0),
+            fg_dim: gray(0xA0),
+            fg_strong: Color::WHITE,
+            fg_disabled: gray(0x70),
+            accent: rgb(0x20, 0x50, 0x90),
+            accent_pressed: rgb(0x10, 0x30, 0x60),
+            accent_fg: Color::WHITE,
+            error: rgb(0xE0, 0x60, 0x50),
+            scrim: Color::black_alpha(0xA0),
+        },
+        metrics: METRICS,
+        focus: FocusStyle { color: rgb(0x40, 0x80, 0xD0), width: 2 },
+        fonts: FontStack { body: None, heading: None, mono: None },
+    };
+
+    pub const LIGHT: Theme = Theme {
+        name: "Light",
+        palette: Palette {
+            background: gray(0xF0),
+            base: Color::WHITE,
+            input: Color::WHITE,
+            surface: gray(0xE8),
+            raised: gray(0xD8),
+            control: gray(0xD0),
+            line: gray(0xDC),
+            border: gray(0xA0),
+            muted: gray(0x90),
+            fg: gray(0x20),
+            fg_dim: gray(0x60),
+            fg_strong: Color::BLACK,
+            fg_disabled: gray(0xA0),
+            accent: rgb(0x30, 0x70, 0xD0),
+            accent_pressed: rgb(0x20, 0x50, 0xA0),
+            accent_fg: Color::WHITE,
+            error: rgb(0xC0, 0x30, 0x20),
+            scrim: Color::black_alpha(0x60),
+        },
+        metrics: METRICS,
+        focus: FocusStyle { color: rgb(0x20, 0x60, 0xC0), width: 2 },
+        fonts: FontStack { body: None, heading: None, mono: None },
+    };
+
+    pub fn with_fonts(mut self, fonts: FontStack) -> Self {
+        self.fonts = fonts;
+        self
+    }
+
+    pub fn current() -> &'static Theme {
+        unsafe { &*core::ptr::addr_of!(CURRENT) }
+    }
+
+    pub fn set(theme: Theme) {
+        unsafe { CURRENT = theme; }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+static mut CURRENT: Theme = Theme::DARK;
@@ -0,0 +1,241 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use uefi::table::runtime::{Daylight, RuntimeServices, Time, TimeParams};
+use uefi::Status;
+
+use crate::env;
+
+pub const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+pub const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct DateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+    pub time_zone: Option<i16>,
+    pub adjust_daylight: bool,
+    pub in_daylight: bool,
+}
+
+pub fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+impl DateTime {
+    pub fn now() -> Option<Self> {
+        let st = env::try_system_table()?;
+        st.runtime_services().get_time().ok().map(Self::from)
+    }
+
+    pub fn set(&self) -> uefi::Result {
+        let time = Time::try_from(*self).map_err(|_| uefi::Error::from(Status::INVALID_PARAMETER))?;
+        let st = env::system_table()?;
+        let rt = st.runtime_services() as *const RuntimeServices as *mut RuntimeServices;
+        unsafe { (*rt).set_time(&time) }
+    }
+
+    pub fn from_unix(seconds: i64) -> Self {
+        let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+        let rem = seconds.rem_euclid(86400);
+        Self {
+            year: year.clamp(0, u16::MAX as i64) as u16,
+            month,
+            day,
+            hour: (rem / 3600) as u8,
+            minute: (rem / 60 % 60) as u8,
+            second: (rem % 60) as u8,
+            time_zone: Some(0),
+            ..Self::default()
+        }
+    }
+
+    pub fn to_unix(&self) -> i64 {
+        let days = days_from_civil(self.year as i64, self.month, self.day);
+        let local = days * 86400 + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64;
+        local + self.time_zone.unwrap_or(0) as i64 * 60
+    }
+
+    pub fn to_utc(&self) -> Self {
+        let mut utc = Self::from_unix(self.to_unix());
+        utc.nanosecond = self.nanosecond;
+        utc
+    }
+
+    pub fn weekday(&self) -> u8 {
+        (days_from_civil(self.year as i64, self.month, self.day) + 3).rem_euclid(7) as u8
+    }
+
+    pub fn weekday_name(&self) -> &'static str {
+        WEEKDAYS[self.weekday() as usize]
+    }
+
+    pub fn month_name(&self) -> &'static str {
+        MONTHS[(self.month.clamp(1, 12) - 1) as usize]
+    }
+
+    pub fn day_of_year(&self) -> u16 {
+        let start = days_from_civil(self.year as i64, 1, 1);
+        (days_from_civil(self.year as i64, self.month, self.day) - start + 1) as u16
+    }
+
+    pub fn is_valid(&self) -> bool {
+        (1..=12).contains(&self.month)
+            && self.day >= 1 && self.day <= days_in_month(self.year as i64, self.month)
+            && self.hour < 24 && self.minute < 60 && self.second < 60
+            && self.nanosecond < 1_000_000_000
+    }
+
+    pub fn same_second(&self, other: &Self) -> bool {
+        Self { nanosecond: 0, ..*self } == Self { nanosecond: 0, ..*other }
+    }
+
+    pub fn date_string(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    pub fn time_string(&self) -> String {
+        format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+
+    pub fn zone_string(&self) -> String {
+        match self.time_zone {
+            None => String::new(),
+            Some(0) => String::from("Z"),
+            Some(tz) => {
+                let offset = -tz;
+                let sign = if offset < 0 { '-' } else { '+' };
+                format!("{}{:02}:{:02}", sign, offset.abs() / 60, offset.abs() % 60)
+            }
+        }
+    }
+
+    pub fn format(&self, pattern: &str) -> String {
+        let mut out = String::new();
+        let mut chars = pattern.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                out.push(ch);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => out.push_str(&format!("{:04}", self.year)),
+                Some('m') => out.push_str(&format!("{:02}", self.month)),
+                Some('d') => out.push_str(&format!("{:02}", self.day)),
+                Some('H') => out.push_str(&format!("{:02}", self.hour)),
+                Some('I') => out.push_str(&format!("{:02}", (self.hour + 11) % 12 + 1)),
+                Some('p') => out.push_str(if self.hour < 12 { "AM" } else { "PM" }),
+                Some('M') => out.push_str(&format!("{:02}", self.minute)),
+                Some('S') => out.push_str(&format!("{:02}", self.second)),
+                Some('f') => out.push_str(&format!("{:03}", self.nanosecond / 1_000_000)),
+                Some('j') => out.push_str(&format!("{:03}", self.day_of_year())),
+                Some('A') => out.push_str(self.weekday_name()),
+                Some('a') => out.push_str(&self.weekday_name()[..3]),
+                Some('B') => out.push_str(self.month_name()),
+                Some('b') => out.push_str(&self.month_name()[..3]),
+                Some('z') => out.push_str(&self.zone_string()),
+                Some('%') => out.push('%'),
+                Some(other) => {
+                    out.push('%');
+                    out.push(other);
+                }
+                None => out.push('%'),
+            }
+        }
+        out
+    }
+}
+
+impl From<Time> for DateTime {
+    fn from(time: Time) -> Self {
+        let daylight = time.daylight();
+        Self {
+            year: time.year(),
+            month: time.month(),
+            day: time.day(),
+            hour: time.hour(),
+            minute: time.minute(),
+            second: time.second(),
+            nanosecond: time.nanosecond(),
+            time_zone: time.time_zone(),
+            adjust_daylight: daylight.contains(Daylight::ADJUST_DAYLIGHT),
+            in_daylight: daylight.contains(Daylight::IN_DAYLIGHT),
+        }
+    }
+}
+
+impl TryFrom<DateTime> for Time {
+    type Error = uefi::table::runtime::TimeError;
+
+    fn try_from(value: DateTime) -> Result<Self, Self::Error> {
+        let mut daylight = Daylight::empty();
+        if value.adjust_daylight {
+            daylight |= Daylight::ADJUST_DAYLIGHT;
+        }
+        if value.in_daylight {
+            daylight |= Daylight::IN_DAYLIGHT;
+        }
+        Time::new(TimeParams {
+            year: value.year,
+            month: value.month,
+            day: value.day,
+            hour: value.hour,
+            minute: value.minute,
+            second: value.second,
+            nanosecond: value.nanosecond,
+            time_zone: value.time_zone,
+            daylight,
+        })
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}{}", self.date_string(), self.time_string(), self.zone_string())
+    }
+}
+
+pub fn timestamp() -> String {
+    DateTime::now().map(|x| x.format("%Y-%m-%d %H:%M:%S.%f")).unwrap_or_default()
+}
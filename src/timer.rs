@@ -0,0 +1,156 @@
+use uefi::table::boot::{EventType, TimerTrigger, Tpl};
+use uefi::ResultExt;
+
+use crate::env;
+use crate::Result;
+
+pub struct Timer {
+    event: uefi::Event,
+    period_us: u64,
+    repeat: bool,
+}
+
+fn ticks(us: u64) -> u64 {
+    (us * 10).max(1)
+}
+
+impl Timer {
+    fn new(period_us: u64, repeat: bool) -> Result<Self> {
+        let st = env::system_table()?;
+        let bs = st.boot_services();
+        let event = unsafe { bs.create_event(EventType::TIMER, Tpl::CALLBACK, None, None) }?;
+        let timer = Self { event, period_us, repeat };
+        timer.restart()?;
+        Ok(timer)
+    }
+
+    pub fn event(&self) -> &uefi::Event {
+        &self.event
+    }
+
+    pub fn period_us(&self) -> u64 {
+        self.period_us
+    }
+
+    pub fn is_periodic(&self) -> bool {
+        self.repeat
+    }
+
+    pub fn restart(&self) -> Result {
+        let trigger = if self.repeat {
+            TimerTrigger::Periodic(ticks(self.period_us))
+        } else {
+            TimerTrigger::Relative(ticks(self.period_us))
+        };
+        let st = env::system_table()?;
+        Ok(st.boot_services().set_timer(&self.event, trigger)?)
+    }
+
+    pub fn set_period(&mut self, period_us: u64) -> Result {
+        self.period_us = period_us;
+        self.restart()
+    }
+
+    pub fn cancel(&self) -> Result {
+        let st = env::system_table()?;
+        Ok(st.boot_services().set_timer(&self.event, TimerTrigger::Cancel)?)
+    }
+
+    pub fn fired(&self) -> bool {
+        env::try_system_table().is_some_and(|st| {
+            st.boot_services().check_event(unsafe { self.event.unsafe_clone() }).unwrap_or(false)
+        })
+    }
+
+    pub fn wait(&self) -> Result {
+        let st = env::system_table()?;
+        let mut events = [unsafe { self.event.unsafe_clone() }];
+        st.boot_services().wait_for_event(&mut events).discard_errdata()?;
+        Ok(())
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if let Some(st) = env::try_system_table() {
+            let _ = st.boot_services().close_event(unsafe { self.event.unsafe_clone() });
+        }
+    }
+}
+
+pub fn periodic(interval_us: u64) -> Result<Timer> {
+    Timer::new(interval_us, true)
+}
+
+pub fn one_shot(delay_us: u64) -> Result<Timer> {
+    Timer::new(delay_us, false)
+}
+
+#[cfg(not(feature = "host"))]
+pub fn sleep(us: u64) -> Result {
+    one_shot(us)?.wait()
+}
+
+#[cfg(feature = "host")]
+pub fn sleep(us: u64) -> Result {
+    std::thread::sleep(std::time::Duration::from_micros(us));
+    Ok(())
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "host")))]
+fn ticks_per_us() -> u64 {
+    static mut TICKS_PER_US: u64 = 0;
+    unsafe {
+        if TICKS_PER_US == 0 {
+            let st = if let Some(x) = env::try_system_table() { x } else { return 1; };
+            let start = core::arch::x86_64::_rdtsc();
+            st.boot_services().stall(1000);
+            TICKS_PER_US = ((core::arch::x86_64::_rdtsc() - start) / 1000).max(1);
+        }
+        TICKS_PER_US
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "host")))]
+pub fn monotonic_us() -> u64 {
+    unsafe { core::arch::x86_64::_rdtsc() / ticks_per_us() }
+}
+
+#[cfg(all(not(target_arch = "x86_64"), not(feature = "host")))]
+pub fn monotonic_us() -> u64 {
+    env::try_system_table()
+        .and_then(|st| st.runtime_services().get_time().ok())
+        .map(|x| {
+            let seconds = crate::time::DateTime::from(x).to_unix() as u64;
+            seconds * 1_000_000 + x.nanosecond() as u64 / 1000
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(feature = "host")]
+pub fn monotonic_us() -> u64 {
+    static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    START.get_or_init(std::time::Instant::now).elapsed().as_micros() as u64
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Stopwatch {
+    start: u64,
+}
+
+impl Stopwatch {
+    pub fn start() -> Self {
+        Self { start: monotonic_us() }
+    }
+
+    pub fn elapsed_us(&self) -> u64 {
+        monotonic_us().saturating_sub(self.start)
+    }
+
+    pub fn restart(&mut self) -> u64 {
+        let now = monotonic_us();
+        let elapsed = now.saturating_sub(self.start);
+        self.start = now;
+        elapsed
+    }
+}
@@ -0,0 +1,178 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use uefi::table::runtime::{VariableAttributes, VariableVendor};
+use uefi::{CStr16, CString16, Guid, Status};
+
+use crate::env;
+
+pub const GLOBAL: Guid = VariableVendor::GLOBAL_VARIABLE.0;
+
+pub const DEFAULT_ATTRIBUTES: VariableAttributes = VariableAttributes::NON_VOLATILE
+    .union(VariableAttributes::BOOTSERVICE_ACCESS)
+    .union(VariableAttributes::RUNTIME_ACCESS);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VarInfo {
+    pub name: String,
+    pub vendor: Guid,
+    pub attributes: VariableAttributes,
+    pub size: usize,
+}
+
+pub(crate) fn name16(name: &str) -> uefi::Result<CString16> {
+    CString16::try_from(name).map_err(|_| Status::INVALID_PARAMETER.into())
+}
+
+pub(crate) fn read(name: &CStr16, vendor: Guid) -> uefi::Result<(Vec<u8>, VariableAttributes)> {
+    let st = env::system_table()?;
+    let rt = st.runtime_services();
+    let vendor = VariableVendor(vendor);
+    let mut buf = vec![0u8; 256];
+    loop {
+        match rt.get_variable(name, &vendor, &mut buf) {
+            Ok((data, attributes)) => {
+                let len = data.len();
+                buf.truncate(len);
+                return Ok((buf, attributes));
+            }
+            Err(err) if err.status() == Status::BUFFER_TOO_SMALL => {
+                let size = (*err.data()).ok_or(uefi::Error::from(Status::BUFFER_TOO_SMALL))?;
+                buf.resize(size, 0);
+            }
+            Err(err) => return Err(err.status().into()),
+        }
+    }
+}
+
+pub fn iter() -> impl Iterator<Item = VarInfo> {
+    let keys = env::try_system_table()
+        .and_then(|st| st.runtime_services().variable_keys().ok())
+        .unwrap_or_default();
+    keys.into_iter().filter_map(|key| {
+        let name = key.name().ok()?;
+        let (data, attributes) = read(name, key.vendor.0).ok()?;
+        Some(VarInfo {
+            name: String::from(name),
+            vendor: key.vendor.0,
+            attributes,
+            size: data.len(),
+        })
+    })
+}
+
+pub fn get(name: &str, vendor: Guid) -> uefi::Result<Vec<u8>> {
+    Ok(read(&name16(name)?, vendor)?.0)
+}
+
+pub fn get_with_attributes(name: &str, vendor: Guid) -> uefi::Result<(Vec<u8>, VariableAttributes)> {
+    read(&name16(name)?, vendor)
+}
+
+pub fn set(name: &str, vendor: Guid, attributes: VariableAttributes, data: &[u8]) -> uefi::Result {
+    let st = env::system_table()?;
+    st.runtime_services().set_variable(&name16(name)?, &VariableVendor(vendor), attributes, data)
+}
+
+pub fn delete(name: &str, vendor: Guid) -> uefi::Result {
+    let st = env::system_table()?;
+    match st.runtime_services().delete_variable(&name16(name)?, &VariableVendor(vendor)) {
+        Err(err) if err.status() == Status::NOT_FOUND => Ok(()),
+        other => other,
+    }
+}
+
+pub fn exists(name: &str, vendor: Guid) -> bool {
+    get(name, vendor).is_ok()
+}
+
+pub fn get_u8(name: &str, vendor: Guid) -> Option<u8> {
+    get(name, vendor).ok()?.first().copied()
+}
+
+pub fn get_u16(name: &str, vendor: Guid) -> Option<u16> {
+    Some(u16::from_le_bytes(get(name, vendor).ok()?.get(..2)?.try_into().ok()?))
+}
+
+pub fn get_u32(name: &str, vendor: Guid) -> Option<u32> {
+    Some(u32::from_le_bytes(get(name, vendor).ok()?.get(..4)?.try_into().ok()?))
+}
+
+pub fn get_u64(name: &str, vendor: Guid) -> Option<u64> {
+    Some(u64::from_le_bytes(get(name, vendor).ok()?.get(..8)?.try_into().ok()?))
+}
+
+pub fn get_u16_list(name: &str, vendor: Guid) -> Option<Vec<u16>> {
+    let data = get(name, vendor).ok()?;
+    Some(data.chunks_exact(2).map(|x| u16::from_le_bytes([x[0], x[1]])).collect())
+}
+
+pub fn get_ascii(name: &str, vendor: Guid) -> Option<String> {
+    let data = get(name, vendor).ok()?;
+    let end = data.iter().position(|x| *x == 0).unwrap_or(data.len());
+    core::str::from_utf8(&data[..end]).ok().map(String::from)
+}
+
+pub fn get_ucs2(name: &str, vendor: Guid) -> Option<String> {
+    let list = get_u16_list(name, vendor)?;
+    let end = list.iter().position(|x| *x == 0).unwrap_or(list.len());
+    char::decode_utf16(list[..end].iter().copied()).collect::<Result<String, _>>().ok()
+}
+
+pub fn set_u8(name: &str, vendor: Guid, value: u8) -> uefi::Result {
+    set(name, vendor, DEFAULT_ATTRIBUTES, &[value])
+}
+
+pub fn set_u16(name: &str, vendor: Guid, value: u16) -> uefi::Result {
+    set(name, vendor, DEFAULT_ATTRIBUTES, &value.to_le_bytes())
+}
+
+pub fn set_u32(name: &str, vendor: Guid, value: u32) -> uefi::Result {
+    set(name, vendor, DEFAULT_ATTRIBUTES, &value.to_le_bytes())
+}
+
+pub fn set_u64(name: &str, vendor: Guid, value: u64) -> uefi::Result {
+    set(name, vendor, DEFAULT_ATTRIBUTES, &value.to_le_bytes())
+}
+
+pub fn set_u16_list(name: &str, vendor: Guid, values: &[u16]) -> uefi::Result {
+    let data: Vec<u8> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+    set(name, vendor, DEFAULT_ATTRIBUTES, &data)
+}
+
+pub fn set_ascii(name: &str, vendor: Guid, value: &str) -> uefi::Result {
+    let mut data = Vec::from(value.as_bytes());
+    data.push(0);
+    set(name, vendor, DEFAULT_ATTRIBUTES, &data)
+}
+
+pub fn set_ucs2(name: &str, vendor: Guid, value: &str) -> uefi::Result {
+    let mut list: Vec<u16> = value.encode_utf16().collect();
+    list.push(0);
+    set_u16_list(name, vendor, &list)
+}
+
+pub fn timeout() -> Option<u16> {
+    get_u16("Timeout", GLOBAL)
+}
+
+pub fn set_timeout(seconds: u16) -> uefi::Result {
+    set_u16("Timeout", GLOBAL, seconds)
+}
+
+pub fn platform_lang() -> Option<String> {
+    get_ascii("PlatformLang", GLOBAL)
+}
+
+pub fn boot_current() -> Option<u16> {
+    get_u16("BootCurrent", GLOBAL)
+}
+
+pub fn secure_boot() -> Option<bool> {
+    get_u8("SecureBoot", GLOBAL).map(|x| x == 1)
+}
+
+pub fn setup_mode() -> Option<bool> {
+    get_u8("SetupMode", GLOBAL).map(|x| x == 1)
+}
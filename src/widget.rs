@@ -0,0 +1,123 @@
+use alloc::string::String;
+
+use crate::gfx::{Buffer, Dim, Rect};
+use crate::input::{KeyCode, PointerEvent};
+
+pub mod bootmenu;
+pub mod button;
+pub mod chart;
+pub mod countdown;
+pub mod cursor;
+pub mod dispatch;
+pub mod editor;
+pub mod filedialog;
+pub mod focus;
+pub mod form;
+pub mod grid;
+pub mod image;
+pub mod immediate;
+pub mod lineeditor;
+pub mod list;
+pub mod msgbox;
+pub mod overlay;
+pub mod scene;
+pub mod scroll;
+pub mod slider;
+pub mod spinner;
+pub mod stack;
+pub mod statusbar;
+pub mod table;
+pub mod tabs;
+pub mod terminal;
+pub mod textbox;
+pub mod toast;
+pub mod tree;
+
+pub use bootmenu::{BootMenu, BootMenuStyle, MenuEntry};
+pub use button::{Button, ButtonContent, ButtonState, ButtonStyle, ButtonVisual};
+pub use chart::{BarChart, ChartSeries, ChartStyle, LineChart, Samples, Scale};
+pub use countdown::{Countdown, CountdownResult, CountdownStyle};
+pub use cursor::{arrow_sprite, Cursor};
+pub use dispatch::{Dispatcher, UiEvent};
+pub use editor::{TextEditor, TextEditorStyle};
+pub use filedialog::{FileDialog, FileDialogMode, FileDialogStyle, PickedFile};
+pub use focus::{Direction, FocusChange, FocusHook, FocusManager};
+pub use form::{FieldKind, FieldValue, Form, FormResult, FormStyle};
+pub use grid::{Grid, GridCell, Track};
+pub use image::{Fit, Image};
+pub use immediate::{Ui, UiState};
+pub use lineeditor::LineEditor;
+pub use list::{ListStyle, ListView, RowDrawFn};
+pub use msgbox::{Buttons, MessageBox, MessageBoxStyle, MessageResult};
+pub use overlay::{Overlay, OverlayStyle};
+pub use scene::{NodeId, Scene};
+pub use scroll::{ScrollBar, ScrollBarStyle, ScrollView};
+pub use slider::{Slider, SliderStyle};
+pub use spinner::Spinner;
+pub use stack::{HStack, Stack, StackItem, VStack};
+pub use statusbar::{StatusBar, StatusBarStyle, StatusField};
+pub use table::{Column, ColumnWidth, Table, TableStyle};
+pub use tabs::{Tab, TabStyle, TabView};
+pub use terminal::{Terminal, EFI_PALETTE};
+pub use textbox::{TextBox, TextBoxStyle};
+pub use toast::{ToastId, ToastKind, ToastStyle, Toasts};
+pub use tree::{TreeLoader, TreeNode, TreeStyle, TreeView};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Response {
+    Ignored,
+    Handled,
+    Clicked,
+    ValueChanged,
+    Submitted,
+    Cancelled,
+}
+
+impl Response {
+    pub fn handled(self) -> bool {
+        self != Response::Ignored
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+}
+
+pub trait Widget {
+    fn rect(&self) -> Rect;
+
+    fn set_rect(&mut self, rect: Rect);
+
+    fn measure(&self) -> Dim {
+        self.rect().dim
+    }
+
+    fn draw(&self, buffer: &mut Buffer);
+
+    fn key(&mut self, _key: KeyCode) -> Response {
+        Response::Ignored
+    }
+
+    fn pointer(&mut self, _event: PointerEvent) -> Response {
+        Response::Ignored
+    }
+
+    fn focusable(&self) -> bool {
+        false
+    }
+
+    fn set_focused(&mut self, _focused: bool) {}
+
+    fn text(&self) -> Option<String> {
+        None
+    }
+}
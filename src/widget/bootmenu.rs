@@ -0,0 +1,267 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, Buffer, Color, Rect, View};
+use crate::input::{KeyCode, PointerEvent};
+use crate::theme::Theme;
+use crate::widget::{Countdown, ListView, Response, Widget};
+
+pub struct MenuEntry {
+    pub title: String,
+    pub description: Option<String>,
+    pub icon: Option<Buffer>,
+    pub hotkey: Option<char>,
+}
+
+impl MenuEntry {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: String::from(title),
+            description: None,
+            icon: None,
+            hotkey: None,
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(String::from(description));
+        self
+    }
+
+    pub fn icon(mut self, icon: Buffer) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn hotkey(mut self, hotkey: char) -> Self {
+        self.hotkey = Some(hotkey);
+        self
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct BootMenuStyle {
+    pub bg: Color,
+    pub title: Color,
+    pub footer: Color,
+    pub line_height: i32,
+    pub icon_size: i32,
+}
+
+impl From<&Theme> for BootMenuStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            bg: p.background,
+            title: p.fg_strong,
+            footer: p.fg_dim,
+            line_height: m.line_height,
+            icon_size: 16,
+        }
+    }
+}
+
+impl Default for BootMenuStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub struct BootMenu<'a> {
+    pub rect: Rect,
+    pub title: String,
+    pub entries: Vec<MenuEntry>,
+    pub style: BootMenuStyle,
+    pub default: usize,
+    list: ListView<'a>,
+    countdown: Countdown<'a>,
+    chosen: Option<usize>,
+    font: &'a Font,
+}
+
+impl<'a> BootMenu<'a> {
+    pub fn new(rect: Rect, font: &'a Font, title: &str, entries: Vec<MenuEntry>) -> Self {
+        let style = BootMenuStyle::default();
+        let items = entries.iter().map(|x| x.title.clone()).collect();
+        let mut list = ListView::new(rect, font, style.line_height + 4, items);
+        list.style.padding = style.icon_size + 8;
+        let mut countdown = Countdown::new(rect, font, 0);
+        countdown.interrupt();
+        let mut menu = Self {
+            rect,
+            title: String::from(title),
+            entries,
+            style,
+            default: 0,
+            list,
+            countdown,
+            chosen: None,
+            font,
+        };
+        menu.layout();
+        menu
+    }
+
+    fn layout(&mut self) {
+        let header = self.style.line_height * 2;
+        let footer = self.style.line_height * 3;
+        let r = self.rect;
+        self.list.set_rect(rect(
+            r.pos + pos(0, header),
+            dim(r.dim.w, (r.dim.h - header - footer).max(0)),
+        ));
+    }
+
+    pub fn set_timeout(&mut self, seconds: Option<u32>) {
+        match seconds {
+            Some(seconds) => self.countdown.start(seconds),
+            None => self.countdown.interrupt(),
+        }
+    }
+
+    pub fn set_default(&mut self, index: usize) {
+        self.default = index;
+        self.list.select(index);
+    }
+
+    pub fn counting_down(&self) -> bool {
+        self.countdown.running()
+    }
+
+    pub fn interrupt(&mut self) {
+        self.countdown.interrupt();
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.list.selected()
+    }
+
+    pub fn chosen(&self) -> Option<&MenuEntry> {
+        self.entries.get(self.chosen?)
+    }
+
+    pub fn chosen_index(&self) -> Option<usize> {
+        self.chosen
+    }
+
+    fn choose(&mut self, index: usize) -> Response {
+        if index >= self.entries.len() {
+            return Response::Ignored;
+        }
+        self.countdown.interrupt();
+        self.chosen = Some(index);
+        Response::Clicked
+    }
+
+    pub fn tick(&mut self, dt: f32) -> Response {
+        self.list.tick(dt);
+        match self.countdown.tick(dt) {
+            Response::Submitted => self.choose(self.default),
+            response => response,
+        }
+    }
+
+    fn footer_text(&self) -> Option<String> {
+        if let Some(text) = self.countdown.text() {
+            return Some(text);
+        }
+        self.entries.get(self.list.selected()?)?.description.clone()
+    }
+}
+
+impl<'a> Widget for BootMenu<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.layout();
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        buffer.fill_over(self.rect.area(), s.bg);
+        let title_dim = gfx::text_dim(self.font, &self.title);
+        let title_rect = rect(self.rect.pos, dim(self.rect.dim.w, s.line_height * 2));
+        buffer.draw_text(title_rect.centered(title_dim).pos, self.font, &self.title, s.title);
+        self.list.draw(buffer);
+        let list_rect = self.list.rect();
+        let mut view = View::new(buffer, list_rect);
+        for (index, entry) in self.entries.iter().enumerate() {
+            let row = if let Some(x) = self.list.row_rect(index) { x } else { continue; };
+            if let Some(icon) = entry.icon.as_ref() {
+                let slot = rect(pos(4, row.pos.y - list_rect.pos.y), dim(s.icon_size, row.dim.h));
+                view.premultiplied_over(icon, icon.area(), slot.centered(icon.dim).pos);
+            }
+            if let Some(hotkey) = entry.hotkey {
+                let text = format!("[{}]", hotkey);
+                let sz = gfx::text_dim(self.font, &text);
+                let loc = pos(
+                    list_rect.dim.w - sz.w - 12,
+                    row.pos.y - list_rect.pos.y + (row.dim.h - sz.h) / 2,
+                );
+                view.draw_text(loc, self.font, &text, s.footer);
+            }
+        }
+        if let Some(text) = self.footer_text() {
+            let footer = rect(
+                pos(self.rect.pos.x, list_rect.pos.y + list_rect.dim.h),
+                dim(self.rect.dim.w, s.line_height * 3),
+            );
+            let sz = gfx::text_dim(self.font, &text);
+            buffer.draw_text(footer.centered(sz).pos, self.font, &text, s.footer);
+        }
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        self.interrupt();
+        match key {
+            KeyCode::Enter => match self.list.selected() {
+                Some(index) => self.choose(index),
+                None => Response::Handled,
+            },
+            KeyCode::Char(ch) => {
+                let ch = ch.to_ascii_lowercase();
+                let index = self.entries.iter()
+                    .position(|x| x.hotkey.map(|x| x.to_ascii_lowercase()) == Some(ch));
+                match index {
+                    Some(index) => {
+                        self.list.select(index);
+                        self.choose(index)
+                    }
+                    None => Response::Handled,
+                }
+            }
+            key => match self.list.key(key) {
+                Response::Ignored => Response::Handled,
+                response => response,
+            },
+        }
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        if self.rect.contains(event.pos()) {
+            self.interrupt();
+        }
+        match self.list.pointer(event) {
+            Response::Clicked => match self.list.selected() {
+                Some(index) => self.choose(index),
+                None => Response::Handled,
+            },
+            response => response,
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.list.set_focused(focused);
+    }
+}
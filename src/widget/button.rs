@@ -0,0 +1,207 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, Buffer, Color, Dim, Rect};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::theme::Theme;
+use crate::widget::{Response, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ButtonState {
+    Normal,
+    Focused,
+    Pressed,
+    Disabled,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ButtonVisual {
+    pub bg: Color,
+    pub fg: Color,
+    pub border: Color,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ButtonStyle {
+    pub normal: ButtonVisual,
+    pub focused: ButtonVisual,
+    pub pressed: ButtonVisual,
+    pub disabled: ButtonVisual,
+    pub border_width: i32,
+}
+
+impl From<&Theme> for ButtonStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            normal: ButtonVisual { bg: p.control, fg: p.fg_strong, border: p.border },
+            focused: ButtonVisual { bg: p.accent, fg: p.accent_fg, border: p.fg_strong },
+            pressed: ButtonVisual { bg: p.accent_pressed, fg: p.accent_fg, border: p.fg_strong },
+            disabled: ButtonVisual { bg: p.line, fg: p.fg_disabled, border: p.raised },
+            border_width: m.border_width,
+        }
+    }
+}
+
+impl Default for ButtonStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+impl ButtonStyle {
+    pub fn visual(&self, state: ButtonState) -> ButtonVisual {
+        match state {
+            ButtonState::Normal => self.normal,
+            ButtonState::Focused => self.focused,
+            ButtonState::Pressed => self.pressed,
+            ButtonState::Disabled => self.disabled,
+        }
+    }
+}
+
+pub enum ButtonContent {
+    Label(String),
+    Icon(Buffer),
+}
+
+pub struct Button<'a> {
+    pub rect: Rect,
+    pub content: ButtonContent,
+    pub style: ButtonStyle,
+    pub enabled: bool,
+    pub on_click: Option<Box<dyn FnMut() + 'a>>,
+    focused: bool,
+    pressed: bool,
+    font: &'a Font,
+}
+
+impl<'a> Button<'a> {
+    pub fn new(rect: Rect, font: &'a Font, content: ButtonContent) -> Self {
+        Self {
+            rect,
+            content,
+            style: ButtonStyle::default(),
+            enabled: true,
+            on_click: None,
+            focused: false,
+            pressed: false,
+            font,
+        }
+    }
+
+    pub fn label(rect: Rect, font: &'a Font, label: &str) -> Self {
+        Self::new(rect, font, ButtonContent::Label(String::from(label)))
+    }
+
+    pub fn state(&self) -> ButtonState {
+        if !self.enabled {
+            ButtonState::Disabled
+        } else if self.pressed {
+            ButtonState::Pressed
+        } else if self.focused {
+            ButtonState::Focused
+        } else {
+            ButtonState::Normal
+        }
+    }
+
+    pub fn press(&mut self) {
+        if self.enabled {
+            self.pressed = true;
+        }
+    }
+
+    pub fn release(&mut self) -> Response {
+        if !self.pressed {
+            return Response::Ignored;
+        }
+        self.pressed = false;
+        self.activate()
+    }
+
+    pub fn activate(&mut self) -> Response {
+        if !self.enabled {
+            return Response::Ignored;
+        }
+        if let Some(on_click) = self.on_click.as_mut() {
+            on_click();
+        }
+        Response::Clicked
+    }
+}
+
+impl<'a> Widget for Button<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn measure(&self) -> Dim {
+        match &self.content {
+            ButtonContent::Label(label) => gfx::text_dim(self.font, label) + dim(24, 12),
+            ButtonContent::Icon(icon) => icon.dim + dim(12, 12),
+        }
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let visual = self.style.visual(self.state());
+        let area = self.rect.area();
+        buffer.fill_over(area, visual.bg);
+        buffer.stroke(area, self.style.border_width, visual.border);
+        match &self.content {
+            ButtonContent::Label(label) => {
+                let sz = gfx::text_dim(self.font, label);
+                let loc = self.rect.centered(sz).pos;
+                buffer.draw_text(loc, self.font, label, visual.fg);
+            }
+            ButtonContent::Icon(icon) => {
+                let loc = self.rect.centered(icon.dim).pos;
+                buffer.premultiplied_over(icon, icon.area(), loc);
+            }
+        }
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        if key.is_activate() {
+            self.activate()
+        } else {
+            Response::Ignored
+        }
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        match event {
+            PointerEvent::Down(pos, PointerButton::Left) if self.rect.contains(pos) => {
+                self.press();
+                Response::Handled
+            }
+            PointerEvent::Up(pos, PointerButton::Left) if self.pressed => {
+                if self.rect.contains(pos) {
+                    self.release()
+                } else {
+                    self.pressed = false;
+                    Response::Handled
+                }
+            }
+            _ => Response::Ignored,
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if !focused {
+            self.pressed = false;
+        }
+    }
+}
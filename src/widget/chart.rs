@@ -0,0 +1,374 @@
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, rgb, Buffer, Color, Pos, Rect};
+use crate::theme::Theme;
+use crate::widget::Widget;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Samples {
+    data: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl Samples {
+    pub fn new(capacity: usize) -> Self {
+        Self { data: VecDeque::with_capacity(capacity), capacity: capacity.max(1) }
+    }
+
+    pub fn push(&mut self, value: f32) {
+        if self.data.len() == self.capacity {
+            self.data.pop_front();
+        }
+        self.data.push_back(value);
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn last(&self) -> Option<f32> {
+        self.data.back().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = f32> + '_ {
+        self.data.iter().copied()
+    }
+
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.iter().fold(None, |acc, x| match acc {
+            None => Some((x, x)),
+            Some((lo, hi)) => Some((lo.min(x), hi.max(x))),
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Scale {
+    Auto,
+    AutoFromZero,
+    Fixed(f32, f32),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ChartStyle {
+    pub bg: Color,
+    pub axis: Color,
+    pub grid: Color,
+    pub text: Color,
+    pub palette: [Color; 4],
+    pub padding: i32,
+    pub ticks: i32,
+    pub bar_gap: i32,
+}
+
+impl From<&Theme> for ChartStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            bg: p.input,
+            axis: p.border,
+            grid: p.line,
+            text: p.fg_dim,
+            palette: [
+                theme.focus.color,
+                rgb(0x40, 0xA0, 0x50),
+                rgb(0xD0, 0xA0, 0x30),
+                p.error,
+            ],
+            padding: m.padding * 2,
+            ticks: 4,
+            bar_gap: 2,
+        }
+    }
+}
+
+impl Default for ChartStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+fn nice_step(raw: f32) -> f32 {
+    if raw <= 0.0 {
+        return 1.0;
+    }
+    let mut mag = 1.0;
+    while mag * 10.0 <= raw {
+        mag *= 10.0;
+    }
+    while mag > raw {
+        mag /= 10.0;
+    }
+    let norm = raw / mag;
+    let nice = if norm <= 1.0 { 1.0 } else if norm <= 2.0 { 2.0 } else if norm <= 5.0 { 5.0 } else { 10.0 };
+    nice * mag
+}
+
+fn decimals(step: f32) -> usize {
+    if step >= 1.0 { 0 } else if step >= 0.1 { 1 } else { 2 }
+}
+
+fn draw_line(buffer: &mut Buffer, a: Pos, b: Pos, color: Color) {
+    let (dx, dy) = ((b.x - a.x).abs(), -(b.y - a.y).abs());
+    let (sx, sy) = (if a.x < b.x { 1 } else { -1 }, if a.y < b.y { 1 } else { -1 });
+    let mut err = dx + dy;
+    let mut p = a;
+    loop {
+        buffer.fill(rect(p, dim(1, 1)).area(), color);
+        if p == b {
+            break;
+        }
+        let e2 = err * 2;
+        if e2 >= dy {
+            err += dy;
+            p.x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            p.y += sy;
+        }
+    }
+}
+
+struct Axes {
+    plot: Rect,
+    lo: f32,
+    hi: f32,
+    step: f32,
+}
+
+impl Axes {
+    fn new(outer: Rect, font: &Font, style: &ChartStyle, scale: Scale, range: Option<(f32, f32)>) -> Self {
+        let (lo, hi) = match (scale, range) {
+            (Scale::Fixed(lo, hi), _) => (lo, hi),
+            (Scale::Auto, Some(r)) => r,
+            (Scale::AutoFromZero, Some((lo, hi))) => (lo.min(0.0), hi.max(0.0)),
+            (_, None) => (0.0, 1.0),
+        };
+        let (lo, hi) = if hi - lo < f32::EPSILON { (lo - 0.5, hi + 0.5) } else { (lo, hi) };
+        let step = nice_step((hi - lo) / style.ticks.max(1) as f32);
+        let lo = gfx::Math::floor(lo / step) * step;
+        let hi = -gfx::Math::floor(-hi / step) * step;
+        let label_w = [lo, hi].iter()
+            .map(|x| gfx::text_dim(font, &format!("{:.*}", decimals(step), x)).w)
+            .max()
+            .unwrap_or(0);
+        let text_h = gfx::text_dim(font, "0").h;
+        let p = style.padding;
+        let plot = rect(
+            outer.pos + pos(p + label_w + p, p + text_h / 2),
+            dim(outer.dim.w - label_w - p * 3, outer.dim.h - p * 2 - text_h),
+        );
+        Self { plot, lo, hi, step }
+    }
+
+    fn y(&self, value: f32) -> i32 {
+        let t = ((value - self.lo) / (self.hi - self.lo)).clamp(0.0, 1.0);
+        self.plot.pos.y + self.plot.dim.h - 1 - (t * (self.plot.dim.h - 1) as f32) as i32
+    }
+
+    fn draw(&self, buffer: &mut Buffer, font: &Font, style: &ChartStyle) {
+        let plot = self.plot;
+        let text_h = gfx::text_dim(font, "0").h;
+        let mut value = self.lo;
+        while value <= self.hi + self.step * 0.5 {
+            let y = self.y(value);
+            buffer.fill(rect(pos(plot.pos.x, y), dim(plot.dim.w, 1)).area(), style.grid);
+            let label = format!("{:.*}", decimals(self.step), value);
+            let w = gfx::text_dim(font, &label).w;
+            buffer.draw_text(pos(plot.pos.x - style.padding - w, y - text_h / 2), font, &label, style.text);
+            value += self.step;
+        }
+        buffer.fill(rect(plot.pos, dim(1, plot.dim.h)).area(), style.axis);
+        let base = self.y(0.0_f32.clamp(self.lo, self.hi));
+        buffer.fill(rect(pos(plot.pos.x, base), dim(plot.dim.w, 1)).area(), style.axis);
+    }
+}
+
+pub struct ChartSeries {
+    pub name: String,
+    pub color: Color,
+    pub samples: Samples,
+}
+
+pub struct LineChart<'a> {
+    pub rect: Rect,
+    pub style: ChartStyle,
+    pub scale: Scale,
+    pub series: Vec<ChartSeries>,
+    font: &'a Font,
+}
+
+impl<'a> LineChart<'a> {
+    pub fn new(rect: Rect, font: &'a Font) -> Self {
+        Self { rect, style: ChartStyle::default(), scale: Scale::Auto, series: Vec::new(), font }
+    }
+
+    pub fn style(mut self, style: ChartStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn scale(mut self, scale: Scale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn add_series(&mut self, name: &str, capacity: usize) -> usize {
+        let color = self.style.palette[self.series.len() % self.style.palette.len()];
+        self.series.push(ChartSeries { name: name.to_string(), color, samples: Samples::new(capacity) });
+        self.series.len() - 1
+    }
+
+    pub fn push(&mut self, series: usize, value: f32) {
+        if let Some(x) = self.series.get_mut(series) {
+            x.samples.push(value);
+        }
+    }
+
+    fn range(&self) -> Option<(f32, f32)> {
+        self.series.iter().filter_map(|x| x.samples.range())
+            .reduce(|a, b| (a.0.min(b.0), a.1.max(b.1)))
+    }
+}
+
+impl<'a> Widget for LineChart<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        buffer.fill(self.rect.area(), self.style.bg);
+        let axes = Axes::new(self.rect, self.font, &self.style, self.scale, self.range());
+        axes.draw(buffer, self.font, &self.style);
+        let plot = axes.plot;
+        for series in self.series.iter() {
+            let slots = series.samples.capacity().max(2) - 1;
+            let offset = series.samples.capacity() - series.samples.len();
+            let mut last: Option<Pos> = None;
+            for (i, value) in series.samples.iter().enumerate() {
+                let x = plot.pos.x + ((i + offset) as i32 * (plot.dim.w - 1)) / slots as i32;
+                let p = pos(x, axes.y(value));
+                if let Some(prev) = last {
+                    draw_line(buffer, prev, p, series.color);
+                }
+                last = Some(p);
+            }
+        }
+        let mut x = plot.pos.x + plot.dim.w;
+        for series in self.series.iter().rev() {
+            x -= gfx::text_dim(self.font, &series.name).w;
+            buffer.draw_text(pos(x, self.rect.pos.y + self.style.padding), self.font, &series.name, series.color);
+            x -= self.style.padding * 2;
+        }
+    }
+}
+
+pub struct BarChart<'a> {
+    pub rect: Rect,
+    pub style: ChartStyle,
+    pub scale: Scale,
+    pub color: Color,
+    pub samples: Samples,
+    pub labels: Vec<String>,
+    font: &'a Font,
+}
+
+impl<'a> BarChart<'a> {
+    pub fn new(rect: Rect, font: &'a Font, capacity: usize) -> Self {
+        let style = ChartStyle::default();
+        Self {
+            rect,
+            color: style.palette[0],
+            style,
+            scale: Scale::AutoFromZero,
+            samples: Samples::new(capacity),
+            labels: Vec::new(),
+            font,
+        }
+    }
+
+    pub fn style(mut self, style: ChartStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn scale(mut self, scale: Scale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn push(&mut self, value: f32) {
+        self.samples.push(value);
+    }
+
+    pub fn set_values(&mut self, values: &[(&str, f32)]) {
+        self.samples = Samples::new(values.len());
+        self.labels.clear();
+        for (label, value) in values {
+            self.samples.push(*value);
+            self.labels.push(label.to_string());
+        }
+    }
+}
+
+impl<'a> Widget for BarChart<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        buffer.fill(self.rect.area(), s.bg);
+        let mut outer = self.rect;
+        let text_h = gfx::text_dim(self.font, "0").h;
+        if !self.labels.is_empty() {
+            outer.dim.h -= text_h + s.padding;
+        }
+        let axes = Axes::new(outer, self.font, s, self.scale, self.samples.range());
+        axes.draw(buffer, self.font, s);
+        let plot = axes.plot;
+        let slots = self.samples.capacity() as i32;
+        let slot_w = (plot.dim.w - 1) / slots.max(1);
+        let base = axes.y(0.0_f32.clamp(axes.lo, axes.hi));
+        let offset = slots - self.samples.len() as i32;
+        for (i, value) in self.samples.iter().enumerate() {
+            let x = plot.pos.x + 1 + (i as i32 + offset) * slot_w + s.bar_gap / 2;
+            let y = axes.y(value);
+            let (top, bottom) = if y < base { (y, base) } else { (base + 1, y + 1) };
+            let bar = rect(pos(x, top), dim((slot_w - s.bar_gap).max(1), bottom - top));
+            buffer.fill(bar.area(), self.color);
+            if let Some(label) = self.labels.get(i) {
+                let w = gfx::text_dim(self.font, label).w;
+                let lx = x + (slot_w - s.bar_gap - w) / 2;
+                buffer.draw_text(pos(lx, plot.pos.y + plot.dim.h + s.padding), self.font, label, s.text);
+            }
+        }
+    }
+}
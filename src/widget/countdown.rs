@@ -0,0 +1,189 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, Buffer, Color, Rect, Screen};
+use crate::input::{KeyCode, Keyboard, PointerButton, PointerEvent, Source, WaitSet};
+use crate::theme::Theme;
+use crate::timer;
+use crate::widget::{Response, Widget};
+
+const TICKS_PER_SECOND: u64 = 10;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CountdownStyle {
+    pub bg: Option<Color>,
+    pub text: Color,
+    pub bar: Color,
+    pub track: Color,
+    pub bar_height: i32,
+}
+
+impl From<&Theme> for CountdownStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        Self {
+            bg: None,
+            text: p.fg_dim,
+            bar: p.accent,
+            track: p.line,
+            bar_height: 2,
+        }
+    }
+}
+
+impl Default for CountdownStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CountdownResult {
+    Expired,
+    Interrupted(KeyCode),
+}
+
+pub struct Countdown<'a> {
+    pub rect: Rect,
+    pub style: CountdownStyle,
+    pub template: String,
+    pub show_bar: bool,
+    total: f32,
+    remaining: Option<f32>,
+    font: &'a Font,
+}
+
+impl<'a> Countdown<'a> {
+    pub fn new(rect: Rect, font: &'a Font, seconds: u32) -> Self {
+        Self {
+            rect,
+            style: CountdownStyle::default(),
+            template: String::from("Booting default entry in {}"),
+            show_bar: false,
+            total: seconds as f32,
+            remaining: Some(seconds as f32),
+            font,
+        }
+    }
+
+    pub fn style(mut self, style: CountdownStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn template(mut self, template: &str) -> Self {
+        self.template = template.to_string();
+        self
+    }
+
+    pub fn bar(mut self, show_bar: bool) -> Self {
+        self.show_bar = show_bar;
+        self
+    }
+
+    pub fn start(&mut self, seconds: u32) {
+        self.total = seconds as f32;
+        self.remaining = Some(seconds as f32);
+    }
+
+    pub fn interrupt(&mut self) {
+        self.remaining = None;
+    }
+
+    pub fn running(&self) -> bool {
+        self.remaining.is_some()
+    }
+
+    pub fn seconds_left(&self) -> Option<u32> {
+        self.remaining.map(|x| x as u32 + 1)
+    }
+
+    pub fn text(&self) -> Option<String> {
+        let secs = self.seconds_left()?;
+        let count = format!("{} second{}", secs, if secs == 1 { "" } else { "s" });
+        Some(self.template.replace("{}", &count))
+    }
+
+    pub fn tick(&mut self, dt: f32) -> Response {
+        let remaining = if let Some(x) = self.remaining { x } else { return Response::Ignored; };
+        let remaining = remaining - dt;
+        if remaining <= 0.0 {
+            self.remaining = None;
+            return Response::Submitted;
+        }
+        self.remaining = Some(remaining);
+        Response::Handled
+    }
+
+    pub fn run(&mut self, screen: &mut Buffer) -> crate::Result<CountdownResult> {
+        let timer = timer::periodic(1_000_000 / TICKS_PER_SECOND)?;
+        let mut set = WaitSet::new();
+        set.keyboard()?;
+        set.timer(0, timer.event());
+        let result = loop {
+            self.draw(screen);
+            Screen::present(self.rect);
+            if set.wait(None)? == Source::Keyboard {
+                if let Some(key) = Keyboard::poll_key() {
+                    self.interrupt();
+                    break CountdownResult::Interrupted(key);
+                }
+                continue;
+            }
+            if self.tick(1.0 / TICKS_PER_SECOND as f32) == Response::Submitted {
+                break CountdownResult::Expired;
+            }
+        };
+        drop(timer);
+        self.draw(screen);
+        Screen::present(self.rect);
+        Ok(result)
+    }
+}
+
+impl<'a> Widget for Countdown<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        if let Some(bg) = s.bg {
+            buffer.fill(self.rect.area(), bg);
+        }
+        let text = if let Some(x) = self.text() { x } else { return; };
+        let sz = gfx::text_dim(self.font, &text);
+        buffer.draw_text(self.rect.centered(sz).pos, self.font, &text, s.text);
+        if self.show_bar && self.total > 0.0 {
+            let r = self.rect;
+            let track = rect(pos(r.pos.x, r.pos.y + r.dim.h - s.bar_height), dim(r.dim.w, s.bar_height));
+            let frac = self.remaining.unwrap_or(0.0) / self.total;
+            buffer.fill(track.area(), s.track);
+            buffer.fill(rect(track.pos, dim((r.dim.w as f32 * frac) as i32, s.bar_height)).area(), s.bar);
+        }
+    }
+
+    fn key(&mut self, _key: KeyCode) -> Response {
+        if !self.running() {
+            return Response::Ignored;
+        }
+        self.interrupt();
+        Response::Cancelled
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        match event {
+            PointerEvent::Down(_, PointerButton::Left) if self.running() => {
+                self.interrupt();
+                Response::Cancelled
+            }
+            _ => Response::Ignored,
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::gfx::{dim, pos, rect, Buffer, Color, Pos, Rect, Screen};
+
+const ARROW: [&str; 17] = [
+    "X           ",
+    "XX          ",
+    "X.X         ",
+    "X..X        ",
+    "X...X       ",
+    "X....X      ",
+    "X.....X     ",
+    "X......X    ",
+    "X.......X   ",
+    "X........X  ",
+    "X.....XXXXX ",
+    "X..X..X     ",
+    "X.X X..X    ",
+    "XX  X..X    ",
+    "X    X..X   ",
+    "     X..X   ",
+    "      XX    ",
+];
+
+pub fn arrow_sprite(outline: Color, fill: Color) -> Buffer {
+    let mut sprite = Buffer::new_cleared(dim(ARROW[0].len() as i32, ARROW.len() as i32), Color::black_alpha(0));
+    for (y, row) in ARROW.iter().enumerate() {
+        for (x, ch) in row.chars().enumerate() {
+            let color = match ch {
+                'X' => outline,
+                '.' => fill,
+                _ => continue,
+            };
+            sprite.data[y * sprite.dim.w as usize + x] = color;
+        }
+    }
+    sprite
+}
+
+pub struct Cursor {
+    sprite: Buffer,
+    hotspot: Pos,
+    pos: Pos,
+    visible: bool,
+    saved: Buffer,
+    drawn: Option<Rect>,
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cursor {
+    pub fn new() -> Self {
+        Self::custom(arrow_sprite(Color::BLACK, Color::WHITE), pos(0, 0))
+    }
+
+    pub fn custom(sprite: Buffer, hotspot: Pos) -> Self {
+        Self {
+            sprite,
+            hotspot,
+            pos: pos(0, 0),
+            visible: true,
+            saved: Buffer::default(),
+            drawn: None,
+        }
+    }
+
+    pub fn pos(&self) -> Pos {
+        self.pos
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn sprite_rect(&self) -> Rect {
+        rect(self.pos - self.hotspot, self.sprite.dim)
+    }
+
+    fn erase(&mut self, screen: &mut Buffer) -> Option<Rect> {
+        let drawn = self.drawn.take()?;
+        screen.blit(&self.saved, self.saved.area(), drawn.pos);
+        Some(drawn)
+    }
+
+    fn paint(&mut self, screen: &mut Buffer) -> Option<Rect> {
+        if !self.visible {
+            return None;
+        }
+        let full = self.sprite_rect();
+        let visible = full.area().intersection(screen.area())?.rect();
+        if self.saved.dim != visible.dim {
+            self.saved = Buffer::new(visible.dim);
+        }
+        self.saved.blit(&*screen, visible.area(), pos(0, 0));
+        let src = rect(visible.pos - full.pos, visible.dim).area();
+        screen.premultiplied_over(&self.sprite, src, visible.pos);
+        self.drawn = Some(visible);
+        Some(visible)
+    }
+
+    fn redraw(&mut self, screen: &mut Buffer, change: impl FnOnce(&mut Self)) {
+        let old = self.erase(screen);
+        change(self);
+        let new = self.paint(screen);
+        let dirty = match (old, new) {
+            (Some(a), Some(b)) => a.union(b),
+            (Some(x), None) | (None, Some(x)) => x,
+            (None, None) => return,
+        };
+        Screen::present(dirty);
+    }
+
+    pub fn move_to(&mut self, screen: &mut Buffer, at: Pos) {
+        if at == self.pos && (self.drawn.is_some() || !self.visible) {
+            return;
+        }
+        self.redraw(screen, |c| c.pos = at);
+    }
+
+    pub fn set_visible(&mut self, screen: &mut Buffer, visible: bool) {
+        self.redraw(screen, |c| c.visible = visible);
+    }
+
+    pub fn set_sprite(&mut self, screen: &mut Buffer, sprite: Buffer, hotspot: Pos) {
+        self.redraw(screen, |c| {
+            c.sprite = sprite;
+            c.hotspot = hotspot;
+        });
+    }
+
+    pub fn present(
+        &mut self, screen: &mut Buffer, render: impl FnOnce(&mut Buffer) -> Option<Rect>
+    ) -> Option<Rect> {
+        let old = self.erase(screen);
+        let rendered = render(screen);
+        let new = self.paint(screen);
+        let dirty = [old, rendered, new].into_iter().flatten().reduce(|a, b| a.union(b))?;
+        Screen::present(dirty);
+        Some(dirty)
+    }
+}
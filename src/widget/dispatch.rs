@@ -0,0 +1,104 @@
+use alloc::string::String;
+
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::widget::{FocusChange, FocusManager, NodeId, Response, Scene};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum UiEvent {
+    Click(NodeId),
+    ValueChanged(NodeId),
+    Submit(NodeId),
+    SubmitText(NodeId, String),
+    Cancel(NodeId),
+    FocusChanged(FocusChange),
+}
+
+pub struct Dispatcher<'a> {
+    pub focus: FocusManager<'a>,
+    capture: Option<NodeId>,
+}
+
+impl<'a> Default for Dispatcher<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Dispatcher<'a> {
+    pub fn new() -> Self {
+        Self {
+            focus: FocusManager::new(),
+            capture: None,
+        }
+    }
+
+    pub fn register_all(&mut self, scene: &Scene) {
+        for (order, id) in scene.walk().into_iter().enumerate() {
+            let widget = if let Some(x) = scene.widget(id) { x } else { continue; };
+            if widget.focusable() {
+                self.focus.register(id, order as i32, widget.rect());
+            } else {
+                self.focus.unregister(id);
+            }
+        }
+    }
+
+    pub fn captured(&self) -> Option<NodeId> {
+        self.capture
+    }
+
+    fn event(scene: &Scene, id: NodeId, response: Response) -> Option<UiEvent> {
+        match response {
+            Response::Clicked => Some(UiEvent::Click(id)),
+            Response::ValueChanged => Some(UiEvent::ValueChanged(id)),
+            Response::Submitted => match scene.widget(id).and_then(|x| x.text()) {
+                Some(text) => Some(UiEvent::SubmitText(id, text)),
+                None => Some(UiEvent::Submit(id)),
+            },
+            Response::Cancelled => Some(UiEvent::Cancel(id)),
+            Response::Handled | Response::Ignored => None,
+        }
+    }
+
+    fn change_focus(scene: &mut Scene, change: Option<FocusChange>) -> Option<UiEvent> {
+        let change = change?;
+        scene.apply_focus(change);
+        Some(UiEvent::FocusChanged(change))
+    }
+
+    pub fn key(&mut self, scene: &mut Scene, key: KeyCode) -> Option<UiEvent> {
+        if let Some(id) = self.focus.focused() {
+            let response = scene.send(id, |x| x.key(key));
+            if response.handled() {
+                return Self::event(scene, id, response);
+            }
+        }
+        Self::change_focus(scene, self.focus.handle_key(key))
+    }
+
+    pub fn pointer(&mut self, scene: &mut Scene, event: PointerEvent) -> Option<UiEvent> {
+        if let Some(id) = self.capture {
+            let response = scene.send(id, |x| x.pointer(event));
+            if let PointerEvent::Up(_, PointerButton::Left) = event {
+                self.capture = None;
+            }
+            return Self::event(scene, id, response);
+        }
+        let mut target = scene.hit_test(event.pos());
+        while let Some(id) = target {
+            let response = scene.send(id, |x| x.pointer(event));
+            if response.handled() {
+                let mut focus = None;
+                if let PointerEvent::Down(_, PointerButton::Left) = event {
+                    self.capture = Some(id);
+                    if scene.widget(id).map_or(false, |x| x.focusable()) {
+                        focus = Self::change_focus(scene, self.focus.focus_pointer(id));
+                    }
+                }
+                return Self::event(scene, id, response).or(focus);
+            }
+            target = scene.parent(id).filter(|x| *x != Scene::ROOT);
+        }
+        None
+    }
+}
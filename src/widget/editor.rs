@@ -0,0 +1,357 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{area, dim, pos, rect, Buffer, Color, Math, Pos, Rect, View};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::text::TextLayout;
+use crate::theme::Theme;
+use crate::widget::{Response, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TextEditorStyle {
+    pub bg: Color,
+    pub fg: Color,
+    pub border: Color,
+    pub border_focused: Color,
+    pub selection: Color,
+    pub caret: Color,
+    pub scrollbar: Color,
+    pub padding: i32,
+}
+
+impl From<&Theme> for TextEditorStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            bg: p.input,
+            fg: p.fg,
+            border: p.border,
+            border_focused: theme.focus.color,
+            selection: p.accent,
+            caret: p.fg_strong,
+            scrollbar: p.fg_disabled,
+            padding: m.padding,
+        }
+    }
+}
+
+impl Default for TextEditorStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub struct TextEditor<'a> {
+    pub rect: Rect,
+    pub style: TextEditorStyle,
+    pub line_height: i32,
+    pub word_wrap: bool,
+    pub read_only: bool,
+    chars: Vec<char>,
+    layout: TextLayout,
+    caret: usize,
+    anchor: Option<usize>,
+    scroll: Pos,
+    blink: f32,
+    focused: bool,
+    dragging: bool,
+    modified: bool,
+    font: &'a Font,
+}
+
+impl<'a> TextEditor<'a> {
+    pub fn new(rect: Rect, font: &'a Font, line_height: i32) -> Self {
+        let mut editor = Self {
+            rect,
+            style: TextEditorStyle::default(),
+            line_height,
+            word_wrap: true,
+            read_only: false,
+            chars: Vec::new(),
+            layout: TextLayout::default(),
+            caret: 0,
+            anchor: None,
+            scroll: pos(0, 0),
+            blink: 0.0,
+            focused: false,
+            dragging: false,
+            modified: false,
+            font,
+        };
+        editor.relayout();
+        editor
+    }
+
+    pub fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.chars = text.chars().collect();
+        self.caret = 0;
+        self.anchor = None;
+        self.modified = false;
+        self.relayout();
+    }
+
+    pub fn modified(&self) -> bool {
+        self.modified
+    }
+
+    pub fn clear_modified(&mut self) {
+        self.modified = false;
+    }
+
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.anchor?;
+        if anchor == self.caret {
+            return None;
+        }
+        Some((anchor.min(self.caret), anchor.max(self.caret)))
+    }
+
+    pub fn set_selection(&mut self, start: usize, end: usize) {
+        self.anchor = Some(start.min(self.chars.len()));
+        self.caret = end.min(self.chars.len());
+        self.ensure_caret_visible();
+    }
+
+    pub fn select_all(&mut self) {
+        self.set_selection(0, self.chars.len());
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        Some(self.chars[start..end].iter().collect())
+    }
+
+    fn inner(&self) -> Rect {
+        let p = self.style.padding;
+        rect(self.rect.pos + pos(p, p), dim(self.rect.dim.w - p * 2, self.rect.dim.h - p * 2))
+    }
+
+    fn relayout(&mut self) {
+        let width = if self.word_wrap { Some(self.inner().dim.w) } else { None };
+        let text: String = self.chars.iter().collect();
+        self.layout = TextLayout::new(self.font, &text, width, self.line_height);
+        self.ensure_caret_visible();
+    }
+
+    fn ensure_caret_visible(&mut self) {
+        let inner = self.inner().dim;
+        let caret = self.layout.caret_pos(self.caret);
+        if caret.y < self.scroll.y {
+            self.scroll.y = caret.y;
+        } else if caret.y + self.line_height > self.scroll.y + inner.h {
+            self.scroll.y = caret.y + self.line_height - inner.h;
+        }
+        if caret.x < self.scroll.x {
+            self.scroll.x = caret.x;
+        } else if caret.x >= self.scroll.x + inner.w {
+            self.scroll.x = caret.x - inner.w + 1;
+        }
+        let content = self.layout.dim();
+        self.scroll.y = self.scroll.y.min((content.h - inner.h).max(0)).max(0);
+        self.scroll.x = self.scroll.x.max(0);
+    }
+
+    fn move_caret(&mut self, caret: usize) {
+        self.caret = caret.min(self.chars.len());
+        self.anchor = None;
+        self.blink = 0.0;
+        self.ensure_caret_visible();
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        let (start, end) = if let Some(x) = self.selection() { x } else { return false; };
+        self.chars.drain(start..end);
+        self.caret = start;
+        self.anchor = None;
+        true
+    }
+
+    pub fn insert(&mut self, text: &str) -> Response {
+        if self.read_only {
+            return Response::Ignored;
+        }
+        self.delete_selection();
+        for ch in text.chars() {
+            self.chars.insert(self.caret, ch);
+            self.caret += 1;
+        }
+        self.modified = true;
+        self.blink = 0.0;
+        self.relayout();
+        Response::ValueChanged
+    }
+
+    fn vertical(&mut self, lines: i32) {
+        let caret = self.layout.caret_pos(self.caret);
+        let target = pos(caret.x, caret.y + lines * self.line_height);
+        let index = if target.y < 0 {
+            0
+        } else if target.y >= self.layout.lines.len() as i32 * self.line_height {
+            self.chars.len()
+        } else {
+            self.layout.index_at(target)
+        };
+        self.move_caret(index);
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.blink = Math::fract(self.blink + dt);
+    }
+
+    fn edit(&mut self, key: KeyCode) -> Response {
+        let page = (self.inner().dim.h / self.line_height).max(1);
+        match key {
+            KeyCode::Escape => return Response::Cancelled,
+            KeyCode::Enter => return self.insert("\n"),
+            KeyCode::Tab => return self.insert("    "),
+            KeyCode::Char(ch) if !ch.is_control() => {
+                let mut buf = [0; 4];
+                return self.insert(ch.encode_utf8(&mut buf));
+            }
+            KeyCode::Backspace | KeyCode::Delete if !self.read_only => {
+                if !self.delete_selection() {
+                    if key == KeyCode::Backspace && self.caret > 0 {
+                        self.caret -= 1;
+                        self.chars.remove(self.caret);
+                    } else if key == KeyCode::Delete && self.caret < self.chars.len() {
+                        self.chars.remove(self.caret);
+                    } else {
+                        return Response::Handled;
+                    }
+                }
+                self.modified = true;
+                self.relayout();
+                return Response::ValueChanged;
+            }
+            KeyCode::Left => match self.selection() {
+                Some((start, _)) => self.move_caret(start),
+                None => self.move_caret(self.caret.saturating_sub(1)),
+            },
+            KeyCode::Right => match self.selection() {
+                Some((_, end)) => self.move_caret(end),
+                None => self.move_caret(self.caret + 1),
+            },
+            KeyCode::Up => self.vertical(-1),
+            KeyCode::Down => self.vertical(1),
+            KeyCode::PageUp => self.vertical(-page),
+            KeyCode::PageDown => self.vertical(page),
+            KeyCode::Home => self.move_caret(self.layout.line_start(self.caret)),
+            KeyCode::End => self.move_caret(self.layout.line_end(self.caret)),
+            _ => return Response::Ignored,
+        }
+        Response::Handled
+    }
+
+    fn index_at(&self, loc: Pos) -> usize {
+        self.layout.index_at(loc - self.inner().pos + self.scroll)
+    }
+
+    fn draw_selection(&self, view: &mut View, start: usize, end: usize) {
+        let first = self.layout.line_of(start);
+        let last = self.layout.line_of(end);
+        for n in first..=last {
+            let line = &self.layout.lines[n];
+            let x1 = if n == first { self.layout.caret_pos(start).x } else { 0 };
+            let x2 = if n == last {
+                self.layout.caret_pos(end).x
+            } else {
+                line.width + self.line_height / 3
+            };
+            let y = line.y - self.scroll.y;
+            view.fill_over(
+                area(pos(x1 - self.scroll.x, y), pos(x2 - self.scroll.x, y + self.line_height)),
+                self.style.selection,
+            );
+        }
+    }
+}
+
+impl<'a> Widget for TextEditor<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.relayout();
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        let outer = self.rect.area();
+        buffer.fill_over(outer, s.bg);
+        buffer.stroke(outer, 1, if self.focused { s.border_focused } else { s.border });
+        let inner = self.inner();
+        let content = self.layout.dim();
+        if content.h > inner.dim.h {
+            let h = (inner.dim.h as i64 * inner.dim.h as i64 / content.h as i64) as i32;
+            let y = (self.scroll.y as i64 * (inner.dim.h - h) as i64
+                / (content.h - inner.dim.h) as i64) as i32;
+            let bar = rect(pos(self.rect.pos.x + self.rect.dim.w - 3, inner.pos.y + y), dim(2, h));
+            buffer.fill_over(bar.area(), s.scrollbar);
+        }
+        let mut view = View::new(buffer, inner);
+        if let Some((start, end)) = self.selection() {
+            self.draw_selection(&mut view, start, end);
+        }
+        self.layout.draw_in(&mut view, pos(0, 0) - self.scroll, self.font, s.fg);
+        if self.focused && !self.read_only && self.blink < 0.5 {
+            let caret = self.layout.caret_pos(self.caret) - self.scroll;
+            view.fill_over(rect(caret, dim(1, self.line_height)).area(), s.caret);
+        }
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        self.edit(key)
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        match event {
+            PointerEvent::Down(loc, PointerButton::Left) if self.rect.contains(loc) => {
+                self.move_caret(self.index_at(loc));
+                self.anchor = Some(self.caret);
+                self.dragging = true;
+                Response::Handled
+            }
+            PointerEvent::Move(loc) if self.dragging => {
+                self.caret = self.index_at(loc);
+                self.ensure_caret_visible();
+                Response::Handled
+            }
+            PointerEvent::Up(_, PointerButton::Left) if self.dragging => {
+                self.dragging = false;
+                Response::Handled
+            }
+            PointerEvent::Wheel(loc, delta) if self.rect.contains(loc) => {
+                let max = (self.layout.dim().h - self.inner().dim.h).max(0);
+                self.scroll.y = (self.scroll.y - delta * self.line_height * 3).clamp(0, max);
+                Response::Handled
+            }
+            _ => Response::Ignored,
+        }
+    }
+
+    fn text(&self) -> Option<String> {
+        Some(self.chars.iter().collect())
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.blink = 0.0;
+    }
+}
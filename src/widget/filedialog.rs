@@ -0,0 +1,399 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use baked_font::Font;
+use uefi::Handle;
+
+use crate::fs::{self, EfiPathBuf, Volume};
+use crate::gfx::{dim, pos, rect, Buffer, Color, Rect, Screen, View};
+use crate::input::{self, KeyCode, PointerButton, PointerEvent};
+use crate::theme::Theme;
+use crate::widget::{ListView, Response, TextBox, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FileDialogMode {
+    Open,
+    Save,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FileDialogStyle {
+    pub dim: Color,
+    pub bg: Color,
+    pub border: Color,
+    pub title: Color,
+    pub location: Color,
+    pub error: Color,
+    pub line_height: i32,
+    pub padding: i32,
+}
+
+impl From<&Theme> for FileDialogStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            dim: p.scrim,
+            bg: p.surface,
+            border: p.muted,
+            title: p.fg_strong,
+            location: p.fg_dim,
+            error: p.error,
+            line_height: m.row_height,
+            padding: m.dialog_padding,
+        }
+    }
+}
+
+impl Default for FileDialogStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PickedFile {
+    pub volume: Handle,
+    pub path: String,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum EntryKind {
+    Volume(usize),
+    Parent,
+    Dir,
+    File,
+}
+
+struct Entry {
+    name: String,
+    kind: EntryKind,
+}
+
+pub struct FileDialog<'a> {
+    pub rect: Rect,
+    pub mode: FileDialogMode,
+    pub style: FileDialogStyle,
+    title: String,
+    filter: Vec<String>,
+    volumes: Vec<Volume>,
+    volume: Option<usize>,
+    dir: Vec<String>,
+    entries: Vec<Entry>,
+    list: ListView<'a>,
+    name: TextBox<'a>,
+    name_focused: bool,
+    error: Option<String>,
+    focused: bool,
+    font: &'a Font,
+}
+
+impl<'a> FileDialog<'a> {
+    pub fn new(rect: Rect, font: &'a Font, mode: FileDialogMode, title: &str) -> Self {
+        let style = FileDialogStyle::default();
+        let mut dialog = Self {
+            rect,
+            mode,
+            style,
+            title: String::from(title),
+            filter: Vec::new(),
+            volumes: fs::volumes(),
+            volume: None,
+            dir: Vec::new(),
+            entries: Vec::new(),
+            list: ListView::new(rect, font, style.line_height, Vec::new()),
+            name: TextBox::new(rect, font),
+            name_focused: false,
+            error: None,
+            focused: false,
+            font,
+        };
+        dialog.layout();
+        dialog.refresh();
+        dialog
+    }
+
+    pub fn filter(mut self, extensions: &[&str]) -> Self {
+        self.filter = extensions.iter()
+            .map(|x| x.trim_start_matches('.').to_ascii_lowercase())
+            .collect();
+        self.refresh();
+        self
+    }
+
+    pub fn location(&self) -> String {
+        match self.volume {
+            Some(n) => format!("fs{}:\\{}", n, self.dir.join("\\")),
+            None => String::from("Volumes"),
+        }
+    }
+
+    fn layout(&mut self) {
+        let s = &self.style;
+        let p = s.padding;
+        let lh = s.line_height;
+        let name_h = if self.mode == FileDialogMode::Save { lh + 8 + p } else { 0 };
+        let top = p + lh * 2;
+        self.list.set_rect(rect(
+            self.rect.pos + pos(p, top),
+            dim(self.rect.dim.w - p * 2, (self.rect.dim.h - top - p - name_h).max(lh)),
+        ));
+        self.name.set_rect(rect(
+            self.rect.pos + pos(p, self.rect.dim.h - p - lh - 8),
+            dim(self.rect.dim.w - p * 2, lh + 8),
+        ));
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        if self.filter.is_empty() {
+            return true;
+        }
+        match name.rsplit_once('.') {
+            Some((_, ext)) => self.filter.iter().any(|x| x.eq_ignore_ascii_case(ext)),
+            None => false,
+        }
+    }
+
+    fn refresh(&mut self) -> bool {
+        let mut entries = Vec::new();
+        match self.volume {
+            None => {
+                for n in 0..self.volumes.len() {
+                    entries.push(Entry { name: format!("fs{}:", n), kind: EntryKind::Volume(n) });
+                }
+            }
+            Some(n) => {
+                let mut path = EfiPathBuf::root();
+                for part in self.dir.iter() {
+                    path.push(part.as_str());
+                }
+                let dir = if let Ok(x) = fs::read_dir(self.volumes[n].handle, &path) { x } else {
+                    self.error = Some(format!("Cannot open {}", self.location()));
+                    return false;
+                };
+                entries.push(Entry { name: String::from(".."), kind: EntryKind::Parent });
+                let mut found: Vec<Entry> = dir
+                    .filter(|x| x.is_dir() || self.matches(&x.name))
+                    .map(|x| Entry {
+                        kind: if x.is_dir() { EntryKind::Dir } else { EntryKind::File },
+                        name: x.name,
+                    })
+                    .collect();
+                found.sort_by(|a, b| (b.kind == EntryKind::Dir).cmp(&(a.kind == EntryKind::Dir))
+                    .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+                entries.extend(found);
+            }
+        }
+        self.list.set_items(entries.iter().map(|x| match x.kind {
+            EntryKind::Dir => format!("{}\\", x.name),
+            _ => x.name.clone(),
+        }).collect());
+        self.entries = entries;
+        self.error = None;
+        true
+    }
+
+    fn reselect(&mut self, name: &str) {
+        if let Some(index) = self.entries.iter().position(|x| x.name == name) {
+            self.list.select(index);
+        }
+    }
+
+    fn go_up(&mut self) -> Response {
+        if let Some(name) = self.dir.pop() {
+            self.refresh();
+            self.reselect(&name);
+        } else if let Some(n) = self.volume.take() {
+            self.refresh();
+            self.list.select(n);
+        }
+        Response::Handled
+    }
+
+    fn path_of(&self, name: &str) -> String {
+        let mut path = String::new();
+        for part in self.dir.iter().map(|x| x.as_str()).chain(core::iter::once(name)) {
+            path.push('\\');
+            path.push_str(part);
+        }
+        path
+    }
+
+    pub fn picked(&self) -> Option<PickedFile> {
+        let volume = self.volumes[self.volume?].handle;
+        match self.mode {
+            FileDialogMode::Open => {
+                let entry = &self.entries[self.list.selected()?];
+                if entry.kind != EntryKind::File {
+                    return None;
+                }
+                Some(PickedFile { volume, path: self.path_of(&entry.name) })
+            }
+            FileDialogMode::Save => {
+                let mut name = self.name.text();
+                if name.is_empty() {
+                    return None;
+                }
+                if !name.contains('.') {
+                    if let Some(ext) = self.filter.first() {
+                        name.push('.');
+                        name.push_str(ext);
+                    }
+                }
+                Some(PickedFile { volume, path: self.path_of(&name) })
+            }
+        }
+    }
+
+    fn activate(&mut self) -> Response {
+        let index = if let Some(x) = self.list.selected() { x } else { return Response::Handled; };
+        let name = self.entries[index].name.clone();
+        match self.entries[index].kind {
+            EntryKind::Volume(n) => {
+                self.volume = Some(n);
+                self.dir.clear();
+                if !self.refresh() {
+                    self.volume = None;
+                }
+                Response::Handled
+            }
+            EntryKind::Parent => self.go_up(),
+            EntryKind::Dir => {
+                self.dir.push(name);
+                if !self.refresh() {
+                    self.dir.pop();
+                }
+                Response::Handled
+            }
+            EntryKind::File => match self.mode {
+                FileDialogMode::Open => Response::Submitted,
+                FileDialogMode::Save => {
+                    self.name.set_text(&name);
+                    self.set_name_focused(true);
+                    Response::ValueChanged
+                }
+            },
+        }
+    }
+
+    fn set_name_focused(&mut self, name_focused: bool) {
+        self.name_focused = name_focused && self.mode == FileDialogMode::Save;
+        self.list.set_focused(self.focused && !self.name_focused);
+        self.name.set_focused(self.focused && self.name_focused);
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.list.tick(dt);
+        self.name.tick(dt);
+    }
+
+    pub fn run(&mut self, screen: &mut Buffer) -> crate::Result<Option<PickedFile>> {
+        let saved = screen.clone();
+        screen.fill_over(screen.area(), self.style.dim);
+        let backdrop = screen.clone();
+        self.set_focused(true);
+        let mut dirty = screen.rect();
+
+        let result = loop {
+            screen.blit(&backdrop, self.rect.area(), self.rect.pos);
+            self.draw(screen);
+            Screen::present(dirty);
+            dirty = self.rect;
+
+            let key = match input::wait_key() {
+                Ok(x) => x,
+                Err(e) => break Err(e),
+            };
+            match self.key(key) {
+                Response::Submitted => {
+                    if let Some(picked) = self.picked() {
+                        break Ok(Some(picked));
+                    }
+                }
+                Response::Cancelled => break Ok(None),
+                _ => {}
+            }
+        };
+
+        *screen = saved;
+        Screen::present(screen.rect());
+        result
+    }
+}
+
+impl<'a> Widget for FileDialog<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.layout();
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        let p = s.padding;
+        buffer.fill_over(self.rect.area(), s.bg);
+        buffer.stroke(self.rect.area(), 1, s.border);
+        let header = rect(self.rect.pos + pos(p, p), dim(self.rect.dim.w - p * 2, s.line_height * 2));
+        let mut view = View::new(buffer, header);
+        view.draw_text(pos(0, 0), self.font, &self.title, s.title);
+        match &self.error {
+            Some(error) => view.draw_text(pos(0, s.line_height), self.font, error, s.error),
+            None => view.draw_text(pos(0, s.line_height), self.font, &self.location(), s.location),
+        }
+        self.list.draw(buffer);
+        if self.mode == FileDialogMode::Save {
+            self.name.draw(buffer);
+        }
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        match key {
+            KeyCode::Escape => return Response::Cancelled,
+            KeyCode::Tab if self.mode == FileDialogMode::Save => {
+                self.set_name_focused(!self.name_focused);
+                return Response::Handled;
+            }
+            _ => {}
+        }
+        if self.name_focused {
+            return self.name.key(key);
+        }
+        match key {
+            KeyCode::Backspace => self.go_up(),
+            _ => match self.list.key(key) {
+                Response::Clicked => self.activate(),
+                response => response,
+            },
+        }
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        if let PointerEvent::Down(loc, PointerButton::Left) = event {
+            if self.mode == FileDialogMode::Save && self.name.rect.contains(loc) {
+                self.set_name_focused(true);
+            } else if self.list.rect.contains(loc) {
+                self.set_name_focused(false);
+            }
+        }
+        if self.name_focused {
+            return self.name.pointer(event);
+        }
+        match self.list.pointer(event) {
+            Response::Clicked => self.activate(),
+            response => response,
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.set_name_focused(self.name_focused);
+    }
+}
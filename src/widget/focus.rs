@@ -0,0 +1,232 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::gfx::{pos, Buffer, Pos, Rect};
+use crate::input::KeyCode;
+use crate::theme::Theme;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FocusChange {
+    pub old: Option<usize>,
+    pub new: Option<usize>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+struct FocusEntry {
+    id: usize,
+    order: i32,
+    rect: Rect,
+    enabled: bool,
+}
+
+pub type FocusHook<'a> = Box<dyn FnMut(FocusChange, bool) + 'a>;
+
+pub struct FocusManager<'a> {
+    pub wrap: bool,
+    pub arrows: bool,
+    pub on_change: Option<FocusHook<'a>>,
+    entries: Vec<FocusEntry>,
+    current: Option<usize>,
+    visible: bool,
+}
+
+fn center(r: Rect) -> Pos {
+    pos(r.pos.x + r.dim.w / 2, r.pos.y + r.dim.h / 2)
+}
+
+impl<'a> Default for FocusManager<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> FocusManager<'a> {
+    pub fn new() -> Self {
+        Self {
+            wrap: true,
+            arrows: true,
+            on_change: None,
+            entries: Vec::new(),
+            current: None,
+            visible: false,
+        }
+    }
+
+    pub fn register(&mut self, id: usize, order: i32, rect: Rect) {
+        self.entries.retain(|x| x.id != id);
+        let at = self.entries.iter().position(|x| x.order > order).unwrap_or(self.entries.len());
+        self.entries.insert(at, FocusEntry { id, order, rect, enabled: true });
+    }
+
+    pub fn unregister(&mut self, id: usize) -> Option<FocusChange> {
+        self.entries.retain(|x| x.id != id);
+        if self.current == Some(id) {
+            return self.set(None, self.visible);
+        }
+        None
+    }
+
+    pub fn set_rect(&mut self, id: usize, rect: Rect) {
+        if let Some(entry) = self.entries.iter_mut().find(|x| x.id == id) {
+            entry.rect = rect;
+        }
+    }
+
+    pub fn set_enabled(&mut self, id: usize, enabled: bool) -> Option<FocusChange> {
+        if let Some(entry) = self.entries.iter_mut().find(|x| x.id == id) {
+            entry.enabled = enabled;
+        }
+        if !enabled && self.current == Some(id) {
+            return self.next();
+        }
+        None
+    }
+
+    pub fn focused(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn is_focused(&self, id: usize) -> bool {
+        self.current == Some(id)
+    }
+
+    pub fn focus_visible(&self) -> bool {
+        self.visible && self.current.is_some()
+    }
+
+    fn set(&mut self, new: Option<usize>, visible: bool) -> Option<FocusChange> {
+        self.visible = visible;
+        if new == self.current {
+            return None;
+        }
+        let change = FocusChange { old: self.current, new };
+        self.current = new;
+        if let Some(hook) = self.on_change.as_mut() {
+            hook(change, visible);
+        }
+        Some(change)
+    }
+
+    pub fn focus(&mut self, id: usize) -> Option<FocusChange> {
+        if !self.entries.iter().any(|x| x.id == id && x.enabled) {
+            return None;
+        }
+        self.set(Some(id), self.visible)
+    }
+
+    pub fn focus_pointer(&mut self, id: usize) -> Option<FocusChange> {
+        if !self.entries.iter().any(|x| x.id == id && x.enabled) {
+            return None;
+        }
+        self.set(Some(id), false)
+    }
+
+    pub fn clear(&mut self) -> Option<FocusChange> {
+        self.set(None, false)
+    }
+
+    fn position(&self) -> Option<usize> {
+        self.entries.iter().position(|x| Some(x.id) == self.current)
+    }
+
+    fn step(&mut self, forward: bool) -> Option<FocusChange> {
+        let n = self.entries.len();
+        let start = self.position();
+        for k in 1..=n {
+            let index = match start {
+                Some(at) if forward => {
+                    if !self.wrap && at + k >= n {
+                        return None;
+                    }
+                    (at + k) % n
+                }
+                Some(at) => {
+                    if !self.wrap && k > at {
+                        return None;
+                    }
+                    (at + n - k) % n
+                }
+                None if forward => k - 1,
+                None => n - k,
+            };
+            let entry = self.entries[index];
+            if entry.enabled {
+                return self.set(Some(entry.id), true);
+            }
+        }
+        None
+    }
+
+    pub fn next(&mut self) -> Option<FocusChange> {
+        self.step(true)
+    }
+
+    pub fn prev(&mut self) -> Option<FocusChange> {
+        self.step(false)
+    }
+
+    pub fn first(&mut self) -> Option<FocusChange> {
+        let id = self.entries.iter().find(|x| x.enabled)?.id;
+        self.set(Some(id), true)
+    }
+
+    pub fn last(&mut self) -> Option<FocusChange> {
+        let id = self.entries.iter().rev().find(|x| x.enabled)?.id;
+        self.set(Some(id), true)
+    }
+
+    pub fn move_dir(&mut self, dir: Direction) -> Option<FocusChange> {
+        let from = if let Some(at) = self.position() { self.entries[at] } else {
+            return self.first();
+        };
+        let origin = center(from.rect);
+        let best = self.entries.iter()
+            .filter(|x| x.enabled && x.id != from.id)
+            .filter_map(|x| {
+                let c = center(x.rect);
+                let (dx, dy) = (c.x - origin.x, c.y - origin.y);
+                let (main, cross) = match dir {
+                    Direction::Up => (-dy, dx),
+                    Direction::Down => (dy, dx),
+                    Direction::Left => (-dx, dy),
+                    Direction::Right => (dx, dy),
+                };
+                if main <= 0 {
+                    return None;
+                }
+                let cross = if cross < 0 { -cross } else { cross };
+                Some((main as i64 + cross as i64 * 2, x.id))
+            })
+            .min_by_key(|x| x.0)?;
+        self.set(Some(best.1), true)
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) -> Option<FocusChange> {
+        match key {
+            KeyCode::Tab => self.next(),
+            KeyCode::BackTab => self.prev(),
+            KeyCode::Up if self.arrows => self.move_dir(Direction::Up),
+            KeyCode::Down if self.arrows => self.move_dir(Direction::Down),
+            KeyCode::Left if self.arrows => self.move_dir(Direction::Left),
+            KeyCode::Right if self.arrows => self.move_dir(Direction::Right),
+            _ => None,
+        }
+    }
+
+    pub fn draw_ring(&self, buffer: &mut Buffer) {
+        if !self.focus_visible() {
+            return;
+        }
+        if let Some(at) = self.position() {
+            Theme::current().focus.draw(buffer, self.entries[at].rect);
+        }
+    }
+}
@@ -0,0 +1,474 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, Buffer, Color, Rect, Screen};
+use crate::input::{self, KeyCode};
+use crate::theme::Theme;
+use crate::widget::{Response, TextBox, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FormStyle {
+    pub bg: Color,
+    pub title: Color,
+    pub label: Color,
+    pub value: Color,
+    pub selected_bg: Color,
+    pub selected_fg: Color,
+    pub changed: Color,
+    pub hint: Color,
+    pub line: Color,
+    pub row_height: i32,
+    pub padding: i32,
+}
+
+impl From<&Theme> for FormStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            bg: p.surface,
+            title: p.fg_strong,
+            label: p.fg,
+            value: p.fg_strong,
+            selected_bg: p.accent,
+            selected_fg: p.accent_fg,
+            changed: theme.focus.color,
+            hint: p.fg_dim,
+            line: p.line,
+            row_height: m.row_height + 4,
+            padding: m.dialog_padding,
+        }
+    }
+}
+
+impl Default for FormStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum FieldKind {
+    Toggle,
+    Choice(Vec<String>),
+    Text(Option<usize>),
+    Number(i64, i64, i64),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum FieldValue {
+    Toggle(bool),
+    Choice(usize),
+    Text(String),
+    Number(i64),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FormResult {
+    Applied,
+    Cancelled,
+}
+
+struct FormRow {
+    key: String,
+    label: String,
+    kind: FieldKind,
+    value: FieldValue,
+    original: FieldValue,
+    enabled: bool,
+}
+
+impl FormRow {
+    fn display(&self) -> String {
+        match (&self.kind, &self.value) {
+            (_, FieldValue::Toggle(on)) => String::from(if *on { "[Enabled]" } else { "[Disabled]" }),
+            (FieldKind::Choice(options), FieldValue::Choice(index)) => {
+                format!("<{}>", options.get(*index).map_or("", |x| x.as_str()))
+            }
+            (_, FieldValue::Choice(index)) => format!("<{}>", index),
+            (_, FieldValue::Text(text)) => text.clone(),
+            (_, FieldValue::Number(n)) => format!("{}", n),
+        }
+    }
+
+    fn adjust(&mut self, delta: i64) -> bool {
+        let next = match (&self.kind, &self.value) {
+            (_, FieldValue::Toggle(on)) => FieldValue::Toggle(!on),
+            (FieldKind::Choice(options), FieldValue::Choice(index)) if !options.is_empty() => {
+                let n = options.len() as i64;
+                FieldValue::Choice((((*index as i64 + delta) % n + n) % n) as usize)
+            }
+            (FieldKind::Number(min, max, step), FieldValue::Number(n)) => {
+                FieldValue::Number((n + delta * step).clamp(*min, *max))
+            }
+            _ => return false,
+        };
+        let changed = next != self.value;
+        self.value = next;
+        changed
+    }
+
+    fn commit_text(&mut self, text: String) -> bool {
+        let next = match self.kind {
+            FieldKind::Number(min, max, _) => match text.trim().parse::<i64>() {
+                Ok(n) => FieldValue::Number(n.clamp(min, max)),
+                Err(_) => return false,
+            },
+            FieldKind::Text(_) => FieldValue::Text(text),
+            _ => return false,
+        };
+        let changed = next != self.value;
+        self.value = next;
+        changed
+    }
+}
+
+pub struct Form<'a> {
+    pub rect: Rect,
+    pub style: FormStyle,
+    pub title: String,
+    rows: Vec<FormRow>,
+    selected: usize,
+    scroll: usize,
+    focused: bool,
+    editor: Option<TextBox<'a>>,
+    font: &'a Font,
+}
+
+impl<'a> Form<'a> {
+    pub fn new(rect: Rect, font: &'a Font, title: &str) -> Self {
+        Self {
+            rect,
+            style: FormStyle::default(),
+            title: title.to_string(),
+            rows: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            focused: false,
+            editor: None,
+            font,
+        }
+    }
+
+    pub fn style(mut self, style: FormStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn push(mut self, key: &str, label: &str, kind: FieldKind, value: FieldValue) -> Self {
+        self.rows.push(FormRow {
+            key: key.to_string(),
+            label: label.to_string(),
+            kind,
+            original: value.clone(),
+            value,
+            enabled: true,
+        });
+        self
+    }
+
+    pub fn toggle(self, key: &str, label: &str, value: bool) -> Self {
+        self.push(key, label, FieldKind::Toggle, FieldValue::Toggle(value))
+    }
+
+    pub fn choice(self, key: &str, label: &str, options: &[&str], value: usize) -> Self {
+        let options = options.iter().map(|x| x.to_string()).collect();
+        self.push(key, label, FieldKind::Choice(options), FieldValue::Choice(value))
+    }
+
+    pub fn text_field(self, key: &str, label: &str, value: &str, max_len: Option<usize>) -> Self {
+        self.push(key, label, FieldKind::Text(max_len), FieldValue::Text(value.to_string()))
+    }
+
+    pub fn number(self, key: &str, label: &str, value: i64, min: i64, max: i64, step: i64) -> Self {
+        let value = value.clamp(min, max);
+        self.push(key, label, FieldKind::Number(min, max, step.max(1)), FieldValue::Number(value))
+    }
+
+    pub fn set_enabled(&mut self, key: &str, enabled: bool) {
+        if let Some(row) = self.rows.iter_mut().find(|x| x.key == key) {
+            row.enabled = enabled;
+        }
+    }
+
+    pub fn value(&self, key: &str) -> Option<&FieldValue> {
+        self.rows.iter().find(|x| x.key == key).map(|x| &x.value)
+    }
+
+    pub fn set_value(&mut self, key: &str, value: FieldValue) {
+        if let Some(row) = self.rows.iter_mut().find(|x| x.key == key) {
+            row.value = value;
+        }
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.rows.iter().any(|x| x.value != x.original)
+    }
+
+    pub fn changes(&self) -> Vec<(&str, &FieldValue)> {
+        self.rows.iter()
+            .filter(|x| x.value != x.original)
+            .map(|x| (x.key.as_str(), &x.value))
+            .collect()
+    }
+
+    pub fn apply(&mut self) {
+        for row in self.rows.iter_mut() {
+            row.original = row.value.clone();
+        }
+    }
+
+    pub fn revert(&mut self) {
+        self.editor = None;
+        for row in self.rows.iter_mut() {
+            row.value = row.original.clone();
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editor.is_some()
+    }
+
+    fn header_height(&self) -> i32 {
+        self.style.row_height + self.style.padding
+    }
+
+    fn visible_rows(&self) -> usize {
+        let h = self.rect.dim.h - self.header_height() * 2;
+        (h / self.style.row_height.max(1)).max(1) as usize
+    }
+
+    fn label_width(&self) -> i32 {
+        self.rows.iter().map(|x| gfx::text_dim(self.font, &x.label).w).max().unwrap_or(0)
+            + self.style.padding * 2
+    }
+
+    fn row_rect(&self, index: usize) -> Rect {
+        let s = &self.style;
+        let y = self.header_height() + (index - self.scroll) as i32 * s.row_height;
+        rect(self.rect.pos + pos(s.padding, y), dim(self.rect.dim.w - s.padding * 2, s.row_height))
+    }
+
+    fn value_rect(&self, index: usize) -> Rect {
+        let row = self.row_rect(index);
+        let x = self.label_width();
+        rect(row.pos + pos(x, 2), dim(row.dim.w - x - self.style.padding, row.dim.h - 4))
+    }
+
+    fn select(&mut self, index: usize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        self.selected = index.min(self.rows.len() - 1);
+        let visible = self.visible_rows();
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + visible {
+            self.scroll = self.selected + 1 - visible;
+        }
+    }
+
+    fn step(&mut self, forward: bool, count: usize) {
+        let mut index = self.selected;
+        let mut moved = 0;
+        while moved < count {
+            let next = if forward { index + 1 } else { index.wrapping_sub(1) };
+            let row = if let Some(x) = self.rows.get(next) { x } else { break; };
+            index = next;
+            if row.enabled {
+                moved += 1;
+                self.select(index);
+            }
+        }
+    }
+
+    fn begin_edit(&mut self) -> Response {
+        let row = &self.rows[self.selected];
+        let (text, max_len) = match (&row.kind, &row.value) {
+            (FieldKind::Text(max_len), FieldValue::Text(text)) => (text.clone(), *max_len),
+            (FieldKind::Number(..), FieldValue::Number(n)) => (format!("{}", n), Some(20)),
+            _ => return Response::Ignored,
+        };
+        let mut editor = TextBox::new(self.value_rect(self.selected), self.font);
+        editor.max_len = max_len;
+        editor.set_text(&text);
+        editor.select_all();
+        editor.set_focused(true);
+        self.editor = Some(editor);
+        Response::Handled
+    }
+
+    fn edit(&mut self, key: KeyCode) -> Response {
+        let editor = if let Some(x) = self.editor.as_mut() { x } else { return Response::Ignored; };
+        match editor.key(key) {
+            Response::Submitted => {
+                let text = editor.text();
+                self.editor = None;
+                if self.rows[self.selected].commit_text(text) {
+                    Response::ValueChanged
+                } else {
+                    Response::Handled
+                }
+            }
+            Response::Cancelled => {
+                self.editor = None;
+                Response::Handled
+            }
+            _ => Response::Handled,
+        }
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        if let Some(editor) = self.editor.as_mut() {
+            editor.tick(dt);
+        }
+    }
+
+    pub fn run(&mut self, screen: &mut Buffer) -> crate::Result<FormResult> {
+        self.set_focused(true);
+        let result = loop {
+            self.draw(screen);
+            Screen::present(self.rect);
+            let key = match input::wait_key() {
+                Ok(x) => x,
+                Err(e) => break Err(e),
+            };
+            match self.key(key) {
+                Response::Submitted => break Ok(FormResult::Applied),
+                Response::Cancelled => break Ok(FormResult::Cancelled),
+                _ => {}
+            }
+        };
+        match result {
+            Ok(FormResult::Applied) => self.apply(),
+            Ok(FormResult::Cancelled) | Err(_) => self.revert(),
+        }
+        result
+    }
+}
+
+impl<'a> Widget for Form<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.select(self.selected);
+        let r = self.value_rect(self.selected);
+        if let Some(editor) = self.editor.as_mut() {
+            editor.set_rect(r);
+        }
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        buffer.fill(self.rect.area(), s.bg);
+        let header = self.header_height();
+        let text_h = gfx::text_dim(self.font, "0").h;
+        let text_dy = (s.row_height - text_h) / 2;
+        buffer.draw_text(self.rect.pos + pos(s.padding, s.padding / 2 + text_dy), self.font, &self.title, s.title);
+        let line_y = self.rect.pos.y + header - s.padding / 2;
+        buffer.fill(rect(pos(self.rect.pos.x, line_y), dim(self.rect.dim.w, 1)).area(), s.line);
+
+        let end = (self.scroll + self.visible_rows()).min(self.rows.len());
+        let label_w = self.label_width();
+        for index in self.scroll..end {
+            let row = &self.rows[index];
+            let r = self.row_rect(index);
+            let selected = index == self.selected && self.focused;
+            let (label, value) = if !row.enabled {
+                (s.hint, s.hint)
+            } else if selected {
+                buffer.fill(r.area(), s.selected_bg);
+                (s.selected_fg, s.selected_fg)
+            } else {
+                (s.label, s.value)
+            };
+            if row.value != row.original {
+                buffer.fill(rect(r.pos, dim(3, r.dim.h)).area(), s.changed);
+            }
+            buffer.draw_text(r.pos + pos(s.padding, text_dy), self.font, &row.label, label);
+            if index == self.selected && self.editor.is_some() {
+                continue;
+            }
+            buffer.draw_text(r.pos + pos(label_w, text_dy), self.font, &row.display(), value);
+        }
+        if let Some(editor) = self.editor.as_ref() {
+            editor.draw(buffer);
+        }
+
+        let footer_y = self.rect.pos.y + self.rect.dim.h - header + s.padding / 2;
+        buffer.fill(rect(pos(self.rect.pos.x, footer_y), dim(self.rect.dim.w, 1)).area(), s.line);
+        let hint = if self.editor.is_some() {
+            "Enter: Accept  Esc: Discard"
+        } else {
+            "Enter/+/-: Change  F10: Save  Esc: Cancel"
+        };
+        buffer.draw_text(pos(self.rect.pos.x + s.padding, footer_y + s.padding / 2 + text_dy), self.font, hint, s.hint);
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        if self.editor.is_some() {
+            return self.edit(key);
+        }
+        if self.rows.is_empty() {
+            return match key {
+                KeyCode::Escape => Response::Cancelled,
+                KeyCode::F(10) => Response::Submitted,
+                _ => Response::Ignored,
+            };
+        }
+        let page = self.visible_rows();
+        let enabled = self.rows[self.selected].enabled;
+        match key {
+            KeyCode::Up => self.step(false, 1),
+            KeyCode::Down => self.step(true, 1),
+            KeyCode::PageUp => self.step(false, page),
+            KeyCode::PageDown => self.step(true, page),
+            KeyCode::Home => {
+                self.selected = 0;
+                self.scroll = 0;
+                if !self.rows[0].enabled {
+                    self.step(true, 1);
+                }
+            }
+            KeyCode::End => {
+                self.select(self.rows.len() - 1);
+                if !self.rows[self.selected].enabled {
+                    self.step(false, 1);
+                }
+            }
+            KeyCode::Escape => return Response::Cancelled,
+            KeyCode::F(10) => return Response::Submitted,
+            KeyCode::Enter if enabled => {
+                if self.begin_edit().handled() {
+                    return Response::Handled;
+                }
+                return if self.rows[self.selected].adjust(1) { Response::ValueChanged } else { Response::Handled };
+            }
+            KeyCode::Left | KeyCode::Char('-') if enabled => {
+                return if self.rows[self.selected].adjust(-1) { Response::ValueChanged } else { Response::Handled };
+            }
+            KeyCode::Right | KeyCode::Char('+') | KeyCode::Char(' ') if enabled => {
+                return if self.rows[self.selected].adjust(1) { Response::ValueChanged } else { Response::Handled };
+            }
+            _ => return Response::Ignored,
+        }
+        Response::Handled
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if !focused {
+            self.editor = None;
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::gfx::{dim, pos, rect, Buffer, Dim, Insets, Rect};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::widget::{Align, Response, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Track {
+    Fixed(i32),
+    Auto,
+    Star(u32),
+}
+
+pub struct GridCell<'a> {
+    pub widget: Box<dyn Widget + 'a>,
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+    pub align_h: Option<Align>,
+    pub align_v: Option<Align>,
+}
+
+impl<'a> GridCell<'a> {
+    pub fn new(widget: Box<dyn Widget + 'a>, row: usize, column: usize) -> Self {
+        Self { widget, row, column, row_span: 1, column_span: 1, align_h: None, align_v: None }
+    }
+
+    pub fn span(mut self, rows: usize, columns: usize) -> Self {
+        self.row_span = rows.max(1);
+        self.column_span = columns.max(1);
+        self
+    }
+
+    pub fn align(mut self, h: Align, v: Align) -> Self {
+        self.align_h = Some(h);
+        self.align_v = Some(v);
+        self
+    }
+}
+
+pub struct Grid<'a> {
+    pub rect: Rect,
+    pub columns: Vec<Track>,
+    pub rows: Vec<Track>,
+    pub padding: Insets,
+    pub column_gap: i32,
+    pub row_gap: i32,
+    cells: Vec<GridCell<'a>>,
+    focus: Option<usize>,
+    focused: bool,
+}
+
+fn solve(tracks: &[Track], auto: &[i32], available: i32, gap: i32) -> Vec<i32> {
+    let mut sizes: Vec<i32> = tracks.iter().zip(auto.iter()).map(|(track, auto)| match track {
+        Track::Fixed(n) => *n,
+        Track::Auto => *auto,
+        Track::Star(_) => 0,
+    }).collect();
+    let used: i32 = sizes.iter().sum::<i32>() + gap * (tracks.len() as i32 - 1).max(0);
+    let rest = (available - used).max(0);
+    let stars: u32 = tracks.iter().map(|x| match x {
+        Track::Star(n) => *n,
+        _ => 0,
+    }).sum();
+    if stars > 0 {
+        for (size, track) in sizes.iter_mut().zip(tracks.iter()) {
+            if let Track::Star(n) = track {
+                *size = (rest as i64 * *n as i64 / stars as i64) as i32;
+            }
+        }
+    }
+    sizes
+}
+
+fn offsets(sizes: &[i32], gap: i32) -> Vec<i32> {
+    let mut at = 0;
+    sizes.iter().map(|size| {
+        let start = at;
+        at += size + gap;
+        start
+    }).collect()
+}
+
+fn spanned(sizes: &[i32], start: usize, span: usize, gap: i32) -> i32 {
+    let end = (start + span).min(sizes.len());
+    if start >= end {
+        return 0;
+    }
+    sizes[start..end].iter().sum::<i32>() + gap * (end - start - 1) as i32
+}
+
+fn fit(align: Option<Align>, start: i32, space: i32, size: i32) -> (i32, i32) {
+    match align {
+        None => (start, space),
+        Some(Align::Start) => (start, size.min(space)),
+        Some(Align::Center) => (start + (space - size.min(space)) / 2, size.min(space)),
+        Some(Align::End) => (start + space - size.min(space), size.min(space)),
+    }
+}
+
+impl<'a> Grid<'a> {
+    pub fn new(rect: Rect, columns: Vec<Track>, rows: Vec<Track>) -> Self {
+        Self {
+            rect,
+            columns,
+            rows,
+            padding: Insets::default(),
+            column_gap: 0,
+            row_gap: 0,
+            cells: Vec::new(),
+            focus: None,
+            focused: false,
+        }
+    }
+
+    pub fn gap(mut self, column_gap: i32, row_gap: i32) -> Self {
+        self.column_gap = column_gap;
+        self.row_gap = row_gap;
+        self.layout();
+        self
+    }
+
+    pub fn padding(mut self, padding: Insets) -> Self {
+        self.padding = padding;
+        self.layout();
+        self
+    }
+
+    pub fn place(&mut self, row: usize, column: usize, widget: Box<dyn Widget + 'a>) {
+        self.place_cell(GridCell::new(widget, row, column));
+    }
+
+    pub fn place_cell(&mut self, cell: GridCell<'a>) {
+        self.cells.push(cell);
+        self.layout();
+    }
+
+    pub fn cells(&self) -> &[GridCell<'a>] {
+        &self.cells
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut (dyn Widget + 'a)> {
+        self.cells.get_mut(index).map(|x| x.widget.as_mut())
+    }
+
+    fn auto_sizes(&self) -> (Vec<i32>, Vec<i32>) {
+        let mut columns = vec![0; self.columns.len()];
+        let mut rows = vec![0; self.rows.len()];
+        for cell in self.cells.iter() {
+            let size = cell.widget.measure();
+            if cell.column_span == 1 && cell.column < columns.len() {
+                columns[cell.column] = columns[cell.column].max(size.w);
+            }
+            if cell.row_span == 1 && cell.row < rows.len() {
+                rows[cell.row] = rows[cell.row].max(size.h);
+            }
+        }
+        (columns, rows)
+    }
+
+    pub fn layout(&mut self) {
+        let inner = self.rect.inset(self.padding);
+        let (auto_columns, auto_rows) = self.auto_sizes();
+        let widths = solve(&self.columns, &auto_columns, inner.dim.w, self.column_gap);
+        let heights = solve(&self.rows, &auto_rows, inner.dim.h, self.row_gap);
+        let xs = offsets(&widths, self.column_gap);
+        let ys = offsets(&heights, self.row_gap);
+        for cell in self.cells.iter_mut() {
+            if cell.column >= widths.len() || cell.row >= heights.len() {
+                cell.widget.set_rect(rect(inner.pos, dim(0, 0)));
+                continue;
+            }
+            let w = spanned(&widths, cell.column, cell.column_span, self.column_gap);
+            let h = spanned(&heights, cell.row, cell.row_span, self.row_gap);
+            let size = cell.widget.measure();
+            let (x, w) = fit(cell.align_h, xs[cell.column], w, size.w);
+            let (y, h) = fit(cell.align_v, ys[cell.row], h, size.h);
+            cell.widget.set_rect(rect(inner.pos + pos(x, y), dim(w, h)));
+        }
+    }
+
+    fn first_focusable(&self) -> Option<usize> {
+        self.cells.iter().position(|x| x.widget.focusable())
+    }
+
+    fn set_focus(&mut self, index: Option<usize>) {
+        if self.focus == index {
+            return;
+        }
+        if let Some(old) = self.focus {
+            self.cells[old].widget.set_focused(false);
+        }
+        if let Some(new) = index {
+            self.cells[new].widget.set_focused(self.focused);
+        }
+        self.focus = index;
+    }
+}
+
+impl<'a> Widget for Grid<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.layout();
+    }
+
+    fn measure(&self) -> Dim {
+        let (auto_columns, auto_rows) = self.auto_sizes();
+        let widths = solve(&self.columns, &auto_columns, 0, self.column_gap);
+        let heights = solve(&self.rows, &auto_rows, 0, self.row_gap);
+        let w = spanned(&widths, 0, widths.len(), self.column_gap);
+        let h = spanned(&heights, 0, heights.len(), self.row_gap);
+        dim(w, h) + self.padding.dim()
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        for cell in self.cells.iter() {
+            cell.widget.draw(buffer);
+        }
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        match self.focus {
+            Some(index) => self.cells[index].widget.key(key),
+            None => Response::Ignored,
+        }
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        if let PointerEvent::Down(loc, PointerButton::Left) = event {
+            let hit = self.cells.iter()
+                .position(|x| x.widget.focusable() && x.widget.rect().contains(loc));
+            if hit.is_some() {
+                self.set_focus(hit);
+            }
+        }
+        for cell in self.cells.iter_mut() {
+            let response = cell.widget.pointer(event);
+            if response.handled() {
+                return response;
+            }
+        }
+        Response::Ignored
+    }
+
+    fn focusable(&self) -> bool {
+        self.first_focusable().is_some()
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if self.focus.is_none() {
+            self.focus = self.first_focusable();
+        }
+        if let Some(index) = self.focus {
+            self.cells[index].widget.set_focused(focused);
+        }
+    }
+}
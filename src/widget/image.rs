@@ -0,0 +1,171 @@
+use crate::gfx::{dim, pos, rect, Area, Buffer, Color, Dim, Rect};
+use crate::widget::{Align, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Fit {
+    Stretch,
+    Contain,
+    Cover,
+    Center,
+    Tile,
+}
+
+fn offset(align: Align, space: i32, size: i32) -> i32 {
+    match align {
+        Align::Start => 0,
+        Align::Center => (space - size) / 2,
+        Align::End => space - size,
+    }
+}
+
+pub struct Image {
+    pub rect: Rect,
+    pub buffer: Buffer,
+    pub fit: Fit,
+    pub align_h: Align,
+    pub align_v: Align,
+    pub background: Option<Color>,
+    pub blend: bool,
+}
+
+impl Image {
+    pub fn new(rect: Rect, buffer: Buffer) -> Self {
+        Self {
+            rect,
+            buffer,
+            fit: Fit::Contain,
+            align_h: Align::Center,
+            align_v: Align::Center,
+            background: None,
+            blend: false,
+        }
+    }
+
+    pub fn fit(mut self, fit: Fit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    pub fn align(mut self, h: Align, v: Align) -> Self {
+        self.align_h = h;
+        self.align_v = v;
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    pub fn blend(mut self, blend: bool) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn set_buffer(&mut self, buffer: Buffer) {
+        self.buffer = buffer;
+    }
+
+    fn aligned(&self, size: Dim) -> Rect {
+        let r = self.rect;
+        rect(
+            r.pos + pos(offset(self.align_h, r.dim.w, size.w), offset(self.align_v, r.dim.h, size.h)),
+            size,
+        )
+    }
+
+    pub fn placement(&self) -> (Area, Rect) {
+        let src = self.buffer.dim;
+        let r = self.rect;
+        match self.fit {
+            Fit::Stretch | Fit::Tile => (self.buffer.area(), r),
+            Fit::Contain => {
+                let (w, h) = if src.w as i64 * r.dim.h as i64 > src.h as i64 * r.dim.w as i64 {
+                    (r.dim.w, (src.h as i64 * r.dim.w as i64 / src.w.max(1) as i64) as i32)
+                } else {
+                    ((src.w as i64 * r.dim.h as i64 / src.h.max(1) as i64) as i32, r.dim.h)
+                };
+                (self.buffer.area(), self.aligned(dim(w, h)))
+            }
+            Fit::Cover => {
+                let (w, h) = if src.w as i64 * r.dim.h as i64 > src.h as i64 * r.dim.w as i64 {
+                    ((r.dim.w as i64 * src.h as i64 / r.dim.h.max(1) as i64) as i32, src.h)
+                } else {
+                    (src.w, (r.dim.h as i64 * src.w as i64 / r.dim.w.max(1) as i64) as i32)
+                };
+                let at = pos(offset(self.align_h, src.w, w), offset(self.align_v, src.h, h));
+                (rect(at, dim(w, h)).area(), r)
+            }
+            Fit::Center => {
+                let placed = self.aligned(src);
+                let visible = if let Some(x) = placed.area().intersection(r.area()) { x } else {
+                    return (rect(pos(0, 0), dim(0, 0)).area(), rect(r.pos, dim(0, 0)));
+                };
+                let visible = visible.rect();
+                (rect(visible.pos - placed.pos, visible.dim).area(), visible)
+            }
+        }
+    }
+
+    fn put(&self, target: &mut Buffer, src: Area, dst: Rect) {
+        let same = src.rect().dim == dst.dim;
+        match (same, self.blend) {
+            (true, false) => target.blit(&self.buffer, src, dst.pos),
+            (true, true) => target.premultiplied_over(&self.buffer, src, dst.pos),
+            (false, false) => target.blit_scaled(&self.buffer, src, dst),
+            (false, true) => {
+                let mut scaled = Buffer::new_cleared(dst.dim, Color::black_alpha(0));
+                scaled.blit_scaled(&self.buffer, src, scaled.rect());
+                target.premultiplied_over(&scaled, scaled.area(), dst.pos);
+            }
+        }
+    }
+
+    fn draw_tiled(&self, target: &mut Buffer) {
+        let tile = self.buffer.dim;
+        if tile.w <= 0 || tile.h <= 0 {
+            return;
+        }
+        let r = self.rect;
+        let mut y = 0;
+        while y < r.dim.h {
+            let mut x = 0;
+            while x < r.dim.w {
+                let part = dim(tile.w.min(r.dim.w - x), tile.h.min(r.dim.h - y));
+                let src = rect(pos(0, 0), part).area();
+                self.put(target, src, rect(r.pos + pos(x, y), part));
+                x += tile.w;
+            }
+            y += tile.h;
+        }
+    }
+}
+
+impl Widget for Image {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn measure(&self) -> Dim {
+        self.buffer.dim
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        if let Some(bg) = self.background {
+            buffer.fill(self.rect.area(), bg);
+        }
+        if self.fit == Fit::Tile {
+            self.draw_tiled(buffer);
+            return;
+        }
+        let (src, dst) = self.placement();
+        if dst.dim.w <= 0 || dst.dim.h <= 0 {
+            return;
+        }
+        self.put(buffer, src, dst);
+    }
+}
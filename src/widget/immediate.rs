@@ -0,0 +1,263 @@
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, Buffer, Pos, Rect, Screen, View};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::theme::Theme;
+use crate::widget::{ButtonState, ButtonStyle};
+
+fn signature(parts: &[&[u8]]) -> u64 {
+    let mut hash = 0xCBF2_9CE4_8422_2325u64;
+    for part in parts {
+        for byte in part.iter() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01B3);
+        }
+    }
+    hash
+}
+
+#[derive(Default)]
+pub struct UiState {
+    keys: Vec<KeyCode>,
+    pointer: Option<Pos>,
+    down: Option<Pos>,
+    up: Option<Pos>,
+    active: Option<usize>,
+    focus: usize,
+    interactive: usize,
+    items: Vec<(Rect, u64)>,
+    drawn: bool,
+}
+
+impl UiState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed_key(&mut self, key: KeyCode) {
+        self.keys.push(key);
+    }
+
+    pub fn feed_pointer(&mut self, event: PointerEvent) {
+        self.pointer = Some(event.pos());
+        match event {
+            PointerEvent::Down(loc, PointerButton::Left) => self.down = Some(loc),
+            PointerEvent::Up(loc, PointerButton::Left) => self.up = Some(loc),
+            _ => {}
+        }
+    }
+
+    pub fn invalidate(&mut self) {
+        self.drawn = false;
+    }
+}
+
+pub struct Ui<'a> {
+    state: &'a mut UiState,
+    buffer: &'a mut Buffer,
+    font: &'a Font,
+    theme: &'a Theme,
+    bounds: Rect,
+    cursor: Pos,
+    row: Option<i32>,
+    activate: bool,
+    index: usize,
+    interactive: usize,
+    items: Vec<(Rect, u64)>,
+    dirty: Option<Rect>,
+    present: bool,
+}
+
+impl<'a> Ui<'a> {
+    pub fn begin(state: &'a mut UiState, buffer: &'a mut Buffer, font: &'a Font, bounds: Rect) -> Self {
+        let theme = Theme::current();
+        let mut activate = false;
+        let count = state.interactive.max(1);
+        for key in core::mem::take(&mut state.keys) {
+            match key {
+                KeyCode::Up | KeyCode::BackTab => state.focus = (state.focus + count - 1) % count,
+                KeyCode::Down | KeyCode::Tab => state.focus = (state.focus + 1) % count,
+                key if key.is_activate() => activate = true,
+                _ => {}
+            }
+        }
+        let mut dirty = None;
+        if !state.drawn {
+            buffer.fill(bounds.area(), theme.palette.background);
+            state.items.clear();
+            state.drawn = true;
+            dirty = Some(bounds);
+        }
+        let p = theme.metrics.dialog_padding;
+        Self {
+            state,
+            buffer,
+            font,
+            theme,
+            bounds,
+            cursor: bounds.pos + pos(p, p),
+            row: None,
+            activate,
+            index: 0,
+            interactive: 0,
+            items: Vec::new(),
+            dirty,
+            present: false,
+        }
+    }
+
+    pub fn screen(state: &'a mut UiState, font: &'a Font) -> Self {
+        let mut ui = Self::begin(state, Screen::get(), font, Screen::rect());
+        ui.present = true;
+        ui
+    }
+
+    fn width(&self) -> i32 {
+        let p = self.theme.metrics.dialog_padding;
+        self.bounds.pos.x + self.bounds.dim.w - p - self.cursor.x
+    }
+
+    fn allocate(&mut self, w: i32, h: i32) -> Rect {
+        let r = rect(self.cursor, dim(w, h));
+        let spacing = self.theme.metrics.spacing;
+        match self.row.as_mut() {
+            Some(row) => {
+                *row = (*row).max(h);
+                self.cursor.x += w + spacing;
+            }
+            None => self.cursor.y += h + spacing,
+        }
+        r
+    }
+
+    fn damaged(&mut self, r: Rect, sig: u64) -> bool {
+        let index = self.index;
+        self.index += 1;
+        self.items.push((r, sig));
+        if self.state.items.get(index) == Some(&(r, sig)) {
+            return false;
+        }
+        if let Some(&(old, _)) = self.state.items.get(index) {
+            self.buffer.fill(old.area(), self.theme.palette.background);
+            self.mark(old);
+        }
+        self.buffer.fill(r.area(), self.theme.palette.background);
+        self.mark(r);
+        true
+    }
+
+    fn mark(&mut self, r: Rect) {
+        self.dirty = Some(match self.dirty {
+            Some(dirty) => dirty.union(r),
+            None => r,
+        });
+    }
+
+    pub fn horizontal(&mut self, f: impl FnOnce(&mut Ui)) {
+        let start = self.cursor;
+        self.row = Some(0);
+        f(self);
+        let h = self.row.take().unwrap_or(0);
+        self.cursor = pos(start.x, start.y + h + self.theme.metrics.spacing);
+    }
+
+    pub fn space(&mut self, h: i32) {
+        self.allocate(0, h);
+    }
+
+    pub fn label(&mut self, text: &str) {
+        let sz = gfx::text_dim(self.font, text);
+        let h = self.theme.metrics.line_height.max(sz.h);
+        let w = if self.row.is_some() { sz.w } else { self.width() };
+        let r = self.allocate(w, h);
+        if self.damaged(r, signature(&[b"label", text.as_bytes()])) {
+            let mut view = View::new(self.buffer, r);
+            view.draw_text(pos(0, (h - sz.h) / 2), self.font, text, self.theme.palette.fg);
+        }
+    }
+
+    pub fn separator(&mut self) {
+        let w = self.width();
+        let r = self.allocate(w, 1);
+        if self.damaged(r, signature(&[b"separator"])) {
+            self.buffer.fill(r.area(), self.theme.palette.line);
+        }
+    }
+
+    pub fn button(&mut self, label: &str) -> bool {
+        let m = self.theme.metrics;
+        let sz = gfx::text_dim(self.font, label);
+        let w = if self.row.is_some() { sz.w + m.dialog_padding * 2 } else { self.width() };
+        let r = self.allocate(w, m.row_height + 8);
+        let id = self.interactive;
+        self.interactive += 1;
+
+        let focused = self.state.focus == id;
+        let hovered = self.state.pointer.map_or(false, |x| r.contains(x));
+        if self.state.down.map_or(false, |x| r.contains(x)) {
+            self.state.active = Some(id);
+            self.state.focus = id;
+        }
+        let pressed = self.state.active == Some(id) && hovered;
+        let mut clicked = focused && self.activate;
+        if self.state.up.map_or(false, |x| r.contains(x)) && self.state.active == Some(id) {
+            clicked = true;
+        }
+
+        let state = if pressed {
+            ButtonState::Pressed
+        } else if focused || hovered {
+            ButtonState::Focused
+        } else {
+            ButtonState::Normal
+        };
+        let sig = signature(&[b"button", label.as_bytes(), &[state as u8]]);
+        if self.damaged(r, sig) {
+            let visual = ButtonStyle::from(self.theme).visual(state);
+            self.buffer.fill(r.area(), visual.bg);
+            self.buffer.stroke(r.area(), m.border_width, visual.border);
+            self.buffer.draw_text(r.centered(sz).pos, self.font, label, visual.fg);
+        }
+        clicked
+    }
+
+    pub fn progress(&mut self, value: f32) {
+        let value = value.clamp(0.0, 1.0);
+        let w = if self.row.is_some() { 200 } else { self.width() };
+        let r = self.allocate(w, self.theme.metrics.padding * 3);
+        let filled = (r.dim.w as f32 * value) as i32;
+        if self.damaged(r, signature(&[b"progress", &filled.to_le_bytes()])) {
+            let p = &self.theme.palette;
+            self.buffer.fill(r.area(), p.line);
+            self.buffer.fill(rect(r.pos, dim(filled, r.dim.h)).area(), self.theme.focus.color);
+        }
+    }
+
+    pub fn end(self) -> Option<Rect> {
+        let mut dirty = self.dirty;
+        for (old, _) in self.state.items.iter().skip(self.items.len()) {
+            self.buffer.fill(old.area(), self.theme.palette.background);
+            dirty = Some(match dirty {
+                Some(dirty) => dirty.union(*old),
+                None => *old,
+            });
+        }
+        let state = self.state;
+        state.items = self.items;
+        state.interactive = self.interactive;
+        state.focus = state.focus.min(self.interactive.saturating_sub(1));
+        if state.up.is_some() {
+            state.active = None;
+        }
+        state.down = None;
+        state.up = None;
+        if self.present {
+            if let Some(dirty) = dirty {
+                Screen::present(dirty);
+            }
+        }
+        dirty
+    }
+}
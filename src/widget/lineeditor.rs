@@ -0,0 +1,229 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::input::{KeyCode, Keyboard};
+use crate::widget::Terminal;
+
+pub struct LineEditor<'a> {
+    pub prompt: String,
+    history: Vec<String>,
+    history_limit: usize,
+    browsing: Option<usize>,
+    stash: Vec<char>,
+    line: Vec<char>,
+    cursor: usize,
+    shown: usize,
+    start: usize,
+    active: bool,
+    completer: Option<Box<dyn FnMut(&str) -> Vec<String> + 'a>>,
+}
+
+fn common_prefix(items: &[String]) -> String {
+    let first = if let Some(x) = items.first() { x } else { return String::new(); };
+    let mut len = first.chars().count();
+    for item in items.iter().skip(1) {
+        len = len.min(first.chars().zip(item.chars()).take_while(|(a, b)| a == b).count());
+    }
+    first.chars().take(len).collect()
+}
+
+impl<'a> LineEditor<'a> {
+    pub fn new(prompt: &str) -> Self {
+        Self {
+            prompt: prompt.to_string(),
+            history: Vec::new(),
+            history_limit: 100,
+            browsing: None,
+            stash: Vec::new(),
+            line: Vec::new(),
+            cursor: 0,
+            shown: 0,
+            start: 0,
+            active: false,
+            completer: None,
+        }
+    }
+
+    pub fn history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = limit;
+        self.trim_history();
+        self
+    }
+
+    pub fn completer(mut self, f: impl FnMut(&str) -> Vec<String> + 'a) -> Self {
+        self.completer = Some(Box::new(f));
+        self
+    }
+
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    pub fn add_history(&mut self, line: &str) {
+        if line.is_empty() || self.history.last().map(|x| x.as_str()) == Some(line) {
+            return;
+        }
+        self.history.push(line.to_string());
+        self.trim_history();
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.browsing = None;
+    }
+
+    fn trim_history(&mut self) {
+        if self.history.len() > self.history_limit {
+            let excess = self.history.len() - self.history_limit;
+            self.history.drain(..excess);
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.line.iter().collect()
+    }
+
+    pub fn set_text(&mut self, term: &mut Terminal, text: &str) {
+        self.line = text.chars().collect();
+        self.cursor = self.line.len();
+        self.refresh(term);
+    }
+
+    fn linear(term: &Terminal) -> usize {
+        let (col, row) = term.cursor();
+        row * term.size().0 + col
+    }
+
+    fn place(term: &mut Terminal, at: usize) {
+        let cols = term.size().0.max(1);
+        term.set_cursor(at % cols, at / cols);
+    }
+
+    pub fn begin(&mut self, term: &mut Terminal) {
+        term.print(&self.prompt);
+        self.line.clear();
+        self.cursor = 0;
+        self.shown = 0;
+        self.browsing = None;
+        self.start = Self::linear(term);
+        self.active = true;
+    }
+
+    fn refresh(&mut self, term: &mut Terminal) {
+        Self::place(term, self.start);
+        let text: String = self.line.iter().collect();
+        term.print(&text);
+        let pad = self.shown.saturating_sub(self.line.len());
+        (0..pad).for_each(|_| term.put_char(' '));
+        self.start = Self::linear(term).saturating_sub(self.line.len() + pad);
+        self.shown = self.line.len();
+        Self::place(term, self.start + self.cursor);
+    }
+
+    fn recall(&mut self, index: Option<usize>) {
+        if self.browsing.is_none() {
+            self.stash = self.line.clone();
+        }
+        self.browsing = index;
+        self.line = match index {
+            Some(i) => self.history[i].chars().collect(),
+            None => core::mem::take(&mut self.stash),
+        };
+        self.cursor = self.line.len();
+    }
+
+    fn word_start(&self) -> usize {
+        self.line[..self.cursor].iter().rposition(|x| x.is_whitespace()).map_or(0, |x| x + 1)
+    }
+
+    fn complete(&mut self, term: &mut Terminal) {
+        let word_start = self.word_start();
+        let word: String = self.line[word_start..self.cursor].iter().collect();
+        let completer = if let Some(x) = self.completer.as_mut() { x } else { return; };
+        let candidates = completer(&word);
+        let mut prefix = common_prefix(&candidates);
+        if candidates.len() == 1 {
+            prefix.push(' ');
+        }
+        if prefix.chars().count() > word.chars().count() {
+            self.line.splice(word_start..self.cursor, prefix.chars());
+            self.cursor = word_start + prefix.chars().count();
+            self.refresh(term);
+            return;
+        }
+        if candidates.len() < 2 {
+            return;
+        }
+        Self::place(term, self.start + self.line.len());
+        term.print("\r\n");
+        term.print(&candidates.join("  "));
+        term.print("\r\n");
+        term.print(&self.prompt);
+        self.start = Self::linear(term);
+        self.shown = 0;
+        self.refresh(term);
+    }
+
+    pub fn key(&mut self, term: &mut Terminal, key: KeyCode) -> Option<String> {
+        if !self.active {
+            self.begin(term);
+        }
+        match key {
+            KeyCode::Enter => {
+                Self::place(term, self.start + self.line.len());
+                term.print("\r\n");
+                let text = self.text();
+                self.add_history(&text);
+                self.active = false;
+                return Some(text);
+            }
+            KeyCode::Char(ch) => {
+                self.line.insert(self.cursor, ch);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.line.remove(self.cursor);
+            }
+            KeyCode::Delete if self.cursor < self.line.len() => {
+                self.line.remove(self.cursor);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.line.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.line.len(),
+            KeyCode::Escape => {
+                self.line.clear();
+                self.cursor = 0;
+                self.browsing = None;
+            }
+            KeyCode::Up if !self.history.is_empty() => {
+                let index = self.browsing.map_or(self.history.len() - 1, |x| x.saturating_sub(1));
+                self.recall(Some(index));
+            }
+            KeyCode::Down if self.browsing.is_some() => {
+                let next = self.browsing.map(|x| x + 1).filter(|x| *x < self.history.len());
+                self.recall(next);
+            }
+            KeyCode::Tab => {
+                self.complete(term);
+                return None;
+            }
+            _ => return None,
+        }
+        self.refresh(term);
+        None
+    }
+
+    pub fn read_line(&mut self, term: &mut Terminal) -> crate::Result<String> {
+        self.begin(term);
+        loop {
+            term.present();
+            if let Some(line) = self.key(term, Keyboard::read_key()?) {
+                term.present();
+                return Ok(line);
+            }
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, Buffer, Color, Rect, View};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::theme::Theme;
+use crate::widget::{Response, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ListStyle {
+    pub bg: Color,
+    pub fg: Color,
+    pub selected_bg: Color,
+    pub selected_fg: Color,
+    pub unfocused_selected_bg: Color,
+    pub scrollbar_track: Color,
+    pub scrollbar_thumb: Color,
+    pub scrollbar_width: i32,
+    pub padding: i32,
+}
+
+impl From<&Theme> for ListStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            bg: p.base,
+            fg: p.fg,
+            selected_bg: p.accent,
+            selected_fg: p.accent_fg,
+            unfocused_selected_bg: p.raised,
+            scrollbar_track: p.line,
+            scrollbar_thumb: p.muted,
+            scrollbar_width: m.scrollbar_width,
+            padding: m.padding,
+        }
+    }
+}
+
+impl Default for ListStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub type RowDrawFn<'a> = Box<dyn Fn(&mut View, Rect, usize, bool) + 'a>;
+
+pub struct ListView<'a> {
+    pub rect: Rect,
+    pub items: Vec<String>,
+    pub row_height: i32,
+    pub style: ListStyle,
+    pub smooth: bool,
+    pub draw_row: Option<RowDrawFn<'a>>,
+    selected: usize,
+    scroll: i32,
+    scroll_target: i32,
+    focused: bool,
+    font: &'a Font,
+}
+
+impl<'a> ListView<'a> {
+    pub fn new(rect: Rect, font: &'a Font, row_height: i32, items: Vec<String>) -> Self {
+        Self {
+            rect,
+            items,
+            row_height: row_height.max(1),
+            style: ListStyle::default(),
+            smooth: false,
+            draw_row: None,
+            selected: 0,
+            scroll: 0,
+            scroll_target: 0,
+            focused: false,
+            font,
+        }
+    }
+
+    pub fn set_items(&mut self, items: Vec<String>) {
+        self.items = items;
+        self.selected = 0;
+        self.scroll = 0;
+        self.scroll_target = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        if self.items.is_empty() { None } else { Some(self.selected) }
+    }
+
+    pub fn selected_item(&self) -> Option<&str> {
+        self.items.get(self.selected).map(|x| x.as_str())
+    }
+
+    pub fn select(&mut self, index: usize) -> Response {
+        if self.items.is_empty() {
+            return Response::Ignored;
+        }
+        let index = index.min(self.items.len() - 1);
+        if index == self.selected {
+            return Response::Handled;
+        }
+        self.selected = index;
+        self.ensure_visible(index);
+        Response::ValueChanged
+    }
+
+    pub fn visible_rows(&self) -> usize {
+        (self.rect.dim.h / self.row_height).max(1) as usize
+    }
+
+    fn content_height(&self) -> i32 {
+        self.items.len() as i32 * self.row_height
+    }
+
+    fn max_scroll(&self) -> i32 {
+        (self.content_height() - self.rect.dim.h).max(0)
+    }
+
+    pub fn scroll_to(&mut self, offset: i32) {
+        self.scroll_target = offset.clamp(0, self.max_scroll());
+        if !self.smooth {
+            self.scroll = self.scroll_target;
+        }
+    }
+
+    pub fn ensure_visible(&mut self, index: usize) {
+        let top = index as i32 * self.row_height;
+        let bottom = top + self.row_height;
+        if top < self.scroll_target {
+            self.scroll_to(top);
+        } else if bottom > self.scroll_target + self.rect.dim.h {
+            self.scroll_to(bottom - self.rect.dim.h);
+        }
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        if self.scroll == self.scroll_target {
+            return;
+        }
+        let diff = self.scroll_target - self.scroll;
+        let step = (diff as f32 * (dt * 12.0).min(1.0)) as i32;
+        self.scroll += if step == 0 { diff.signum() } else { step };
+    }
+
+    fn draw_scrollbar(&self, buffer: &mut Buffer) {
+        let content = self.content_height();
+        if content <= self.rect.dim.h {
+            return;
+        }
+        let w = self.style.scrollbar_width;
+        let r = self.rect;
+        let track = rect(pos(r.pos.x + r.dim.w - w, r.pos.y), dim(w, r.dim.h));
+        let thumb_h = (r.dim.h as i64 * r.dim.h as i64 / content as i64).max(w as i64) as i32;
+        let thumb_y = (self.scroll as i64 * (r.dim.h - thumb_h) as i64
+            / self.max_scroll().max(1) as i64) as i32;
+        buffer.fill_over(track.area(), self.style.scrollbar_track);
+        buffer.fill_over(rect(track.pos + pos(0, thumb_y), dim(w, thumb_h)).area(),
+            self.style.scrollbar_thumb);
+    }
+
+    pub fn row_rect(&self, index: usize) -> Option<Rect> {
+        let y = index as i32 * self.row_height - self.scroll;
+        if index >= self.items.len() || y + self.row_height <= 0 || y >= self.rect.dim.h {
+            return None;
+        }
+        Some(rect(self.rect.pos + pos(0, y), dim(self.rect.dim.w, self.row_height)))
+    }
+
+    fn row_at(&self, y: i32) -> Option<usize> {
+        let index = (y - self.rect.pos.y + self.scroll) / self.row_height;
+        if y < self.rect.pos.y || index as usize >= self.items.len() {
+            return None;
+        }
+        Some(index as usize)
+    }
+}
+
+impl<'a> Widget for ListView<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.scroll_to(self.scroll_target);
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        buffer.fill_over(self.rect.area(), s.bg);
+        let bar = if self.content_height() > self.rect.dim.h { s.scrollbar_width } else { 0 };
+        let width = self.rect.dim.w - bar;
+        {
+            let mut view = View::new(buffer, self.rect.resize(dim(width, self.rect.dim.h)));
+            let first = (self.scroll / self.row_height) as usize;
+            let last = (first + self.visible_rows() + 1).min(self.items.len());
+            for index in first..last {
+                let y = index as i32 * self.row_height - self.scroll;
+                let row = rect(pos(0, y), dim(width, self.row_height));
+                let selected = index == self.selected;
+                if selected {
+                    let bg = if self.focused { s.selected_bg } else { s.unfocused_selected_bg };
+                    view.fill_over(row.area(), bg);
+                }
+                if let Some(draw_row) = self.draw_row.as_ref() {
+                    draw_row(&mut view, row, index, selected);
+                    continue;
+                }
+                let text = &self.items[index];
+                let sz = gfx::text_dim(self.font, text);
+                let loc = pos(s.padding, y + (self.row_height - sz.h) / 2);
+                let fg = if selected { s.selected_fg } else { s.fg };
+                view.draw_text(loc, self.font, text, fg);
+            }
+        }
+        self.draw_scrollbar(buffer);
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        if self.items.is_empty() {
+            return Response::Ignored;
+        }
+        let page = self.visible_rows();
+        match key {
+            KeyCode::Up => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Down => self.select(self.selected + 1),
+            KeyCode::PageUp => self.select(self.selected.saturating_sub(page)),
+            KeyCode::PageDown => self.select(self.selected + page),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(self.items.len() - 1),
+            KeyCode::Enter => Response::Clicked,
+            _ => Response::Ignored,
+        }
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        if !self.rect.contains(event.pos()) {
+            return Response::Ignored;
+        }
+        match event {
+            PointerEvent::Down(loc, PointerButton::Left) => match self.row_at(loc.y) {
+                Some(index) if index == self.selected => Response::Clicked,
+                Some(index) => self.select(index),
+                None => Response::Handled,
+            },
+            PointerEvent::Wheel(_, delta) => {
+                self.scroll_to(self.scroll_target - delta * self.row_height);
+                Response::Handled
+            }
+            _ => Response::Ignored,
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+}
+
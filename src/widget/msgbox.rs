@@ -0,0 +1,184 @@
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, Buffer, Color, Dim, GlyphCoordIteratorExt,
+    GlyphIteratorExt, Rect, Screen};
+use crate::input::{self, KeyCode};
+use crate::theme::Theme;
+use crate::widget::{Button, Response, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Buttons {
+    Ok,
+    OkCancel,
+    YesNo,
+    YesNoCancel,
+    RetryCancel,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MessageResult {
+    Ok,
+    Cancel,
+    Yes,
+    No,
+    Retry,
+}
+
+impl MessageResult {
+    pub fn label(self) -> &'static str {
+        match self {
+            MessageResult::Ok => "OK",
+            MessageResult::Cancel => "Cancel",
+            MessageResult::Yes => "Yes",
+            MessageResult::No => "No",
+            MessageResult::Retry => "Retry",
+        }
+    }
+}
+
+impl Buttons {
+    pub fn results(self) -> &'static [MessageResult] {
+        match self {
+            Buttons::Ok => &[MessageResult::Ok],
+            Buttons::OkCancel => &[MessageResult::Ok, MessageResult::Cancel],
+            Buttons::YesNo => &[MessageResult::Yes, MessageResult::No],
+            Buttons::YesNoCancel => &[MessageResult::Yes, MessageResult::No, MessageResult::Cancel],
+            Buttons::RetryCancel => &[MessageResult::Retry, MessageResult::Cancel],
+        }
+    }
+
+    pub fn escape(self) -> MessageResult {
+        *self.results().last().unwrap()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MessageBoxStyle {
+    pub dim: Color,
+    pub bg: Color,
+    pub border: Color,
+    pub title: Color,
+    pub text: Color,
+    pub line_height: i32,
+    pub padding: i32,
+    pub button: Dim,
+}
+
+impl From<&Theme> for MessageBoxStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            dim: p.scrim,
+            bg: p.surface,
+            border: p.muted,
+            title: p.fg_strong,
+            text: p.fg,
+            line_height: m.line_height,
+            padding: m.dialog_padding,
+            button: dim(96, m.row_height + 8),
+        }
+    }
+}
+
+impl Default for MessageBoxStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub struct MessageBox {}
+
+impl MessageBox {
+    pub fn show(
+        screen: &mut Buffer, font: &Font, title: &str, text: &str, buttons: Buttons
+    ) -> crate::Result<MessageResult> {
+        Self::show_styled(screen, font, title, text, buttons, &MessageBoxStyle::default())
+    }
+
+    pub fn show_styled(
+        screen: &mut Buffer, font: &Font, title: &str, text: &str, buttons: Buttons,
+        style: &MessageBoxStyle,
+    ) -> crate::Result<MessageResult> {
+        let saved = screen.clone();
+        screen.fill_over(screen.area(), style.dim);
+        let backdrop = screen.clone();
+
+        let p = style.padding;
+        let results = buttons.results();
+        let buttons_w = results.len() as i32 * (style.button.w + p) - p;
+        let max_w = (screen.dim.w * 2 / 3).max(buttons_w);
+        let title_dim = gfx::text_dim(font, title);
+        let text_dim = gfx::wrapped_text_dim(font, text, max_w, style.line_height);
+        let inner = dim(
+            title_dim.w.max(text_dim.w).max(buttons_w),
+            style.line_height * 2 + text_dim.h + p + style.button.h,
+        );
+        let dialog = screen.rect().centered(inner + dim(p * 2, p * 2));
+        let origin = dialog.pos + pos(p, p);
+
+        let mut widgets: Vec<Button> = results.iter().enumerate().map(|(i, result)| {
+            let x = dialog.pos.x + dialog.dim.w - p - buttons_w + i as i32 * (style.button.w + p);
+            let y = dialog.pos.y + dialog.dim.h - p - style.button.h;
+            Button::label(rect(pos(x, y), style.button), font, result.label())
+        }).collect();
+        let mut focus = 0;
+        widgets[focus].set_focused(true);
+        let mut dirty = screen.rect();
+
+        let result = loop {
+            screen.blit(&backdrop, dialog.area(), dialog.pos);
+            Self::draw_frame(screen, dialog, style);
+            screen.draw_text(origin, font, title, style.title);
+            font.lookup_string(text)
+                .glyph_coords()
+                .line_wrap(max_w, style.line_height)
+                .draw_each(screen, origin + pos(0, style.line_height * 2), font, style.text);
+            for button in widgets.iter() {
+                button.draw(screen);
+            }
+            Screen::present(dirty);
+            dirty = dialog;
+
+            let key = match input::wait_key() {
+                Ok(x) => x,
+                Err(e) => break Err(e),
+            };
+            let next = match key {
+                KeyCode::Left | KeyCode::BackTab => Some((focus + widgets.len() - 1) % widgets.len()),
+                KeyCode::Right | KeyCode::Tab => Some((focus + 1) % widgets.len()),
+                KeyCode::Escape => break Ok(buttons.escape()),
+                _ => None,
+            };
+            if let Some(next) = next {
+                widgets[focus].set_focused(false);
+                widgets[next].set_focused(true);
+                focus = next;
+                continue;
+            }
+            if let KeyCode::Char(ch) = key {
+                let ch = ch.to_ascii_lowercase();
+                let hit = results.iter().find(|x| {
+                    x.label().chars().next().map(|x| x.to_ascii_lowercase()) == Some(ch)
+                });
+                if let Some(hit) = hit {
+                    break Ok(*hit);
+                }
+            }
+            if widgets[focus].key(key) == Response::Clicked {
+                break Ok(results[focus]);
+            }
+        };
+
+        *screen = saved;
+        Screen::present(screen.rect());
+        result
+    }
+
+    fn draw_frame(screen: &mut Buffer, dialog: Rect, style: &MessageBoxStyle) {
+        screen.fill_over(dialog.area(), style.bg);
+        screen.stroke(dialog.area(), 1, style.border);
+    }
+}
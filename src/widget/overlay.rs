@@ -0,0 +1,143 @@
+use alloc::string::{String, ToString};
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, Buffer, Color, Dim, Rect, Screen};
+use crate::theme::Theme;
+use crate::widget::{Spinner, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct OverlayStyle {
+    pub panel: Color,
+    pub border: Color,
+    pub text: Color,
+    pub spinner: Color,
+    pub spinner_size: i32,
+    pub padding: i32,
+    pub spacing: i32,
+}
+
+impl From<&Theme> for OverlayStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            panel: p.surface,
+            border: p.muted,
+            text: p.fg_strong,
+            spinner: p.fg_strong,
+            spinner_size: 48,
+            padding: m.dialog_padding,
+            spacing: m.spacing,
+        }
+    }
+}
+
+impl Default for OverlayStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub struct Overlay<'a> {
+    pub style: OverlayStyle,
+    saved: Buffer,
+    backdrop: Buffer,
+    spinner: Option<Spinner>,
+    message: Option<(String, &'a Font)>,
+    panel: Option<Rect>,
+}
+
+impl<'a> Overlay<'a> {
+    pub fn dim(screen: &mut Buffer, alpha: u8) -> Self {
+        let saved = screen.clone();
+        screen.fill_over(screen.area(), Color::black_alpha(alpha));
+        Screen::present(screen.rect());
+        Self {
+            style: OverlayStyle::default(),
+            backdrop: screen.clone(),
+            saved,
+            spinner: None,
+            message: None,
+            panel: None,
+        }
+    }
+
+    pub fn style(mut self, style: OverlayStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn spinner(mut self) -> Self {
+        let size = self.style.spinner_size;
+        self.spinner = Some(Spinner::new(rect(pos(0, 0), dim(size, size)), self.style.spinner));
+        self
+    }
+
+    pub fn message(mut self, font: &'a Font, text: &str) -> Self {
+        self.message = Some((text.to_string(), font));
+        self
+    }
+
+    pub fn set_message(&mut self, text: &str) {
+        if let Some(message) = self.message.as_mut() {
+            message.0 = text.to_string();
+        }
+    }
+
+    fn content_dim(&self) -> Dim {
+        let s = &self.style;
+        let mut content = dim(0, 0);
+        if let Some(spinner) = self.spinner.as_ref() {
+            content = spinner.rect.dim;
+        }
+        if let Some((text, font)) = self.message.as_ref() {
+            let text = gfx::text_dim(font, text);
+            let gap = if self.spinner.is_some() { s.spacing } else { 0 };
+            content = dim(content.w.max(text.w), content.h + gap + text.h);
+        }
+        content
+    }
+
+    pub fn draw(&mut self, screen: &mut Buffer) -> Option<Rect> {
+        if self.spinner.is_none() && self.message.is_none() {
+            return None;
+        }
+        let s = self.style;
+        let panel = screen.rect().centered(self.content_dim() + dim(s.padding * 2, s.padding * 2));
+        let dirty = self.panel.map_or(panel, |x| x.union(panel));
+        screen.blit(&self.backdrop, dirty.area(), dirty.pos);
+        screen.fill(panel.area(), s.panel);
+        screen.stroke(panel.area(), 1, s.border);
+        let mut y = panel.pos.y + s.padding;
+        if let Some(spinner) = self.spinner.as_mut() {
+            let size = spinner.rect.dim;
+            spinner.set_rect(rect(pos(panel.pos.x + (panel.dim.w - size.w) / 2, y), size));
+            spinner.draw(screen);
+            y += size.h + s.spacing;
+        }
+        if let Some((text, font)) = self.message.as_ref() {
+            let w = gfx::text_dim(font, text).w;
+            screen.draw_text(pos(panel.pos.x + (panel.dim.w - w) / 2, y), font, text, s.text);
+        }
+        self.panel = Some(panel);
+        Screen::present(dirty);
+        Some(dirty)
+    }
+
+    pub fn tick(&mut self, screen: &mut Buffer, dt: f32) -> Option<Rect> {
+        if let Some(spinner) = self.spinner.as_mut() {
+            spinner.tick(dt);
+        }
+        self.draw(screen)
+    }
+
+    pub fn dismiss(self, screen: &mut Buffer) {
+        if screen.dim == self.saved.dim {
+            *screen = self.saved;
+        } else {
+            screen.blit(&self.saved, self.saved.area(), pos(0, 0));
+        }
+        Screen::present(screen.rect());
+    }
+}
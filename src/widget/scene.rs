@@ -0,0 +1,246 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::gfx::{Buffer, Color, Pos, Rect, Screen};
+use crate::widget::{FocusChange, Response, Widget};
+
+pub type NodeId = usize;
+
+struct Node<'a> {
+    widget: Option<Box<dyn Widget + 'a>>,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    visible: bool,
+}
+
+pub struct Scene<'a> {
+    pub rect: Rect,
+    pub background: Color,
+    nodes: Vec<Option<Node<'a>>>,
+    dirty: Vec<Rect>,
+    scratch: Buffer,
+}
+
+fn overlaps(a: Rect, b: Rect) -> bool {
+    a.area().intersection(b.area()).is_some()
+}
+
+impl<'a> Scene<'a> {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new(rect: Rect, background: Color) -> Self {
+        let root = Node { widget: None, parent: None, children: Vec::new(), visible: true };
+        Self {
+            rect,
+            background,
+            nodes: vec![Some(root)],
+            dirty: vec![rect],
+            scratch: Buffer::new(rect.dim + rect.pos.dim()),
+        }
+    }
+
+    fn node(&self, id: NodeId) -> Option<&Node<'a>> {
+        self.nodes.get(id).and_then(|x| x.as_ref())
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Option<&mut Node<'a>> {
+        self.nodes.get_mut(id).and_then(|x| x.as_mut())
+    }
+
+    pub fn add(&mut self, parent: NodeId, widget: Box<dyn Widget + 'a>) -> Option<NodeId> {
+        self.node(parent)?;
+        let r = widget.rect();
+        let node = Node { widget: Some(widget), parent: Some(parent), children: Vec::new(), visible: true };
+        let id = match self.nodes.iter().position(|x| x.is_none()) {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        };
+        self.node_mut(parent)?.children.push(id);
+        self.invalidate_rect(r);
+        Some(id)
+    }
+
+    pub fn remove(&mut self, id: NodeId) {
+        if id == Self::ROOT {
+            return;
+        }
+        self.invalidate(id);
+        let node = if let Some(x) = self.nodes.get_mut(id).and_then(|x| x.take()) { x } else { return; };
+        if let Some(parent) = node.parent.and_then(|x| self.node_mut(x)) {
+            parent.children.retain(|x| *x != id);
+        }
+        for child in node.children {
+            if let Some(child) = self.node_mut(child) {
+                child.parent = None;
+            }
+            self.remove(child);
+        }
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id)?.parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        self.node(id).map_or(&[][..], |x| x.children.as_slice())
+    }
+
+    pub fn widget(&self, id: NodeId) -> Option<&(dyn Widget + 'a)> {
+        self.node(id)?.widget.as_deref()
+    }
+
+    pub fn widget_mut(&mut self, id: NodeId) -> Option<&mut (dyn Widget + 'a)> {
+        self.invalidate(id);
+        self.node_mut(id)?.widget.as_deref_mut()
+    }
+
+    pub fn update<R>(&mut self, id: NodeId, f: impl FnOnce(&mut (dyn Widget + 'a)) -> R) -> Option<R> {
+        self.invalidate(id);
+        let widget = self.node_mut(id)?.widget.as_deref_mut()?;
+        let result = f(widget);
+        self.invalidate(id);
+        Some(result)
+    }
+
+    pub fn set_visible(&mut self, id: NodeId, visible: bool) {
+        if let Some(node) = self.node_mut(id) {
+            if node.visible != visible {
+                node.visible = visible;
+                self.invalidate(id);
+            }
+        }
+    }
+
+    pub fn apply_focus(&mut self, change: FocusChange) {
+        if let Some(old) = change.old {
+            self.update(old, |x| x.set_focused(false));
+        }
+        if let Some(new) = change.new {
+            self.update(new, |x| x.set_focused(true));
+        }
+    }
+
+    pub fn send(&mut self, id: NodeId, f: impl FnOnce(&mut (dyn Widget + 'a)) -> Response) -> Response {
+        let before = if let Some(x) = self.rect_of(id) { x } else { return Response::Ignored; };
+        let widget = if let Some(x) = self.node_mut(id).and_then(|x| x.widget.as_deref_mut()) { x } else {
+            return Response::Ignored;
+        };
+        let response = f(widget);
+        if response.handled() {
+            self.invalidate_rect(before);
+            self.invalidate(id);
+        }
+        response
+    }
+
+    pub fn walk(&self) -> Vec<NodeId> {
+        let mut order = Vec::new();
+        let mut stack = vec![Self::ROOT];
+        while let Some(id) = stack.pop() {
+            let node = if let Some(x) = self.node(id) { x } else { continue; };
+            if !node.visible {
+                continue;
+            }
+            if id != Self::ROOT {
+                order.push(id);
+            }
+            stack.extend(node.children.iter().rev());
+        }
+        order
+    }
+
+    pub fn hit_test(&self, loc: Pos) -> Option<NodeId> {
+        self.walk().into_iter().rev().find(|id| self.rect_of(*id).map_or(false, |x| x.contains(loc)))
+    }
+
+    pub fn rect_of(&self, id: NodeId) -> Option<Rect> {
+        match self.node(id)?.widget.as_ref() {
+            Some(widget) => Some(widget.rect()),
+            None => Some(self.rect),
+        }
+    }
+
+    pub fn invalidate(&mut self, id: NodeId) {
+        if let Some(r) = self.rect_of(id) {
+            self.invalidate_rect(r);
+        }
+    }
+
+    pub fn invalidate_rect(&mut self, r: Rect) {
+        let r = if let Some(x) = r.area().intersection(self.rect.area()) { x } else { return; };
+        let mut r = Rect { pos: r.pos1, dim: (r.pos2 - r.pos1).dim() };
+        while let Some(index) = self.dirty.iter().position(|x| overlaps(*x, r)) {
+            r = r.union(self.dirty.swap_remove(index));
+        }
+        self.dirty.push(r);
+    }
+
+    pub fn invalidate_all(&mut self) {
+        self.dirty.clear();
+        self.dirty.push(self.rect);
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    fn draw_node(&self, id: NodeId, clip: Rect, buffer: &mut Buffer) {
+        let node = if let Some(x) = self.node(id) { x } else { return; };
+        if !node.visible {
+            return;
+        }
+        if let Some(widget) = node.widget.as_ref() {
+            if !overlaps(widget.rect(), clip) {
+                return;
+            }
+            widget.draw(buffer);
+        }
+        for child in node.children.iter() {
+            self.draw_node(*child, clip, buffer);
+        }
+    }
+
+    pub fn render(&mut self, target: &mut Buffer) -> Option<Rect> {
+        self.render_with(target, |_| {})
+    }
+
+    pub fn render_with(
+        &mut self, target: &mut Buffer, mut overlay: impl FnMut(&mut Buffer)
+    ) -> Option<Rect> {
+        let dirty = core::mem::take(&mut self.dirty);
+        let extent = self.rect.dim + self.rect.pos.dim();
+        if self.scratch.dim != extent {
+            self.scratch = Buffer::new(extent);
+        }
+        let mut scratch = core::mem::take(&mut self.scratch);
+        let mut presented: Option<Rect> = None;
+        for r in dirty.iter() {
+            scratch.fill(r.area(), self.background);
+            self.draw_node(Self::ROOT, *r, &mut scratch);
+            overlay(&mut scratch);
+            target.blit(&scratch, r.area(), r.pos);
+            presented = Some(presented.map_or(*r, |x| x.union(*r)));
+        }
+        self.scratch = scratch;
+        presented
+    }
+
+    pub fn present(&mut self) -> Option<Rect> {
+        self.present_with(|_| {})
+    }
+
+    pub fn present_with(&mut self, overlay: impl FnMut(&mut Buffer)) -> Option<Rect> {
+        let dirty = self.render_with(Screen::get(), overlay);
+        if let Some(dirty) = dirty {
+            Screen::present(dirty);
+        }
+        dirty
+    }
+}
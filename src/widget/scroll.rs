@@ -0,0 +1,339 @@
+use alloc::boxed::Box;
+
+use crate::gfx::{dim, pos, rect, Buffer, Color, Dim, Pos, Rect};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::theme::Theme;
+use crate::widget::{Orientation, Response, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ScrollBarStyle {
+    pub track: Color,
+    pub thumb: Color,
+    pub thumb_active: Color,
+    pub width: i32,
+    pub min_thumb: i32,
+}
+
+impl From<&Theme> for ScrollBarStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            track: p.surface,
+            thumb: p.fg_disabled,
+            thumb_active: p.fg_dim,
+            width: m.scrollbar_width,
+            min_thumb: 16,
+        }
+    }
+}
+
+impl Default for ScrollBarStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub struct ScrollBar {
+    pub rect: Rect,
+    pub orientation: Orientation,
+    pub style: ScrollBarStyle,
+    content: i32,
+    viewport: i32,
+    offset: i32,
+    grab: Option<i32>,
+}
+
+impl ScrollBar {
+    pub fn new(rect: Rect, orientation: Orientation) -> Self {
+        Self {
+            rect,
+            orientation,
+            style: ScrollBarStyle::default(),
+            content: 0,
+            viewport: 0,
+            offset: 0,
+            grab: None,
+        }
+    }
+
+    pub fn set_extent(&mut self, content: i32, viewport: i32) {
+        self.content = content.max(0);
+        self.viewport = viewport.max(0);
+        self.offset = self.offset.clamp(0, self.max_offset());
+    }
+
+    pub fn max_offset(&self) -> i32 {
+        (self.content - self.viewport).max(0)
+    }
+
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    pub fn needed(&self) -> bool {
+        self.content > self.viewport
+    }
+
+    pub fn set_offset(&mut self, offset: i32) -> Response {
+        let offset = offset.clamp(0, self.max_offset());
+        if offset == self.offset {
+            return Response::Handled;
+        }
+        self.offset = offset;
+        Response::ValueChanged
+    }
+
+    fn track_len(&self) -> i32 {
+        match self.orientation {
+            Orientation::Horizontal => self.rect.dim.w,
+            Orientation::Vertical => self.rect.dim.h,
+        }
+    }
+
+    fn thumb(&self) -> (i32, i32) {
+        let track = self.track_len();
+        if self.content <= 0 {
+            return (0, track);
+        }
+        let len = (track as i64 * self.viewport as i64 / self.content as i64) as i32;
+        let len = len.clamp(self.style.min_thumb.min(track), track);
+        let start = (self.offset as i64 * (track - len) as i64
+            / self.max_offset().max(1) as i64) as i32;
+        (start, len)
+    }
+
+    pub fn thumb_rect(&self) -> Rect {
+        let (start, len) = self.thumb();
+        match self.orientation {
+            Orientation::Horizontal => rect(self.rect.pos + pos(start, 0), dim(len, self.rect.dim.h)),
+            Orientation::Vertical => rect(self.rect.pos + pos(0, start), dim(self.rect.dim.w, len)),
+        }
+    }
+
+    fn along(&self, loc: Pos) -> i32 {
+        match self.orientation {
+            Orientation::Horizontal => loc.x - self.rect.pos.x,
+            Orientation::Vertical => loc.y - self.rect.pos.y,
+        }
+    }
+
+    fn offset_for(&self, thumb_start: i32) -> i32 {
+        let (_, len) = self.thumb();
+        let free = (self.track_len() - len).max(1);
+        (thumb_start as i64 * self.max_offset() as i64 / free as i64) as i32
+    }
+}
+
+impl Widget for ScrollBar {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        if !self.needed() {
+            return;
+        }
+        buffer.fill_over(self.rect.area(), self.style.track);
+        let thumb = if self.grab.is_some() { self.style.thumb_active } else { self.style.thumb };
+        buffer.fill_over(self.thumb_rect().area(), thumb);
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        if !self.needed() {
+            return Response::Ignored;
+        }
+        match event {
+            PointerEvent::Down(loc, PointerButton::Left) if self.rect.contains(loc) => {
+                let at = self.along(loc);
+                let (start, len) = self.thumb();
+                if at >= start && at < start + len {
+                    self.grab = Some(at - start);
+                    Response::Handled
+                } else if at < start {
+                    self.set_offset(self.offset - self.viewport)
+                } else {
+                    self.set_offset(self.offset + self.viewport)
+                }
+            }
+            PointerEvent::Move(loc) if self.grab.is_some() => {
+                let start = self.along(loc) - self.grab.unwrap();
+                self.set_offset(self.offset_for(start))
+            }
+            PointerEvent::Up(_, PointerButton::Left) if self.grab.is_some() => {
+                self.grab = None;
+                Response::Handled
+            }
+            _ => Response::Ignored,
+        }
+    }
+}
+
+pub struct ScrollView<'a> {
+    pub rect: Rect,
+    pub step: i32,
+    child: Box<dyn Widget + 'a>,
+    content: Dim,
+    vbar: ScrollBar,
+    hbar: ScrollBar,
+}
+
+impl<'a> ScrollView<'a> {
+    pub fn new(rect: Rect, content: Dim, child: Box<dyn Widget + 'a>) -> Self {
+        let mut view = Self {
+            rect,
+            step: 24,
+            child,
+            content,
+            vbar: ScrollBar::new(rect, Orientation::Vertical),
+            hbar: ScrollBar::new(rect, Orientation::Horizontal),
+        };
+        view.layout();
+        view
+    }
+
+    pub fn child(&mut self) -> &mut (dyn Widget + 'a) {
+        self.child.as_mut()
+    }
+
+    pub fn set_content(&mut self, content: Dim) {
+        self.content = content;
+        self.layout();
+    }
+
+    pub fn offset(&self) -> Pos {
+        pos(self.hbar.offset(), self.vbar.offset())
+    }
+
+    pub fn scroll_to(&mut self, offset: Pos) -> Response {
+        let h = self.hbar.set_offset(offset.x);
+        let v = self.vbar.set_offset(offset.y);
+        self.place_child();
+        if h == Response::ValueChanged || v == Response::ValueChanged {
+            Response::ValueChanged
+        } else {
+            Response::Handled
+        }
+    }
+
+    pub fn scroll_into_view(&mut self, target: Rect) -> Response {
+        let viewport = self.viewport().dim;
+        let mut off = self.offset();
+        if target.pos.y < off.y {
+            off.y = target.pos.y;
+        } else if target.pos.y + target.dim.h > off.y + viewport.h {
+            off.y = target.pos.y + target.dim.h - viewport.h;
+        }
+        if target.pos.x < off.x {
+            off.x = target.pos.x;
+        } else if target.pos.x + target.dim.w > off.x + viewport.w {
+            off.x = target.pos.x + target.dim.w - viewport.w;
+        }
+        self.scroll_to(off)
+    }
+
+    pub fn viewport(&self) -> Rect {
+        let w = self.vbar.style.width;
+        let v = if self.content.h > self.rect.dim.h { w } else { 0 };
+        let h = if self.content.w > self.rect.dim.w - v { w } else { 0 };
+        rect(self.rect.pos, dim(self.rect.dim.w - v, self.rect.dim.h - h))
+    }
+
+    fn layout(&mut self) {
+        let viewport = self.viewport();
+        let r = self.rect;
+        let w = self.vbar.style.width;
+        self.vbar.set_rect(rect(pos(r.pos.x + viewport.dim.w, r.pos.y), dim(w, viewport.dim.h)));
+        self.hbar.set_rect(rect(pos(r.pos.x, r.pos.y + viewport.dim.h), dim(viewport.dim.w, w)));
+        self.vbar.set_extent(self.content.h, viewport.dim.h);
+        self.hbar.set_extent(self.content.w, viewport.dim.w);
+        self.place_child();
+    }
+
+    fn place_child(&mut self) {
+        let off = self.offset();
+        self.child.set_rect(rect(pos(-off.x, -off.y), self.content));
+    }
+
+    fn to_child(&self, event: PointerEvent) -> PointerEvent {
+        event.translate(pos(0, 0) - self.viewport().pos)
+    }
+}
+
+impl<'a> Widget for ScrollView<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.layout();
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let viewport = self.viewport();
+        let mut offscreen = Buffer::new_cleared(viewport.dim, Color::default());
+        self.child.draw(&mut offscreen);
+        buffer.premultiplied_over(&offscreen, offscreen.area(), viewport.pos);
+        self.vbar.draw(buffer);
+        self.hbar.draw(buffer);
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        let response = self.child.key(key);
+        if response.handled() {
+            return response;
+        }
+        let off = self.offset();
+        let page = self.viewport().dim.h;
+        match key {
+            KeyCode::Up => self.scroll_to(off - pos(0, self.step)),
+            KeyCode::Down => self.scroll_to(off + pos(0, self.step)),
+            KeyCode::Left => self.scroll_to(off - pos(self.step, 0)),
+            KeyCode::Right => self.scroll_to(off + pos(self.step, 0)),
+            KeyCode::PageUp => self.scroll_to(off - pos(0, page)),
+            KeyCode::PageDown => self.scroll_to(off + pos(0, page)),
+            KeyCode::Home => self.scroll_to(pos(off.x, 0)),
+            KeyCode::End => self.scroll_to(pos(off.x, self.content.h)),
+            _ => Response::Ignored,
+        }
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        let mut response = self.vbar.pointer(event);
+        if !response.handled() {
+            response = self.hbar.pointer(event);
+        }
+        if response.handled() {
+            self.place_child();
+            return response;
+        }
+        let inside = self.viewport().contains(event.pos());
+        let response = if inside || !matches!(event, PointerEvent::Down(..)) {
+            self.child.pointer(self.to_child(event))
+        } else {
+            Response::Ignored
+        };
+        if response.handled() {
+            return response;
+        }
+        match event {
+            PointerEvent::Wheel(loc, delta) if self.rect.contains(loc) => {
+                self.scroll_to(self.offset() - pos(0, delta * self.step))
+            }
+            _ => response,
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        self.child.focusable()
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.child.set_focused(focused);
+    }
+}
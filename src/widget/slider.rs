@@ -0,0 +1,194 @@
+use crate::gfx::{dim, pos, rect, Buffer, Color, Pos, Rect};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::theme::Theme;
+use crate::widget::{Orientation, Response, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SliderStyle {
+    pub track: Color,
+    pub fill: Color,
+    pub thumb: Color,
+    pub thumb_focused: Color,
+    pub thumb_disabled: Color,
+    pub track_width: i32,
+    pub thumb_size: i32,
+}
+
+impl From<&Theme> for SliderStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        Self {
+            track: p.control,
+            fill: theme.focus.color,
+            thumb: p.fg,
+            thumb_focused: p.fg_strong,
+            thumb_disabled: p.border,
+            track_width: 4,
+            thumb_size: 12,
+        }
+    }
+}
+
+impl Default for SliderStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub struct Slider {
+    pub rect: Rect,
+    pub orientation: Orientation,
+    pub min: i32,
+    pub max: i32,
+    pub step: i32,
+    pub style: SliderStyle,
+    pub enabled: bool,
+    value: i32,
+    focused: bool,
+    dragging: bool,
+}
+
+impl Slider {
+    pub fn new(rect: Rect, orientation: Orientation, min: i32, max: i32, step: i32) -> Self {
+        Self {
+            rect,
+            orientation,
+            min,
+            max: max.max(min),
+            step: step.max(1),
+            style: SliderStyle::default(),
+            enabled: true,
+            value: min,
+            focused: false,
+            dragging: false,
+        }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    pub fn set_value(&mut self, value: i32) -> Response {
+        let value = value.clamp(self.min, self.max);
+        let value = self.min + (value - self.min + self.step / 2) / self.step * self.step;
+        let value = value.min(self.max);
+        if value == self.value {
+            return Response::Handled;
+        }
+        self.value = value;
+        Response::ValueChanged
+    }
+
+    fn length(&self) -> i32 {
+        let len = match self.orientation {
+            Orientation::Horizontal => self.rect.dim.w,
+            Orientation::Vertical => self.rect.dim.h,
+        };
+        (len - self.style.thumb_size).max(1)
+    }
+
+    fn thumb_offset(&self) -> i32 {
+        let range = (self.max - self.min).max(1) as i64;
+        ((self.value - self.min) as i64 * self.length() as i64 / range) as i32
+    }
+
+    fn value_at(&self, loc: Pos) -> i32 {
+        let rel = match self.orientation {
+            Orientation::Horizontal => loc.x - self.rect.pos.x,
+            Orientation::Vertical => self.rect.pos.y + self.rect.dim.h - loc.y,
+        } - self.style.thumb_size / 2;
+        let rel = rel.clamp(0, self.length()) as i64;
+        self.min + (rel * (self.max - self.min) as i64 / self.length() as i64) as i32
+    }
+}
+
+impl Widget for Slider {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        let r = self.rect;
+        let off = self.thumb_offset();
+        let half = s.thumb_size / 2;
+        let (track, fill, thumb) = match self.orientation {
+            Orientation::Horizontal => {
+                let y = r.pos.y + (r.dim.h - s.track_width) / 2;
+                let track = rect(pos(r.pos.x + half, y), dim(r.dim.w - s.thumb_size, s.track_width));
+                let fill = track.resize(dim(off, s.track_width));
+                let thumb = rect(pos(r.pos.x + off, r.pos.y), dim(s.thumb_size, r.dim.h));
+                (track, fill, thumb)
+            }
+            Orientation::Vertical => {
+                let x = r.pos.x + (r.dim.w - s.track_width) / 2;
+                let track = rect(pos(x, r.pos.y + half), dim(s.track_width, r.dim.h - s.thumb_size));
+                let bottom = r.pos.y + r.dim.h - half;
+                let fill = rect(pos(x, bottom - off), dim(s.track_width, off));
+                let thumb = rect(pos(r.pos.x, bottom - off - half), dim(r.dim.w, s.thumb_size));
+                (track, fill, thumb)
+            }
+        };
+        let thumb_color = if !self.enabled {
+            s.thumb_disabled
+        } else if self.focused || self.dragging {
+            s.thumb_focused
+        } else {
+            s.thumb
+        };
+        buffer.fill_over(track.area(), s.track);
+        buffer.fill_over(fill.area(), s.fill);
+        buffer.fill_over(thumb.area(), thumb_color);
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        if !self.enabled {
+            return Response::Ignored;
+        }
+        let big = ((self.max - self.min) / 10).max(self.step);
+        match (self.orientation, key) {
+            (Orientation::Horizontal, KeyCode::Left) |
+            (Orientation::Vertical, KeyCode::Down) => self.set_value(self.value - self.step),
+            (Orientation::Horizontal, KeyCode::Right) |
+            (Orientation::Vertical, KeyCode::Up) => self.set_value(self.value + self.step),
+            (_, KeyCode::PageDown) => self.set_value(self.value - big),
+            (_, KeyCode::PageUp) => self.set_value(self.value + big),
+            (_, KeyCode::Home) => self.set_value(self.min),
+            (_, KeyCode::End) => self.set_value(self.max),
+            _ => Response::Ignored,
+        }
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        if !self.enabled {
+            return Response::Ignored;
+        }
+        match event {
+            PointerEvent::Down(loc, PointerButton::Left) if self.rect.contains(loc) => {
+                self.dragging = true;
+                self.set_value(self.value_at(loc))
+            }
+            PointerEvent::Move(loc) if self.dragging => self.set_value(self.value_at(loc)),
+            PointerEvent::Up(loc, PointerButton::Left) if self.dragging => {
+                self.dragging = false;
+                self.set_value(self.value_at(loc))
+            }
+            PointerEvent::Wheel(loc, delta) if self.rect.contains(loc) => {
+                self.set_value(self.value - delta * self.step)
+            }
+            _ => Response::Ignored,
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+}
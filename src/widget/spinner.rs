@@ -0,0 +1,64 @@
+use core::f32::consts::TAU;
+
+use crate::gfx::{dim, pos, Buffer, Color, Math, Rect};
+use crate::widget::Widget;
+
+pub struct Spinner {
+    pub rect: Rect,
+    pub segments: u32,
+    pub revolutions_per_sec: f32,
+    pub color: Color,
+    pub dot_radius: i32,
+    pub min_alpha: u8,
+    phase: f32,
+}
+
+impl Spinner {
+    pub fn new(rect: Rect, color: Color) -> Self {
+        Self {
+            rect,
+            segments: 12,
+            revolutions_per_sec: 1.0,
+            color,
+            dot_radius: (rect.dim.w.min(rect.dim.h) / 12).max(1),
+            min_alpha: 0x30,
+            phase: 0.0,
+        }
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.phase = Math::fract(self.phase + dt * self.revolutions_per_sec);
+    }
+
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+}
+
+impl Widget for Spinner {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let segments = self.segments.max(1);
+        let center = self.rect.centered(dim(0, 0)).pos;
+        let radius = (self.rect.dim.w.min(self.rect.dim.h) / 2 - self.dot_radius - 1) as f32;
+        let head = self.phase * segments as f32;
+        for i in 0..segments {
+            let angle = i as f32 / segments as f32 * TAU;
+            let behind = Math::fract((head - i as f32) / segments as f32);
+            let span = 255 - self.min_alpha as u32;
+            let alpha = self.min_alpha as u32 + ((1.0 - behind) * span as f32) as u32;
+            let dot = center + pos(
+                (Math::sin(angle) * radius) as i32,
+                (-Math::cos(angle) * radius) as i32,
+            );
+            buffer.fill_circle(dot, self.dot_radius, self.color.apply_alpha(alpha as u8));
+        }
+    }
+}
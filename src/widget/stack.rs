@@ -0,0 +1,262 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::gfx::{dim, pos, rect, Buffer, Dim, Insets, Rect};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::widget::{Align, Orientation, Response, Widget};
+
+pub struct StackItem<'a> {
+    pub widget: Box<dyn Widget + 'a>,
+    pub margin: Insets,
+    pub align: Option<Align>,
+    pub grow: u32,
+}
+
+impl<'a> StackItem<'a> {
+    pub fn new(widget: Box<dyn Widget + 'a>) -> Self {
+        Self { widget, margin: Insets::default(), align: None, grow: 0 }
+    }
+
+    pub fn margin(mut self, margin: Insets) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = Some(align);
+        self
+    }
+
+    pub fn grow(mut self, grow: u32) -> Self {
+        self.grow = grow;
+        self
+    }
+}
+
+pub struct Stack<'a> {
+    pub rect: Rect,
+    pub orientation: Orientation,
+    pub padding: Insets,
+    pub spacing: i32,
+    pub justify: Align,
+    pub align: Align,
+    pub stretch: bool,
+    items: Vec<StackItem<'a>>,
+    focus: Option<usize>,
+    focused: bool,
+}
+
+pub struct HStack {}
+
+impl HStack {
+    pub fn new<'a>(rect: Rect) -> Stack<'a> {
+        Stack::new(rect, Orientation::Horizontal)
+    }
+}
+
+pub struct VStack {}
+
+impl VStack {
+    pub fn new<'a>(rect: Rect) -> Stack<'a> {
+        Stack::new(rect, Orientation::Vertical)
+    }
+}
+
+fn main_of(orientation: Orientation, d: Dim) -> i32 {
+    match orientation {
+        Orientation::Horizontal => d.w,
+        Orientation::Vertical => d.h,
+    }
+}
+
+fn cross_of(orientation: Orientation, d: Dim) -> i32 {
+    match orientation {
+        Orientation::Horizontal => d.h,
+        Orientation::Vertical => d.w,
+    }
+}
+
+fn aligned(align: Align, space: i32, size: i32) -> i32 {
+    match align {
+        Align::Start => 0,
+        Align::Center => (space - size) / 2,
+        Align::End => space - size,
+    }
+}
+
+impl<'a> Stack<'a> {
+    pub fn new(rect: Rect, orientation: Orientation) -> Self {
+        Self {
+            rect,
+            orientation,
+            padding: Insets::default(),
+            spacing: 0,
+            justify: Align::Start,
+            align: Align::Start,
+            stretch: true,
+            items: Vec::new(),
+            focus: None,
+            focused: false,
+        }
+    }
+
+    pub fn padding(mut self, padding: Insets) -> Self {
+        self.padding = padding;
+        self.layout();
+        self
+    }
+
+    pub fn spacing(mut self, spacing: i32) -> Self {
+        self.spacing = spacing;
+        self.layout();
+        self
+    }
+
+    pub fn push(&mut self, widget: Box<dyn Widget + 'a>) {
+        self.push_item(StackItem::new(widget));
+    }
+
+    pub fn push_item(&mut self, item: StackItem<'a>) {
+        self.items.push(item);
+        self.layout();
+    }
+
+    pub fn items(&self) -> &[StackItem<'a>] {
+        &self.items
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut (dyn Widget + 'a)> {
+        self.items.get_mut(index).map(|x| x.widget.as_mut())
+    }
+
+    pub fn layout(&mut self) {
+        let o = self.orientation;
+        let inner = self.rect.inset(self.padding);
+        let sizes: Vec<Dim> = self.items.iter()
+            .map(|x| x.widget.measure() + x.margin.dim())
+            .collect();
+        let gaps = self.spacing * (self.items.len() as i32 - 1).max(0);
+        let used: i32 = sizes.iter().map(|x| main_of(o, *x)).sum::<i32>() + gaps;
+        let extra = (main_of(o, inner.dim) - used).max(0);
+        let grow: u32 = self.items.iter().map(|x| x.grow).sum();
+        let mut cursor = if grow > 0 { 0 } else { aligned(self.justify, extra, 0) };
+        let cross_space = cross_of(o, inner.dim);
+        for (item, size) in self.items.iter_mut().zip(sizes) {
+            let mut main = main_of(o, size);
+            if grow > 0 {
+                main += (extra as i64 * item.grow as i64 / grow as i64) as i32;
+            }
+            let m = item.margin;
+            let (main_margin, cross_margin) = match o {
+                Orientation::Horizontal => ((m.left, m.horizontal()), (m.top, m.vertical())),
+                Orientation::Vertical => ((m.top, m.vertical()), (m.left, m.horizontal())),
+            };
+            let slot = cross_space - cross_margin.1;
+            let (cross, offset) = match item.align {
+                None if self.stretch => (slot, 0),
+                align => {
+                    let cross = (cross_of(o, size) - cross_margin.1).min(slot);
+                    (cross, aligned(align.unwrap_or(self.align), slot, cross))
+                }
+            };
+            let along = cursor + main_margin.0;
+            let across = cross_margin.0 + offset;
+            let len = main - main_margin.1;
+            let r = match o {
+                Orientation::Horizontal => rect(inner.pos + pos(along, across), dim(len, cross)),
+                Orientation::Vertical => rect(inner.pos + pos(across, along), dim(cross, len)),
+            };
+            item.widget.set_rect(r);
+            cursor += main + self.spacing;
+        }
+    }
+
+    fn first_focusable(&self) -> Option<usize> {
+        self.items.iter().position(|x| x.widget.focusable())
+    }
+
+    fn set_focus(&mut self, index: Option<usize>) {
+        if self.focus == index {
+            return;
+        }
+        if let Some(old) = self.focus {
+            self.items[old].widget.set_focused(false);
+        }
+        if let Some(new) = index {
+            self.items[new].widget.set_focused(self.focused);
+        }
+        self.focus = index;
+    }
+}
+
+impl<'a> Widget for Stack<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.layout();
+    }
+
+    fn measure(&self) -> Dim {
+        let o = self.orientation;
+        let gaps = self.spacing * (self.items.len() as i32 - 1).max(0);
+        let mut main = gaps;
+        let mut cross = 0;
+        for item in self.items.iter() {
+            let size = item.widget.measure() + item.margin.dim();
+            main += main_of(o, size);
+            cross = cross.max(cross_of(o, size));
+        }
+        let content = match o {
+            Orientation::Horizontal => dim(main, cross),
+            Orientation::Vertical => dim(cross, main),
+        };
+        content + self.padding.dim()
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        for item in self.items.iter() {
+            item.widget.draw(buffer);
+        }
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        match self.focus {
+            Some(index) => self.items[index].widget.key(key),
+            None => Response::Ignored,
+        }
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        if let PointerEvent::Down(loc, PointerButton::Left) = event {
+            let hit = self.items.iter()
+                .position(|x| x.widget.focusable() && x.widget.rect().contains(loc));
+            if hit.is_some() {
+                self.set_focus(hit);
+            }
+        }
+        for item in self.items.iter_mut() {
+            let response = item.widget.pointer(event);
+            if response.handled() {
+                return response;
+            }
+        }
+        Response::Ignored
+    }
+
+    fn focusable(&self) -> bool {
+        self.first_focusable().is_some()
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if self.focus.is_none() {
+            self.focus = self.first_focusable();
+        }
+        if let Some(index) = self.focus {
+            self.items[index].widget.set_focused(focused);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::env;
+use crate::gfx::{self, dim, pos, rect, Buffer, Color, Dim, Rect};
+use crate::theme::Theme;
+use crate::time::DateTime;
+use crate::widget::Widget;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct StatusBarStyle {
+    pub bg: Color,
+    pub title: Color,
+    pub label: Color,
+    pub value: Color,
+    pub separator: Color,
+    pub clock: Color,
+    pub height: i32,
+    pub padding: i32,
+    pub spacing: i32,
+}
+
+impl From<&Theme> for StatusBarStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            bg: p.base,
+            title: p.fg_strong,
+            label: p.fg_dim,
+            value: p.fg,
+            separator: p.border,
+            clock: p.fg_strong,
+            height: m.row_height + 4,
+            padding: m.dialog_padding,
+            spacing: m.spacing * 2,
+        }
+    }
+}
+
+impl Default for StatusBarStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct StatusField {
+    pub label: String,
+    pub value: String,
+}
+
+pub fn firmware_vendor() -> String {
+    let st = if let Some(x) = env::try_system_table() { x } else { return String::new(); };
+    let revision = st.firmware_revision();
+    format!("{} {}.{}", st.firmware_vendor(), revision >> 16, revision & 0xFFFF)
+}
+
+pub fn uefi_revision() -> String {
+    let st = if let Some(x) = env::try_system_table() { x } else { return String::new(); };
+    let revision = st.uefi_revision();
+    format!("{}.{}", revision.major(), revision.minor())
+}
+
+#[cfg(target_arch = "x86_64")]
+#[allow(unused_unsafe)]
+pub fn cpu_brand() -> Option<String> {
+    use core::arch::x86_64::__cpuid;
+    if unsafe { __cpuid(0x8000_0000) }.eax < 0x8000_0004 {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(48);
+    for leaf in 0x8000_0002..=0x8000_0004u32 {
+        let r = unsafe { __cpuid(leaf) };
+        for reg in [r.eax, r.ebx, r.ecx, r.edx] {
+            bytes.extend_from_slice(&reg.to_le_bytes());
+        }
+    }
+    let end = bytes.iter().position(|x| *x == 0).unwrap_or(bytes.len());
+    core::str::from_utf8(&bytes[..end]).ok().map(|x| x.trim().to_string())
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn cpu_brand() -> Option<String> {
+    None
+}
+
+pub struct StatusBar<'a> {
+    pub rect: Rect,
+    pub style: StatusBarStyle,
+    pub title: String,
+    pub fields: Vec<StatusField>,
+    pub show_clock: bool,
+    pub show_date: bool,
+    clock: Option<DateTime>,
+    font: &'a Font,
+}
+
+impl<'a> StatusBar<'a> {
+    pub fn new(rect: Rect, font: &'a Font, title: &str) -> Self {
+        Self {
+            rect,
+            style: StatusBarStyle::default(),
+            title: title.to_string(),
+            fields: Vec::new(),
+            show_clock: true,
+            show_date: false,
+            clock: None,
+            font,
+        }
+    }
+
+    pub fn style(mut self, style: StatusBarStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn date(mut self, show_date: bool) -> Self {
+        self.show_date = show_date;
+        self
+    }
+
+    pub fn field(mut self, label: &str, value: &str) -> Self {
+        self.set_field(label, value);
+        self
+    }
+
+    pub fn firmware_info(mut self) -> Self {
+        self.set_field("Firmware", &firmware_vendor());
+        self.set_field("UEFI", &uefi_revision());
+        if let Some(cpu) = cpu_brand() {
+            self.set_field("CPU", &cpu);
+        }
+        self
+    }
+
+    pub fn set_field(&mut self, label: &str, value: &str) {
+        match self.fields.iter_mut().find(|x| x.label == label) {
+            Some(field) => field.value = value.to_string(),
+            None => self.fields.push(StatusField { label: label.to_string(), value: value.to_string() }),
+        }
+    }
+
+    pub fn remove_field(&mut self, label: &str) {
+        self.fields.retain(|x| x.label != label);
+    }
+
+    fn clock_text(&self) -> Option<String> {
+        let t = self.clock?;
+        Some(if self.show_date {
+            format!("{} {}", t.date_string(), t.time_string())
+        } else {
+            t.time_string()
+        })
+    }
+
+    pub fn clock_rect(&self) -> Rect {
+        let sample = if self.show_date { "0000-00-00 00:00:00" } else { "00:00:00" };
+        let w = gfx::text_dim(self.font, sample).w + self.style.padding * 2;
+        let r = self.rect;
+        rect(pos(r.pos.x + r.dim.w - w, r.pos.y), dim(w, r.dim.h))
+    }
+
+    pub fn tick(&mut self) -> Option<Rect> {
+        if !self.show_clock {
+            return None;
+        }
+        let now = DateTime::now();
+        let same = match (&now, &self.clock) {
+            (Some(a), Some(b)) => a.same_second(b),
+            (None, None) => true,
+            _ => false,
+        };
+        if same {
+            return None;
+        }
+        self.clock = now;
+        Some(self.clock_rect())
+    }
+}
+
+impl<'a> Widget for StatusBar<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn measure(&self) -> Dim {
+        dim(self.rect.dim.w, self.style.height)
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        let r = self.rect;
+        buffer.fill(r.area(), s.bg);
+        let text_y = r.pos.y + (r.dim.h - gfx::text_dim(self.font, "0").h) / 2;
+        let mut x = r.pos.x + s.padding;
+        buffer.draw_text(pos(x, text_y), self.font, &self.title, s.title);
+        x += gfx::text_dim(self.font, &self.title).w + s.spacing;
+        let limit = if self.show_clock { self.clock_rect().pos.x } else { r.pos.x + r.dim.w };
+        for field in self.fields.iter() {
+            let label = format!("{}: ", field.label);
+            let label_w = gfx::text_dim(self.font, &label).w;
+            let value_w = gfx::text_dim(self.font, &field.value).w;
+            if x + label_w + value_w > limit {
+                break;
+            }
+            buffer.fill(rect(pos(x - s.spacing / 2, r.pos.y + 4), dim(1, r.dim.h - 8)).area(), s.separator);
+            buffer.draw_text(pos(x, text_y), self.font, &label, s.label);
+            buffer.draw_text(pos(x + label_w, text_y), self.font, &field.value, s.value);
+            x += label_w + value_w + s.spacing;
+        }
+        if let Some(text) = self.clock_text() {
+            let clock = self.clock_rect();
+            let w = gfx::text_dim(self.font, &text).w;
+            let x = clock.pos.x + clock.dim.w - s.padding - w;
+            buffer.draw_text(pos(x, text_y), self.font, &text, s.clock);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, Buffer, Color, Rect, View};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::theme::Theme;
+use crate::widget::{Align, Response, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ColumnWidth {
+    Fixed(i32),
+    Auto,
+    Star(u32),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Column {
+    pub title: String,
+    pub align: Align,
+    pub width: ColumnWidth,
+}
+
+impl Column {
+    pub fn new(title: &str, width: ColumnWidth) -> Self {
+        Self { title: String::from(title), align: Align::Start, width }
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TableStyle {
+    pub bg: Color,
+    pub fg: Color,
+    pub header_bg: Color,
+    pub header_fg: Color,
+    pub grid: Color,
+    pub selected_bg: Color,
+    pub selected_fg: Color,
+    pub scrollbar: Color,
+    pub padding: i32,
+}
+
+impl From<&Theme> for TableStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            bg: p.base,
+            fg: p.fg,
+            header_bg: p.raised,
+            header_fg: p.fg_strong,
+            grid: p.line,
+            selected_bg: p.accent,
+            selected_fg: p.accent_fg,
+            scrollbar: p.muted,
+            padding: m.padding + 2,
+        }
+    }
+}
+
+impl Default for TableStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub struct Table<'a> {
+    pub rect: Rect,
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<String>>,
+    pub row_height: i32,
+    pub style: TableStyle,
+    selected: usize,
+    scroll: usize,
+    focused: bool,
+    font: &'a Font,
+}
+
+impl<'a> Table<'a> {
+    pub fn new(rect: Rect, font: &'a Font, row_height: i32, columns: Vec<Column>) -> Self {
+        Self {
+            rect,
+            columns,
+            rows: Vec::new(),
+            row_height: row_height.max(1),
+            style: TableStyle::default(),
+            selected: 0,
+            scroll: 0,
+            focused: false,
+            font,
+        }
+    }
+
+    pub fn push_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        if self.rows.is_empty() { None } else { Some(self.selected) }
+    }
+
+    pub fn selected_row(&self) -> Option<&[String]> {
+        self.rows.get(self.selected).map(|x| x.as_slice())
+    }
+
+    fn visible_rows(&self) -> usize {
+        ((self.rect.dim.h - self.row_height) / self.row_height).max(1) as usize
+    }
+
+    pub fn select(&mut self, index: usize) -> Response {
+        if self.rows.is_empty() {
+            return Response::Ignored;
+        }
+        let index = index.min(self.rows.len() - 1);
+        if index == self.selected {
+            return Response::Handled;
+        }
+        self.selected = index;
+        let visible = self.visible_rows();
+        if index < self.scroll {
+            self.scroll = index;
+        } else if index >= self.scroll + visible {
+            self.scroll = index + 1 - visible;
+        }
+        Response::ValueChanged
+    }
+
+    pub fn column_widths(&self) -> Vec<i32> {
+        let pad = self.style.padding * 2;
+        let mut widths: Vec<i32> = self.columns.iter().enumerate().map(|(i, col)| {
+            match col.width {
+                ColumnWidth::Fixed(w) => w,
+                ColumnWidth::Auto => self.rows.iter()
+                    .filter_map(|row| row.get(i))
+                    .map(|cell| gfx::text_dim(self.font, cell).w)
+                    .chain(core::iter::once(gfx::text_dim(self.font, &col.title).w))
+                    .max().unwrap_or(0) + pad,
+                ColumnWidth::Star(_) => 0,
+            }
+        }).collect();
+        let used: i32 = widths.iter().sum();
+        let stars: u32 = self.columns.iter().map(|x| match x.width {
+            ColumnWidth::Star(n) => n,
+            _ => 0,
+        }).sum();
+        let rest = (self.rect.dim.w - used).max(0);
+        if stars > 0 {
+            for (width, col) in widths.iter_mut().zip(self.columns.iter()) {
+                if let ColumnWidth::Star(n) = col.width {
+                    *width = (rest as i64 * n as i64 / stars as i64) as i32;
+                }
+            }
+        }
+        widths
+    }
+
+    fn draw_cell(&self, view: &mut View, cell: Rect, text: &str, align: Align, color: Color) {
+        let sz = gfx::text_dim(self.font, text);
+        let pad = self.style.padding;
+        let x = match align {
+            Align::Start => cell.pos.x + pad,
+            Align::Center => cell.pos.x + (cell.dim.w - sz.w) / 2,
+            Align::End => cell.pos.x + cell.dim.w - pad - sz.w,
+        };
+        let y = cell.pos.y + (cell.dim.h - sz.h) / 2;
+        let mut cell_view = view.view(cell);
+        let local = pos(x, y) - cell.pos;
+        cell_view.draw_text(local, self.font, text, color);
+    }
+}
+
+impl<'a> Widget for Table<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        let visible = self.visible_rows();
+        if self.selected >= self.scroll + visible {
+            self.scroll = self.selected + 1 - visible;
+        }
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        let widths = self.column_widths();
+        let rh = self.row_height;
+        buffer.fill_over(self.rect.area(), s.bg);
+        let mut view = View::new(buffer, self.rect);
+        view.fill_over(rect(pos(0, 0), dim(self.rect.dim.w, rh)).area(), s.header_bg);
+        let mut x = 0;
+        for (col, w) in self.columns.iter().zip(widths.iter()) {
+            self.draw_cell(&mut view, rect(pos(x, 0), dim(*w, rh)), &col.title, col.align, s.header_fg);
+            x += w;
+            view.fill_over(rect(pos(x - 1, 0), dim(1, self.rect.dim.h)).area(), s.grid);
+        }
+        let last = (self.scroll + self.visible_rows()).min(self.rows.len());
+        for (n, index) in (self.scroll..last).enumerate() {
+            let y = rh * (n as i32 + 1);
+            let selected = index == self.selected;
+            if selected {
+                let bg = if self.focused { s.selected_bg } else { s.grid };
+                view.fill_over(rect(pos(0, y), dim(self.rect.dim.w, rh)).area(), bg);
+            }
+            let fg = if selected { s.selected_fg } else { s.fg };
+            let mut x = 0;
+            for ((col, w), cell) in self.columns.iter().zip(widths.iter()).zip(self.rows[index].iter()) {
+                self.draw_cell(&mut view, rect(pos(x, y), dim(*w, rh)), cell, col.align, fg);
+                x += w;
+            }
+        }
+        let visible = self.visible_rows();
+        if self.rows.len() > visible {
+            let track = self.rect.dim.h - rh;
+            let h = (track as i64 * visible as i64 / self.rows.len() as i64) as i32;
+            let y = rh + (track - h) * self.scroll as i32 / (self.rows.len() - visible) as i32;
+            view.fill_over(rect(pos(self.rect.dim.w - 3, y), dim(3, h)).area(), s.scrollbar);
+        }
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        let page = self.visible_rows();
+        match key {
+            KeyCode::Up => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Down => self.select(self.selected + 1),
+            KeyCode::PageUp => self.select(self.selected.saturating_sub(page)),
+            KeyCode::PageDown => self.select(self.selected + page),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(self.rows.len().saturating_sub(1)),
+            KeyCode::Enter if !self.rows.is_empty() => Response::Clicked,
+            _ => Response::Ignored,
+        }
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        if !self.rect.contains(event.pos()) {
+            return Response::Ignored;
+        }
+        match event {
+            PointerEvent::Down(loc, PointerButton::Left) => {
+                let row = (loc.y - self.rect.pos.y) / self.row_height;
+                if row == 0 {
+                    return Response::Handled;
+                }
+                let index = self.scroll + row as usize - 1;
+                if index >= self.rows.len() {
+                    return Response::Handled;
+                }
+                if index == self.selected { Response::Clicked } else { self.select(index) }
+            }
+            PointerEvent::Wheel(_, delta) => {
+                let max = self.rows.len().saturating_sub(self.visible_rows());
+                self.scroll = (self.scroll as i32 - delta).clamp(0, max as i32) as usize;
+                Response::Handled
+            }
+            _ => Response::Ignored,
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+}
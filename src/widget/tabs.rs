@@ -0,0 +1,191 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, Buffer, Color, Rect};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::theme::Theme;
+use crate::widget::{Response, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TabStyle {
+    pub strip_bg: Color,
+    pub tab_bg: Color,
+    pub tab_fg: Color,
+    pub active_bg: Color,
+    pub active_fg: Color,
+    pub focus: Color,
+    pub content_bg: Color,
+    pub strip_height: i32,
+    pub padding: i32,
+}
+
+impl From<&Theme> for TabStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            strip_bg: p.input,
+            tab_bg: p.surface,
+            tab_fg: p.fg_dim,
+            active_bg: p.raised,
+            active_fg: p.fg_strong,
+            focus: theme.focus.color,
+            content_bg: p.raised,
+            strip_height: m.row_height + 8,
+            padding: m.dialog_padding,
+        }
+    }
+}
+
+impl Default for TabStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub struct Tab<'a> {
+    pub title: String,
+    pub content: Box<dyn Widget + 'a>,
+}
+
+pub struct TabView<'a> {
+    pub rect: Rect,
+    pub style: TabStyle,
+    tabs: Vec<Tab<'a>>,
+    active: usize,
+    focused: bool,
+    font: &'a Font,
+}
+
+impl<'a> TabView<'a> {
+    pub fn new(rect: Rect, font: &'a Font) -> Self {
+        Self {
+            rect,
+            style: TabStyle::default(),
+            tabs: Vec::new(),
+            active: 0,
+            focused: false,
+            font,
+        }
+    }
+
+    pub fn content_rect(&self) -> Rect {
+        let h = self.style.strip_height;
+        rect(self.rect.pos + pos(0, h), dim(self.rect.dim.w, (self.rect.dim.h - h).max(0)))
+    }
+
+    pub fn push(&mut self, title: &str, mut content: Box<dyn Widget + 'a>) {
+        content.set_rect(self.content_rect());
+        self.tabs.push(Tab { title: String::from(title), content });
+    }
+
+    pub fn tabs(&self) -> &[Tab<'a>] {
+        &self.tabs
+    }
+
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    pub fn active_content(&mut self) -> Option<&mut (dyn Widget + 'a)> {
+        self.tabs.get_mut(self.active).map(|x| x.content.as_mut())
+    }
+
+    pub fn set_active(&mut self, index: usize) -> Response {
+        if index >= self.tabs.len() || index == self.active {
+            return Response::Handled;
+        }
+        if self.focused {
+            self.tabs[self.active].content.set_focused(false);
+            self.tabs[index].content.set_focused(true);
+        }
+        self.active = index;
+        Response::ValueChanged
+    }
+
+    fn tab_rects(&self) -> Vec<Rect> {
+        let mut x = self.rect.pos.x;
+        self.tabs.iter().map(|tab| {
+            let w = gfx::text_dim(self.font, &tab.title).w + self.style.padding * 2;
+            let r = rect(pos(x, self.rect.pos.y), dim(w, self.style.strip_height));
+            x += w + 2;
+            r
+        }).collect()
+    }
+}
+
+impl<'a> Widget for TabView<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        let content = self.content_rect();
+        for tab in self.tabs.iter_mut() {
+            tab.content.set_rect(content);
+        }
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        buffer.fill_over(rect(self.rect.pos, dim(self.rect.dim.w, s.strip_height)).area(), s.strip_bg);
+        for (index, (tab, r)) in self.tabs.iter().zip(self.tab_rects()).enumerate() {
+            let active = index == self.active;
+            buffer.fill_over(r.area(), if active { s.active_bg } else { s.tab_bg });
+            if active && self.focused {
+                buffer.fill_over(rect(r.pos, dim(r.dim.w, 2)).area(), s.focus);
+            }
+            let sz = gfx::text_dim(self.font, &tab.title);
+            let fg = if active { s.active_fg } else { s.tab_fg };
+            buffer.draw_text(r.centered(sz).pos, self.font, &tab.title, fg);
+        }
+        buffer.fill_over(self.content_rect().area(), s.content_bg);
+        if let Some(tab) = self.tabs.get(self.active) {
+            tab.content.draw(buffer);
+        }
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        if let Some(tab) = self.tabs.get_mut(self.active) {
+            let response = tab.content.key(key);
+            if response.handled() {
+                return response;
+            }
+        }
+        match key {
+            KeyCode::Left if self.active > 0 => self.set_active(self.active - 1),
+            KeyCode::Right => self.set_active(self.active + 1),
+            KeyCode::F(n) if n >= 1 && (n as usize) <= self.tabs.len() => {
+                self.set_active(n as usize - 1)
+            }
+            _ => Response::Ignored,
+        }
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        if let PointerEvent::Down(loc, PointerButton::Left) = event {
+            if let Some(index) = self.tab_rects().iter().position(|x| x.contains(loc)) {
+                return self.set_active(index);
+            }
+        }
+        match self.tabs.get_mut(self.active) {
+            Some(tab) => tab.content.pointer(event),
+            None => Response::Ignored,
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if let Some(tab) = self.tabs.get_mut(self.active) {
+            tab.content.set_focused(focused);
+        }
+    }
+}
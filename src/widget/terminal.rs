@@ -0,0 +1,518 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::fmt;
+use core::ptr;
+
+use baked_font::Font;
+use uefi::proto::console::text::Output;
+use uefi::{Handle, Identify, Status};
+
+use crate::env;
+use crate::hash::crc32;
+use crate::gfx::{self, dim, pos, rect, rgb, Buffer, Color, Dim, Rect, Screen};
+use crate::input::SerialConsole;
+use crate::widget::Widget;
+
+pub const EFI_PALETTE: [Color; 16] = [
+    rgb(0x00, 0x00, 0x00),
+    rgb(0x00, 0x00, 0xAA),
+    rgb(0x00, 0xAA, 0x00),
+    rgb(0x00, 0xAA, 0xAA),
+    rgb(0xAA, 0x00, 0x00),
+    rgb(0xAA, 0x00, 0xAA),
+    rgb(0xAA, 0x55, 0x00),
+    rgb(0xAA, 0xAA, 0xAA),
+    rgb(0x55, 0x55, 0x55),
+    rgb(0x55, 0x55, 0xFF),
+    rgb(0x55, 0xFF, 0x55),
+    rgb(0x55, 0xFF, 0xFF),
+    rgb(0xFF, 0x55, 0x55),
+    rgb(0xFF, 0x55, 0xFF),
+    rgb(0xFF, 0xFF, 0x55),
+    rgb(0xFF, 0xFF, 0xFF),
+];
+
+const DEFAULT_ATTR: u8 = 0x07;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+struct Cell {
+    ch: char,
+    attr: u8,
+}
+
+const BLANK: Cell = Cell { ch: ' ', attr: DEFAULT_ATTR };
+
+pub struct Terminal<'a> {
+    pub rect: Rect,
+    pub palette: [Color; 16],
+    cols: usize,
+    rows: usize,
+    cell: Dim,
+    cells: Vec<Cell>,
+    cursor: (usize, usize),
+    attr: u8,
+    cursor_visible: bool,
+    dirty: Option<Rect>,
+    mirror: Option<SerialConsole>,
+    font: &'a Font,
+}
+
+impl<'a> Terminal<'a> {
+    pub fn new(rect: Rect, font: &'a Font) -> Self {
+        let cell = gfx::text_dim(font, "M");
+        let cell = dim(cell.w.max(1), cell.h.max(1));
+        let cols = (rect.dim.w / cell.w).max(1) as usize;
+        let rows = (rect.dim.h / cell.h).max(1) as usize;
+        Self {
+            rect,
+            palette: EFI_PALETTE,
+            cols,
+            rows,
+            cell,
+            cells: vec![BLANK; cols * rows],
+            cursor: (0, 0),
+            attr: DEFAULT_ATTR,
+            cursor_visible: true,
+            dirty: Some(rect),
+            mirror: None,
+            font,
+        }
+    }
+
+    pub fn mirror(mut self, serial: SerialConsole) -> Self {
+        self.mirror = Some(serial);
+        self
+    }
+
+    pub fn take_mirror(&mut self) -> Option<SerialConsole> {
+        self.mirror.take()
+    }
+
+    pub fn grid(mut self, cols: usize, rows: usize) -> Self {
+        self.resize(cols, rows);
+        self
+    }
+
+    pub fn resize(&mut self, cols: usize, rows: usize) {
+        let (cols, rows) = (cols.max(1), rows.max(1));
+        let mut cells = vec![BLANK; cols * rows];
+        for row in 0..rows.min(self.rows) {
+            for col in 0..cols.min(self.cols) {
+                cells[row * cols + col] = self.cells[row * self.cols + col];
+            }
+        }
+        self.cells = cells;
+        self.cols = cols;
+        self.rows = rows;
+        self.cursor = (self.cursor.0.min(cols - 1), self.cursor.1.min(rows - 1));
+        self.dirty = Some(self.rect);
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.cols, self.rows)
+    }
+
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+
+    pub fn attribute(&self) -> u8 {
+        self.attr
+    }
+
+    pub fn set_attribute(&mut self, attr: u8) {
+        self.attr = attr & 0x7F;
+    }
+
+    pub fn set_colors(&mut self, fg: u8, bg: u8) {
+        self.set_attribute((fg & 0x0F) | ((bg & 0x07) << 4));
+    }
+
+    pub fn set_cursor(&mut self, col: usize, row: usize) -> bool {
+        if col >= self.cols || row >= self.rows {
+            return false;
+        }
+        self.invalidate_cell(self.cursor.0, self.cursor.1);
+        self.cursor = (col, row);
+        self.invalidate_cell(col, row);
+        if let Some(mirror) = self.mirror.as_mut() {
+            mirror.set_cursor(col, row);
+        }
+        true
+    }
+
+    pub fn enable_cursor(&mut self, visible: bool) {
+        self.cursor_visible = visible;
+        self.invalidate_cell(self.cursor.0, self.cursor.1);
+    }
+
+    pub fn clear(&mut self) {
+        let blank = Cell { ch: ' ', attr: self.attr };
+        self.cells.iter_mut().for_each(|x| *x = blank);
+        self.cursor = (0, 0);
+        self.dirty = Some(self.rect);
+        if let Some(mirror) = self.mirror.as_mut() {
+            mirror.clear();
+        }
+    }
+
+    fn cell_rect(&self, col: usize, row: usize) -> Rect {
+        rect(
+            self.rect.pos + pos(col as i32 * self.cell.w, row as i32 * self.cell.h),
+            self.cell,
+        )
+    }
+
+    fn invalidate(&mut self, r: Rect) {
+        self.dirty = Some(self.dirty.map_or(r, |x| x.union(r)));
+    }
+
+    fn invalidate_cell(&mut self, col: usize, row: usize) {
+        self.invalidate(self.cell_rect(col, row));
+    }
+
+    pub fn take_dirty(&mut self) -> Option<Rect> {
+        self.dirty.take()
+    }
+
+    fn scroll(&mut self) {
+        self.cells.drain(..self.cols);
+        self.cells.extend(core::iter::repeat(Cell { ch: ' ', attr: self.attr }).take(self.cols));
+        self.dirty = Some(self.rect);
+    }
+
+    fn newline(&mut self) {
+        self.invalidate_cell(self.cursor.0, self.cursor.1);
+        if self.cursor.1 + 1 >= self.rows {
+            self.scroll();
+        } else {
+            self.cursor.1 += 1;
+        }
+    }
+
+    pub fn put_char(&mut self, ch: char) {
+        if let Some(mirror) = self.mirror.as_mut() {
+            match ch {
+                '\u{8}' => mirror.write_raw(b"\x08 \x08"),
+                ch if ch.is_control() && ch != '\r' && ch != '\n' => {}
+                ch => mirror.put_char(ch),
+            }
+        }
+        let (col, row) = self.cursor;
+        match ch {
+            '\r' => {
+                self.invalidate_cell(col, row);
+                self.cursor.0 = 0;
+            }
+            '\n' => self.newline(),
+            '\u{8}' => {
+                if col > 0 {
+                    self.cursor.0 -= 1;
+                    self.cells[row * self.cols + col - 1] = Cell { ch: ' ', attr: self.attr };
+                    self.invalidate_cell(col - 1, row);
+                    self.invalidate_cell(col, row);
+                }
+            }
+            ch if ch.is_control() => {}
+            ch => {
+                if col >= self.cols {
+                    self.cursor.0 = 0;
+                    self.newline();
+                }
+                let (col, row) = self.cursor;
+                self.cells[row * self.cols + col] = Cell { ch, attr: self.attr };
+                self.invalidate_cell(col, row);
+                self.cursor.0 += 1;
+                if self.cursor.0 < self.cols {
+                    self.invalidate_cell(self.cursor.0, row);
+                }
+            }
+        }
+    }
+
+    pub fn print(&mut self, text: &str) {
+        text.chars().for_each(|x| self.put_char(x));
+    }
+
+    pub fn present(&mut self) {
+        let dirty = if let Some(x) = self.take_dirty() { x } else { return; };
+        self.draw(Screen::get());
+        Screen::present(dirty);
+    }
+}
+
+impl<'a> fmt::Write for Terminal<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.print(s);
+        Ok(())
+    }
+}
+
+impl<'a> Widget for Terminal<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        let cols = (rect.dim.w / self.cell.w).max(1) as usize;
+        let rows = (rect.dim.h / self.cell.h).max(1) as usize;
+        self.resize(cols, rows);
+    }
+
+    fn measure(&self) -> Dim {
+        dim(self.cols as i32 * self.cell.w, self.rows as i32 * self.cell.h)
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        buffer.fill(self.rect.area(), self.palette[(DEFAULT_ATTR >> 4) as usize]);
+        let mut text = [0u8; 4];
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let cell = self.cells[row * self.cols + col];
+                let r = self.cell_rect(col, row);
+                let cursor = self.cursor_visible && self.cursor == (col, row);
+                let (fg, bg) = (cell.attr & 0x0F, (cell.attr >> 4) & 0x07);
+                let (fg, bg) = if cursor { (bg, fg) } else { (fg, bg) };
+                buffer.fill(r.area(), self.palette[bg as usize]);
+                if cell.ch != ' ' {
+                    let text = cell.ch.encode_utf8(&mut text);
+                    buffer.draw_text(r.pos, self.font, text, self.palette[fg as usize]);
+                }
+            }
+        }
+    }
+}
+
+#[repr(C)]
+struct RawMode {
+    max_mode: i32,
+    mode: i32,
+    attribute: i32,
+    cursor_column: i32,
+    cursor_row: i32,
+    cursor_visible: u8,
+}
+
+#[repr(C)]
+struct RawOutput {
+    reset: unsafe extern "efiapi" fn(*mut RawOutput, u8) -> Status,
+    output_string: unsafe extern "efiapi" fn(*mut RawOutput, *const u16) -> Status,
+    test_string: unsafe extern "efiapi" fn(*mut RawOutput, *const u16) -> Status,
+    query_mode: unsafe extern "efiapi" fn(*mut RawOutput, usize, *mut usize, *mut usize) -> Status,
+    set_mode: unsafe extern "efiapi" fn(*mut RawOutput, usize) -> Status,
+    set_attribute: unsafe extern "efiapi" fn(*mut RawOutput, usize) -> Status,
+    clear_screen: unsafe extern "efiapi" fn(*mut RawOutput) -> Status,
+    set_cursor_position: unsafe extern "efiapi" fn(*mut RawOutput, usize, usize) -> Status,
+    enable_cursor: unsafe extern "efiapi" fn(*mut RawOutput, u8) -> Status,
+    mode: *mut RawMode,
+}
+
+#[repr(C)]
+struct RawHeader {
+    signature: u64,
+    revision: u32,
+    size: u32,
+    crc32: u32,
+    reserved: u32,
+}
+
+#[repr(C)]
+struct RawSystemTable {
+    header: RawHeader,
+    firmware_vendor: *const u16,
+    firmware_revision: u32,
+    stdin_handle: *mut c_void,
+    stdin: *mut c_void,
+    stdout_handle: *mut c_void,
+    stdout: *mut RawOutput,
+    stderr_handle: *mut c_void,
+    stderr: *mut RawOutput,
+}
+
+struct Saved {
+    stdout_handle: *mut c_void,
+    stdout: *mut RawOutput,
+    stderr_handle: *mut c_void,
+    stderr: *mut RawOutput,
+    handle: Handle,
+}
+
+static mut INSTALLED: Option<Terminal<'static>> = None;
+static mut SAVED: Option<Saved> = None;
+static mut MODE: RawMode = RawMode {
+    max_mode: 1,
+    mode: 0,
+    attribute: DEFAULT_ATTR as i32,
+    cursor_column: 0,
+    cursor_row: 0,
+    cursor_visible: 1,
+};
+static mut OUTPUT: RawOutput = RawOutput {
+    reset: raw_reset,
+    output_string: raw_output_string,
+    test_string: raw_test_string,
+    query_mode: raw_query_mode,
+    set_mode: raw_set_mode,
+    set_attribute: raw_set_attribute,
+    clear_screen: raw_clear_screen,
+    set_cursor_position: raw_set_cursor_position,
+    enable_cursor: raw_enable_cursor,
+    mode: ptr::null_mut(),
+};
+
+unsafe fn with_terminal(f: impl FnOnce(&mut Terminal<'static>) -> Status) -> Status {
+    let mut term = if let Some(x) = (*ptr::addr_of_mut!(INSTALLED)).take() { x } else {
+        return Status::DEVICE_ERROR;
+    };
+    let status = f(&mut term);
+    let mode = &mut *ptr::addr_of_mut!(MODE);
+    mode.attribute = term.attr as i32;
+    mode.cursor_column = term.cursor.0 as i32;
+    mode.cursor_row = term.cursor.1 as i32;
+    mode.cursor_visible = term.cursor_visible as u8;
+    term.present();
+    INSTALLED = Some(term);
+    status
+}
+
+unsafe fn update_crc(table: &mut RawSystemTable) {
+    table.header.crc32 = 0;
+    let bytes = core::slice::from_raw_parts(
+        table as *const RawSystemTable as *const u8, table.header.size as usize);
+    table.header.crc32 = crc32(bytes);
+}
+
+unsafe extern "efiapi" fn raw_reset(_this: *mut RawOutput, _extended: u8) -> Status {
+    with_terminal(|term| {
+        term.set_attribute(DEFAULT_ATTR);
+        term.clear();
+        Status::SUCCESS
+    })
+}
+
+unsafe extern "efiapi" fn raw_output_string(_this: *mut RawOutput, string: *const u16) -> Status {
+    let mut len = 0;
+    while *string.add(len) != 0 {
+        len += 1;
+    }
+    let units = core::slice::from_raw_parts(string, len);
+    with_terminal(|term| {
+        char::decode_utf16(units.iter().copied())
+            .for_each(|x| term.put_char(x.unwrap_or(char::REPLACEMENT_CHARACTER)));
+        Status::SUCCESS
+    })
+}
+
+unsafe extern "efiapi" fn raw_test_string(_this: *mut RawOutput, _string: *const u16) -> Status {
+    Status::SUCCESS
+}
+
+unsafe extern "efiapi" fn raw_query_mode(
+    _this: *mut RawOutput, mode: usize, cols: *mut usize, rows: *mut usize,
+) -> Status {
+    if mode != 0 {
+        return Status::UNSUPPORTED;
+    }
+    with_terminal(|term| {
+        *cols = term.cols;
+        *rows = term.rows;
+        Status::SUCCESS
+    })
+}
+
+unsafe extern "efiapi" fn raw_set_mode(_this: *mut RawOutput, mode: usize) -> Status {
+    if mode != 0 {
+        return Status::UNSUPPORTED;
+    }
+    with_terminal(|term| {
+        term.clear();
+        Status::SUCCESS
+    })
+}
+
+unsafe extern "efiapi" fn raw_set_attribute(_this: *mut RawOutput, attr: usize) -> Status {
+    with_terminal(|term| {
+        term.set_attribute(attr as u8);
+        Status::SUCCESS
+    })
+}
+
+unsafe extern "efiapi" fn raw_clear_screen(_this: *mut RawOutput) -> Status {
+    with_terminal(|term| {
+        term.clear();
+        Status::SUCCESS
+    })
+}
+
+unsafe extern "efiapi" fn raw_set_cursor_position(_this: *mut RawOutput, col: usize, row: usize) -> Status {
+    with_terminal(|term| {
+        if term.set_cursor(col, row) { Status::SUCCESS } else { Status::UNSUPPORTED }
+    })
+}
+
+unsafe extern "efiapi" fn raw_enable_cursor(_this: *mut RawOutput, visible: u8) -> Status {
+    with_terminal(|term| {
+        term.enable_cursor(visible != 0);
+        Status::SUCCESS
+    })
+}
+
+pub fn install(term: Terminal<'static>, stderr: bool) -> crate::Result {
+    let st = env::system_table()?;
+    unsafe {
+        let table = &mut *(st.as_ptr() as *mut RawSystemTable);
+        OUTPUT.mode = ptr::addr_of_mut!(MODE);
+        let output = ptr::addr_of_mut!(OUTPUT);
+        if (*ptr::addr_of!(SAVED)).is_none() {
+            let handle = st.boot_services()
+                .install_protocol_interface(None, &Output::GUID, output as *mut c_void)?;
+            SAVED = Some(Saved {
+                stdout_handle: table.stdout_handle,
+                stdout: table.stdout,
+                stderr_handle: table.stderr_handle,
+                stderr: table.stderr,
+                handle,
+            });
+        }
+        let handle = (*ptr::addr_of!(SAVED)).as_ref().map(|x| x.handle.as_ptr());
+        let handle = handle.unwrap_or(ptr::null_mut());
+        INSTALLED = Some(term);
+        table.stdout_handle = handle;
+        table.stdout = output;
+        if stderr {
+            table.stderr_handle = handle;
+            table.stderr = output;
+        }
+        update_crc(table);
+        with_terminal(|_| Status::SUCCESS);
+    }
+    Ok(())
+}
+
+pub fn uninstall() -> Option<Terminal<'static>> {
+    let st = env::try_system_table()?;
+    unsafe {
+        let table = &mut *(st.as_ptr() as *mut RawSystemTable);
+        if let Some(saved) = (*ptr::addr_of_mut!(SAVED)).take() {
+            table.stdout_handle = saved.stdout_handle;
+            table.stdout = saved.stdout;
+            table.stderr_handle = saved.stderr_handle;
+            table.stderr = saved.stderr;
+            update_crc(table);
+            let output = ptr::addr_of_mut!(OUTPUT) as *mut c_void;
+            let _ = st.boot_services().uninstall_protocol_interface(saved.handle, &Output::GUID, output);
+        }
+        (*ptr::addr_of_mut!(INSTALLED)).take()
+    }
+}
+
+pub fn with_installed<R>(f: impl FnOnce(&mut Terminal<'static>) -> R) -> Option<R> {
+    unsafe {
+        let mut term = (*ptr::addr_of_mut!(INSTALLED)).take()?;
+        let result = f(&mut term);
+        INSTALLED = Some(term);
+        Some(result)
+    }
+}
@@ -0,0 +1,326 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{self, area, dim, pos, rect, Buffer, Color, Math, Rect, View};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::theme::Theme;
+use crate::widget::{Response, Widget};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TextBoxStyle {
+    pub bg: Color,
+    pub fg: Color,
+    pub border: Color,
+    pub border_focused: Color,
+    pub selection: Color,
+    pub caret: Color,
+    pub padding: i32,
+}
+
+impl From<&Theme> for TextBoxStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            bg: p.input,
+            fg: p.fg,
+            border: p.border,
+            border_focused: theme.focus.color,
+            selection: p.accent,
+            caret: p.fg_strong,
+            padding: m.padding,
+        }
+    }
+}
+
+impl Default for TextBoxStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub struct TextBox<'a> {
+    pub rect: Rect,
+    pub style: TextBoxStyle,
+    pub mask: Option<char>,
+    pub max_len: Option<usize>,
+    pub enabled: bool,
+    chars: Vec<char>,
+    caret: usize,
+    anchor: Option<usize>,
+    scroll: i32,
+    blink: f32,
+    focused: bool,
+    dragging: bool,
+    font: &'a Font,
+}
+
+impl<'a> TextBox<'a> {
+    pub fn new(rect: Rect, font: &'a Font) -> Self {
+        Self {
+            rect,
+            style: TextBoxStyle::default(),
+            mask: None,
+            max_len: None,
+            enabled: true,
+            chars: Vec::new(),
+            caret: 0,
+            anchor: None,
+            scroll: 0,
+            blink: 0.0,
+            focused: false,
+            dragging: false,
+            font,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.chars = text.chars().collect();
+        self.caret = self.chars.len();
+        self.anchor = None;
+        self.update_scroll();
+    }
+
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    pub fn set_caret(&mut self, caret: usize) {
+        self.caret = caret.min(self.chars.len());
+        self.anchor = None;
+        self.blink = 0.0;
+        self.update_scroll();
+    }
+
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.anchor?;
+        if anchor == self.caret {
+            return None;
+        }
+        Some((anchor.min(self.caret), anchor.max(self.caret)))
+    }
+
+    pub fn set_selection(&mut self, start: usize, end: usize) {
+        self.anchor = Some(start.min(self.chars.len()));
+        self.caret = end.min(self.chars.len());
+        self.update_scroll();
+    }
+
+    pub fn select_all(&mut self) {
+        self.set_selection(0, self.chars.len());
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        Some(self.chars[start..end].iter().collect())
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        let (start, end) = if let Some(x) = self.selection() { x } else { return false; };
+        self.chars.drain(start..end);
+        self.caret = start;
+        self.anchor = None;
+        true
+    }
+
+    pub fn insert(&mut self, text: &str) -> Response {
+        self.delete_selection();
+        for ch in text.chars() {
+            if self.max_len.map_or(false, |x| self.chars.len() >= x) {
+                break;
+            }
+            self.chars.insert(self.caret, ch);
+            self.caret += 1;
+        }
+        self.anchor = None;
+        self.blink = 0.0;
+        self.update_scroll();
+        Response::ValueChanged
+    }
+
+    fn display(&self, end: usize) -> String {
+        match self.mask {
+            Some(mask) => (0..end).map(|_| mask).collect(),
+            None => self.chars[..end].iter().collect(),
+        }
+    }
+
+    fn x_of(&self, index: usize) -> i32 {
+        gfx::text_dim(self.font, &self.display(index)).w
+    }
+
+    fn index_at(&self, x: i32) -> usize {
+        let x = x - self.rect.pos.x - self.style.padding + self.scroll;
+        let mut prev = 0;
+        for index in 1..=self.chars.len() {
+            let cur = self.x_of(index);
+            if x < (prev + cur) / 2 {
+                return index - 1;
+            }
+            prev = cur;
+        }
+        self.chars.len()
+    }
+
+    fn inner_width(&self) -> i32 {
+        (self.rect.dim.w - self.style.padding * 2).max(1)
+    }
+
+    fn update_scroll(&mut self) {
+        let caret_x = self.x_of(self.caret);
+        if caret_x - self.scroll < 0 {
+            self.scroll = caret_x;
+        } else if caret_x - self.scroll > self.inner_width() - 1 {
+            self.scroll = caret_x - self.inner_width() + 1;
+        }
+        let total = self.x_of(self.chars.len());
+        self.scroll = self.scroll.min((total - self.inner_width() + 1).max(0));
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.blink = Math::fract(self.blink + dt);
+    }
+
+    fn edit(&mut self, key: KeyCode) -> Response {
+        match key {
+            KeyCode::Enter => Response::Submitted,
+            KeyCode::Escape => Response::Cancelled,
+            KeyCode::Char(ch) if !ch.is_control() => {
+                let mut buf = [0; 4];
+                self.insert(ch.encode_utf8(&mut buf))
+            }
+            KeyCode::Backspace => {
+                if !self.delete_selection() {
+                    if self.caret == 0 {
+                        return Response::Handled;
+                    }
+                    self.caret -= 1;
+                    self.chars.remove(self.caret);
+                }
+                self.update_scroll();
+                Response::ValueChanged
+            }
+            KeyCode::Delete => {
+                if !self.delete_selection() {
+                    if self.caret >= self.chars.len() {
+                        return Response::Handled;
+                    }
+                    self.chars.remove(self.caret);
+                }
+                self.update_scroll();
+                Response::ValueChanged
+            }
+            KeyCode::Left => {
+                match self.selection() {
+                    Some((start, _)) => self.set_caret(start),
+                    None => self.set_caret(self.caret.saturating_sub(1)),
+                }
+                Response::Handled
+            }
+            KeyCode::Right => {
+                match self.selection() {
+                    Some((_, end)) => self.set_caret(end),
+                    None => self.set_caret(self.caret + 1),
+                }
+                Response::Handled
+            }
+            KeyCode::Home => {
+                self.set_caret(0);
+                Response::Handled
+            }
+            KeyCode::End => {
+                self.set_caret(self.chars.len());
+                Response::Handled
+            }
+            _ => Response::Ignored,
+        }
+    }
+}
+
+impl<'a> Widget for TextBox<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.update_scroll();
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        let outer = self.rect.area();
+        buffer.fill_over(outer, s.bg);
+        let border = if self.focused { s.border_focused } else { s.border };
+        buffer.stroke(outer, 1, border);
+        let inner = rect(
+            self.rect.pos + pos(s.padding, 1),
+            dim(self.inner_width(), self.rect.dim.h - 2),
+        );
+        let text = self.display(self.chars.len());
+        let text_h = gfx::text_dim(self.font, &text).h.max(inner.dim.h / 2);
+        let y = (inner.dim.h - text_h) / 2;
+        let mut view = View::new(buffer, inner);
+        if let Some((start, end)) = self.selection() {
+            let x1 = self.x_of(start) - self.scroll;
+            let x2 = self.x_of(end) - self.scroll;
+            view.fill_over(area(pos(x1, 0), pos(x2, inner.dim.h)), s.selection);
+        }
+        let fg = if self.enabled { s.fg } else { s.border };
+        view.draw_text(pos(-self.scroll, y), self.font, &text, fg);
+        if self.focused && self.blink < 0.5 {
+            let x = self.x_of(self.caret) - self.scroll;
+            view.fill_over(rect(pos(x, y), dim(1, text_h)).area(), s.caret);
+        }
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        if !self.enabled {
+            return Response::Ignored;
+        }
+        self.edit(key)
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        if !self.enabled {
+            return Response::Ignored;
+        }
+        match event {
+            PointerEvent::Down(loc, PointerButton::Left) if self.rect.contains(loc) => {
+                self.set_caret(self.index_at(loc.x));
+                self.anchor = Some(self.caret);
+                self.dragging = true;
+                Response::Handled
+            }
+            PointerEvent::Move(loc) if self.dragging => {
+                self.caret = self.index_at(loc.x);
+                self.update_scroll();
+                Response::Handled
+            }
+            PointerEvent::Up(_, PointerButton::Left) if self.dragging => {
+                self.dragging = false;
+                Response::Handled
+            }
+            _ => Response::Ignored,
+        }
+    }
+
+    fn text(&self) -> Option<String> {
+        Some(self.chars.iter().collect())
+    }
+
+    fn focusable(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.blink = 0.0;
+    }
+}
@@ -0,0 +1,248 @@
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::anim::Easing;
+use crate::debug::Corner;
+use crate::gfx::{self, dim, pos, rect, rgb, Buffer, Color, GlyphCoordIteratorExt,
+    GlyphIteratorExt, Rect};
+use crate::theme::Theme;
+use crate::widget::Scene;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ToastKind {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ToastStyle {
+    pub bg: Color,
+    pub text: Color,
+    pub info: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub width: i32,
+    pub margin: i32,
+    pub padding: i32,
+    pub spacing: i32,
+    pub stripe: i32,
+    pub line_height: i32,
+    pub fade: f32,
+    pub duration: f32,
+}
+
+impl ToastStyle {
+    pub fn accent(&self, kind: ToastKind) -> Color {
+        match kind {
+            ToastKind::Info => self.info,
+            ToastKind::Success => self.success,
+            ToastKind::Warning => self.warning,
+            ToastKind::Error => self.error,
+        }
+    }
+}
+
+impl From<&Theme> for ToastStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            bg: p.raised,
+            text: p.fg_strong,
+            info: p.accent,
+            success: rgb(0x40, 0xA0, 0x50),
+            warning: rgb(0xD0, 0xA0, 0x30),
+            error: p.error,
+            width: 280,
+            margin: m.spacing * 2,
+            padding: m.dialog_padding,
+            spacing: m.spacing,
+            stripe: 4,
+            line_height: m.line_height,
+            fade: 0.25,
+            duration: 4.0,
+        }
+    }
+}
+
+impl Default for ToastStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub type ToastId = u32;
+
+struct Toast {
+    id: ToastId,
+    text: String,
+    kind: ToastKind,
+    duration: f32,
+    age: f32,
+}
+
+pub struct Toasts<'a> {
+    pub font: &'a Font,
+    pub style: ToastStyle,
+    pub corner: Corner,
+    pub bounds: Rect,
+    pub max_visible: usize,
+    shown: Vec<Toast>,
+    queue: VecDeque<Toast>,
+    next_id: ToastId,
+}
+
+impl<'a> Toasts<'a> {
+    pub fn new(bounds: Rect, font: &'a Font) -> Self {
+        Self {
+            font,
+            style: ToastStyle::default(),
+            corner: Corner::BottomRight,
+            bounds,
+            max_visible: 4,
+            shown: Vec::new(),
+            queue: VecDeque::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn style(mut self, style: ToastStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn corner(mut self, corner: Corner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    pub fn push(&mut self, kind: ToastKind, text: &str) -> ToastId {
+        self.push_for(kind, text, self.style.duration)
+    }
+
+    pub fn push_for(&mut self, kind: ToastKind, text: &str, duration: f32) -> ToastId {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        let duration = duration.max(self.style.fade * 2.0);
+        self.queue.push_back(Toast { id, text: String::from(text), kind, duration, age: 0.0 });
+        id
+    }
+
+    pub fn info(&mut self, text: &str) -> ToastId {
+        self.push(ToastKind::Info, text)
+    }
+
+    pub fn error(&mut self, text: &str) -> ToastId {
+        self.push(ToastKind::Error, text)
+    }
+
+    pub fn dismiss(&mut self, id: ToastId) {
+        self.queue.retain(|x| x.id != id);
+        let fade = self.style.fade;
+        if let Some(toast) = self.shown.iter_mut().find(|x| x.id == id) {
+            toast.age = toast.age.max(toast.duration - fade);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        for id in self.shown.iter().map(|x| x.id).collect::<Vec<_>>() {
+            self.dismiss(id);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shown.is_empty() && self.queue.is_empty()
+    }
+
+    fn alpha(&self, toast: &Toast) -> u8 {
+        let fade = self.style.fade;
+        if fade <= 0.0 {
+            return 255;
+        }
+        let t = (toast.age / fade).min((toast.duration - toast.age) / fade).clamp(0.0, 1.0);
+        (Easing::QuadOut.apply(t) * 255.0) as u8
+    }
+
+    fn height(&self, toast: &Toast) -> i32 {
+        let s = &self.style;
+        let text_w = s.width - s.padding * 2 - s.stripe;
+        let text = gfx::wrapped_text_dim(self.font, &toast.text, text_w, s.line_height);
+        text.h.max(s.line_height) + s.padding * 2
+    }
+
+    fn layout(&self) -> Vec<Rect> {
+        let s = &self.style;
+        let b = self.bounds;
+        let x = match self.corner {
+            Corner::TopLeft | Corner::BottomLeft => b.pos.x + s.margin,
+            Corner::TopRight | Corner::BottomRight => b.pos.x + b.dim.w - s.margin - s.width,
+        };
+        let top = matches!(self.corner, Corner::TopLeft | Corner::TopRight);
+        let mut y = if top { b.pos.y + s.margin } else { b.pos.y + b.dim.h - s.margin };
+        self.shown.iter().map(|toast| {
+            let h = self.height(toast);
+            if top {
+                let r = rect(pos(x, y), dim(s.width, h));
+                y += h + s.spacing;
+                r
+            } else {
+                y -= h;
+                let r = rect(pos(x, y), dim(s.width, h));
+                y -= s.spacing;
+                r
+            }
+        }).collect()
+    }
+
+    pub fn rect(&self) -> Option<Rect> {
+        self.layout().into_iter().reduce(|a, b| a.union(b))
+    }
+
+    pub fn tick(&mut self, dt: f32) -> Option<Rect> {
+        let before = self.rect();
+        for toast in self.shown.iter_mut() {
+            toast.age += dt;
+        }
+        self.shown.retain(|x| x.age < x.duration);
+        while self.shown.len() < self.max_visible {
+            let toast = if let Some(x) = self.queue.pop_front() { x } else { break; };
+            self.shown.push(toast);
+        }
+        match (before, self.rect()) {
+            (Some(a), Some(b)) => Some(a.union(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    pub fn update(&mut self, scene: &mut Scene, dt: f32) {
+        if let Some(damage) = self.tick(dt) {
+            scene.invalidate_rect(damage);
+        }
+    }
+
+    pub fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        for (toast, r) in self.shown.iter().zip(self.layout()) {
+            let alpha = self.alpha(toast);
+            if alpha == 0 {
+                continue;
+            }
+            buffer.fill_over(r.area(), s.bg.apply_alpha(alpha));
+            let stripe = rect(r.pos, dim(s.stripe, r.dim.h));
+            buffer.fill_over(stripe.area(), s.accent(toast.kind).apply_alpha(alpha));
+            let text_w = s.width - s.padding * 2 - s.stripe;
+            self.font.lookup_string(&toast.text)
+                .glyph_coords()
+                .line_wrap(text_w, s.line_height)
+                .draw_each(buffer, r.pos + pos(s.stripe + s.padding, s.padding), self.font,
+                    s.text.apply_alpha(alpha));
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use baked_font::Font;
+
+use crate::gfx::{self, dim, pos, rect, Buffer, Color, Rect, View};
+use crate::input::{KeyCode, PointerButton, PointerEvent};
+use crate::theme::Theme;
+use crate::widget::{Response, Widget};
+
+pub struct TreeNode {
+    pub label: String,
+    pub key: String,
+    pub expandable: bool,
+    pub expanded: bool,
+    pub loaded: bool,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    pub fn leaf(label: &str, key: &str) -> Self {
+        Self {
+            label: String::from(label),
+            key: String::from(key),
+            expandable: false,
+            expanded: false,
+            loaded: true,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn branch(label: &str, key: &str) -> Self {
+        Self { expandable: true, loaded: false, ..Self::leaf(label, key) }
+    }
+
+    pub fn with_children(label: &str, key: &str, children: Vec<TreeNode>) -> Self {
+        Self { expandable: true, loaded: true, children, ..Self::leaf(label, key) }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TreeStyle {
+    pub bg: Color,
+    pub fg: Color,
+    pub guide: Color,
+    pub marker: Color,
+    pub selected_bg: Color,
+    pub selected_fg: Color,
+    pub indent: i32,
+}
+
+impl From<&Theme> for TreeStyle {
+    fn from(theme: &Theme) -> Self {
+        let p = &theme.palette;
+        let m = &theme.metrics;
+        Self {
+            bg: p.base,
+            fg: p.fg,
+            guide: p.raised,
+            marker: p.fg_dim,
+            selected_bg: p.accent,
+            selected_fg: p.accent_fg,
+            indent: m.indent,
+        }
+    }
+}
+
+impl Default for TreeStyle {
+    fn default() -> Self {
+        Self::from(Theme::current())
+    }
+}
+
+pub type TreeLoader<'a> = Box<dyn FnMut(&TreeNode) -> Vec<TreeNode> + 'a>;
+
+struct Row {
+    path: Vec<usize>,
+    depth: usize,
+}
+
+pub struct TreeView<'a> {
+    pub rect: Rect,
+    pub roots: Vec<TreeNode>,
+    pub row_height: i32,
+    pub style: TreeStyle,
+    pub loader: Option<TreeLoader<'a>>,
+    rows: Vec<Row>,
+    selected: usize,
+    scroll: usize,
+    focused: bool,
+    font: &'a Font,
+}
+
+impl<'a> TreeView<'a> {
+    pub fn new(rect: Rect, font: &'a Font, row_height: i32, roots: Vec<TreeNode>) -> Self {
+        let mut tree = Self {
+            rect,
+            roots,
+            row_height: row_height.max(1),
+            style: TreeStyle::default(),
+            loader: None,
+            rows: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            focused: false,
+            font,
+        };
+        tree.rebuild();
+        tree
+    }
+
+    pub fn node(&self, path: &[usize]) -> Option<&TreeNode> {
+        let (first, rest) = path.split_first()?;
+        let mut node = self.roots.get(*first)?;
+        for index in rest {
+            node = node.children.get(*index)?;
+        }
+        Some(node)
+    }
+
+    pub fn node_mut(&mut self, path: &[usize]) -> Option<&mut TreeNode> {
+        let (first, rest) = path.split_first()?;
+        let mut node = self.roots.get_mut(*first)?;
+        for index in rest {
+            node = node.children.get_mut(*index)?;
+        }
+        Some(node)
+    }
+
+    pub fn selected(&self) -> Option<&TreeNode> {
+        self.node(&self.rows.get(self.selected)?.path)
+    }
+
+    pub fn selected_path(&self) -> Option<&[usize]> {
+        self.rows.get(self.selected).map(|x| x.path.as_slice())
+    }
+
+    pub fn rebuild(&mut self) {
+        fn walk(nodes: &[TreeNode], path: &mut Vec<usize>, rows: &mut Vec<Row>) {
+            for (index, node) in nodes.iter().enumerate() {
+                path.push(index);
+                rows.push(Row { path: path.clone(), depth: path.len() - 1 });
+                if node.expanded {
+                    walk(&node.children, path, rows);
+                }
+                path.pop();
+            }
+        }
+        let mut rows = Vec::new();
+        walk(&self.roots, &mut Vec::new(), &mut rows);
+        self.rows = rows;
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+        self.ensure_visible();
+    }
+
+    pub fn set_expanded(&mut self, path: &[usize], expanded: bool) -> Response {
+        let mut loader = self.loader.take();
+        let node = if let Some(x) = self.node_mut(path) { x } else { return Response::Ignored; };
+        if !node.expandable || node.expanded == expanded {
+            self.loader = loader;
+            return Response::Handled;
+        }
+        if expanded && !node.loaded {
+            if let Some(loader) = loader.as_mut() {
+                node.children = loader(&*node);
+            }
+            node.loaded = true;
+        }
+        node.expanded = expanded;
+        self.loader = loader;
+        self.rebuild();
+        Response::ValueChanged
+    }
+
+    fn visible_rows(&self) -> usize {
+        (self.rect.dim.h / self.row_height).max(1) as usize
+    }
+
+    fn ensure_visible(&mut self) {
+        let visible = self.visible_rows();
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + visible {
+            self.scroll = self.selected + 1 - visible;
+        }
+    }
+
+    pub fn select(&mut self, index: usize) -> Response {
+        if self.rows.is_empty() {
+            return Response::Ignored;
+        }
+        let index = index.min(self.rows.len() - 1);
+        if index == self.selected {
+            return Response::Handled;
+        }
+        self.selected = index;
+        self.ensure_visible();
+        Response::ValueChanged
+    }
+
+    fn toggle_selected(&mut self) -> Response {
+        let path = if let Some(x) = self.selected_path() { Vec::from(x) } else { return Response::Ignored; };
+        let expanded = self.node(&path).map_or(false, |x| x.expanded);
+        self.set_expanded(&path, !expanded)
+    }
+}
+
+impl<'a> Widget for TreeView<'a> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.ensure_visible();
+    }
+
+    fn draw(&self, buffer: &mut Buffer) {
+        let s = &self.style;
+        let rh = self.row_height;
+        buffer.fill_over(self.rect.area(), s.bg);
+        let mut view = View::new(buffer, self.rect);
+        let last = (self.scroll + self.visible_rows()).min(self.rows.len());
+        for (n, index) in (self.scroll..last).enumerate() {
+            let row = &self.rows[index];
+            let node = if let Some(x) = self.node(&row.path) { x } else { continue; };
+            let y = n as i32 * rh;
+            let selected = index == self.selected;
+            if selected {
+                let bg = if self.focused { s.selected_bg } else { s.guide };
+                view.fill_over(rect(pos(0, y), dim(self.rect.dim.w, rh)).area(), bg);
+            }
+            for depth in 0..row.depth {
+                let x = depth as i32 * s.indent + s.indent / 2;
+                view.fill_over(rect(pos(x, y), dim(1, rh)).area(), s.guide);
+            }
+            let x = row.depth as i32 * s.indent;
+            if node.expandable {
+                let marker = if node.expanded { "-" } else { "+" };
+                let sz = gfx::text_dim(self.font, marker);
+                let slot = rect(pos(x, y), dim(s.indent, rh));
+                view.draw_text(slot.centered(sz).pos, self.font, marker, s.marker);
+            } else {
+                view.fill_over(rect(pos(x + s.indent / 2, y + rh / 2), dim(s.indent / 2, 1)).area(), s.guide);
+            }
+            let sz = gfx::text_dim(self.font, &node.label);
+            let fg = if selected { s.selected_fg } else { s.fg };
+            view.draw_text(pos(x + s.indent + 2, y + (rh - sz.h) / 2), self.font, &node.label, fg);
+        }
+    }
+
+    fn key(&mut self, key: KeyCode) -> Response {
+        let page = self.visible_rows();
+        match key {
+            KeyCode::Up => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Down => self.select(self.selected + 1),
+            KeyCode::PageUp => self.select(self.selected.saturating_sub(page)),
+            KeyCode::PageDown => self.select(self.selected + page),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(self.rows.len().saturating_sub(1)),
+            KeyCode::Right => {
+                let path = if let Some(x) = self.selected_path() { Vec::from(x) } else { return Response::Ignored; };
+                match self.node(&path) {
+                    Some(node) if node.expandable && !node.expanded => self.set_expanded(&path, true),
+                    Some(node) if node.expanded && !node.children.is_empty() => self.select(self.selected + 1),
+                    _ => Response::Handled,
+                }
+            }
+            KeyCode::Left => {
+                let path = if let Some(x) = self.selected_path() { Vec::from(x) } else { return Response::Ignored; };
+                if self.node(&path).map_or(false, |x| x.expanded) {
+                    return self.set_expanded(&path, false);
+                }
+                let parent = &path[..path.len() - 1];
+                match self.rows.iter().position(|x| x.path == parent) {
+                    Some(index) => self.select(index),
+                    None => Response::Handled,
+                }
+            }
+            KeyCode::Char(' ') => self.toggle_selected(),
+            KeyCode::Enter if !self.rows.is_empty() => Response::Clicked,
+            _ => Response::Ignored,
+        }
+    }
+
+    fn pointer(&mut self, event: PointerEvent) -> Response {
+        if !self.rect.contains(event.pos()) {
+            return Response::Ignored;
+        }
+        match event {
+            PointerEvent::Down(loc, PointerButton::Left) => {
+                let index = self.scroll + ((loc.y - self.rect.pos.y) / self.row_height) as usize;
+                let row = if let Some(x) = self.rows.get(index) { x } else { return Response::Handled; };
+                let marker_x = self.rect.pos.x + row.depth as i32 * self.style.indent;
+                let on_marker = loc.x >= marker_x && loc.x < marker_x + self.style.indent;
+                self.select(index);
+                if on_marker {
+                    self.toggle_selected()
+                } else {
+                    Response::Clicked
+                }
+            }
+            PointerEvent::Wheel(_, delta) => {
+                let max = self.rows.len().saturating_sub(self.visible_rows());
+                self.scroll = (self.scroll as i32 - delta).clamp(0, max as i32) as usize;
+                Response::Handled
+            }
+            _ => Response::Ignored,
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+}
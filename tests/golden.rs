@@ -0,0 +1,17 @@
+use uefapi::gfx::{dim, gray, pos, rect, rgb};
+use uefapi::testing::{self, Golden};
+
+fn golden() -> Golden {
+    Golden::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden"))
+        .output(concat!(env!("CARGO_TARGET_TMPDIR"), "/golden"))
+}
+
+#[test]
+fn overlapping_fills() {
+    let buffer = testing::render(dim(16, 12), gray(32), |buffer| {
+        buffer.fill(rect(pos(2, 2), dim(8, 5)).area(), rgb(200, 40, 40));
+        buffer.fill(rect(pos(6, 5), dim(8, 5)).area(), rgb(40, 160, 220));
+        buffer.fill(rect(pos(-3, 10), dim(40, 4)).area(), rgb(250, 250, 250));
+    });
+    golden().assert("overlapping_fills", &buffer);
+}
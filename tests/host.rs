@@ -0,0 +1,24 @@
+use uefapi::gfx::{dim, gray, pos, rect, rgba, Buffer, Canvas, Color};
+
+#[test]
+fn fill_and_stroke() {
+    let mut buffer = Buffer::new_cleared(dim(8, 8), Color::BLACK);
+    let bounds = buffer.area();
+    buffer.fill(rect(pos(2, 2), dim(4, 4)).area(), Color::WHITE);
+    buffer.stroke(bounds, 1, Color::RED);
+    assert_eq!(buffer.get(pos(0, 0)), Some(Color::RED));
+    assert_eq!(buffer.get(pos(7, 4)), Some(Color::RED));
+    assert_eq!(buffer.get(pos(1, 1)), Some(Color::BLACK));
+    assert_eq!(buffer.get(pos(3, 3)), Some(Color::WHITE));
+    assert_eq!(buffer.get(pos(6, 6)), Some(Color::BLACK));
+    assert_eq!(buffer.get(pos(8, 8)), None);
+}
+
+#[test]
+fn fill_over_clips_and_blends() {
+    let mut buffer = Buffer::new_cleared(dim(4, 4), Color::BLACK);
+    buffer.fill_over(rect(pos(-2, -2), dim(4, 4)).area(), rgba(255, 255, 255, 128));
+    assert_eq!(buffer.get(pos(1, 1)), Some(gray(128)));
+    assert_eq!(buffer.get(pos(2, 2)), Some(Color::BLACK));
+    assert_eq!(buffer.data.iter().filter(|x| **x == gray(128)).count(), 4);
+}
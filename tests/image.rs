@@ -0,0 +1,34 @@
+use uefapi::gfx::{dim, rgb, rgba, Buffer};
+use uefapi::image::{self, ImageError};
+
+fn qoi_sample() -> Buffer {
+    let (base, diff, luma) = (rgb(10, 20, 30), rgb(11, 21, 29), rgb(16, 31, 34));
+    Buffer { data: vec![base, base, base, base, diff, luma, base, base], dim: dim(8, 1) }
+}
+
+#[test]
+fn qoi_round_trip() {
+    let buffer = qoi_sample();
+    let data = image::encode_qoi(&buffer);
+    let ops = &data[14..data.len() - 8];
+    assert_eq!(ops, [0xFE, 10, 20, 30, 0xC2, 0x7D, 0xAA, 0x33, 0x09, 0xC0]);
+    assert_eq!(image::decode(&data), Ok(buffer));
+}
+
+#[test]
+fn qoi_truncated() {
+    let data = image::encode_qoi(&qoi_sample());
+    assert_eq!(image::decode_qoi(&data[..10]), Err(ImageError::Truncated));
+    assert_eq!(image::decode_qoi(&data[..20]), Err(ImageError::Truncated));
+}
+
+#[test]
+fn bmp_round_trip() {
+    let buffer = Buffer {
+        data: vec![rgb(255, 0, 0), rgb(0, 255, 0), rgb(0, 0, 255), rgb(1, 2, 3), rgba(0, 0, 0, 0), rgb(250, 240, 230)],
+        dim: dim(3, 2),
+    };
+    let data = image::encode_bmp(&buffer);
+    assert_eq!(image::decode(&data), Ok(buffer));
+    assert_eq!(image::decode_bmp(&data[..data.len() - 1]), Err(ImageError::Truncated));
+}